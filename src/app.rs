@@ -1 +1,4 @@
+pub mod alarms;
+pub mod attachments;
 pub mod calendar;
+pub mod dav;
@@ -0,0 +1,4 @@
+pub mod alarm_queue;
+pub mod alarm_scheduler;
+pub mod alarm_store;
+pub mod notifier;
@@ -0,0 +1,94 @@
+/*
+Priority queue of the upcoming alarms, the earliest first.
+*/
+
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+};
+
+use chrono::{DateTime, Utc};
+
+use super::notifier::AlarmNotification;
+use crate::app::calendar::models::calendar_object::CalendarObject;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ScheduledAlarm(AlarmNotification);
+
+#[derive(Debug, Default)]
+pub struct AlarmQueue {
+    alarms: BinaryHeap<Reverse<ScheduledAlarm>>,
+}
+
+impl ScheduledAlarm {
+    fn get_key(&self) -> (DateTime<Utc>, &str, DateTime<Utc>, usize) {
+        (self.0.fire_at, &self.0.uid, self.0.instance_start, self.0.alarm_index)
+    }
+}
+
+impl Ord for ScheduledAlarm {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.get_key().cmp(&other.get_key())
+    }
+}
+
+impl PartialOrd for ScheduledAlarm {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl AlarmQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, notification: AlarmNotification) {
+        self.alarms.push(Reverse(ScheduledAlarm(notification)));
+    }
+
+    pub fn add_object(&mut self, object: &CalendarObject, after: DateTime<Utc>, until: DateTime<Utc>) {
+        /* Alarms of the events and to-dos of the resource firing after `after` (exclusive) up to `until` */
+        let overridden = object.get_overridden();
+        for event in object.get_events() {
+            for time in event.get_alarm_times(&overridden, after, until) {
+                if time.fire_at > after {
+                    self.push(AlarmNotification::from_event(event, &time));
+                }
+            }
+        }
+        /* Completed to-dos need no reminder */
+        for todo in object.get_todos().iter().filter(|todo| !todo.is_completed()) {
+            for time in todo.get_alarm_times(&overridden, after, until) {
+                if time.fire_at > after {
+                    self.push(AlarmNotification::from_todo(todo, &time));
+                }
+            }
+        }
+    }
+
+    pub fn get_next_time(&self) -> Option<DateTime<Utc>> {
+        self.alarms.peek().map(|alarm| alarm.0 .0.fire_at)
+    }
+
+    pub fn pop_due(&mut self, now: DateTime<Utc>) -> Vec<AlarmNotification> {
+        /* Removes the alarms firing up to `now`, in firing order */
+        let mut due = Vec::new();
+        while self.get_next_time().is_some_and(|fire_at| fire_at <= now) {
+            due.push(self.alarms.pop().unwrap().0 .0);
+        }
+        due
+    }
+
+    pub fn clear(&mut self) {
+        self.alarms.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.alarms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.alarms.is_empty()
+    }
+}
@@ -0,0 +1,230 @@
+/*
+Background task firing the alarms of the stored events and to-dos.
+
+The upcoming trigger times, recurrences included, are loaded into a priority queue for a lookahead window and
+reloaded periodically to pick up changes. Only the events and to-dos whose next alarm fires within the window are
+loaded, their next alarm being moved forward once fired. The task sleeps until the next alarm or reload, dispatches
+the due alarms to every notifier handling them and persists the time up to which the alarms were processed as its
+cursor. After a restart, the alarms fired while the server was down are dispatched from the cursor on, but those
+older than the maximum delay, which are no longer useful reminders.
+*/
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use tokio::task::JoinHandle;
+
+use super::{
+    alarm_queue::AlarmQueue,
+    alarm_store::{AlarmStore, MongoAlarmStore},
+    notifier::{
+        log_notifier::LogNotifier, smtp_notifier::SmtpNotifier, webhook_notifier::WebhookNotifier, AlarmNotification,
+        Notifier,
+    },
+};
+use crate::{
+    app::calendar::models::calendar_object::CalendarObject,
+    architecture::{app_config::AppConfig, mongodb::MongoDb},
+};
+
+pub struct AlarmScheduler {
+    store: Arc<dyn AlarmStore>,
+    notifiers: Vec<Arc<dyn Notifier>>,
+
+    /* How far ahead the alarms are loaded into the queue */
+    lookahead: Duration,
+
+    /* How often the alarms are reloaded from the store */
+    refresh_interval: Duration,
+
+    /* How late an alarm can still be fired */
+    max_delay: Duration,
+}
+
+/* State kept by the scheduler task between ticks */
+#[derive(Debug, Default)]
+pub struct AlarmSchedulerState {
+    queue: AlarmQueue,
+    cursor: Option<DateTime<Utc>>,
+    loaded_at: Option<DateTime<Utc>>,
+
+    /* Resources in the queue, whose next alarm is moved forward once fired */
+    objects: Vec<CalendarObject>,
+}
+
+impl AlarmScheduler {
+    pub fn new(store: Arc<dyn AlarmStore>, notifiers: Vec<Arc<dyn Notifier>>) -> Self {
+        Self {
+            store,
+            notifiers,
+            lookahead: Duration::hours(1),
+            refresh_interval: Duration::minutes(1),
+            max_delay: Duration::hours(1),
+        }
+    }
+
+    pub fn from_config(config: &AppConfig, mongo: &MongoDb) -> Self {
+        let mut notifiers: Vec<Arc<dyn Notifier>> = vec![Arc::new(LogNotifier::new())];
+        if let Some(url) = &config.alarm_webhook_url {
+            notifiers.push(Arc::new(WebhookNotifier::new(url)));
+        }
+        if let Some(host) = &config.smtp_host {
+            let credentials = config.smtp_username.clone().zip(config.smtp_password.clone());
+            match SmtpNotifier::new(
+                host,
+                config.smtp_port,
+                &config.smtp_from,
+                credentials,
+                config.smtp_implicit_tls,
+            ) {
+                Ok(notifier) => notifiers.push(Arc::new(notifier)),
+                Err(error) => log::error!("Email alarms disabled: {}", error),
+            }
+        }
+
+        Self::new(Arc::new(MongoAlarmStore::new(mongo.db.clone())), notifiers)
+    }
+
+    pub fn with_lookahead(mut self, lookahead: Duration) -> Self {
+        self.lookahead = lookahead;
+        self
+    }
+
+    pub fn with_refresh_interval(mut self, refresh_interval: Duration) -> Self {
+        self.refresh_interval = refresh_interval;
+        self
+    }
+
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    pub fn start(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut state = AlarmSchedulerState::default();
+            loop {
+                let now = Utc::now();
+                let wake_at = match self.tick(&mut state, now).await {
+                    Ok(wake_at) => wake_at,
+                    Err(error) => {
+                        log::error!("Alarm scheduler error: {}", error);
+                        now + self.refresh_interval
+                    }
+                };
+                let sleep = (wake_at - Utc::now()).to_std().unwrap_or_default();
+                tokio::time::sleep(sleep).await;
+            }
+        })
+    }
+
+    pub async fn tick(
+        &self,
+        state: &mut AlarmSchedulerState,
+        now: DateTime<Utc>,
+    ) -> mongodb::error::Result<DateTime<Utc>> {
+        /*
+        Fires the alarms due at `now` and returns when the scheduler should wake up next.
+        The first run without a cursor starts from `now`, without firing past alarms.
+        */
+        let cursor = match state.cursor {
+            Some(cursor) => cursor,
+            None => {
+                let cursor = match self.store.get_cursor().await? {
+                    Some(cursor) => cursor,
+                    None => {
+                        self.store.save_cursor(now).await?;
+                        now
+                    }
+                };
+                state.cursor = Some(cursor);
+                cursor
+            }
+        };
+
+        /* Alarms missed for longer than the maximum delay are dropped */
+        let after = cursor.max(now - self.max_delay);
+        if state
+            .loaded_at
+            .is_none_or(|loaded_at| now - loaded_at >= self.refresh_interval)
+        {
+            self.load(state, after, now + self.lookahead).await?;
+            state.loaded_at = Some(now);
+        }
+
+        for notification in state.queue.pop_due(now) {
+            if notification.fire_at > after {
+                self.dispatch(&notification).await;
+            }
+        }
+        self.store.save_cursor(now).await?;
+        state.cursor = Some(now);
+        /* The next alarms up to `now` were processed, they are moved to the following ones */
+        self.update_next_alarms(state, now, |next_alarm_at| {
+            next_alarm_at.is_some_and(|next| next <= now)
+        })
+        .await?;
+
+        let next_refresh = state.loaded_at.unwrap_or(now) + self.refresh_interval;
+        Ok(state
+            .queue
+            .get_next_time()
+            .map_or(next_refresh, |next| next.min(next_refresh)))
+    }
+
+    async fn load(
+        &self,
+        state: &mut AlarmSchedulerState,
+        after: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> mongodb::error::Result<()> {
+        state.objects = self.store.get_objects_with_alarms(until).await?;
+        /* Stored before the next alarm was kept */
+        self.update_next_alarms(state, after, |next_alarm_at| next_alarm_at.is_none())
+            .await?;
+
+        state.queue.clear();
+        for object in state.objects.iter() {
+            state.queue.add_object(object, after, until);
+        }
+        Ok(())
+    }
+
+    async fn update_next_alarms(
+        &self,
+        state: &mut AlarmSchedulerState,
+        after: DateTime<Utc>,
+        needs_update: impl Fn(Option<DateTime<Utc>>) -> bool,
+    ) -> mongodb::error::Result<()> {
+        for object in state.objects.iter_mut() {
+            let overridden = object.get_overridden();
+            for event in object.get_events_mut() {
+                if !event.get_alarms().is_empty() && needs_update(event.get_next_alarm_at()) {
+                    event.update_next_alarm(&overridden, after);
+                    self.store.save_event_next_alarm(event).await?;
+                }
+            }
+            for todo in object.get_todos_mut() {
+                if !todo.get_alarms().is_empty() && needs_update(todo.get_next_alarm_at()) {
+                    todo.update_next_alarm(&overridden, after);
+                    self.store.save_todo_next_alarm(todo).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn dispatch(&self, notification: &AlarmNotification) {
+        /* A failing notifier does not prevent the others from delivering the alarm */
+        for notifier in self.notifiers.iter().filter(|notifier| notifier.handles(notification)) {
+            if let Err(error) = notifier.notify(notification).await {
+                log::error!(
+                    "Alarm {} of {} not delivered: {}",
+                    notification.alarm_index,
+                    notification.uid,
+                    error
+                );
+            }
+        }
+    }
+}
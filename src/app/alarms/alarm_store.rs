@@ -0,0 +1,176 @@
+/*
+Storage used by the alarm scheduler: the resources with upcoming alarms, and the cursor with the time up to which the
+alarms were processed, so no alarm is lost or repeated across restarts.
+
+The events and to-dos hold the time of their next alarm, so the scheduler only loads those firing soon. It is set when
+they are written, and moved forward by the scheduler once their alarms fired. Those stored before it existed have no
+such field and are loaded until the scheduler sets it.
+*/
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use mongodb::{
+    bson::{doc, Bson, Document},
+    options::{FindOptions, ReplaceOptions},
+    Database,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::app::calendar::{
+    calendar_store::group_objects,
+    models::{calendar_object::CalendarObject, event::Event, todo::Todo},
+};
+
+#[async_trait]
+pub trait AlarmStore: Send + Sync {
+    async fn get_cursor(&self) -> mongodb::error::Result<Option<DateTime<Utc>>>;
+
+    async fn save_cursor(&self, cursor: DateTime<Utc>) -> mongodb::error::Result<()>;
+
+    /* Every component of the resources with an alarm firing up to `until`, the overrides included */
+    async fn get_objects_with_alarms(&self, until: DateTime<Utc>) -> mongodb::error::Result<Vec<CalendarObject>>;
+
+    /* Saves the time of the next alarm, unless the event or to-do changed since read */
+    async fn save_event_next_alarm(&self, event: &Event) -> mongodb::error::Result<()>;
+
+    async fn save_todo_next_alarm(&self, todo: &Todo) -> mongodb::error::Result<()>;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SchedulerCursor {
+    #[serde(rename = "_id")]
+    id: String,
+
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    processed_until: DateTime<Utc>,
+}
+
+pub struct MongoAlarmStore {
+    db: Database,
+}
+
+impl MongoAlarmStore {
+    /* MongoDB collection storing the state of the scheduler */
+    pub const COLLECTION: &'static str = "alarm_scheduler";
+    const CURSOR_ID: &'static str = "cursor";
+
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    fn get_alarms_filter(until: DateTime<Utc>) -> Document {
+        doc! {
+            "alarms.0": { "$exists": true },
+            "$or": [
+                { "next_alarm_at": { "$lte": bson::DateTime::from_chrono(until) } },
+                { "next_alarm_at": { "$exists": false } },
+            ],
+        }
+    }
+
+    fn get_next_alarm_update(next_alarm_at: Option<DateTime<Utc>>) -> Document {
+        let next_alarm_at = next_alarm_at.map_or(Bson::Null, |next_alarm_at| {
+            Bson::DateTime(bson::DateTime::from_chrono(next_alarm_at))
+        });
+        doc! { "$set": { "next_alarm_at": next_alarm_at } }
+    }
+}
+
+#[async_trait]
+impl AlarmStore for MongoAlarmStore {
+    async fn get_cursor(&self) -> mongodb::error::Result<Option<DateTime<Utc>>> {
+        let cursor = self
+            .db
+            .collection::<SchedulerCursor>(Self::COLLECTION)
+            .find_one(doc! { "_id": Self::CURSOR_ID }, None)
+            .await?;
+        Ok(cursor.map(|cursor| cursor.processed_until))
+    }
+
+    async fn save_cursor(&self, cursor: DateTime<Utc>) -> mongodb::error::Result<()> {
+        let cursor = SchedulerCursor {
+            id: Self::CURSOR_ID.to_string(),
+            processed_until: cursor,
+        };
+        self.db
+            .collection::<SchedulerCursor>(Self::COLLECTION)
+            .replace_one(
+                doc! { "_id": Self::CURSOR_ID },
+                cursor,
+                ReplaceOptions::builder().upsert(true).build(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn get_objects_with_alarms(&self, until: DateTime<Utc>) -> mongodb::error::Result<Vec<CalendarObject>> {
+        let options = FindOptions::builder()
+            .sort(doc! { "calendar_id": 1, "resource_name": 1 })
+            .build();
+        let events = self.db.collection::<Event>(Event::COLLECTION);
+        let resources: Vec<Document> = events
+            .find(Self::get_alarms_filter(until), None)
+            .await?
+            .try_collect::<Vec<Event>>()
+            .await?
+            .iter()
+            .map(|event| doc! { "calendar_id": event.get_calendar_id(), "resource_name": event.get_resource_name() })
+            .collect();
+        let events: Vec<Event> = match resources.is_empty() {
+            true => Vec::new(),
+            false => {
+                events
+                    .find(doc! { "$or": resources }, options.clone())
+                    .await?
+                    .try_collect()
+                    .await?
+            }
+        };
+
+        let todos = self.db.collection::<Todo>(Todo::COLLECTION);
+        let resources: Vec<Document> = todos
+            .find(Self::get_alarms_filter(until), None)
+            .await?
+            .try_collect::<Vec<Todo>>()
+            .await?
+            .iter()
+            .map(|todo| doc! { "calendar_id": todo.get_calendar_id(), "resource_name": todo.get_resource_name() })
+            .collect();
+        let todos: Vec<Todo> = match resources.is_empty() {
+            true => Vec::new(),
+            false => {
+                todos
+                    .find(doc! { "$or": resources }, options)
+                    .await?
+                    .try_collect()
+                    .await?
+            }
+        };
+        Ok(group_objects(events, todos, Vec::new()))
+    }
+
+    async fn save_event_next_alarm(&self, event: &Event) -> mongodb::error::Result<()> {
+        self.db
+            .collection::<Event>(Event::COLLECTION)
+            .update_one(
+                doc! { "_id": event.get_id(), "etag": event.get_etag() },
+                Self::get_next_alarm_update(event.get_next_alarm_at()),
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn save_todo_next_alarm(&self, todo: &Todo) -> mongodb::error::Result<()> {
+        self.db
+            .collection::<Todo>(Todo::COLLECTION)
+            .update_one(
+                doc! { "_id": todo.get_id(), "etag": todo.get_etag() },
+                Self::get_next_alarm_update(todo.get_next_alarm_at()),
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+/*
+Notifiers deliver the alarms fired by the scheduler.
+
+Every notifier receives the alarms it handles: the log and webhook notifiers handle every action, while the SMTP
+notifier only sends EMAIL alarms, to their ATTENDEE recipients (RFC 5545 section 3.6.6).
+*/
+
+pub mod log_notifier;
+pub mod smtp_notifier;
+pub mod webhook_notifier;
+
+use std::fmt::{Display, Formatter};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::app::calendar::models::{
+    calendar_types::alarm::{Alarm, AlarmAction, AlarmTime},
+    event::Event,
+    todo::Todo,
+};
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct AlarmNotification {
+    /* UID of the event or to-do the alarm belongs to */
+    pub uid: String,
+
+    /* UID of the alarm, for clients to acknowledge it */
+    pub alarm_uid: Option<String>,
+
+    pub action: AlarmAction,
+
+    pub summary: Option<String>,
+
+    pub description: Option<String>,
+
+    /* Email addresses of the ATTENDEE properties of the alarm */
+    pub recipients: Vec<String>,
+
+    pub fire_at: DateTime<Utc>,
+
+    pub instance_start: DateTime<Utc>,
+
+    pub alarm_index: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotifierError {
+    pub message: String,
+}
+
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    fn handles(&self, _notification: &AlarmNotification) -> bool {
+        true
+    }
+
+    async fn notify(&self, notification: &AlarmNotification) -> Result<(), NotifierError>;
+}
+
+impl AlarmNotification {
+    pub fn new(uid: &str, alarm: &Alarm, time: &AlarmTime, summary: Option<&str>, description: Option<&str>) -> Self {
+        /* The alarm texts fall back to the ones of the event or to-do */
+        Self {
+            uid: uid.to_string(),
+            alarm_uid: alarm.uid.clone(),
+            action: alarm.action,
+            summary: alarm.summary.clone().or_else(|| summary.map(str::to_string)),
+            description: alarm.description.clone().or_else(|| description.map(str::to_string)),
+            recipients: alarm.attendees.iter().map(|attendee| attendee.email.clone()).collect(),
+            fire_at: time.fire_at,
+            instance_start: time.instance_start,
+            alarm_index: time.alarm_index,
+        }
+    }
+
+    pub fn from_event(event: &Event, time: &AlarmTime) -> Self {
+        Self::new(
+            event.get_uid(),
+            &event.get_alarms()[time.alarm_index],
+            time,
+            event.get_summary(),
+            event.get_description(),
+        )
+    }
+
+    pub fn from_todo(todo: &Todo, time: &AlarmTime) -> Self {
+        Self::new(
+            todo.get_uid(),
+            &todo.get_alarms()[time.alarm_index],
+            time,
+            todo.get_summary(),
+            todo.get_description(),
+        )
+    }
+
+    pub fn get_subject(&self) -> String {
+        self.summary.clone().unwrap_or_else(|| "Reminder".to_string())
+    }
+}
+
+impl NotifierError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for NotifierError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Notifier error: {}", self.message)
+    }
+}
+
+impl std::error::Error for NotifierError {}
@@ -0,0 +1,27 @@
+use async_trait::async_trait;
+
+use super::{AlarmNotification, Notifier, NotifierError};
+
+/* Writes every alarm to the application log */
+#[derive(Default)]
+pub struct LogNotifier;
+
+impl LogNotifier {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Notifier for LogNotifier {
+    async fn notify(&self, notification: &AlarmNotification) -> Result<(), NotifierError> {
+        log::info!(
+            "Alarm {} of {} fired at {}: {}",
+            notification.alarm_index,
+            notification.uid,
+            notification.fire_at,
+            notification.get_subject()
+        );
+        Ok(())
+    }
+}
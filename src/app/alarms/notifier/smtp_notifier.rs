@@ -0,0 +1,104 @@
+use async_trait::async_trait;
+use lettre::{
+    message::Mailbox,
+    transport::smtp::{authentication::Credentials, AsyncSmtpTransportBuilder},
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+
+use super::{AlarmNotification, Notifier, NotifierError};
+use crate::app::calendar::models::calendar_types::alarm::AlarmAction;
+
+/* Sends EMAIL alarms to their attendees through an SMTP server */
+pub struct SmtpNotifier {
+    from: Mailbox,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl SmtpNotifier {
+    pub fn new(
+        host: &str,
+        port: u16,
+        from: &str,
+        credentials: Option<(String, String)>,
+        implicit_tls: bool,
+    ) -> Result<Self, NotifierError> {
+        /*
+        Connection secured with TLS, the certificate of the relay being verified: upgraded with STARTTLS (port 587),
+        which the relay must support, or TLS from the start (port 465) with `implicit_tls`.
+        */
+        let builder = match implicit_tls {
+            true => AsyncSmtpTransport::<Tokio1Executor>::relay(host),
+            false => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host),
+        }
+        .map_err(|error| NotifierError::new(error.to_string()))?;
+        Self::build(builder.port(port), from, credentials)
+    }
+
+    pub fn insecure(
+        host: &str,
+        port: u16,
+        from: &str,
+        credentials: Option<(String, String)>,
+    ) -> Result<Self, NotifierError> {
+        /*
+        Plain connection, without TLS, for local test relays only: the messages and the credentials can be read on the
+        way to the relay.
+        */
+        Self::build(
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host).port(port),
+            from,
+            credentials,
+        )
+    }
+
+    fn build(
+        mut builder: AsyncSmtpTransportBuilder,
+        from: &str,
+        credentials: Option<(String, String)>,
+    ) -> Result<Self, NotifierError> {
+        let from = from
+            .parse::<Mailbox>()
+            .map_err(|error| NotifierError::new(error.to_string()))?;
+        if let Some((username, password)) = credentials {
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+
+        Ok(Self {
+            from,
+            transport: builder.build(),
+        })
+    }
+
+    fn build_message(&self, notification: &AlarmNotification) -> Result<Message, NotifierError> {
+        let mut builder = Message::builder()
+            .from(self.from.clone())
+            .subject(notification.get_subject());
+        for recipient in &notification.recipients {
+            let mailbox = recipient
+                .trim_start_matches("mailto:")
+                .parse::<Mailbox>()
+                .map_err(|error| NotifierError::new(error.to_string()))?;
+            builder = builder.to(mailbox);
+        }
+
+        builder
+            .body(notification.description.clone().unwrap_or_default())
+            .map_err(|error| NotifierError::new(error.to_string()))
+    }
+}
+
+#[async_trait]
+impl Notifier for SmtpNotifier {
+    fn handles(&self, notification: &AlarmNotification) -> bool {
+        notification.action == AlarmAction::Email && !notification.recipients.is_empty()
+    }
+
+    async fn notify(&self, notification: &AlarmNotification) -> Result<(), NotifierError> {
+        let message = self.build_message(notification)?;
+        self.transport
+            .send(message)
+            .await
+            .map_err(|error| NotifierError::new(error.to_string()))?;
+        Ok(())
+    }
+}
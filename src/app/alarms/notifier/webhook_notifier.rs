@@ -0,0 +1,32 @@
+use async_trait::async_trait;
+
+use super::{AlarmNotification, Notifier, NotifierError};
+
+/* Posts every alarm as JSON to a URL */
+pub struct WebhookNotifier {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    async fn notify(&self, notification: &AlarmNotification) -> Result<(), NotifierError> {
+        self.client
+            .post(&self.url)
+            .json(notification)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|error| NotifierError::new(error.to_string()))?;
+        Ok(())
+    }
+}
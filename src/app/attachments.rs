@@ -0,0 +1,3 @@
+pub mod attachment_store;
+pub mod blob_store;
+pub mod managed_attachments;
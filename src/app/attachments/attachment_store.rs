@@ -0,0 +1,119 @@
+/*
+Moves the inline attachments of calendar components to the blob store, so the documents stay small, and loads them
+back to return the components.
+
+Inline attachments larger than the configured maximum are rejected before storing anything.
+*/
+
+use std::{
+    fmt::{Display, Formatter},
+    sync::Arc,
+};
+
+use super::blob_store::{BlobStore, GridFsBlobStore};
+use crate::{
+    app::calendar::models::calendar_types::attachment::{Attachment, AttachmentContent},
+    architecture::{app_config::AppConfig, mongodb::MongoDb},
+};
+
+#[derive(Debug)]
+pub enum AttachmentError {
+    TooLarge { size: u64, max_size: u64 },
+    Storage(mongodb::error::Error),
+}
+
+pub struct AttachmentStore {
+    blobs: Arc<dyn BlobStore>,
+
+    /* Maximum size in bytes of an inline attachment */
+    max_size: u64,
+}
+
+impl AttachmentStore {
+    pub fn new(blobs: Arc<dyn BlobStore>, max_size: u64) -> Self {
+        Self { blobs, max_size }
+    }
+
+    pub fn from_config(config: &AppConfig, mongo: &MongoDb) -> Self {
+        Self::new(Arc::new(GridFsBlobStore::new(&mongo.db)), config.max_attachment_size)
+    }
+
+    pub fn get_max_size(&self) -> u64 {
+        self.max_size
+    }
+
+    pub fn validate(&self, attachments: &[Attachment]) -> Result<(), AttachmentError> {
+        for attachment in attachments {
+            if let AttachmentContent::Binary(data) = &attachment.content {
+                let size = data.len() as u64;
+                if size > self.max_size {
+                    return Err(AttachmentError::TooLarge {
+                        size,
+                        max_size: self.max_size,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn save(&self, attachments: &mut [Attachment]) -> Result<(), AttachmentError> {
+        /* Uploads the inline attachments, replacing their content with the reference to the stored file */
+        self.validate(attachments)?;
+        for attachment in attachments.iter_mut() {
+            if let AttachmentContent::Binary(data) = &attachment.content {
+                let filename = attachment.filename.as_deref().unwrap_or_default();
+                let file_id = self
+                    .blobs
+                    .upload(filename, attachment.format_type.as_deref(), data)
+                    .await?;
+                attachment.content = AttachmentContent::Stored {
+                    file_id,
+                    size: data.len() as u64,
+                };
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn load(&self, attachments: &mut [Attachment]) -> Result<(), AttachmentError> {
+        for attachment in attachments.iter_mut() {
+            if let AttachmentContent::Stored { file_id, .. } = &attachment.content {
+                attachment.content = AttachmentContent::Binary(self.blobs.download(*file_id).await?);
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn delete(&self, attachments: &[Attachment]) -> Result<(), AttachmentError> {
+        for attachment in attachments {
+            if let AttachmentContent::Stored { file_id, .. } = &attachment.content {
+                self.blobs.delete(*file_id).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<mongodb::error::Error> for AttachmentError {
+    fn from(error: mongodb::error::Error) -> Self {
+        AttachmentError::Storage(error)
+    }
+}
+
+impl Display for AttachmentError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AttachmentError::TooLarge { size, max_size } => {
+                write!(
+                    f,
+                    "Attachment of {} bytes exceeds the maximum of {} bytes",
+                    size, max_size
+                )
+            }
+            AttachmentError::Storage(error) => write!(f, "Attachment storage error: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for AttachmentError {}
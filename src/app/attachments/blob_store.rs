@@ -0,0 +1,97 @@
+/*
+Storage of binary contents outside of the documents referencing them, in GridFS.
+
+The media type of a blob is kept in the metadata of its file, to serve it back as uploaded.
+*/
+
+use async_trait::async_trait;
+use bson::{doc, oid::ObjectId, Bson};
+use futures::{io::Cursor, TryStreamExt};
+use mongodb::{
+    gridfs::GridFsBucket,
+    options::{GridFsBucketOptions, GridFsUploadOptions},
+    Database,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobInfo {
+    pub filename: Option<String>,
+
+    pub content_type: Option<String>,
+
+    /* Size in bytes */
+    pub size: u64,
+}
+
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    async fn upload(&self, filename: &str, content_type: Option<&str>, data: &[u8])
+        -> mongodb::error::Result<ObjectId>;
+
+    async fn download(&self, id: ObjectId) -> mongodb::error::Result<Vec<u8>>;
+
+    /* None when there is no blob with the id */
+    async fn get_info(&self, id: ObjectId) -> mongodb::error::Result<Option<BlobInfo>>;
+
+    async fn delete(&self, id: ObjectId) -> mongodb::error::Result<()>;
+}
+
+pub struct GridFsBlobStore {
+    bucket: GridFsBucket,
+}
+
+impl GridFsBlobStore {
+    /* GridFS bucket storing the attachments */
+    pub const BUCKET: &'static str = "attachments";
+
+    pub fn new(db: &Database) -> Self {
+        let options = GridFsBucketOptions::builder()
+            .bucket_name(Some(Self::BUCKET.to_string()))
+            .build();
+        Self {
+            bucket: db.gridfs_bucket(options),
+        }
+    }
+}
+
+#[async_trait]
+impl BlobStore for GridFsBlobStore {
+    async fn upload(
+        &self,
+        filename: &str,
+        content_type: Option<&str>,
+        data: &[u8],
+    ) -> mongodb::error::Result<ObjectId> {
+        let options = GridFsUploadOptions::builder()
+            .metadata(content_type.map(|content_type| doc! { "contentType": content_type }))
+            .build();
+        self.bucket
+            .upload_from_futures_0_3_reader(filename, Cursor::new(data), options)
+            .await
+    }
+
+    async fn download(&self, id: ObjectId) -> mongodb::error::Result<Vec<u8>> {
+        let mut data = Cursor::new(Vec::new());
+        self.bucket
+            .download_to_futures_0_3_writer(Bson::ObjectId(id), &mut data)
+            .await?;
+        Ok(data.into_inner())
+    }
+
+    async fn get_info(&self, id: ObjectId) -> mongodb::error::Result<Option<BlobInfo>> {
+        let file = self.bucket.find(doc! { "_id": id }, None).await?.try_next().await?;
+        Ok(file.map(|file| BlobInfo {
+            content_type: file
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.get_str("contentType").ok())
+                .map(str::to_string),
+            filename: file.filename.filter(|filename| !filename.is_empty()),
+            size: file.length,
+        }))
+    }
+
+    async fn delete(&self, id: ObjectId) -> mongodb::error::Result<()> {
+        self.bucket.delete(Bson::ObjectId(id)).await
+    }
+}
@@ -0,0 +1,308 @@
+/*
+Managed attachments (RFC 8607).
+
+Clients upload attachments apart from the calendar resource, by POST requests on the resource with an action:
+
+       POST /events/123.ics?action=attachment-add&rid=20100101T100000Z
+       POST /events/123.ics?action=attachment-update&managed-id=97S
+       POST /events/123.ics?action=attachment-remove&managed-id=97S&rid=M
+
+   rid:  Comma-separated list of the RECURRENCE-ID values of the instances changed, "M" being the master component.
+      Without it, every component of the resource is changed.
+
+The server stores the data and adds to the components an ATTACH property with the URL serving it and a MANAGED-ID
+identifying it. The same attachment is shared by the master component and its overrides, so its data is deleted once
+no component of the resource references it anymore.
+*/
+
+use std::{
+    fmt::{Display, Formatter},
+    sync::Arc,
+};
+
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+
+use super::{
+    attachment_store::AttachmentError,
+    blob_store::{BlobInfo, BlobStore, GridFsBlobStore},
+};
+use crate::{
+    app::calendar::{
+        ical::values::parse_date_time,
+        models::{
+            calendar_types::attachment::{Attachment, AttachmentContent},
+            event::Event,
+        },
+    },
+    architecture::{app_config::AppConfig, mongodb::MongoDb},
+};
+
+/* Compliance class advertised in the DAV header */
+pub const CALENDAR_MANAGED_ATTACHMENTS: &str = "calendar-managed-attachments";
+
+/* Path under which the attachments are served */
+pub const ATTACHMENTS_PATH: &str = "/attachments";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManagedAttachmentAction {
+    Add,
+    Update,
+    Remove,
+}
+
+#[derive(Debug)]
+pub enum ManagedAttachmentError {
+    /* The MANAGED-ID is not one of the attachments of the components (CALDAV:valid-managed-id) */
+    InvalidManagedId(String),
+
+    /* The rid does not match any component of the resource (CALDAV:valid-rid) */
+    InvalidRecurrenceId(String),
+
+    Attachment(AttachmentError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct AttachmentUpload {
+    pub data: Vec<u8>,
+
+    /* Media type of the data (Content-Type) */
+    pub format_type: Option<String>,
+
+    /* Suggested name to save the data (Content-Disposition) */
+    pub filename: Option<String>,
+}
+
+pub struct ManagedAttachments {
+    blobs: Arc<dyn BlobStore>,
+
+    /* Maximum size in bytes of an attachment */
+    max_size: u64,
+
+    /* Base URL of the server, prefixed to the attachment URLs */
+    public_url: String,
+}
+
+impl ManagedAttachmentAction {
+    pub fn from_query(value: &str) -> Option<Self> {
+        match value {
+            "attachment-add" => Some(Self::Add),
+            "attachment-update" => Some(Self::Update),
+            "attachment-remove" => Some(Self::Remove),
+            _ => None,
+        }
+    }
+
+    pub fn to_query(&self) -> &'static str {
+        match self {
+            Self::Add => "attachment-add",
+            Self::Update => "attachment-update",
+            Self::Remove => "attachment-remove",
+        }
+    }
+}
+
+impl ManagedAttachments {
+    pub fn new(blobs: Arc<dyn BlobStore>, max_size: u64, public_url: &str) -> Self {
+        Self {
+            blobs,
+            max_size,
+            public_url: public_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    pub fn from_config(config: &AppConfig, mongo: &MongoDb) -> Self {
+        Self::new(
+            Arc::new(GridFsBlobStore::new(&mongo.db)),
+            config.max_attachment_size,
+            &config.public_url,
+        )
+    }
+
+    pub fn get_max_size(&self) -> u64 {
+        self.max_size
+    }
+
+    pub fn get_url(&self, managed_id: &str) -> String {
+        format!("{}{}/{}", self.public_url, ATTACHMENTS_PATH, managed_id)
+    }
+
+    pub async fn add(
+        &self,
+        events: &mut [Event],
+        rid: Option<&str>,
+        upload: &AttachmentUpload,
+    ) -> Result<String, ManagedAttachmentError> {
+        /* Stores the data and attaches it to the selected components, returning its MANAGED-ID */
+        let selected = select_components(events, rid)?;
+        let attachment = self.upload(upload).await?;
+        for index in selected {
+            events[index].add_attachment(attachment.clone());
+        }
+        Ok(attachment.managed_id.unwrap_or_default())
+    }
+
+    pub async fn update(
+        &self,
+        events: &mut [Event],
+        managed_id: &str,
+        upload: &AttachmentUpload,
+    ) -> Result<String, ManagedAttachmentError> {
+        /* Replaces the data in every component having the attachment, which gets a new MANAGED-ID */
+        if !events.iter().any(|event| event.has_managed_attachment(managed_id)) {
+            return Err(ManagedAttachmentError::InvalidManagedId(managed_id.to_string()));
+        }
+        let attachment = self.upload(upload).await?;
+        for event in events.iter_mut() {
+            for existing in event.get_attachments_mut() {
+                if existing.managed_id.as_deref() == Some(managed_id) {
+                    *existing = attachment.clone();
+                }
+            }
+        }
+        self.delete_blob(managed_id).await?;
+        Ok(attachment.managed_id.unwrap_or_default())
+    }
+
+    pub async fn remove(
+        &self,
+        events: &mut [Event],
+        managed_id: &str,
+        rid: Option<&str>,
+    ) -> Result<(), ManagedAttachmentError> {
+        /* Every selected component MUST have the attachment */
+        let selected = select_components(events, rid)?;
+        if selected.is_empty()
+            || !selected
+                .iter()
+                .all(|index| events[*index].has_managed_attachment(managed_id))
+        {
+            return Err(ManagedAttachmentError::InvalidManagedId(managed_id.to_string()));
+        }
+        for index in selected {
+            events[index].remove_managed_attachment(managed_id);
+        }
+        if count_references(events, managed_id) == 0 {
+            self.delete_blob(managed_id).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn release(&self, events: &[Event]) -> Result<(), ManagedAttachmentError> {
+        /* Deletes the data of the attachments of a resource being deleted */
+        let mut managed_ids: Vec<&str> = events
+            .iter()
+            .flat_map(|event| event.get_attachments())
+            .filter_map(|attachment| attachment.managed_id.as_deref())
+            .collect();
+        managed_ids.sort_unstable();
+        managed_ids.dedup();
+        for managed_id in managed_ids {
+            self.delete_blob(managed_id).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn get_info(&self, managed_id: &str) -> Result<Option<BlobInfo>, ManagedAttachmentError> {
+        match ObjectId::parse_str(managed_id) {
+            Ok(id) => Ok(self.blobs.get_info(id).await.map_err(AttachmentError::from)?),
+            Err(_) => Ok(None),
+        }
+    }
+
+    pub async fn download(&self, managed_id: &str) -> Result<Vec<u8>, ManagedAttachmentError> {
+        let id = parse_managed_id(managed_id)?;
+        Ok(self.blobs.download(id).await.map_err(AttachmentError::from)?)
+    }
+
+    async fn upload(&self, upload: &AttachmentUpload) -> Result<Attachment, ManagedAttachmentError> {
+        let size = upload.data.len() as u64;
+        if size > self.max_size {
+            return Err(AttachmentError::TooLarge {
+                size,
+                max_size: self.max_size,
+            }
+            .into());
+        }
+
+        let filename = upload.filename.as_deref().unwrap_or_default();
+        let id = self
+            .blobs
+            .upload(filename, upload.format_type.as_deref(), &upload.data)
+            .await
+            .map_err(AttachmentError::from)?;
+        let managed_id = id.to_hex();
+        Ok(Attachment {
+            content: AttachmentContent::Uri(self.get_url(&managed_id)),
+            format_type: upload.format_type.clone(),
+            filename: upload.filename.clone(),
+            managed_id: Some(managed_id),
+            size: Some(size),
+        })
+    }
+
+    async fn delete_blob(&self, managed_id: &str) -> Result<(), ManagedAttachmentError> {
+        let id = parse_managed_id(managed_id)?;
+        Ok(self.blobs.delete(id).await.map_err(AttachmentError::from)?)
+    }
+}
+
+fn parse_managed_id(managed_id: &str) -> Result<ObjectId, ManagedAttachmentError> {
+    ObjectId::parse_str(managed_id).map_err(|_| ManagedAttachmentError::InvalidManagedId(managed_id.to_string()))
+}
+
+fn parse_recurrence_ids(rid: &str) -> Result<Vec<Option<DateTime<Utc>>>, ManagedAttachmentError> {
+    /* None stands for the master component */
+    rid.split(',')
+        .map(|value| match value.trim() {
+            "M" => Ok(None),
+            value => parse_date_time(value)
+                .map(Some)
+                .ok_or_else(|| ManagedAttachmentError::InvalidRecurrenceId(value.to_string())),
+        })
+        .collect()
+}
+
+fn select_components(events: &[Event], rid: Option<&str>) -> Result<Vec<usize>, ManagedAttachmentError> {
+    /* Indexes of the components matching the rid, all of them without it */
+    let Some(rid) = rid else {
+        return Ok((0..events.len()).collect());
+    };
+
+    let mut selected = Vec::new();
+    for recurrence_id in parse_recurrence_ids(rid)? {
+        let index = events
+            .iter()
+            .position(|event| event.get_recurrence_id() == recurrence_id)
+            .ok_or_else(|| ManagedAttachmentError::InvalidRecurrenceId(rid.to_string()))?;
+        if !selected.contains(&index) {
+            selected.push(index);
+        }
+    }
+    Ok(selected)
+}
+
+pub fn count_references(events: &[Event], managed_id: &str) -> usize {
+    events
+        .iter()
+        .filter(|event| event.has_managed_attachment(managed_id))
+        .count()
+}
+
+impl From<AttachmentError> for ManagedAttachmentError {
+    fn from(error: AttachmentError) -> Self {
+        ManagedAttachmentError::Attachment(error)
+    }
+}
+
+impl Display for ManagedAttachmentError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ManagedAttachmentError::InvalidManagedId(managed_id) => write!(f, "Invalid MANAGED-ID: {}", managed_id),
+            ManagedAttachmentError::InvalidRecurrenceId(rid) => write!(f, "Invalid recurrence id: {}", rid),
+            ManagedAttachmentError::Attachment(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for ManagedAttachmentError {}
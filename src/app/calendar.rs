@@ -1 +1,3 @@
+pub mod calendar_store;
+pub mod ical;
 pub mod models;
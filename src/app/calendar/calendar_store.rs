@@ -0,0 +1,465 @@
+/*
+Storage of the calendar collections and their resources.
+
+The events, to-dos and journal entries are stored apart, each type in its collection, referencing the calendar and the name of the
+resource holding them, so the components of a resource are grouped back to build the calendar object resource.
+*/
+
+use async_trait::async_trait;
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use mongodb::{
+    bson::{doc, Document},
+    error::{TRANSIENT_TRANSACTION_ERROR, UNKNOWN_TRANSACTION_COMMIT_RESULT},
+    options::{FindOptions, UpdateOptions},
+    ClientSession, Database,
+};
+use serde::de::DeserializeOwned;
+
+use super::models::{calendar::Calendar, calendar_object::CalendarObject, event::Event, journal::Journal, todo::Todo};
+
+/* Collections storing the components of the resources, one per component type */
+const COMPONENT_COLLECTIONS: [&str; 3] = [Event::COLLECTION, Todo::COLLECTION, Journal::COLLECTION];
+
+#[async_trait]
+pub trait CalendarStore: Send + Sync {
+    async fn get_calendars(&self) -> mongodb::error::Result<Vec<Calendar>>;
+
+    async fn get_calendar(&self, name: &str) -> mongodb::error::Result<Option<Calendar>>;
+
+    /* Inserts the calendar unless one has the same name, returning its id */
+    async fn create_calendar(&self, calendar: &Calendar) -> mongodb::error::Result<Option<ObjectId>>;
+
+    /* Saves the properties of the calendar */
+    async fn update_calendar(&self, calendar: &Calendar) -> mongodb::error::Result<()>;
+
+    async fn get_objects(&self, calendar_id: ObjectId) -> mongodb::error::Result<Vec<CalendarObject>>;
+
+    /*
+    Resources that may have an event in the time range, of unbounded sides when None: those with a recurring event,
+    whose instances are not stored, and the others by the dates of their events. Every to-do and journal entry is
+    returned, their dates being matched by the filter.
+    */
+    async fn get_objects_in_range(
+        &self,
+        calendar_id: ObjectId,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> mongodb::error::Result<Vec<CalendarObject>>;
+
+    async fn get_object(&self, calendar_id: ObjectId, name: &str) -> mongodb::error::Result<Option<CalendarObject>>;
+
+    /* Resources with the names, those missing being skipped */
+    async fn get_objects_by_names(
+        &self,
+        calendar_id: ObjectId,
+        names: &[String],
+    ) -> mongodb::error::Result<Vec<CalendarObject>>;
+
+    /* Name of the resource holding the components with the UID */
+    async fn find_uid(&self, calendar_id: ObjectId, uid: &str) -> mongodb::error::Result<Option<String>>;
+
+    /*
+    Replaces the components of the resource, if it is still as previously read: absent, or with the same entity tag.
+    Returns false when it changed meanwhile.
+    */
+    async fn save_object(
+        &self,
+        calendar_id: ObjectId,
+        object: &CalendarObject,
+        previous: Option<&CalendarObject>,
+    ) -> mongodb::error::Result<bool>;
+
+    /* Saves the dead properties of the resource, if it is still as read */
+    async fn update_object_properties(&self, object: &CalendarObject) -> mongodb::error::Result<bool>;
+
+    /* Deletes the resource, if it is still as read */
+    async fn delete_object(&self, calendar_id: ObjectId, object: &CalendarObject) -> mongodb::error::Result<bool>;
+
+    /* Marks the calendar as changed, updating its CS:getctag */
+    async fn touch_calendar(&self, calendar_id: ObjectId, now: DateTime<Utc>) -> mongodb::error::Result<()>;
+}
+
+pub struct MongoCalendarStore {
+    db: Database,
+}
+
+impl MongoCalendarStore {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    async fn find_components<T>(&self, collection: &str, filter: Document) -> mongodb::error::Result<Vec<T>>
+    where
+        T: DeserializeOwned + Unpin + Send + Sync,
+    {
+        let options = FindOptions::builder().sort(doc! { "resource_name": 1 }).build();
+        self.db
+            .collection::<T>(collection)
+            .find(filter, options)
+            .await?
+            .try_collect()
+            .await
+    }
+
+    async fn find_objects(&self, filter: Document) -> mongodb::error::Result<Vec<CalendarObject>> {
+        let events = self.find_components(Event::COLLECTION, filter.clone()).await?;
+        let todos = self.find_components(Todo::COLLECTION, filter.clone()).await?;
+        let journals = self.find_components(Journal::COLLECTION, filter).await?;
+        Ok(group_objects(events, todos, journals))
+    }
+
+    async fn write_object(&self, calendar_id: ObjectId, write: ObjectWrite<'_>) -> mongodb::error::Result<bool> {
+        /*
+        Writes the components of the resource in a transaction, so readers see either its previous version or the new
+        one. Concurrent writers conflict on the documents they update, the transaction of those losing being retried:
+        they then find the resource changed.
+        */
+        let mut session = self
+            .db
+            .collection::<Event>(Event::COLLECTION)
+            .client()
+            .start_session(None)
+            .await?;
+        loop {
+            session.start_transaction(None).await?;
+            let result = match self.write_components(&mut session, calendar_id, &write).await {
+                Ok(true) => commit(&mut session).await.map(|_| true),
+                Ok(false) => session.abort_transaction().await.map(|_| false),
+                Err(error) => {
+                    session.abort_transaction().await.ok();
+                    Err(error)
+                }
+            };
+            match result {
+                Err(error) if error.contains_label(TRANSIENT_TRANSACTION_ERROR) => continue,
+                result => return result,
+            }
+        }
+    }
+
+    async fn write_components(
+        &self,
+        session: &mut ClientSession,
+        calendar_id: ObjectId,
+        write: &ObjectWrite<'_>,
+    ) -> mongodb::error::Result<bool> {
+        let (object, previous, etag) = match *write {
+            ObjectWrite::Save { object, previous } => (object, previous, object.get_etag()),
+            ObjectWrite::Delete(object) => (object, Some(object), String::new()),
+        };
+        let filter = doc! { "calendar_id": calendar_id, "resource_name": object.get_name() };
+
+        match previous {
+            Some(previous) => {
+                if !self.claim_object(session, previous, &etag).await? {
+                    return Ok(false);
+                }
+                /* The new components may be of another type */
+                for collection in COMPONENT_COLLECTIONS {
+                    self.db
+                        .collection::<Document>(collection)
+                        .delete_many_with_session(filter.clone(), None, session)
+                        .await?;
+                }
+            }
+            None => {
+                /* Writers creating resources in the calendar conflict on its document */
+                self.db
+                    .collection::<Calendar>(Calendar::COLLECTION)
+                    .update_one_with_session(
+                        doc! { "_id": calendar_id },
+                        doc! { "$currentDate": { "updated_at": true } },
+                        None,
+                        session,
+                    )
+                    .await?;
+                for collection in COMPONENT_COLLECTIONS {
+                    let component = self
+                        .db
+                        .collection::<Document>(collection)
+                        .find_one_with_session(filter.clone(), None, session)
+                        .await?;
+                    if component.is_some() {
+                        return Ok(false);
+                    }
+                }
+            }
+        }
+        if let ObjectWrite::Save { object, .. } = *write {
+            if !object.get_events().is_empty() {
+                self.db
+                    .collection::<Event>(Event::COLLECTION)
+                    .insert_many_with_session(object.get_events(), None, session)
+                    .await?;
+            }
+            if !object.get_todos().is_empty() {
+                self.db
+                    .collection::<Todo>(Todo::COLLECTION)
+                    .insert_many_with_session(object.get_todos(), None, session)
+                    .await?;
+            }
+            if !object.get_journals().is_empty() {
+                self.db
+                    .collection::<Journal>(Journal::COLLECTION)
+                    .insert_many_with_session(object.get_journals(), None, session)
+                    .await?;
+            }
+        }
+        Ok(true)
+    }
+
+    async fn claim_object(
+        &self,
+        session: &mut ClientSession,
+        previous: &CalendarObject,
+        etag: &str,
+    ) -> mongodb::error::Result<bool> {
+        /*
+        Changes the entity tag of the first component read, if it is unchanged: same version (SEQUENCE), bumped on
+        significant changes, and same entity tag, changed on every write. Writers having read the same state race on
+        this single document update, which only one of them wins.
+        */
+        let Some(first) = previous.get_revision() else {
+            return Ok(false);
+        };
+        let result = self
+            .db
+            .collection::<Document>(first.collection)
+            .update_one_with_session(
+                doc! { "_id": first.id, "version": first.version, "etag": first.etag },
+                doc! { "$set": { "etag": etag } },
+                None,
+                session,
+            )
+            .await?;
+        Ok(result.modified_count == 1)
+    }
+}
+
+/* Write of the components of a resource */
+enum ObjectWrite<'a> {
+    Save {
+        object: &'a CalendarObject,
+        previous: Option<&'a CalendarObject>,
+    },
+    Delete(&'a CalendarObject),
+}
+
+async fn commit(session: &mut ClientSession) -> mongodb::error::Result<()> {
+    /* Committing again is safe when the outcome of the commit is unknown */
+    loop {
+        match session.commit_transaction().await {
+            Err(error) if error.contains_label(UNKNOWN_TRANSACTION_COMMIT_RESULT) => continue,
+            result => return result,
+        }
+    }
+}
+
+#[async_trait]
+impl CalendarStore for MongoCalendarStore {
+    async fn get_calendars(&self) -> mongodb::error::Result<Vec<Calendar>> {
+        let options = FindOptions::builder().sort(doc! { "name": 1 }).build();
+        self.db
+            .collection::<Calendar>(Calendar::COLLECTION)
+            .find(None, options)
+            .await?
+            .try_collect()
+            .await
+    }
+
+    async fn get_calendar(&self, name: &str) -> mongodb::error::Result<Option<Calendar>> {
+        self.db
+            .collection::<Calendar>(Calendar::COLLECTION)
+            .find_one(doc! { "name": name }, None)
+            .await
+    }
+
+    async fn create_calendar(&self, calendar: &Calendar) -> mongodb::error::Result<Option<ObjectId>> {
+        let result = self
+            .db
+            .collection::<Calendar>(Calendar::COLLECTION)
+            .update_one(
+                doc! { "name": calendar.get_name() },
+                doc! { "$setOnInsert": bson::to_document(calendar)? },
+                UpdateOptions::builder().upsert(true).build(),
+            )
+            .await?;
+        Ok(result.upserted_id.and_then(|id| id.as_object_id()))
+    }
+
+    async fn update_calendar(&self, calendar: &Calendar) -> mongodb::error::Result<()> {
+        /* The identity of the calendar does not change */
+        let mut fields = bson::to_document(calendar)?;
+        for field in ["_id", "name", "created_at"] {
+            fields.remove(field);
+        }
+        self.db
+            .collection::<Calendar>(Calendar::COLLECTION)
+            .update_one(doc! { "_id": calendar.get_id() }, doc! { "$set": fields }, None)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_objects(&self, calendar_id: ObjectId) -> mongodb::error::Result<Vec<CalendarObject>> {
+        self.find_objects(doc! { "calendar_id": calendar_id }).await
+    }
+
+    async fn get_objects_in_range(
+        &self,
+        calendar_id: ObjectId,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> mongodb::error::Result<Vec<CalendarObject>> {
+        /* Events without end or duration end when they start, they are kept when the end is unknown */
+        let mut single = doc! {};
+        if let Some(end) = end {
+            single.insert("start", doc! { "$lt": bson::DateTime::from_chrono(end) });
+        }
+        if let Some(start) = start {
+            single.insert(
+                "$or",
+                vec![
+                    doc! { "end": null },
+                    doc! { "end": { "$gte": bson::DateTime::from_chrono(start) } },
+                ],
+            );
+        }
+        let filter = doc! {
+            "calendar_id": calendar_id,
+            "$or": [{ "recurrence": { "$ne": null } }, single],
+        };
+
+        let names = self
+            .db
+            .collection::<Event>(Event::COLLECTION)
+            .distinct("resource_name", filter, None)
+            .await?;
+        let events = self
+            .find_components(
+                Event::COLLECTION,
+                doc! { "calendar_id": calendar_id, "resource_name": { "$in": names } },
+            )
+            .await?;
+        let todos = self
+            .find_components(Todo::COLLECTION, doc! { "calendar_id": calendar_id })
+            .await?;
+        let journals = self
+            .find_components(Journal::COLLECTION, doc! { "calendar_id": calendar_id })
+            .await?;
+        Ok(group_objects(events, todos, journals))
+    }
+
+    async fn get_object(&self, calendar_id: ObjectId, name: &str) -> mongodb::error::Result<Option<CalendarObject>> {
+        let objects = self
+            .find_objects(doc! { "calendar_id": calendar_id, "resource_name": name })
+            .await?;
+        Ok(objects.into_iter().next())
+    }
+
+    async fn get_objects_by_names(
+        &self,
+        calendar_id: ObjectId,
+        names: &[String],
+    ) -> mongodb::error::Result<Vec<CalendarObject>> {
+        self.find_objects(doc! { "calendar_id": calendar_id, "resource_name": { "$in": names } })
+            .await
+    }
+
+    async fn find_uid(&self, calendar_id: ObjectId, uid: &str) -> mongodb::error::Result<Option<String>> {
+        for collection in COMPONENT_COLLECTIONS {
+            let component = self
+                .db
+                .collection::<Document>(collection)
+                .find_one(doc! { "calendar_id": calendar_id, "uid": uid }, None)
+                .await?;
+            if let Some(component) = component {
+                return Ok(component.get_str("resource_name").ok().map(str::to_string));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn save_object(
+        &self,
+        calendar_id: ObjectId,
+        object: &CalendarObject,
+        previous: Option<&CalendarObject>,
+    ) -> mongodb::error::Result<bool> {
+        if object.get_revision().is_none() {
+            return Ok(false);
+        }
+        self.write_object(calendar_id, ObjectWrite::Save { object, previous })
+            .await
+    }
+
+    async fn update_object_properties(&self, object: &CalendarObject) -> mongodb::error::Result<bool> {
+        /* Held by the first component, unchanged unless the entity tag is */
+        let (Some(first), Some(properties)) = (object.get_revision(), object.get_dead_properties()) else {
+            return Ok(false);
+        };
+        let result = self
+            .db
+            .collection::<Document>(first.collection)
+            .update_one(
+                doc! { "_id": first.id, "etag": first.etag },
+                doc! { "$set": { "dead_properties": bson::to_bson(properties)? } },
+                None,
+            )
+            .await?;
+        Ok(result.matched_count == 1)
+    }
+
+    async fn delete_object(&self, calendar_id: ObjectId, object: &CalendarObject) -> mongodb::error::Result<bool> {
+        self.write_object(calendar_id, ObjectWrite::Delete(object)).await
+    }
+
+    async fn touch_calendar(&self, calendar_id: ObjectId, now: DateTime<Utc>) -> mongodb::error::Result<()> {
+        self.db
+            .collection::<Calendar>(Calendar::COLLECTION)
+            .update_one(
+                doc! { "_id": calendar_id },
+                doc! { "$set": { "updated_at": bson::DateTime::from_chrono(now) } },
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+pub fn group_objects(events: Vec<Event>, todos: Vec<Todo>, journals: Vec<Journal>) -> Vec<CalendarObject> {
+    /* Components sorted by calendar and resource name, those of a resource being of a single type */
+    let mut objects: Vec<CalendarObject> = Vec::new();
+    for event in events {
+        let name = event.get_resource_name().unwrap_or_default().to_string();
+        match objects.last_mut() {
+            Some(object) if object.get_name() == name && object.get_calendar_id() == event.get_calendar_id() => {
+                object.add_event(event)
+            }
+            _ => objects.push(CalendarObject::new(&name, vec![event])),
+        }
+    }
+    let events_count = objects.len();
+    for todo in todos {
+        let name = todo.get_resource_name().unwrap_or_default().to_string();
+        match objects[events_count..].last_mut() {
+            Some(object) if object.get_name() == name && object.get_calendar_id() == todo.get_calendar_id() => {
+                object.add_todo(todo)
+            }
+            _ => objects.push(CalendarObject::from_todos(&name, vec![todo])),
+        }
+    }
+    let todos_count = objects.len();
+    for journal in journals {
+        let name = journal.get_resource_name().unwrap_or_default().to_string();
+        match objects[todos_count..].last_mut() {
+            Some(object) if object.get_name() == name && object.get_calendar_id() == journal.get_calendar_id() => {
+                object.add_journal(journal)
+            }
+            _ => objects.push(CalendarObject::from_journals(&name, vec![journal])),
+        }
+    }
+    objects.sort_by(|a, b| (a.get_calendar_id(), a.get_name()).cmp(&(b.get_calendar_id(), b.get_name())));
+    objects
+}
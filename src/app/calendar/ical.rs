@@ -0,0 +1,83 @@
+/*
+Generic representation of iCalendar (RFC 5545) objects.
+
+An iCalendar object is a tree of components (VCALENDAR, VEVENT, VALARM...), each one holding a list of properties.
+Property values are kept in their RFC 5545 textual form, already unescaped, so the different representations
+(text/calendar, application/calendar+xml) only need to convert from and to this tree.
+*/
+
+pub mod component;
+pub mod ics;
+pub mod property;
+pub mod value_type;
+pub mod values;
+pub mod xcal;
+
+use std::fmt;
+
+use component::ICalComponent;
+
+pub const PRODUCT_ID: &str = "-//CalDavFast//CalDavFast//EN";
+pub const ICALENDAR_VERSION: &str = "2.0";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ICalError {
+    MissingProperty(String),
+    InvalidValue { property: String, value: String },
+    InvalidComponent(String),
+    InvalidFormat(String),
+}
+
+impl fmt::Display for ICalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ICalError::MissingProperty(property) => write!(f, "Missing required property {}", property),
+            ICalError::InvalidValue { property, value } => write!(f, "Invalid value '{}' for {}", value, property),
+            ICalError::InvalidComponent(component) => write!(f, "Invalid component {}", component),
+            ICalError::InvalidFormat(message) => write!(f, "Invalid iCalendar format: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ICalError {}
+
+/* Representations of the iCalendar objects, by media type */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ICalFormat {
+    #[default]
+    Ics,
+    Xcal,
+}
+
+impl ICalFormat {
+    pub const ALL: [ICalFormat; 2] = [ICalFormat::Ics, ICalFormat::Xcal];
+
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        /* text/calendar; charset=utf-8 */
+        let media_type = content_type.split(';').next()?.trim();
+        Self::ALL
+            .into_iter()
+            .find(|format| media_type.eq_ignore_ascii_case(format.get_content_type()))
+    }
+
+    pub fn get_content_type(&self) -> &'static str {
+        match self {
+            ICalFormat::Ics => ics::ICS_CONTENT_TYPE,
+            ICalFormat::Xcal => xcal::XCAL_CONTENT_TYPE,
+        }
+    }
+
+    pub fn parse(&self, data: &str) -> Result<ICalComponent, ICalError> {
+        match self {
+            ICalFormat::Ics => ics::parse(data),
+            ICalFormat::Xcal => xcal::parse(data),
+        }
+    }
+
+    pub fn write(&self, calendar: &ICalComponent) -> String {
+        match self {
+            ICalFormat::Ics => ics::write(calendar),
+            ICalFormat::Xcal => xcal::write(calendar),
+        }
+    }
+}
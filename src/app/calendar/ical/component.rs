@@ -0,0 +1,93 @@
+use super::{property::ICalProperty, ICalError, ICALENDAR_VERSION, PRODUCT_ID};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ICalComponent {
+    pub name: String,
+    pub properties: Vec<ICalProperty>,
+    pub components: Vec<ICalComponent>,
+}
+
+impl ICalComponent {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_uppercase(),
+            properties: Vec::new(),
+            components: Vec::new(),
+        }
+    }
+
+    pub fn calendar(components: Vec<ICalComponent>) -> Self {
+        /* Returns a VCALENDAR object wrapping the given components */
+        let mut calendar = Self::new("VCALENDAR");
+        calendar.add_property(ICalProperty::new("PRODID", PRODUCT_ID));
+        calendar.add_property(ICalProperty::new("VERSION", ICALENDAR_VERSION));
+        calendar.components = components;
+        calendar
+    }
+
+    pub fn is(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+    }
+
+    pub fn add_property(&mut self, property: ICalProperty) {
+        self.properties.push(property);
+    }
+
+    pub fn add_component(&mut self, component: ICalComponent) {
+        self.components.push(component);
+    }
+
+    pub fn get_property(&self, name: &str) -> Option<&ICalProperty> {
+        self.properties
+            .iter()
+            .find(|property| property.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn get_properties<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a ICalProperty> {
+        self.properties
+            .iter()
+            .filter(move |property| property.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn get_required_property(&self, name: &str) -> Result<&ICalProperty, ICalError> {
+        self.get_property(name)
+            .ok_or_else(|| ICalError::MissingProperty(name.to_string()))
+    }
+
+    pub fn get_text(&self, name: &str) -> Option<String> {
+        self.get_property(name).map(|property| property.get_value().to_string())
+    }
+
+    pub fn get_texts(&self, name: &str) -> Option<Vec<String>> {
+        /* Values of every occurrence of the property, None if there are none */
+        let values: Vec<String> = self
+            .get_properties(name)
+            .flat_map(|property| property.values.iter().cloned())
+            .collect();
+
+        if values.is_empty() {
+            None
+        } else {
+            Some(values)
+        }
+    }
+
+    pub fn add_text(&mut self, name: &str, value: &Option<String>) {
+        if let Some(value) = value {
+            self.add_property(ICalProperty::new(name, value));
+        }
+    }
+
+    pub fn add_texts(&mut self, name: &str, values: &Option<Vec<String>>) {
+        /* One property per value */
+        for value in values.iter().flatten() {
+            self.add_property(ICalProperty::new(name, value));
+        }
+    }
+
+    pub fn get_components<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a ICalComponent> {
+        self.components
+            .iter()
+            .filter(move |component| component.name.eq_ignore_ascii_case(name))
+    }
+}
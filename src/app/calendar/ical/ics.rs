@@ -0,0 +1,227 @@
+/*
+Textual iCalendar format (text/calendar) of RFC 5545 section 3.1.
+
+       contentline   = name *(";" param ) ":" value CRLF
+
+       param         = param-name "=" param-value *("," param-value)
+       param-value   = paramtext / quoted-string
+
+Lines longer than 75 octets are folded: split with a CRLF immediately followed by a single space or tab, which is
+removed again when unfolding. TEXT values escape backslashes, semicolons, commas and newlines:
+
+       DESCRIPTION:Meeting with John\, Paul and George\nBring the agenda
+*/
+
+use super::{
+    component::ICalComponent,
+    property::{ICalParameter, ICalProperty},
+    value_type::ICalValueType,
+    ICalError,
+};
+
+pub const ICS_CONTENT_TYPE: &str = "text/calendar";
+
+const MAX_LINE_LENGTH: usize = 75;
+
+/* Properties whose value is a comma separated list */
+const MULTI_VALUED_PROPERTIES: [&str; 5] = ["CATEGORIES", "RESOURCES", "EXDATE", "RDATE", "FREEBUSY"];
+
+pub fn parse(text: &str) -> Result<ICalComponent, ICalError> {
+    /* Returns the root component of the object, usually a VCALENDAR */
+    let mut stack: Vec<ICalComponent> = Vec::new();
+
+    for line in unfold(text) {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let property = parse_line(&line)?;
+        match property.name.as_str() {
+            "BEGIN" => stack.push(ICalComponent::new(property.get_value())),
+            "END" => {
+                let component = stack
+                    .pop()
+                    .filter(|component| component.is(property.get_value()))
+                    .ok_or_else(|| ICalError::InvalidFormat(format!("unexpected END:{}", property.get_value())))?;
+                match stack.last_mut() {
+                    Some(parent) => parent.add_component(component),
+                    None => return Ok(component),
+                }
+            }
+            _ => stack
+                .last_mut()
+                .ok_or_else(|| ICalError::InvalidFormat(format!("property {} outside component", property.name)))?
+                .add_property(property),
+        }
+    }
+
+    Err(ICalError::InvalidFormat("missing END of component".to_string()))
+}
+
+pub fn write(component: &ICalComponent) -> String {
+    let mut text = String::new();
+    write_component(component, &mut text);
+    text
+}
+
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.split('\n') {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        match (
+            line.strip_prefix(' ').or_else(|| line.strip_prefix('\t')),
+            lines.last_mut(),
+        ) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+fn parse_line(line: &str) -> Result<ICalProperty, ICalError> {
+    let invalid = || ICalError::InvalidFormat(format!("invalid content line '{}'", line));
+
+    let (name_end, _) = line
+        .char_indices()
+        .find(|(_, character)| *character == ';' || *character == ':')
+        .ok_or_else(invalid)?;
+    let mut property = ICalProperty::with_values(&line[..name_end], Vec::new());
+
+    /* Parameters, until the first colon outside quotes */
+    let mut rest = &line[name_end..];
+    while let Some(parameters) = rest.strip_prefix(';') {
+        let (name, after_name) = parameters.split_once('=').ok_or_else(invalid)?;
+        let mut values = Vec::new();
+        let mut remaining = after_name;
+        loop {
+            let (value, after_value) = if let Some(quoted) = remaining.strip_prefix('"') {
+                let (value, after_quote) = quoted.split_once('"').ok_or_else(invalid)?;
+                (value.to_string(), after_quote)
+            } else {
+                let end = remaining.find([',', ';', ':']).ok_or_else(invalid)?;
+                (remaining[..end].to_string(), &remaining[end..])
+            };
+            values.push(value);
+            match after_value.strip_prefix(',') {
+                Some(next) => remaining = next,
+                None => {
+                    rest = after_value;
+                    break;
+                }
+            }
+        }
+        property.parameters.push(ICalParameter::with_values(name, values));
+    }
+
+    let value = rest.strip_prefix(':').ok_or_else(invalid)?;
+    let is_text = property.get_value_type().is_text();
+    property.values = if MULTI_VALUED_PROPERTIES.contains(&property.name.as_str()) {
+        split_values(value).iter().map(|item| unescape(item, is_text)).collect()
+    } else {
+        vec![unescape(value, is_text)]
+    };
+
+    Ok(property)
+}
+
+fn split_values(value: &str) -> Vec<String> {
+    /* Splits on commas not escaped with a backslash */
+    let mut values = vec![String::new()];
+    let mut escaped = false;
+    for character in value.chars() {
+        if character == ',' && !escaped {
+            values.push(String::new());
+            continue;
+        }
+        escaped = character == '\\' && !escaped;
+        values.last_mut().unwrap().push(character);
+    }
+    values
+}
+
+fn unescape(value: &str, is_text: bool) -> String {
+    if !is_text {
+        return value.to_string();
+    }
+
+    let mut result = String::with_capacity(value.len());
+    let mut characters = value.chars();
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            result.push(character);
+            continue;
+        }
+        match characters.next() {
+            Some('n') | Some('N') => result.push('\n'),
+            Some(escaped) => result.push(escaped),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn write_component(component: &ICalComponent, text: &mut String) {
+    write_line(&format!("BEGIN:{}", component.name), text);
+    for property in component.properties.iter() {
+        write_line(&format_property(property), text);
+    }
+    for child in component.components.iter() {
+        write_component(child, text);
+    }
+    write_line(&format!("END:{}", component.name), text);
+}
+
+fn format_property(property: &ICalProperty) -> String {
+    let mut line = property.name.clone();
+
+    for parameter in property.parameters.iter() {
+        let values: Vec<String> = parameter
+            .values
+            .iter()
+            .map(|value| {
+                if value.contains([':', ';', ',']) {
+                    format!("\"{}\"", value)
+                } else {
+                    value.clone()
+                }
+            })
+            .collect();
+        line.push_str(&format!(";{}={}", parameter.name, values.join(",")));
+    }
+
+    let value_type = property.get_value_type();
+    let values: Vec<String> = property
+        .values
+        .iter()
+        .map(|value| match value_type {
+            ICalValueType::Text => escape(value),
+            _ => value.clone(),
+        })
+        .collect();
+    line.push(':');
+    line.push_str(&values.join(","));
+    line
+}
+
+fn write_line(line: &str, text: &mut String) {
+    /* Folds the line in chunks of at most 75 octets, without splitting multi-byte characters */
+    let mut length = 0;
+    for character in line.chars() {
+        if length + character.len_utf8() > MAX_LINE_LENGTH {
+            text.push_str("\r\n ");
+            length = 1;
+        }
+        text.push(character);
+        length += character.len_utf8();
+    }
+    text.push_str("\r\n");
+}
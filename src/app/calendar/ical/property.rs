@@ -0,0 +1,93 @@
+use super::{value_type::ICalValueType, ICalError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ICalParameter {
+    pub name: String,
+    pub values: Vec<String>,
+}
+
+impl ICalParameter {
+    pub fn new(name: &str, value: &str) -> Self {
+        Self {
+            name: name.to_uppercase(),
+            values: vec![value.to_string()],
+        }
+    }
+
+    pub fn with_values(name: &str, values: Vec<String>) -> Self {
+        Self {
+            name: name.to_uppercase(),
+            values,
+        }
+    }
+
+    pub fn get_value(&self) -> &str {
+        self.values.first().map(|value| value.as_str()).unwrap_or("")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ICalProperty {
+    pub name: String,
+    pub parameters: Vec<ICalParameter>,
+    /* Values in their textual form, unescaped. Only multi-valued properties (CATEGORIES, EXDATE...) have more than one */
+    pub values: Vec<String>,
+}
+
+impl ICalProperty {
+    pub fn new(name: &str, value: &str) -> Self {
+        Self {
+            name: name.to_uppercase(),
+            parameters: Vec::new(),
+            values: vec![value.to_string()],
+        }
+    }
+
+    pub fn with_values(name: &str, values: Vec<String>) -> Self {
+        Self {
+            name: name.to_uppercase(),
+            parameters: Vec::new(),
+            values,
+        }
+    }
+
+    pub fn with_parameter(mut self, name: &str, value: &str) -> Self {
+        self.set_parameter(name, value);
+        self
+    }
+
+    pub fn set_parameter(&mut self, name: &str, value: &str) {
+        self.remove_parameter(name);
+        self.parameters.push(ICalParameter::new(name, value));
+    }
+
+    pub fn remove_parameter(&mut self, name: &str) {
+        self.parameters
+            .retain(|parameter| !parameter.name.eq_ignore_ascii_case(name));
+    }
+
+    pub fn get_parameter(&self, name: &str) -> Option<&ICalParameter> {
+        self.parameters
+            .iter()
+            .find(|parameter| parameter.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn get_parameter_value(&self, name: &str) -> Option<&str> {
+        self.get_parameter(name).map(|parameter| parameter.get_value())
+    }
+
+    pub fn get_value(&self) -> &str {
+        self.values.first().map(|value| value.as_str()).unwrap_or("")
+    }
+
+    pub fn get_value_type(&self) -> ICalValueType {
+        ICalValueType::for_property(self)
+    }
+
+    pub fn invalid_value(&self) -> ICalError {
+        ICalError::InvalidValue {
+            property: self.name.clone(),
+            value: self.values.join(","),
+        }
+    }
+}
@@ -0,0 +1,111 @@
+/*
+Value data types of RFC 5545 section 3.3.
+
+Each property has a default value type, which can be overridden with the VALUE parameter:
+
+       DTSTART;VALUE=DATE:19971102
+
+The xCal representation (RFC 6321) uses the lowercase type name as the element wrapping each value, so the type is
+needed to convert from and to it. GEO and REQUEST-STATUS are structured values with their own xCal elements.
+*/
+
+use super::property::ICalProperty;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ICalValueType {
+    Binary,
+    Boolean,
+    CalAddress,
+    Date,
+    DateTime,
+    Duration,
+    Float,
+    Geo,
+    Integer,
+    Period,
+    Recur,
+    RequestStatus,
+    Text,
+    Time,
+    Uri,
+    UtcOffset,
+    Unknown,
+}
+
+impl ICalValueType {
+    pub fn for_property(property: &ICalProperty) -> Self {
+        match property.get_parameter_value("VALUE") {
+            Some(value) => Self::from_name(value).unwrap_or(ICalValueType::Unknown),
+            None => Self::default_for(&property.name),
+        }
+    }
+
+    pub fn default_for(property_name: &str) -> Self {
+        match property_name.to_uppercase().as_str() {
+            "DTSTAMP" | "DTSTART" | "DTEND" | "DUE" | "RECURRENCE-ID" | "EXDATE" | "RDATE" | "CREATED"
+            | "LAST-MODIFIED" | "COMPLETED" | "ACKNOWLEDGED" => ICalValueType::DateTime,
+            "DURATION" | "TRIGGER" | "REFRESH-INTERVAL" => ICalValueType::Duration,
+            "PRIORITY" | "SEQUENCE" | "PERCENT-COMPLETE" | "REPEAT" => ICalValueType::Integer,
+            "RRULE" | "EXRULE" => ICalValueType::Recur,
+            "ATTENDEE" | "ORGANIZER" => ICalValueType::CalAddress,
+            "URL" | "ATTACH" | "TZURL" | "SOURCE" => ICalValueType::Uri,
+            "TZOFFSETFROM" | "TZOFFSETTO" => ICalValueType::UtcOffset,
+            "FREEBUSY" => ICalValueType::Period,
+            "GEO" => ICalValueType::Geo,
+            "REQUEST-STATUS" => ICalValueType::RequestStatus,
+            name if name.starts_with("X-") => ICalValueType::Unknown,
+            _ => ICalValueType::Text,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_uppercase().as_str() {
+            "BINARY" => Some(ICalValueType::Binary),
+            "BOOLEAN" => Some(ICalValueType::Boolean),
+            "CAL-ADDRESS" => Some(ICalValueType::CalAddress),
+            "DATE" => Some(ICalValueType::Date),
+            "DATE-TIME" => Some(ICalValueType::DateTime),
+            "DURATION" => Some(ICalValueType::Duration),
+            "FLOAT" => Some(ICalValueType::Float),
+            "INTEGER" => Some(ICalValueType::Integer),
+            "PERIOD" => Some(ICalValueType::Period),
+            "RECUR" => Some(ICalValueType::Recur),
+            "TEXT" => Some(ICalValueType::Text),
+            "TIME" => Some(ICalValueType::Time),
+            "URI" => Some(ICalValueType::Uri),
+            "UTC-OFFSET" => Some(ICalValueType::UtcOffset),
+            "UNKNOWN" => Some(ICalValueType::Unknown),
+            _ => None,
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            ICalValueType::Binary => "BINARY",
+            ICalValueType::Boolean => "BOOLEAN",
+            ICalValueType::CalAddress => "CAL-ADDRESS",
+            ICalValueType::Date => "DATE",
+            ICalValueType::DateTime => "DATE-TIME",
+            ICalValueType::Duration => "DURATION",
+            ICalValueType::Float => "FLOAT",
+            ICalValueType::Geo => "GEO",
+            ICalValueType::Integer => "INTEGER",
+            ICalValueType::Period => "PERIOD",
+            ICalValueType::Recur => "RECUR",
+            ICalValueType::RequestStatus => "REQUEST-STATUS",
+            ICalValueType::Text => "TEXT",
+            ICalValueType::Time => "TIME",
+            ICalValueType::Uri => "URI",
+            ICalValueType::UtcOffset => "UTC-OFFSET",
+            ICalValueType::Unknown => "UNKNOWN",
+        }
+    }
+
+    pub fn get_xcal_name(&self) -> String {
+        self.get_name().to_lowercase()
+    }
+
+    pub fn is_text(&self) -> bool {
+        matches!(self, ICalValueType::Text)
+    }
+}
@@ -0,0 +1,96 @@
+/*
+Conversions between the textual form of iCalendar values and the types used by the models.
+
+       date-time  = date "T" time ;As specified in the DATE and TIME value definitions
+       date       = date-value       ; YYYYMMDD
+       time       = time-hour time-minute time-second [time-utc]
+
+   Example:  19980118T230000 (floating), 19980119T070000Z (UTC), 19970714 (date)
+
+       dur-value  = (["+"] / "-") "P" (dur-date / dur-time / dur-week)
+       dur-date   = dur-day [dur-time]
+       dur-time   = "T" (dur-hour / dur-minute / dur-second)
+       dur-week   = 1*DIGIT "W"
+
+   Example:  P15DT5H0M20S (15 days, 5 hours and 20 seconds), -PT15M (15 minutes before), P7W (7 weeks)
+*/
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+use super::{property::ICalProperty, value_type::ICalValueType, ICalError};
+use crate::app::calendar::models::calendar_types::duration::Duration as ICalDuration;
+
+const DATE_TIME_FORMAT: &str = "%Y%m%dT%H%M%S";
+const DATE_FORMAT: &str = "%Y%m%d";
+
+pub fn parse_date_time(value: &str) -> Option<DateTime<Utc>> {
+    /* Floating and TZID qualified times are interpreted as UTC, dates as midnight UTC */
+    let value = value.trim();
+    let naive = if value.len() == 8 {
+        NaiveDate::parse_from_str(value, DATE_FORMAT)
+            .ok()?
+            .and_hms_opt(0, 0, 0)?
+    } else {
+        NaiveDateTime::parse_from_str(value.trim_end_matches('Z'), DATE_TIME_FORMAT).ok()?
+    };
+    Some(Utc.from_utc_datetime(&naive))
+}
+
+pub fn format_date_time(date: &DateTime<Utc>) -> String {
+    format!("{}Z", date.format(DATE_TIME_FORMAT))
+}
+
+pub fn format_local_date_time(date: &NaiveDateTime) -> String {
+    /* Floating or TZID qualified value, without the UTC designator */
+    date.format(DATE_TIME_FORMAT).to_string()
+}
+
+pub fn format_date(date: &DateTime<Utc>) -> String {
+    date.format(DATE_FORMAT).to_string()
+}
+
+pub fn parse_token(value: &str) -> Option<String> {
+    /* iana-token / x-name, uppercased as their comparison is case-insensitive */
+    let value = value.trim();
+    if value.is_empty() || !value.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return None;
+    }
+    Some(value.to_ascii_uppercase())
+}
+
+pub fn is_date(value: &str) -> bool {
+    value.trim().len() == 8
+}
+
+pub fn is_date_property(property: &ICalProperty) -> bool {
+    /* DATE value, by its VALUE parameter or its form */
+    match property.get_parameter_value("VALUE") {
+        Some(_) => property.get_value_type() == ICalValueType::Date,
+        None => is_date(property.get_value()),
+    }
+}
+
+pub fn parse_duration(value: &str) -> Option<Duration> {
+    /* Exact duration, with nominal days taken as 24 hours as every date is in UTC */
+    ICalDuration::from_ical(value).map(|duration| duration.to_exact())
+}
+
+pub fn format_duration(duration: &Duration) -> String {
+    ICalDuration::from_exact(duration).to_ical()
+}
+
+pub fn get_date_time(property: &ICalProperty) -> Result<DateTime<Utc>, ICalError> {
+    parse_date_time(property.get_value()).ok_or_else(|| property.invalid_value())
+}
+
+pub fn get_integer<T: std::str::FromStr>(property: &ICalProperty) -> Result<T, ICalError> {
+    property
+        .get_value()
+        .trim()
+        .parse::<T>()
+        .map_err(|_| property.invalid_value())
+}
+
+pub fn get_duration(property: &ICalProperty) -> Result<Duration, ICalError> {
+    parse_duration(property.get_value()).ok_or_else(|| property.invalid_value())
+}
@@ -0,0 +1,354 @@
+/*
+xCal: The XML Format for iCalendar (RFC 6321).
+
+Example:
+
+   <?xml version="1.0" encoding="utf-8"?>
+   <icalendar xmlns="urn:ietf:params:xml:ns:icalendar-2.0">
+     <vcalendar>
+       <properties>
+         <prodid><text>-//Example Inc.//Example Calendar//EN</text></prodid>
+         <version><text>2.0</text></version>
+       </properties>
+       <components>
+         <vevent>
+           <properties>
+             <dtstamp><date-time>2008-02-05T19:12:24Z</date-time></dtstamp>
+             <dtstart>
+               <parameters><tzid><text>Europe/Madrid</text></tzid></parameters>
+               <date-time>2008-10-06T10:00:00</date-time>
+             </dtstart>
+             <summary><text>Planning meeting</text></summary>
+             <uid><text>4088E990AD89CB3DBB484909</text></uid>
+           </properties>
+         </vevent>
+       </components>
+     </vcalendar>
+   </icalendar>
+
+Components and properties map one to one with the iCalendar tree, using lowercase names. Each value is wrapped in an
+element named after its value type, and the VALUE parameter is never written: the type element replaces it.
+*/
+
+use crate::architecture::xml::XmlElement;
+
+use super::{
+    component::ICalComponent,
+    property::{ICalParameter, ICalProperty},
+    value_type::ICalValueType,
+    ICalError,
+};
+
+pub const XCAL_NAMESPACE: &str = "urn:ietf:params:xml:ns:icalendar-2.0";
+pub const XCAL_CONTENT_TYPE: &str = "application/calendar+xml";
+
+const RECUR_PARTS: [&str; 14] = [
+    "FREQ",
+    "UNTIL",
+    "COUNT",
+    "INTERVAL",
+    "BYSECOND",
+    "BYMINUTE",
+    "BYHOUR",
+    "BYDAY",
+    "BYMONTHDAY",
+    "BYYEARDAY",
+    "BYWEEKNO",
+    "BYMONTH",
+    "BYSETPOS",
+    "WKST",
+];
+
+pub fn write(calendar: &ICalComponent) -> String {
+    to_xml(calendar).to_xml()
+}
+
+pub fn to_xml(calendar: &ICalComponent) -> XmlElement {
+    /* Returns the <icalendar> root element for the given VCALENDAR */
+    XmlElement::new(XCAL_NAMESPACE, "icalendar").with_child(component_to_xml(calendar))
+}
+
+pub fn parse(xml: &str) -> Result<ICalComponent, ICalError> {
+    let root = XmlElement::parse(xml).map_err(|error| ICalError::InvalidFormat(error.to_string()))?;
+    from_xml(&root)
+}
+
+pub fn from_xml(root: &XmlElement) -> Result<ICalComponent, ICalError> {
+    /* Returns the VCALENDAR inside the given <icalendar> root element */
+    if !root.is(XCAL_NAMESPACE, "icalendar") {
+        return Err(ICalError::InvalidFormat("root element must be icalendar".to_string()));
+    }
+
+    let calendar = root
+        .child(XCAL_NAMESPACE, "vcalendar")
+        .ok_or_else(|| ICalError::InvalidComponent("VCALENDAR".to_string()))?;
+
+    component_from_xml(calendar)
+}
+
+fn component_to_xml(component: &ICalComponent) -> XmlElement {
+    let mut element = XmlElement::new(XCAL_NAMESPACE, &component.name.to_lowercase());
+
+    if !component.properties.is_empty() {
+        let properties = component.properties.iter().map(property_to_xml).collect();
+        element = element.with_child(XmlElement::new(XCAL_NAMESPACE, "properties").with_children(properties));
+    }
+
+    if !component.components.is_empty() {
+        let components = component.components.iter().map(component_to_xml).collect();
+        element = element.with_child(XmlElement::new(XCAL_NAMESPACE, "components").with_children(components));
+    }
+
+    element
+}
+
+fn property_to_xml(property: &ICalProperty) -> XmlElement {
+    let mut element = XmlElement::new(XCAL_NAMESPACE, &property.name.to_lowercase());
+
+    let parameters: Vec<XmlElement> = property
+        .parameters
+        .iter()
+        .filter(|parameter| parameter.name != "VALUE")
+        .map(parameter_to_xml)
+        .collect();
+    if !parameters.is_empty() {
+        element = element.with_child(XmlElement::new(XCAL_NAMESPACE, "parameters").with_children(parameters));
+    }
+
+    let value_type = property.get_value_type();
+    for value in property.values.iter() {
+        element = element.with_child(value_to_xml(value_type, value));
+    }
+
+    element
+}
+
+fn parameter_to_xml(parameter: &ICalParameter) -> XmlElement {
+    let value_type = get_parameter_type(&parameter.name);
+    let values = parameter
+        .values
+        .iter()
+        .map(|value| value_to_xml(value_type, value))
+        .collect();
+
+    XmlElement::new(XCAL_NAMESPACE, &parameter.name.to_lowercase()).with_children(values)
+}
+
+fn value_to_xml(value_type: ICalValueType, value: &str) -> XmlElement {
+    match value_type {
+        ICalValueType::Geo => {
+            let (latitude, longitude) = value.split_once(';').unwrap_or((value, ""));
+            XmlElement::new(XCAL_NAMESPACE, "geo")
+                .with_child(XmlElement::new(XCAL_NAMESPACE, "latitude").with_text(latitude))
+                .with_child(XmlElement::new(XCAL_NAMESPACE, "longitude").with_text(longitude))
+        }
+        ICalValueType::RequestStatus => {
+            let mut parts = value.splitn(3, ';');
+            let mut element = XmlElement::new(XCAL_NAMESPACE, "request-status");
+            for name in ["code", "description", "data"] {
+                if let Some(part) = parts.next() {
+                    element = element.with_child(XmlElement::new(XCAL_NAMESPACE, name).with_text(part));
+                }
+            }
+            element
+        }
+        ICalValueType::Period => {
+            let (start, end) = value.split_once('/').unwrap_or((value, ""));
+            let end_name = if end.starts_with('P') || end.starts_with("-P") || end.starts_with("+P") {
+                "duration"
+            } else {
+                "end"
+            };
+            XmlElement::new(XCAL_NAMESPACE, "period")
+                .with_child(XmlElement::new(XCAL_NAMESPACE, "start").with_text(&date_time_to_xcal(start)))
+                .with_child(XmlElement::new(XCAL_NAMESPACE, end_name).with_text(&date_time_to_xcal(end)))
+        }
+        ICalValueType::Recur => {
+            let mut element = XmlElement::new(XCAL_NAMESPACE, "recur");
+            for part in value.split(';').filter(|part| !part.is_empty()) {
+                let (name, part_values) = part.split_once('=').unwrap_or((part, ""));
+                for part_value in part_values.split(',') {
+                    let text = if name.eq_ignore_ascii_case("UNTIL") {
+                        date_time_to_xcal(part_value)
+                    } else {
+                        part_value.to_string()
+                    };
+                    element =
+                        element.with_child(XmlElement::new(XCAL_NAMESPACE, &name.to_lowercase()).with_text(&text));
+                }
+            }
+            element
+        }
+        ICalValueType::Date | ICalValueType::DateTime => {
+            XmlElement::new(XCAL_NAMESPACE, &value_type.get_xcal_name()).with_text(&date_time_to_xcal(value))
+        }
+        ICalValueType::Time => {
+            XmlElement::new(XCAL_NAMESPACE, &value_type.get_xcal_name()).with_text(&time_to_xcal(value))
+        }
+        ICalValueType::UtcOffset => {
+            XmlElement::new(XCAL_NAMESPACE, &value_type.get_xcal_name()).with_text(&utc_offset_to_xcal(value))
+        }
+        ICalValueType::Boolean => {
+            XmlElement::new(XCAL_NAMESPACE, &value_type.get_xcal_name()).with_text(&value.to_lowercase())
+        }
+        _ => XmlElement::new(XCAL_NAMESPACE, &value_type.get_xcal_name()).with_text(value),
+    }
+}
+
+fn component_from_xml(element: &XmlElement) -> Result<ICalComponent, ICalError> {
+    let mut component = ICalComponent::new(&element.name);
+
+    if let Some(properties) = element.child(XCAL_NAMESPACE, "properties") {
+        for property in properties.children.iter() {
+            component.add_property(property_from_xml(property)?);
+        }
+    }
+
+    if let Some(components) = element.child(XCAL_NAMESPACE, "components") {
+        for child in components.children.iter() {
+            component.add_component(component_from_xml(child)?);
+        }
+    }
+
+    Ok(component)
+}
+
+fn property_from_xml(element: &XmlElement) -> Result<ICalProperty, ICalError> {
+    let mut property = ICalProperty::with_values(&element.name, Vec::new());
+
+    if let Some(parameters) = element.child(XCAL_NAMESPACE, "parameters") {
+        for parameter in parameters.children.iter() {
+            let values = parameter.children.iter().map(value_from_xml).collect();
+            property
+                .parameters
+                .push(ICalParameter::with_values(&parameter.name, values));
+        }
+    }
+
+    let values: Vec<&XmlElement> = element
+        .children
+        .iter()
+        .filter(|child| !child.is(XCAL_NAMESPACE, "parameters"))
+        .collect();
+
+    let first_value = values
+        .first()
+        .ok_or_else(|| ICalError::MissingProperty(property.name.clone()))?;
+
+    let value_type = get_xcal_value_type(first_value)
+        .ok_or_else(|| ICalError::InvalidFormat(format!("unknown value type {}", first_value.name)))?;
+    if value_type != ICalValueType::default_for(&property.name) && value_type != ICalValueType::Unknown {
+        property.set_parameter("VALUE", value_type.get_name());
+    }
+
+    property.values = values.into_iter().map(value_from_xml).collect();
+    Ok(property)
+}
+
+fn value_from_xml(element: &XmlElement) -> String {
+    let child_text = |name: &str| {
+        element
+            .child(XCAL_NAMESPACE, name)
+            .map(|child| child.text.clone())
+            .unwrap_or_default()
+    };
+
+    match get_xcal_value_type(element) {
+        Some(ICalValueType::Geo) => format!("{};{}", child_text("latitude"), child_text("longitude")),
+        Some(ICalValueType::RequestStatus) => element
+            .children
+            .iter()
+            .map(|child| child.text.clone())
+            .collect::<Vec<String>>()
+            .join(";"),
+        Some(ICalValueType::Period) => {
+            let end = match element.child(XCAL_NAMESPACE, "duration") {
+                Some(duration) => duration.text.clone(),
+                None => date_time_from_xcal(&child_text("end")),
+            };
+            format!("{}/{}", date_time_from_xcal(&child_text("start")), end)
+        }
+        Some(ICalValueType::Recur) => {
+            let mut parts = Vec::new();
+            for name in RECUR_PARTS {
+                let part_values: Vec<String> = element
+                    .children_named(XCAL_NAMESPACE, &name.to_lowercase())
+                    .map(|child| {
+                        if name == "UNTIL" {
+                            date_time_from_xcal(&child.text)
+                        } else {
+                            child.text.clone()
+                        }
+                    })
+                    .collect();
+                if !part_values.is_empty() {
+                    parts.push(format!("{}={}", name, part_values.join(",")));
+                }
+            }
+            parts.join(";")
+        }
+        Some(ICalValueType::Date) | Some(ICalValueType::DateTime) => date_time_from_xcal(&element.text),
+        Some(ICalValueType::Time) => element.text.replace(':', ""),
+        Some(ICalValueType::UtcOffset) => element.text.replace(':', ""),
+        Some(ICalValueType::Boolean) => element.text.to_uppercase(),
+        _ => element.text.clone(),
+    }
+}
+
+fn get_xcal_value_type(element: &XmlElement) -> Option<ICalValueType> {
+    match element.name.as_str() {
+        "geo" => Some(ICalValueType::Geo),
+        "request-status" => Some(ICalValueType::RequestStatus),
+        name => ICalValueType::from_name(name),
+    }
+}
+
+fn get_parameter_type(name: &str) -> ICalValueType {
+    match name {
+        "DELEGATED-FROM" | "DELEGATED-TO" | "MEMBER" | "SENT-BY" => ICalValueType::CalAddress,
+        "ALTREP" | "DIR" => ICalValueType::Uri,
+        "RSVP" => ICalValueType::Boolean,
+        _ => ICalValueType::Text,
+    }
+}
+
+fn date_time_to_xcal(value: &str) -> String {
+    /* 20080205T191224Z -> 2008-02-05T19:12:24Z, 20080205 -> 2008-02-05, other values are kept */
+    let parts = (value.get(0..4), value.get(4..6), value.get(6..8), value.get(8..));
+    let (Some(year), Some(month), Some(day), Some(time)) = parts else {
+        return value.to_string();
+    };
+    let mut result = format!("{}-{}-{}", year, month, day);
+    if let Some(time) = time.strip_prefix('T') {
+        result.push('T');
+        result.push_str(&time_to_xcal(time));
+    }
+    result
+}
+
+fn date_time_from_xcal(value: &str) -> String {
+    value.replace(['-', ':'], "")
+}
+
+fn time_to_xcal(value: &str) -> String {
+    /* 191224Z -> 19:12:24Z */
+    let parts = (value.get(0..2), value.get(2..4), value.get(4..6), value.get(6..));
+    let (Some(hour), Some(minute), Some(second), Some(rest)) = parts else {
+        return value.to_string();
+    };
+    format!("{}:{}:{}{}", hour, minute, second, rest)
+}
+
+fn utc_offset_to_xcal(value: &str) -> String {
+    /* +0100 -> +01:00, -013015 -> -01:30:15 */
+    let parts = (value.get(0..3), value.get(3..5), value.get(5..));
+    let (Some(hour), Some(minute), Some(second)) = parts else {
+        return value.to_string();
+    };
+    let mut result = format!("{}:{}", hour, minute);
+    if !second.is_empty() {
+        result.push(':');
+        result.push_str(second);
+    }
+    result
+}
@@ -1,2 +1,7 @@
+pub mod calendar;
+pub mod calendar_object;
 pub mod calendar_types;
+pub mod dead_property;
 pub mod event;
+pub mod journal;
+pub mod todo;
@@ -0,0 +1,155 @@
+/*
+Calendar collection (RFC 4791 section 4.2).
+
+A calendar collection contains calendar object resources, each one holding the components of a single UID. It is
+identified by its name, the last segment of its URL:
+
+       /calendars/{name}/
+       /calendars/{name}/{resource}.ics
+*/
+
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::dead_property::DeadProperties;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Calendar {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    id: Option<ObjectId>,
+
+    /* Segment of the collection URL */
+    name: String,
+
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    created_at: DateTime<Utc>,
+
+    /* Updated on every change of the collection or its resources */
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    updated_at: DateTime<Utc>,
+
+    display_name: Option<String>,
+
+    description: Option<String>,
+
+    /* Color shown by clients, as #RRGGBB or #RRGGBBAA (Apple calendar-color property) */
+    color: Option<String>,
+
+    /* VCALENDAR holding the VTIMEZONE used for floating times and date values (calendar-timezone) */
+    timezone: Option<String>,
+
+    /* Component types the resources may contain (supported-calendar-component-set) */
+    supported_components: Vec<String>,
+
+    /* Properties set by clients that the server does not interpret */
+    #[serde(default)]
+    dead_properties: DeadProperties,
+}
+
+impl Calendar {
+    /* MongoDB collection storing the calendars */
+    pub const COLLECTION: &'static str = "calendars";
+
+    /* Components stored by the server */
+    pub const DEFAULT_COMPONENTS: [&'static str; 3] = ["VEVENT", "VTODO", "VJOURNAL"];
+
+    pub fn new(name: &str) -> Self {
+        let now = Utc::now();
+        Self {
+            id: None,
+            name: name.to_string(),
+            created_at: now,
+            updated_at: now,
+            display_name: None,
+            description: None,
+            color: None,
+            timezone: None,
+            supported_components: Self::DEFAULT_COMPONENTS.iter().map(|name| name.to_string()).collect(),
+            dead_properties: DeadProperties::new(),
+        }
+    }
+
+    pub fn get_id(&self) -> Option<ObjectId> {
+        self.id
+    }
+
+    pub fn set_id(&mut self, id: ObjectId) {
+        self.id = Some(id);
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn get_updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    pub fn touch(&mut self, now: DateTime<Utc>) {
+        self.updated_at = now;
+    }
+
+    pub fn get_display_name(&self) -> Option<&str> {
+        self.display_name.as_deref()
+    }
+
+    pub fn set_display_name(&mut self, display_name: Option<String>) {
+        self.display_name = display_name;
+    }
+
+    pub fn get_description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn set_description(&mut self, description: Option<String>) {
+        self.description = description;
+    }
+
+    pub fn get_color(&self) -> Option<&str> {
+        self.color.as_deref()
+    }
+
+    pub fn set_color(&mut self, color: Option<String>) {
+        self.color = color;
+    }
+
+    pub fn get_timezone(&self) -> Option<&str> {
+        self.timezone.as_deref()
+    }
+
+    pub fn set_timezone(&mut self, timezone: Option<String>) {
+        self.timezone = timezone;
+    }
+
+    pub fn get_supported_components(&self) -> &[String] {
+        &self.supported_components
+    }
+
+    pub fn set_supported_components(&mut self, components: Vec<String>) {
+        self.supported_components = components;
+    }
+
+    pub fn supports_component(&self, name: &str) -> bool {
+        self.supported_components
+            .iter()
+            .any(|component| component.eq_ignore_ascii_case(name))
+    }
+
+    pub fn get_dead_properties(&self) -> &DeadProperties {
+        &self.dead_properties
+    }
+
+    pub fn get_dead_properties_mut(&mut self) -> &mut DeadProperties {
+        &mut self.dead_properties
+    }
+
+    pub fn get_ctag(&self) -> String {
+        /* Changes with every change of the collection (CS:getctag) */
+        format!("\"{}\"", self.updated_at.timestamp_micros())
+    }
+}
@@ -0,0 +1,431 @@
+/*
+Calendar object resource (RFC 4791 section 4.1).
+
+A resource of a calendar collection, holding a VCALENDAR with the components of a single UID: the master component
+and its overrides (components with a RECURRENCE-ID). They are all events, to-dos or journal entries, stored apart in the
+collection of their type, referencing the calendar collection and the name of the resource.
+
+The entity tag of the resource is the digest of its iCalendar text when written, so it changes with every change of its
+content.
+*/
+
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+
+use super::{
+    calendar_types::attachment::Attachment, dead_property::DeadProperties, event::Event, journal::Journal, todo::Todo,
+};
+use crate::app::calendar::ical::{component::ICalComponent, ics, ICalError};
+use crate::app::calendar::models::calendar_types::{
+    recurrence::date::Date, timezone::timezones_by_reference::apply_timezones_preference,
+};
+
+pub const CALENDAR_CONTENT_TYPE: &str = "text/calendar; charset=utf-8";
+
+/* Preconditions of RFC 4791 section 5.3.2.1 failed by the content of a resource */
+#[derive(Debug)]
+pub enum CalendarObjectError {
+    /* Not valid iCalendar data (CALDAV:valid-calendar-data) */
+    InvalidData(ICalError),
+
+    /* Component type the calendar does not store (CALDAV:supported-calendar-component) */
+    UnsupportedComponent(String),
+
+    /* Not a single UID or component type, or a METHOD property (CALDAV:valid-calendar-object-resource) */
+    InvalidResource(String),
+
+    /* A recurrence with more instances than the server expands (CALDAV:max-instances) */
+    TooManyInstances,
+}
+
+#[derive(Debug, Clone)]
+pub struct CalendarObject {
+    /* Segment of the resource URL */
+    name: String,
+
+    /* Components of the resource, of a single type */
+    events: Vec<Event>,
+    todos: Vec<Todo>,
+    journals: Vec<Journal>,
+}
+
+/* First component of a resource, holding its entity tag and dead properties */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentRevision {
+    /* MongoDB collection storing the components of the resource */
+    pub collection: &'static str,
+    pub id: Option<ObjectId>,
+    pub version: u32,
+    pub etag: Option<String>,
+}
+
+impl CalendarObject {
+    pub fn new(name: &str, mut events: Vec<Event>) -> Self {
+        /* The master component first */
+        events.sort_by_key(Event::is_override);
+        Self {
+            name: name.to_string(),
+            events,
+            todos: Vec::new(),
+            journals: Vec::new(),
+        }
+    }
+
+    pub fn from_todos(name: &str, mut todos: Vec<Todo>) -> Self {
+        todos.sort_by_key(Todo::is_override);
+        Self {
+            name: name.to_string(),
+            events: Vec::new(),
+            todos,
+            journals: Vec::new(),
+        }
+    }
+
+    pub fn from_journals(name: &str, mut journals: Vec<Journal>) -> Self {
+        journals.sort_by_key(Journal::is_override);
+        Self {
+            name: name.to_string(),
+            events: Vec::new(),
+            todos: Vec::new(),
+            journals,
+        }
+    }
+
+    pub fn from_ical(name: &str, calendar: &ICalComponent, supported: &[String]) -> Result<Self, CalendarObjectError> {
+        /*
+        RFC 4791 section 4.1: Calendar components in a calendar collection that have different UID property values
+        MUST be stored in separate calendar object resources. [...] Calendar object resources contained in calendar
+        collections MUST NOT specify the iCalendar METHOD property.
+        */
+        if !calendar.is("VCALENDAR") {
+            return Err(CalendarObjectError::InvalidData(ICalError::InvalidComponent(
+                calendar.name.clone(),
+            )));
+        }
+        if calendar.get_property("METHOD").is_some() {
+            return Err(CalendarObjectError::InvalidResource("METHOD property".to_string()));
+        }
+        if let Some(component) = calendar
+            .components
+            .iter()
+            .find(|component| !component.is("VTIMEZONE") && !supported.iter().any(|name| component.is(name)))
+        {
+            return Err(CalendarObjectError::UnsupportedComponent(component.name.clone()));
+        }
+
+        let events = Event::from_calendar(calendar).map_err(CalendarObjectError::InvalidData)?;
+        let todos = Todo::from_calendar(calendar).map_err(CalendarObjectError::InvalidData)?;
+        let journals = Journal::from_calendar(calendar).map_err(CalendarObjectError::InvalidData)?;
+        let object = match (events.is_empty(), todos.is_empty(), journals.is_empty()) {
+            (_, true, true) => Self::new(name, events),
+            (true, false, true) => Self::from_todos(name, todos),
+            (true, true, false) => Self::from_journals(name, journals),
+            _ => {
+                return Err(CalendarObjectError::InvalidResource(
+                    "components of several types".to_string(),
+                ))
+            }
+        };
+
+        /* UID and RECURRENCE-ID of each component */
+        let identifiers: Vec<(&str, Option<DateTime<Utc>>)> = object
+            .events
+            .iter()
+            .map(|event| (event.get_uid(), event.get_recurrence_id()))
+            .chain(
+                object
+                    .todos
+                    .iter()
+                    .map(|todo| (todo.get_uid(), todo.get_recurrence_id())),
+            )
+            .chain(
+                object
+                    .journals
+                    .iter()
+                    .map(|journal| (journal.get_uid(), journal.get_recurrence_id())),
+            )
+            .collect();
+        let Some((uid, _)) = identifiers.first() else {
+            return Err(CalendarObjectError::InvalidResource("no component".to_string()));
+        };
+        if identifiers.iter().any(|(other, _)| other != uid) {
+            return Err(CalendarObjectError::InvalidResource("multiple UIDs".to_string()));
+        }
+        for (index, (_, recurrence_id)) in identifiers.iter().enumerate() {
+            if identifiers[..index].iter().any(|(_, other)| other == recurrence_id) {
+                return Err(CalendarObjectError::InvalidResource(
+                    "duplicate RECURRENCE-ID".to_string(),
+                ));
+            }
+        }
+
+        /* Recurrences with a start: events, and to-dos and journal entries with a DTSTART */
+        let recurrences = object
+            .events
+            .iter()
+            .filter_map(|event| Some((event.get_recurrence()?, event.get_start())))
+            .chain(
+                object
+                    .todos
+                    .iter()
+                    .filter_map(|todo| Some((todo.get_recurrence()?, todo.get_start()?))),
+            )
+            .chain(
+                object
+                    .journals
+                    .iter()
+                    .filter_map(|journal| Some((journal.get_recurrence()?, journal.get_start()?))),
+            );
+        for (recurrence, start) in recurrences {
+            if recurrence.exceeds_max_instances(Date::new(start)) {
+                return Err(CalendarObjectError::TooManyInstances);
+            }
+        }
+
+        Ok(object)
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_events(&self) -> &[Event] {
+        &self.events
+    }
+
+    pub fn get_events_mut(&mut self) -> &mut [Event] {
+        &mut self.events
+    }
+
+    pub fn add_event(&mut self, event: Event) {
+        if event.is_override() {
+            self.events.push(event);
+        } else {
+            self.events.insert(0, event);
+        }
+    }
+
+    pub fn into_events(self) -> Vec<Event> {
+        self.events
+    }
+
+    pub fn get_todos(&self) -> &[Todo] {
+        &self.todos
+    }
+
+    pub fn get_todos_mut(&mut self) -> &mut [Todo] {
+        &mut self.todos
+    }
+
+    pub fn add_todo(&mut self, todo: Todo) {
+        if todo.is_override() {
+            self.todos.push(todo);
+        } else {
+            self.todos.insert(0, todo);
+        }
+    }
+
+    pub fn get_journals(&self) -> &[Journal] {
+        &self.journals
+    }
+
+    pub fn get_journals_mut(&mut self) -> &mut [Journal] {
+        &mut self.journals
+    }
+
+    pub fn add_journal(&mut self, journal: Journal) {
+        if journal.is_override() {
+            self.journals.push(journal);
+        } else {
+            self.journals.insert(0, journal);
+        }
+    }
+
+    pub fn get_uid(&self) -> Option<&str> {
+        self.events
+            .first()
+            .map(Event::get_uid)
+            .or_else(|| self.todos.first().map(Todo::get_uid))
+            .or_else(|| self.journals.first().map(Journal::get_uid))
+    }
+
+    pub fn get_calendar_id(&self) -> Option<ObjectId> {
+        self.events
+            .first()
+            .map(Event::get_calendar_id)
+            .or_else(|| self.todos.first().map(Todo::get_calendar_id))
+            .or_else(|| self.journals.first().map(Journal::get_calendar_id))
+            .flatten()
+    }
+
+    pub fn get_revision(&self) -> Option<ComponentRevision> {
+        let revision = |collection, id, version, etag: Option<&str>| ComponentRevision {
+            collection,
+            id,
+            version,
+            etag: etag.map(str::to_string),
+        };
+        match (self.events.first(), self.todos.first(), self.journals.first()) {
+            (Some(event), _, _) => Some(revision(
+                Event::COLLECTION,
+                event.get_id(),
+                event.get_version(),
+                event.get_etag(),
+            )),
+            (None, Some(todo), _) => Some(revision(
+                Todo::COLLECTION,
+                todo.get_id(),
+                todo.get_version(),
+                todo.get_etag(),
+            )),
+            (None, None, Some(journal)) => Some(revision(
+                Journal::COLLECTION,
+                journal.get_id(),
+                journal.get_version(),
+                journal.get_etag(),
+            )),
+            (None, None, None) => None,
+        }
+    }
+
+    pub fn get_master(&self) -> Option<&Event> {
+        self.events.iter().find(|event| !event.is_override())
+    }
+
+    pub fn get_overridden(&self) -> Vec<DateTime<Utc>> {
+        /* Instances of the master replaced by an override */
+        self.events
+            .iter()
+            .filter_map(Event::get_recurrence_id)
+            .chain(self.todos.iter().filter_map(Todo::get_recurrence_id))
+            .chain(self.journals.iter().filter_map(Journal::get_recurrence_id))
+            .collect()
+    }
+
+    pub fn update_next_alarms(&mut self, after: DateTime<Utc>) {
+        let overridden = self.get_overridden();
+        for event in self.events.iter_mut() {
+            event.update_next_alarm(&overridden, after);
+        }
+        for todo in self.todos.iter_mut() {
+            todo.update_next_alarm(&overridden, after);
+        }
+    }
+
+    pub fn get_attachments(&self) -> Vec<&[Attachment]> {
+        /* Inline attachments of each component */
+        self.events
+            .iter()
+            .map(Event::get_attachments)
+            .chain(self.todos.iter().map(Todo::get_attachments))
+            .chain(self.journals.iter().map(Journal::get_attachments))
+            .collect()
+    }
+
+    pub fn get_attachments_mut(&mut self) -> Vec<&mut [Attachment]> {
+        self.events
+            .iter_mut()
+            .map(Event::get_attachments_mut)
+            .chain(self.todos.iter_mut().map(Todo::get_attachments_mut))
+            .chain(self.journals.iter_mut().map(Journal::get_attachments_mut))
+            .collect()
+    }
+
+    pub fn get_dead_properties(&self) -> Option<&DeadProperties> {
+        /* Held by the first component */
+        self.events
+            .first()
+            .map(Event::get_dead_properties)
+            .or_else(|| self.todos.first().map(Todo::get_dead_properties))
+            .or_else(|| self.journals.first().map(Journal::get_dead_properties))
+    }
+
+    pub fn get_dead_properties_mut(&mut self) -> Option<&mut DeadProperties> {
+        if let Some(event) = self.events.first_mut() {
+            return Some(event.get_dead_properties_mut());
+        }
+        if let Some(todo) = self.todos.first_mut() {
+            return Some(todo.get_dead_properties_mut());
+        }
+        self.journals.first_mut().map(Journal::get_dead_properties_mut)
+    }
+
+    pub fn set_dead_properties(&mut self, properties: DeadProperties) {
+        if let Some(dead_properties) = self.get_dead_properties_mut() {
+            *dead_properties = properties;
+        }
+    }
+
+    pub fn get_created_at(&self) -> Option<DateTime<Utc>> {
+        self.events
+            .iter()
+            .map(Event::get_created_at)
+            .chain(self.todos.iter().map(Todo::get_created_at))
+            .chain(self.journals.iter().map(Journal::get_created_at))
+            .min()
+    }
+
+    pub fn get_last_modified(&self) -> Option<DateTime<Utc>> {
+        self.events
+            .iter()
+            .map(Event::get_updated_at)
+            .chain(self.todos.iter().map(Todo::get_updated_at))
+            .chain(self.journals.iter().map(Journal::get_updated_at))
+            .max()
+    }
+
+    pub fn get_content_type(&self) -> String {
+        let component = match (self.todos.is_empty(), self.journals.is_empty()) {
+            (false, _) => "vtodo",
+            (true, false) => "vjournal",
+            (true, true) => "vevent",
+        };
+        format!("{}; component={}", CALENDAR_CONTENT_TYPE, component)
+    }
+
+    pub fn to_ical(&self) -> ICalComponent {
+        ICalComponent::calendar(
+            self.events
+                .iter()
+                .map(Event::to_ical)
+                .chain(self.todos.iter().map(Todo::to_ical))
+                .chain(self.journals.iter().map(Journal::to_ical))
+                .collect(),
+        )
+    }
+
+    pub fn to_ics(&self, include_timezones: bool) -> String {
+        let mut calendar = self.to_ical();
+        apply_timezones_preference(&mut calendar, include_timezones);
+        ics::write(&calendar)
+    }
+
+    pub fn get_etag(&self) -> String {
+        /* Stored on write, as inline attachments are moved out of the components */
+        match self.get_revision().and_then(|revision| revision.etag) {
+            Some(etag) => etag,
+            None => self.compute_etag(),
+        }
+    }
+
+    pub fn update_etag(&mut self) {
+        let etag = self.compute_etag();
+        for event in self.events.iter_mut() {
+            event.set_etag(&etag);
+        }
+        for todo in self.todos.iter_mut() {
+            todo.set_etag(&etag);
+        }
+        for journal in self.journals.iter_mut() {
+            journal.set_etag(&etag);
+        }
+    }
+
+    fn compute_etag(&self) -> String {
+        /* Strong entity tag, quoted */
+        let digest = Sha256::digest(ics::write(&self.to_ical()).as_bytes());
+        let hex: String = digest[..16].iter().map(|byte| format!("{:02x}", byte)).collect();
+        format!("\"{}\"", hex)
+    }
+}
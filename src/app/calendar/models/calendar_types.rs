@@ -1,5 +1,18 @@
+pub mod alarm;
+pub mod attachment;
 pub mod attendee;
+pub mod cal_address;
+pub mod duration;
 pub mod event_classification;
 pub mod event_status;
 pub mod geolocation;
+pub mod journal_status;
+pub mod organizer;
+pub mod other_property;
+pub mod priority;
 pub mod recurrence;
+pub mod relation;
+pub mod sequence;
+pub mod timezone;
+pub mod todo_status;
+pub mod uri;
@@ -0,0 +1,308 @@
+/*
+BEGIN:VALARM
+ACTION: ; (Required, MUST NOT occur more than once)
+TRIGGER: ; (Required, MUST NOT occur more than once)
+DURATION: ; (Optional, MUST occur with REPEAT)
+REPEAT: ; (Optional, MUST occur with DURATION)
+ATTACH: ; (Optional, MUST NOT occur more than once for AUDIO, MAY occur more than once for EMAIL)
+DESCRIPTION: ; (Required for DISPLAY and EMAIL)
+SUMMARY: ; (Required for EMAIL)
+ATTENDEE: ; (Required for EMAIL, MAY occur more than once)
+END:VALARM
+
+The TRIGGER is either relative to the start or the end of its event or to-do:
+
+       TRIGGER:-PT15M                   ; 15 minutes before the start
+       TRIGGER;RELATED=END:PT5M         ; 5 minutes after the end
+       TRIGGER;VALUE=DATE-TIME:19980101T050000Z
+
+After the trigger, the alarm is repeated REPEAT times, every DURATION.
+
+RFC 9074 adds a UID to alarms and the ACKNOWLEDGED date, set by the client dismissing the alarm. Triggers up to that
+date are acknowledged and not fired again by any device. Snoozing acknowledges the alarm and adds a snooze alarm with
+an absolute trigger, related to the snoozed alarm:
+
+       BEGIN:VALARM
+       UID:DE7B5C34-83FF-47FE-BE9E-FF41AE6DD097
+       TRIGGER;VALUE=DATE-TIME:20210302T101500Z
+       RELATED-TO;RELTYPE=SNOOZE:8297C37D-BA2D-4476-91AE-C1EAA364F8E1
+       ACTION:DISPLAY
+       DESCRIPTION:Breakfast meeting with executive team.
+       END:VALARM
+*/
+
+mod alarm_ical;
+
+use bson::oid::ObjectId;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::architecture::mongodb::optional_chrono_datetime_as_bson_datetime;
+
+use super::{
+    attendee::Attendee,
+    recurrence::{date::Date, Recurrence},
+};
+
+/* Days searched for the next alarm */
+const NEXT_ALARM_DAYS: i64 = 36525;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AlarmAction {
+    Audio,
+    Display,
+    Email,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AlarmTriggerRelation {
+    Start,
+    End,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum AlarmTrigger {
+    /* Offset in seconds from the start or end of the event, negative before it */
+    Relative { offset: i64, related: AlarmTriggerRelation },
+    Absolute(#[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")] DateTime<Utc>),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Alarm {
+    #[serde(default)]
+    pub uid: Option<String>,
+
+    pub action: AlarmAction,
+
+    pub trigger: AlarmTrigger,
+
+    /* Number of additional repetitions after the trigger */
+    pub repeat: u32,
+
+    /* Seconds between repetitions (DURATION) */
+    pub repeat_interval: Option<i64>,
+
+    pub description: Option<String>,
+
+    pub summary: Option<String>,
+
+    /* Recipients of EMAIL alarms */
+    pub attendees: Vec<Attendee>,
+
+    /* Sound for AUDIO alarms, or attachments of EMAIL alarms */
+    pub attachments: Vec<String>,
+
+    /* Last time the alarm was acknowledged */
+    #[serde(default, with = "optional_chrono_datetime_as_bson_datetime")]
+    pub acknowledged: Option<DateTime<Utc>>,
+
+    /* UID of the alarm snoozed by this one */
+    #[serde(default)]
+    pub snoozed_alarm_uid: Option<String>,
+}
+
+/* An alarm of an event or to-do, firing for one of its instances */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlarmTime {
+    pub fire_at: DateTime<Utc>,
+
+    /* Start of the instance the alarm belongs to */
+    pub instance_start: DateTime<Utc>,
+
+    /* Position of the alarm in the alarms of the event or to-do */
+    pub alarm_index: usize,
+}
+
+impl AlarmAction {
+    pub fn from_ical(value: &str) -> Option<Self> {
+        match value.to_uppercase().as_str() {
+            "AUDIO" => Some(AlarmAction::Audio),
+            "DISPLAY" => Some(AlarmAction::Display),
+            "EMAIL" => Some(AlarmAction::Email),
+            _ => None,
+        }
+    }
+
+    pub fn to_ical(&self) -> &'static str {
+        match self {
+            AlarmAction::Audio => "AUDIO",
+            AlarmAction::Display => "DISPLAY",
+            AlarmAction::Email => "EMAIL",
+        }
+    }
+}
+
+impl AlarmTriggerRelation {
+    pub fn from_ical(value: &str) -> Option<Self> {
+        match value.to_uppercase().as_str() {
+            "START" => Some(AlarmTriggerRelation::Start),
+            "END" => Some(AlarmTriggerRelation::End),
+            _ => None,
+        }
+    }
+
+    pub fn to_ical(&self) -> &'static str {
+        match self {
+            AlarmTriggerRelation::Start => "START",
+            AlarmTriggerRelation::End => "END",
+        }
+    }
+}
+
+impl Alarm {
+    pub fn is_acknowledged(&self, fire_at: DateTime<Utc>) -> bool {
+        self.acknowledged.is_some_and(|acknowledged| fire_at <= acknowledged)
+    }
+
+    pub fn is_snooze(&self) -> bool {
+        self.snoozed_alarm_uid.is_some()
+    }
+
+    pub fn acknowledge(&mut self, at: DateTime<Utc>) {
+        self.acknowledged = Some(at);
+    }
+
+    pub fn snooze(&mut self, until: DateTime<Utc>, now: DateTime<Utc>) -> Alarm {
+        /*
+        Acknowledges the alarm and returns the snooze alarm firing at `until`.
+        The snooze of a snooze alarm relates to the original alarm, so only one snooze is kept per alarm.
+        */
+        self.acknowledge(now);
+        let snoozed_alarm_uid = match &self.snoozed_alarm_uid {
+            Some(uid) => uid.clone(),
+            None => self.uid.get_or_insert_with(|| ObjectId::new().to_hex()).clone(),
+        };
+
+        Alarm {
+            uid: Some(ObjectId::new().to_hex()),
+            trigger: AlarmTrigger::Absolute(until),
+            repeat: 0,
+            repeat_interval: None,
+            acknowledged: None,
+            snoozed_alarm_uid: Some(snoozed_alarm_uid),
+            ..self.clone()
+        }
+    }
+
+    pub fn get_trigger_times(&self, start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>) -> Vec<DateTime<Utc>> {
+        /*
+        Returns the trigger followed by its repetitions, for an instance with the given start and end.
+        Relative triggers need the date they are related to, so none is returned without it.
+        */
+        let first = match &self.trigger {
+            AlarmTrigger::Absolute(date) => Some(*date),
+            AlarmTrigger::Relative { offset, related } => {
+                let related_date = match related {
+                    AlarmTriggerRelation::Start => start,
+                    AlarmTriggerRelation::End => end,
+                };
+                related_date.map(|date| date + Duration::seconds(*offset))
+            }
+        };
+
+        let first = match first {
+            Some(first) => first,
+            None => return Vec::new(),
+        };
+        let interval = self.repeat_interval.unwrap_or(0);
+        (0..=self.repeat as i64)
+            .map(|repetition| first + Duration::seconds(repetition * interval))
+            .collect()
+    }
+
+    fn get_max_offset(&self) -> i64 {
+        /* Longest time between a relative trigger date and its last repetition */
+        match &self.trigger {
+            AlarmTrigger::Absolute(_) => 0,
+            AlarmTrigger::Relative { offset, .. } => {
+                offset.abs() + self.repeat as i64 * self.repeat_interval.unwrap_or(0).abs()
+            }
+        }
+    }
+}
+
+pub fn calculate_alarm_times(
+    alarms: &[Alarm],
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    recurrence: Option<&Recurrence>,
+    overridden: &[DateTime<Utc>],
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Vec<AlarmTime> {
+    /*
+    Returns the alarms firing between `from` and `to` for every instance of an event or to-do, sorted by time.
+    Absolute triggers fire once, for the first instance, and acknowledged triggers are skipped. The overridden
+    instances are skipped too: their alarms are those of the overriding component.
+    */
+    let mut times = Vec::new();
+    if alarms.is_empty() {
+        return times;
+    }
+
+    let duration = match (start, end) {
+        (Some(start), Some(end)) => (end - start).max(Duration::zero()),
+        _ => Duration::zero(),
+    };
+    let instance_starts = match (recurrence, start) {
+        (Some(recurrence), Some(start)) => {
+            /* Instances whose alarms could fire between `from` and `to` */
+            let max_offset = Duration::seconds(alarms.iter().map(Alarm::get_max_offset).max().unwrap_or(0));
+            recurrence
+                .calculate_ocurrences_between(
+                    Date::new(start),
+                    Date::new(from - max_offset - duration),
+                    Date::new(to + max_offset),
+                )
+                .iter()
+                .map(Date::get_date_time)
+                .filter(|instance_start| !overridden.contains(instance_start))
+                .map(Some)
+                .collect()
+        }
+        _ => vec![start],
+    };
+
+    for (alarm_index, alarm) in alarms.iter().enumerate() {
+        let alarm_instances = match alarm.trigger {
+            AlarmTrigger::Absolute(_) => vec![start],
+            AlarmTrigger::Relative { .. } => instance_starts.clone(),
+        };
+        for instance_start in alarm_instances {
+            let instance_end = end
+                .and(instance_start.map(|instance_start| instance_start + duration))
+                .or(end);
+            for fire_at in alarm.get_trigger_times(instance_start, instance_end) {
+                if fire_at >= from && fire_at <= to && !alarm.is_acknowledged(fire_at) {
+                    times.push(AlarmTime {
+                        fire_at,
+                        instance_start: instance_start.or(start).unwrap_or(fire_at),
+                        alarm_index,
+                    });
+                }
+            }
+        }
+    }
+
+    times.sort_by_key(|time| (time.fire_at, time.alarm_index));
+    times
+}
+
+pub fn calculate_next_alarm_time(
+    alarms: &[Alarm],
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    recurrence: Option<&Recurrence>,
+    overridden: &[DateTime<Utc>],
+    after: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    /*
+    Returns the first alarm firing after `after` (exclusive), up to NEXT_ALARM_DAYS later. The instances of a recurrence
+    are expanded once, the expansion stopping at MAX_INSTANCES.
+    */
+    let to = after + Duration::days(NEXT_ALARM_DAYS);
+    calculate_alarm_times(alarms, start, end, recurrence, overridden, after, to)
+        .into_iter()
+        .find(|time| time.fire_at > after)
+        .map(|time| time.fire_at)
+}
@@ -0,0 +1,156 @@
+use chrono::Duration;
+
+use crate::app::calendar::{
+    ical::{
+        component::ICalComponent,
+        property::ICalProperty,
+        value_type::ICalValueType,
+        values::{format_date_time, format_duration, get_date_time, get_duration, get_integer},
+        ICalError,
+    },
+    models::calendar_types::{
+        attendee::Attendee,
+        relation::{Relation, RelationType},
+    },
+};
+
+use super::{Alarm, AlarmAction, AlarmTrigger, AlarmTriggerRelation};
+
+impl Alarm {
+    pub fn from_ical(component: &ICalComponent) -> Result<Self, ICalError> {
+        if !component.is("VALARM") {
+            return Err(ICalError::InvalidComponent(component.name.clone()));
+        }
+
+        let action_property = component.get_required_property("ACTION")?;
+        let action =
+            AlarmAction::from_ical(action_property.get_value()).ok_or_else(|| action_property.invalid_value())?;
+
+        let trigger_property = component.get_required_property("TRIGGER")?;
+        let trigger = match ICalValueType::for_property(trigger_property) {
+            ICalValueType::DateTime => AlarmTrigger::Absolute(get_date_time(trigger_property)?),
+            ICalValueType::Duration => {
+                let related = match trigger_property.get_parameter_value("RELATED") {
+                    Some(value) => AlarmTriggerRelation::from_ical(value).ok_or_else(|| ICalError::InvalidValue {
+                        property: "TRIGGER;RELATED".to_string(),
+                        value: value.to_string(),
+                    })?,
+                    None => AlarmTriggerRelation::Start,
+                };
+                AlarmTrigger::Relative {
+                    offset: get_duration(trigger_property)?.num_seconds(),
+                    related,
+                }
+            }
+            _ => return Err(trigger_property.invalid_value()),
+        };
+
+        /* DURATION and REPEAT MUST both occur if one of them does */
+        let (repeat, repeat_interval) = match (component.get_property("REPEAT"), component.get_property("DURATION")) {
+            (Some(repeat), Some(duration)) => {
+                (get_integer::<u32>(repeat)?, Some(get_duration(duration)?.num_seconds()))
+            }
+            (None, None) => (0, None),
+            (Some(_), None) => return Err(ICalError::MissingProperty("DURATION".to_string())),
+            (None, Some(_)) => return Err(ICalError::MissingProperty("REPEAT".to_string())),
+        };
+
+        let attendees = component
+            .get_properties("ATTENDEE")
+            .map(Attendee::from_ical)
+            .collect::<Result<Vec<Attendee>, ICalError>>()?;
+
+        let acknowledged = match component.get_property("ACKNOWLEDGED") {
+            Some(property) => Some(get_date_time(property)?),
+            None => None,
+        };
+
+        /* Other relationships of alarms are not defined, so they are ignored */
+        let mut snoozed_alarm_uid = None;
+        for property in component.get_properties("RELATED-TO") {
+            let relation = Relation::from_ical(property)?;
+            if relation.relation_type == RelationType::Snooze {
+                snoozed_alarm_uid = Some(relation.uid);
+            }
+        }
+
+        let alarm = Self {
+            uid: component.get_text("UID"),
+            action,
+            trigger,
+            repeat,
+            repeat_interval,
+            description: component.get_text("DESCRIPTION"),
+            summary: component.get_text("SUMMARY"),
+            attendees,
+            attachments: component.get_texts("ATTACH").unwrap_or_default(),
+            acknowledged,
+            snoozed_alarm_uid,
+        };
+        alarm.validate()?;
+        Ok(alarm)
+    }
+
+    fn validate(&self) -> Result<(), ICalError> {
+        /* Properties required by each action */
+        let missing = |name: &str| Err(ICalError::MissingProperty(name.to_string()));
+        match self.action {
+            AlarmAction::Audio if self.attachments.len() > 1 => Err(ICalError::InvalidComponent(
+                "VALARM with more than one ATTACH".to_string(),
+            )),
+            AlarmAction::Display if self.description.is_none() => missing("DESCRIPTION"),
+            AlarmAction::Email if self.description.is_none() => missing("DESCRIPTION"),
+            AlarmAction::Email if self.summary.is_none() => missing("SUMMARY"),
+            AlarmAction::Email if self.attendees.is_empty() => missing("ATTENDEE"),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn to_ical(&self) -> ICalComponent {
+        let mut component = ICalComponent::new("VALARM");
+
+        component.add_text("UID", &self.uid);
+        component.add_property(ICalProperty::new("ACTION", self.action.to_ical()));
+        component.add_property(match &self.trigger {
+            AlarmTrigger::Absolute(date) => {
+                ICalProperty::new("TRIGGER", &format_date_time(date)).with_parameter("VALUE", "DATE-TIME")
+            }
+            AlarmTrigger::Relative { offset, related } => {
+                let property = ICalProperty::new("TRIGGER", &format_duration(&Duration::seconds(*offset)));
+                match related {
+                    AlarmTriggerRelation::Start => property,
+                    AlarmTriggerRelation::End => property.with_parameter("RELATED", related.to_ical()),
+                }
+            }
+        });
+
+        if let Some(repeat_interval) = self.repeat_interval {
+            component.add_property(ICalProperty::new(
+                "DURATION",
+                &format_duration(&Duration::seconds(repeat_interval)),
+            ));
+            component.add_property(ICalProperty::new("REPEAT", &self.repeat.to_string()));
+        }
+
+        component.add_text("DESCRIPTION", &self.description);
+        component.add_text("SUMMARY", &self.summary);
+        for attendee in self.attendees.iter() {
+            component.add_property(attendee.to_ical());
+        }
+        for attachment in self.attachments.iter() {
+            component.add_property(ICalProperty::new("ATTACH", attachment));
+        }
+        if let Some(acknowledged) = &self.acknowledged {
+            component.add_property(ICalProperty::new("ACKNOWLEDGED", &format_date_time(acknowledged)));
+        }
+        if let Some(uid) = &self.snoozed_alarm_uid {
+            let relation = Relation {
+                uid: uid.clone(),
+                relation_type: RelationType::Snooze,
+            };
+            component.add_property(relation.to_ical());
+        }
+
+        component
+    }
+}
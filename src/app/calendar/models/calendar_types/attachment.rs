@@ -0,0 +1,169 @@
+/*
+Format Definition:  This property is defined by the following
+      notation:
+
+       attach     = "ATTACH" attachparam ( ":" uri ) /
+                    (
+                      ";" "ENCODING" "=" "BASE64"
+                      ";" "VALUE" "=" "BINARY"
+                      ":" binary
+                    )
+                    CRLF
+
+       attachparam = *(
+                   ;
+                   ; The following is OPTIONAL for a URI value,
+                   ; RECOMMENDED for a BINARY value,
+                   ; and MUST NOT occur more than once.
+                   ;
+                   (";" fmttypeparam) /
+                   ;
+                   ; The following is OPTIONAL,
+                   ; and MAY occur more than once.
+                   ;
+                   (";" other-param)
+                   ;
+                   )
+
+   Example:  The following are examples of this property:
+
+       ATTACH:CID:jsmith.part3.960817T083000.xyzMail@example.com
+
+       ATTACH;FMTTYPE=application/postscript:ftp://example.com/pub/reports/r-960812.ps
+
+The FILENAME parameter of RFC 8607 gives the name suggested to save the attachment. Managed attachments, uploaded to
+the server apart from their component, are URIs identified by the MANAGED-ID parameter, with their SIZE in octets:
+
+       ATTACH;MANAGED-ID=97S;FMTTYPE=text/plain;SIZE=234;FILENAME=f.txt:https://attachments.example.com/abcd.txt
+
+Inline binary attachments are kept in the blob store, not in the document of their component, so they must be loaded
+before writing the component back.
+*/
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use crate::app::calendar::ical::{property::ICalProperty, value_type::ICalValueType, ICalError};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum AttachmentContent {
+    Uri(String),
+
+    /* Inline binary value */
+    Binary(Vec<u8>),
+
+    /* Inline binary value kept in the blob store */
+    Stored { file_id: ObjectId, size: u64 },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Attachment {
+    pub content: AttachmentContent,
+
+    /* Media type (FMTTYPE) */
+    pub format_type: Option<String>,
+
+    pub filename: Option<String>,
+
+    /* Identifier of a managed attachment (MANAGED-ID) */
+    #[serde(default)]
+    pub managed_id: Option<String>,
+
+    /* Size in octets of a URI attachment (SIZE) */
+    #[serde(default)]
+    pub size: Option<u64>,
+}
+
+impl Attachment {
+    pub fn from_uri(uri: &str) -> Self {
+        Self {
+            content: AttachmentContent::Uri(uri.to_string()),
+            format_type: None,
+            filename: None,
+            managed_id: None,
+            size: None,
+        }
+    }
+
+    pub fn is_managed(&self) -> bool {
+        self.managed_id.is_some()
+    }
+
+    pub fn get_uri(&self) -> Option<&str> {
+        match &self.content {
+            AttachmentContent::Uri(uri) => Some(uri),
+            _ => None,
+        }
+    }
+
+    pub fn get_size(&self) -> Option<u64> {
+        match &self.content {
+            AttachmentContent::Uri(_) => self.size,
+            AttachmentContent::Binary(data) => Some(data.len() as u64),
+            AttachmentContent::Stored { size, .. } => Some(*size),
+        }
+    }
+
+    pub fn from_ical(property: &ICalProperty) -> Result<Self, ICalError> {
+        let content = match property.get_value_type() {
+            ICalValueType::Binary => {
+                /* Binary values MUST be BASE64 encoded */
+                let encoding = property.get_parameter_value("ENCODING").unwrap_or_default();
+                if !encoding.eq_ignore_ascii_case("BASE64") {
+                    return Err(ICalError::InvalidValue {
+                        property: format!("{};ENCODING", property.name),
+                        value: encoding.to_string(),
+                    });
+                }
+                let data = STANDARD
+                    .decode(property.get_value().trim())
+                    .map_err(|_| property.invalid_value())?;
+                AttachmentContent::Binary(data)
+            }
+            ICalValueType::Uri => AttachmentContent::Uri(property.get_value().to_string()),
+            _ => return Err(property.invalid_value()),
+        };
+
+        let size = match property.get_parameter_value("SIZE") {
+            Some(value) => Some(value.parse::<u64>().map_err(|_| ICalError::InvalidValue {
+                property: format!("{};SIZE", property.name),
+                value: value.to_string(),
+            })?),
+            None => None,
+        };
+
+        let parameter = |name: &str| property.get_parameter_value(name).map(str::to_string);
+        Ok(Self {
+            content,
+            format_type: parameter("FMTTYPE"),
+            filename: parameter("FILENAME"),
+            managed_id: parameter("MANAGED-ID"),
+            size,
+        })
+    }
+
+    pub fn to_ical(&self) -> Option<ICalProperty> {
+        /* None for attachments in the blob store, which are written once loaded */
+        let mut property = match &self.content {
+            AttachmentContent::Uri(uri) => ICalProperty::new("ATTACH", uri),
+            AttachmentContent::Binary(data) => ICalProperty::new("ATTACH", &STANDARD.encode(data))
+                .with_parameter("ENCODING", "BASE64")
+                .with_parameter("VALUE", "BINARY"),
+            AttachmentContent::Stored { .. } => return None,
+        };
+        if let Some(format_type) = &self.format_type {
+            property.set_parameter("FMTTYPE", format_type);
+        }
+        if let Some(filename) = &self.filename {
+            property.set_parameter("FILENAME", filename);
+        }
+        if let Some(managed_id) = &self.managed_id {
+            property.set_parameter("MANAGED-ID", managed_id);
+        }
+        if let (AttachmentContent::Uri(_), Some(size)) = (&self.content, self.size) {
+            property.set_parameter("SIZE", &size.to_string());
+        }
+        Some(property)
+    }
+}
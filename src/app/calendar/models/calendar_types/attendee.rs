@@ -12,46 +12,450 @@ INDIVIDUAL: Indicates that the attendee is an individual person.
 GROUP: Indicates that the attendee is a group of people, such as a mailing list.
 RESOURCE: Indicates that the attendee is a physical resource, such as a meeting room or equipment.
 ROOM: Indicates that the attendee is a room or other physical space.
+UNKNOWN: Indicates that the type of the attendee is not known.
 And here are the possible values for the ROLE parameter:
 
 CHAIR: Indicates that the attendee is the chairperson or organizer of the event.
 REQ-PARTICIPANT: Indicates that the attendee is a required participant in the event.
 OPT-PARTICIPANT: Indicates that the attendee is an optional participant in the event.
 NON-PARTICIPANT: Indicates that the attendee is not expected to participate in the event, but is being kept informed.
+
+The three parameters also accept x-name and iana-token values, which applications MUST treat the same way as they
+would NEEDS-ACTION (PARTSTAT), UNKNOWN (CUTYPE) and REQ-PARTICIPANT (ROLE) when they don't recognize them.
+
+The other parameters of the ATTENDEE property are used by the scheduling flows (RFC 5546 and RFC 6638):
+
+RSVP: Whether a reply is expected from the attendee.
+DELEGATED-TO and DELEGATED-FROM: The calendar users the participation was delegated to, or received from.
+An attendee with PARTSTAT=DELEGATED MUST have DELEGATED-TO.
+MEMBER: The groups the attendee is a member of, for the expansion of group attendees.
+SENT-BY: The calendar user acting on behalf of the attendee.
+DIR: Reference to the directory entry of the attendee.
+SCHEDULE-AGENT: Who handles the scheduling messages: SERVER (default), CLIENT or NONE.
+SCHEDULE-STATUS: Status codes of the last delivery of a scheduling message to the attendee.
+
+       ATTENDEE;ROLE=REQ-PARTICIPANT;PARTSTAT=DELEGATED;DELEGATED-TO="mailto:jdoe@example.com":mailto:jsmith@example.com
+       ATTENDEE;DELEGATED-FROM="mailto:jsmith@example.com";RSVP=TRUE:mailto:jdoe@example.com
+
+Other parameters, like X-names, are kept and written back as received.
 */
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+use super::cal_address::{format_cal_address, get_cal_address, get_cal_address_parameter, parse_cal_address};
+use crate::app::calendar::ical::{
+    property::{ICalParameter, ICalProperty},
+    values::parse_token,
+    ICalError,
+};
+
+const KNOWN_PARAMETERS: [&str; 13] = [
+    "CN",
+    "PARTSTAT",
+    "CUTYPE",
+    "ROLE",
+    "RSVP",
+    "DELEGATED-TO",
+    "DELEGATED-FROM",
+    "MEMBER",
+    "SENT-BY",
+    "DIR",
+    "LANGUAGE",
+    "SCHEDULE-AGENT",
+    "SCHEDULE-STATUS",
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub enum AttendeeStatus {
     Accepted,
     Declined,
     Tentative,
     Delegated,
     NeedsAction,
+
+    /* iana-token or x-name, uppercased */
+    Other(String),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub enum AttendeeType {
     Individual,
     Group,
     Resource,
     Room,
+    Unknown,
+
+    /* iana-token or x-name, uppercased */
+    Other(String),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub enum AttendeeRole {
     Chair,
     ReqParticipant,
     OptParticipant,
     NonParticipant,
+
+    /* iana-token or x-name, uppercased */
+    Other(String),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScheduleAgent {
+    #[default]
+    Server,
+    Client,
+    None,
+}
+
+/* Parameter not interpreted by the server */
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct OtherParameter {
+    pub name: String,
+    pub values: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Attendee {
     pub name: String,
     pub email: String,
     pub status: AttendeeStatus,
     pub attendee_type: AttendeeType,
     pub attendee_role: AttendeeRole,
+
+    #[serde(default)]
+    pub rsvp: bool,
+
+    /* Emails of the calendar users the participation was delegated to */
+    #[serde(default)]
+    pub delegated_to: Vec<String>,
+
+    /* Emails of the calendar users that delegated their participation */
+    #[serde(default)]
+    pub delegated_from: Vec<String>,
+
+    /* Emails of the groups the attendee is a member of */
+    #[serde(default)]
+    pub member_of: Vec<String>,
+
+    #[serde(default)]
+    pub sent_by: Option<String>,
+
+    #[serde(default)]
+    pub directory: Option<String>,
+
+    #[serde(default)]
+    pub language: Option<String>,
+
+    #[serde(default)]
+    pub schedule_agent: ScheduleAgent,
+
+    #[serde(default)]
+    pub schedule_status: Vec<String>,
+
+    #[serde(default)]
+    pub other_parameters: Vec<OtherParameter>,
+}
+
+impl AttendeeStatus {
+    pub fn from_ical(value: &str) -> Option<Self> {
+        let value = parse_token(value)?;
+        Some(match value.as_str() {
+            "ACCEPTED" => AttendeeStatus::Accepted,
+            "DECLINED" => AttendeeStatus::Declined,
+            "TENTATIVE" => AttendeeStatus::Tentative,
+            "DELEGATED" => AttendeeStatus::Delegated,
+            "NEEDS-ACTION" => AttendeeStatus::NeedsAction,
+            _ => AttendeeStatus::Other(value),
+        })
+    }
+
+    pub fn to_ical(&self) -> &str {
+        match self {
+            AttendeeStatus::Accepted => "ACCEPTED",
+            AttendeeStatus::Declined => "DECLINED",
+            AttendeeStatus::Tentative => "TENTATIVE",
+            AttendeeStatus::Delegated => "DELEGATED",
+            AttendeeStatus::NeedsAction => "NEEDS-ACTION",
+            AttendeeStatus::Other(value) => value,
+        }
+    }
+
+    pub fn get_effective(&self) -> Self {
+        match self {
+            AttendeeStatus::Other(_) => AttendeeStatus::NeedsAction,
+            status => status.clone(),
+        }
+    }
+}
+
+impl AttendeeType {
+    pub fn from_ical(value: &str) -> Option<Self> {
+        let value = parse_token(value)?;
+        Some(match value.as_str() {
+            "INDIVIDUAL" => AttendeeType::Individual,
+            "GROUP" => AttendeeType::Group,
+            "RESOURCE" => AttendeeType::Resource,
+            "ROOM" => AttendeeType::Room,
+            "UNKNOWN" => AttendeeType::Unknown,
+            _ => AttendeeType::Other(value),
+        })
+    }
+
+    pub fn to_ical(&self) -> &str {
+        match self {
+            AttendeeType::Individual => "INDIVIDUAL",
+            AttendeeType::Group => "GROUP",
+            AttendeeType::Resource => "RESOURCE",
+            AttendeeType::Room => "ROOM",
+            AttendeeType::Unknown => "UNKNOWN",
+            AttendeeType::Other(value) => value,
+        }
+    }
+
+    pub fn get_effective(&self) -> Self {
+        match self {
+            AttendeeType::Other(_) => AttendeeType::Unknown,
+            attendee_type => attendee_type.clone(),
+        }
+    }
+}
+
+impl AttendeeRole {
+    pub fn from_ical(value: &str) -> Option<Self> {
+        let value = parse_token(value)?;
+        Some(match value.as_str() {
+            "CHAIR" => AttendeeRole::Chair,
+            "REQ-PARTICIPANT" => AttendeeRole::ReqParticipant,
+            "OPT-PARTICIPANT" => AttendeeRole::OptParticipant,
+            "NON-PARTICIPANT" => AttendeeRole::NonParticipant,
+            _ => AttendeeRole::Other(value),
+        })
+    }
+
+    pub fn to_ical(&self) -> &str {
+        match self {
+            AttendeeRole::Chair => "CHAIR",
+            AttendeeRole::ReqParticipant => "REQ-PARTICIPANT",
+            AttendeeRole::OptParticipant => "OPT-PARTICIPANT",
+            AttendeeRole::NonParticipant => "NON-PARTICIPANT",
+            AttendeeRole::Other(value) => value,
+        }
+    }
+
+    pub fn get_effective(&self) -> Self {
+        match self {
+            AttendeeRole::Other(_) => AttendeeRole::ReqParticipant,
+            role => role.clone(),
+        }
+    }
+}
+
+impl ScheduleAgent {
+    pub fn from_ical(value: &str) -> Option<Self> {
+        match value.to_uppercase().as_str() {
+            "SERVER" => Some(ScheduleAgent::Server),
+            "CLIENT" => Some(ScheduleAgent::Client),
+            "NONE" => Some(ScheduleAgent::None),
+            _ => None,
+        }
+    }
+
+    pub fn to_ical(&self) -> &'static str {
+        match self {
+            ScheduleAgent::Server => "SERVER",
+            ScheduleAgent::Client => "CLIENT",
+            ScheduleAgent::None => "NONE",
+        }
+    }
+}
+
+impl OtherParameter {
+    pub fn from_ical(parameter: &ICalParameter) -> Self {
+        Self {
+            name: parameter.name.clone(),
+            values: parameter.values.clone(),
+        }
+    }
+
+    pub fn to_ical(&self) -> ICalParameter {
+        ICalParameter::with_values(&self.name, self.values.clone())
+    }
+}
+
+fn is_schedule_status(value: &str) -> bool {
+    /* statcode = 1*DIGIT 1*2("." 1*DIGIT) */
+    let codes: Vec<&str> = value.split('.').collect();
+    (2..=3).contains(&codes.len())
+        && codes
+            .iter()
+            .all(|code| !code.is_empty() && code.chars().all(|character| character.is_ascii_digit()))
+}
+
+impl Attendee {
+    pub fn new(email: &str) -> Self {
+        Self {
+            name: String::new(),
+            email: email.to_string(),
+            status: AttendeeStatus::NeedsAction,
+            attendee_type: AttendeeType::Individual,
+            attendee_role: AttendeeRole::ReqParticipant,
+            rsvp: false,
+            delegated_to: Vec::new(),
+            delegated_from: Vec::new(),
+            member_of: Vec::new(),
+            sent_by: None,
+            directory: None,
+            language: None,
+            schedule_agent: ScheduleAgent::Server,
+            schedule_status: Vec::new(),
+            other_parameters: Vec::new(),
+        }
+    }
+
+    pub fn is_delegated(&self) -> bool {
+        matches!(self.status, AttendeeStatus::Delegated)
+    }
+
+    pub fn delegate(&mut self, email: &str) -> Attendee {
+        /* Delegates the participation, returning the delegate that replaces this attendee */
+        self.status = AttendeeStatus::Delegated;
+        if !self.delegated_to.iter().any(|delegate| delegate == email) {
+            self.delegated_to.push(email.to_string());
+        }
+
+        Attendee {
+            attendee_role: self.attendee_role.clone(),
+            rsvp: true,
+            delegated_from: vec![self.email.clone()],
+            ..Attendee::new(email)
+        }
+    }
+
+    /* ATTENDEE;CN=John Smith;PARTSTAT=ACCEPTED;CUTYPE=INDIVIDUAL;ROLE=REQ-PARTICIPANT:mailto:jsmith@example.com */
+    pub fn from_ical(property: &ICalProperty) -> Result<Self, ICalError> {
+        let invalid_parameter = |name: &str| ICalError::InvalidValue {
+            property: format!("{};{}", property.name, name),
+            value: property.get_parameter_value(name).unwrap_or_default().to_string(),
+        };
+
+        let status = match property.get_parameter_value("PARTSTAT") {
+            Some(value) => AttendeeStatus::from_ical(value).ok_or_else(|| invalid_parameter("PARTSTAT"))?,
+            None => AttendeeStatus::NeedsAction,
+        };
+        let attendee_type = match property.get_parameter_value("CUTYPE") {
+            Some(value) => AttendeeType::from_ical(value).ok_or_else(|| invalid_parameter("CUTYPE"))?,
+            None => AttendeeType::Individual,
+        };
+        let attendee_role = match property.get_parameter_value("ROLE") {
+            Some(value) => AttendeeRole::from_ical(value).ok_or_else(|| invalid_parameter("ROLE"))?,
+            None => AttendeeRole::ReqParticipant,
+        };
+
+        let rsvp = match property.get_parameter_value("RSVP") {
+            Some(value) if value.eq_ignore_ascii_case("TRUE") => true,
+            Some(value) if value.eq_ignore_ascii_case("FALSE") => false,
+            Some(_) => return Err(invalid_parameter("RSVP")),
+            None => false,
+        };
+        let schedule_agent = match property.get_parameter_value("SCHEDULE-AGENT") {
+            Some(value) => ScheduleAgent::from_ical(value).ok_or_else(|| invalid_parameter("SCHEDULE-AGENT"))?,
+            None => ScheduleAgent::Server,
+        };
+        let schedule_status = property
+            .get_parameter("SCHEDULE-STATUS")
+            .map(|parameter| parameter.values.clone())
+            .unwrap_or_default();
+        if !schedule_status.iter().all(|status| is_schedule_status(status)) {
+            return Err(invalid_parameter("SCHEDULE-STATUS"));
+        }
+
+        let addresses = |name: &str| -> Result<Vec<String>, ICalError> {
+            match property.get_parameter(name) {
+                Some(parameter) => parameter
+                    .values
+                    .iter()
+                    .map(|value| parse_cal_address(value).ok_or_else(|| invalid_parameter(name)))
+                    .collect(),
+                None => Ok(Vec::new()),
+            }
+        };
+        let delegated_to = addresses("DELEGATED-TO")?;
+        if matches!(status, AttendeeStatus::Delegated) && delegated_to.is_empty() {
+            return Err(ICalError::MissingProperty(format!("{};DELEGATED-TO", property.name)));
+        }
+
+        let parameter = |name: &str| property.get_parameter_value(name).map(str::to_string);
+        Ok(Self {
+            name: property.get_parameter_value("CN").unwrap_or_default().to_string(),
+            email: get_cal_address(property)?,
+            status,
+            attendee_type,
+            attendee_role,
+            rsvp,
+            delegated_to,
+            delegated_from: addresses("DELEGATED-FROM")?,
+            member_of: addresses("MEMBER")?,
+            sent_by: get_cal_address_parameter(property, "SENT-BY")?,
+            directory: parameter("DIR"),
+            language: parameter("LANGUAGE"),
+            schedule_agent,
+            schedule_status,
+            other_parameters: property
+                .parameters
+                .iter()
+                .filter(|parameter| !KNOWN_PARAMETERS.contains(&parameter.name.as_str()))
+                .map(OtherParameter::from_ical)
+                .collect(),
+        })
+    }
+
+    pub fn to_ical(&self) -> ICalProperty {
+        let mut property = ICalProperty::new("ATTENDEE", &format_cal_address(&self.email));
+        if !self.name.is_empty() {
+            property.set_parameter("CN", &self.name);
+        }
+        property.set_parameter("PARTSTAT", self.status.to_ical());
+        property.set_parameter("CUTYPE", self.attendee_type.to_ical());
+        property.set_parameter("ROLE", self.attendee_role.to_ical());
+        if self.rsvp {
+            property.set_parameter("RSVP", "TRUE");
+        }
+
+        let addresses = |emails: &[String]| emails.iter().map(|email| format_cal_address(email)).collect();
+        for (name, emails) in [
+            ("DELEGATED-TO", &self.delegated_to),
+            ("DELEGATED-FROM", &self.delegated_from),
+            ("MEMBER", &self.member_of),
+        ] {
+            if !emails.is_empty() {
+                property
+                    .parameters
+                    .push(ICalParameter::with_values(name, addresses(emails)));
+            }
+        }
+
+        if let Some(sent_by) = &self.sent_by {
+            property.set_parameter("SENT-BY", &format_cal_address(sent_by));
+        }
+        if let Some(directory) = &self.directory {
+            property.set_parameter("DIR", directory);
+        }
+        if let Some(language) = &self.language {
+            property.set_parameter("LANGUAGE", language);
+        }
+        if self.schedule_agent != ScheduleAgent::Server {
+            property.set_parameter("SCHEDULE-AGENT", self.schedule_agent.to_ical());
+        }
+        if !self.schedule_status.is_empty() {
+            property.parameters.push(ICalParameter::with_values(
+                "SCHEDULE-STATUS",
+                self.schedule_status.clone(),
+            ));
+        }
+        for parameter in self.other_parameters.iter() {
+            property.parameters.push(parameter.to_ical());
+        }
+        property
+    }
 }
@@ -0,0 +1,69 @@
+/*
+Value Name:  CAL-ADDRESS
+
+   Purpose:  This value type is used to identify properties that contain a calendar user address.
+
+   Format Definition:  This value type is defined by the following notation:
+
+       cal-address        = uri
+
+   Description:  The value is a URI as defined by [RFC3986] or any other IANA-registered form for a URI. When used
+      to address an Internet email transport address for a calendar user, the value MUST be a mailto URI, as defined
+      by [RFC2368].
+
+   Example:
+
+       mailto:jane_doe@example.com
+
+Calendar users are stored by their email address. Addresses without scheme are accepted as plain email addresses,
+other URI schemes can not be represented.
+*/
+
+use crate::app::calendar::ical::{property::ICalProperty, ICalError};
+
+const MAILTO: &str = "mailto:";
+
+pub fn is_valid_email(email: &str) -> bool {
+    match email.rsplit_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.is_empty()
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !email.contains(|character: char| character.is_whitespace() || character.is_control())
+                && !domain.contains(['@', ',', ';', ':', '"', '<', '>'])
+        }
+        None => false,
+    }
+}
+
+pub fn parse_cal_address(address: &str) -> Option<String> {
+    /* Email address of a mailto URI */
+    let address = address.trim();
+    let email = match address.get(..MAILTO.len()) {
+        Some(scheme) if scheme.eq_ignore_ascii_case(MAILTO) => &address[MAILTO.len()..],
+        _ => address,
+    };
+    is_valid_email(email).then(|| email.to_string())
+}
+
+pub fn format_cal_address(email: &str) -> String {
+    format!("{}{}", MAILTO, email)
+}
+
+pub fn get_cal_address(property: &ICalProperty) -> Result<String, ICalError> {
+    parse_cal_address(property.get_value()).ok_or_else(|| property.invalid_value())
+}
+
+pub fn get_cal_address_parameter(property: &ICalProperty, name: &str) -> Result<Option<String>, ICalError> {
+    /* Parameters holding a calendar user address, like SENT-BY */
+    match property.get_parameter_value(name) {
+        Some(value) => parse_cal_address(value)
+            .map(Some)
+            .ok_or_else(|| ICalError::InvalidValue {
+                property: format!("{};{}", property.name, name),
+                value: value.to_string(),
+            }),
+        None => Ok(None),
+    }
+}
@@ -0,0 +1,206 @@
+/*
+Value Name:  DURATION
+
+   Purpose:  This value type is used to identify properties that contain a duration of time.
+
+   Format Definition:  This value type is defined by the following notation:
+
+       dur-value  = (["+"] / "-") "P" (dur-date / dur-time / dur-week)
+
+       dur-date   = dur-day [dur-time]
+       dur-time   = "T" (dur-hour / dur-minute / dur-second)
+       dur-week   = 1*DIGIT "W"
+       dur-hour   = 1*DIGIT "H" [dur-minute]
+       dur-minute = 1*DIGIT "M" [dur-second]
+       dur-second = 1*DIGIT "S"
+       dur-day    = 1*DIGIT "D"
+
+   Description:  If the property permits, multiple "duration" values are specified by a COMMA-separated list of
+      values. The format is based on the [ISO.8601.2004] complete representation basic format with designators for
+      the duration of time. The format can represent nominal durations (weeks and days) and accurate durations
+      (hours, minutes, and seconds). Note that unlike [ISO.8601.2004], this value type doesn't support the "Y" and
+      "M" designators to specify durations in terms of years and months.
+
+      The duration of a week or a day depends on its position in the calendar. In the case of discontinuities in the
+      time scale, such as the change from standard time to daylight time and back, the computation of the exact
+      duration requires the subtraction or addition of the change of duration of the discontinuity.
+
+   Example:  A duration of 15 days, 5 hours, and 20 seconds would be:
+
+       P15DT5H0M20S
+
+   A duration of 7 weeks would be:
+
+       P7W
+*/
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::timezone::iana_timezone::{get_iana_timezone, iana_to_local, iana_to_utc};
+
+const DAY_SECONDS: i64 = 24 * 3600;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Duration {
+    negative: bool,
+
+    /* Nominal part */
+    weeks: u32,
+    days: u32,
+
+    /* Exact part, in seconds */
+    seconds: u32,
+}
+
+impl Duration {
+    pub fn new(negative: bool, weeks: u32, days: u32, seconds: u32) -> Self {
+        Self {
+            negative,
+            weeks,
+            days,
+            seconds,
+        }
+    }
+
+    pub fn days(days: i64) -> Self {
+        Self::new(days < 0, 0, days.unsigned_abs() as u32, 0)
+    }
+
+    pub fn from_exact(duration: &chrono::Duration) -> Self {
+        /* Whole days of an exact duration are written as days, or weeks when they are a whole number of them */
+        let total = duration.num_seconds();
+        let seconds = total.unsigned_abs();
+        let week = 7 * DAY_SECONDS as u64;
+        if seconds != 0 && seconds.is_multiple_of(week) {
+            return Self::new(total < 0, (seconds / week) as u32, 0, 0);
+        }
+        Self::new(
+            total < 0,
+            0,
+            (seconds / DAY_SECONDS as u64) as u32,
+            (seconds % DAY_SECONDS as u64) as u32,
+        )
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative && !self.is_zero()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.weeks == 0 && self.days == 0 && self.seconds == 0
+    }
+
+    pub fn has_time(&self) -> bool {
+        self.seconds != 0
+    }
+
+    pub fn get_nominal_days(&self) -> i64 {
+        let days = self.weeks as i64 * 7 + self.days as i64;
+        if self.negative {
+            -days
+        } else {
+            days
+        }
+    }
+
+    pub fn get_exact_seconds(&self) -> i64 {
+        if self.negative {
+            -(self.seconds as i64)
+        } else {
+            self.seconds as i64
+        }
+    }
+
+    pub fn to_exact(&self) -> chrono::Duration {
+        /* Days taken as 24 hours, as they are in UTC */
+        chrono::Duration::seconds(self.get_nominal_days() * DAY_SECONDS + self.get_exact_seconds())
+    }
+
+    pub fn add_to(&self, date: DateTime<Utc>, tzid: Option<&str>) -> DateTime<Utc> {
+        /*
+        Nominal days are added in the local time of the timezone, so a day across a daylight saving transition lasts
+        23 or 25 hours. The exact part is added afterwards.
+        */
+        let date = match tzid.and_then(get_iana_timezone) {
+            Some(timezone) => {
+                let local = iana_to_local(&timezone, &date) + chrono::Duration::days(self.get_nominal_days());
+                iana_to_utc(&timezone, &local)
+            }
+            None => date + chrono::Duration::days(self.get_nominal_days()),
+        };
+        date + chrono::Duration::seconds(self.get_exact_seconds())
+    }
+
+    pub fn from_ical(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let (negative, value) = match value.strip_prefix('-') {
+            Some(value) => (true, value),
+            None => (false, value.strip_prefix('+').unwrap_or(value)),
+        };
+        let value = value.strip_prefix('P').or_else(|| value.strip_prefix('p'))?;
+
+        let mut duration = Self::new(negative, 0, 0, 0);
+        let mut number = String::new();
+        let mut in_time = false;
+        let mut has_units = false;
+        let mut has_time_units = false;
+        for character in value.chars() {
+            match character.to_ascii_uppercase() {
+                digit if digit.is_ascii_digit() => number.push(digit),
+                'T' if !in_time && number.is_empty() => in_time = true,
+                unit => {
+                    let amount: u32 = number.parse().ok()?;
+                    number.clear();
+                    has_units = true;
+                    has_time_units = in_time;
+                    match (unit, in_time) {
+                        ('W', false) => duration.weeks = duration.weeks.checked_add(amount)?,
+                        ('D', false) => duration.days = duration.days.checked_add(amount)?,
+                        ('H', true) => duration.seconds = duration.seconds.checked_add(amount.checked_mul(3600)?)?,
+                        ('M', true) => duration.seconds = duration.seconds.checked_add(amount.checked_mul(60)?)?,
+                        ('S', true) => duration.seconds = duration.seconds.checked_add(amount)?,
+                        _ => return None,
+                    }
+                }
+            }
+        }
+        /* A time designator must be followed by some time unit */
+        if !number.is_empty() || !has_units || (in_time && !has_time_units) {
+            return None;
+        }
+
+        Some(duration)
+    }
+
+    pub fn to_ical(&self) -> String {
+        if self.is_zero() {
+            return "PT0S".to_string();
+        }
+
+        let mut value = format!("{}P", if self.negative { "-" } else { "" });
+        if self.weeks > 0 && self.days == 0 && self.seconds == 0 {
+            value.push_str(&format!("{}W", self.weeks));
+            return value;
+        }
+
+        let days = self.weeks * 7 + self.days;
+        if days > 0 {
+            value.push_str(&format!("{}D", days));
+        }
+        if self.seconds > 0 {
+            let (hours, minutes, seconds) = (self.seconds / 3600, self.seconds % 3600 / 60, self.seconds % 60);
+            value.push('T');
+            if hours > 0 {
+                value.push_str(&format!("{}H", hours));
+            }
+            if minutes > 0 {
+                value.push_str(&format!("{}M", minutes));
+            }
+            if seconds > 0 {
+                value.push_str(&format!("{}S", seconds));
+            }
+        }
+        value
+    }
+}
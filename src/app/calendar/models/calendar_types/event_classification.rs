@@ -13,13 +13,54 @@ Format Definition:  This property is defined by the following
    Example:  The following is an example of this property:
 
        CLASS:PUBLIC
+
+   Description:  Applications MUST treat x-name and iana-token values they don't recognize the same way as they
+      would the PRIVATE value.
 */
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::app::calendar::ical::values::parse_token;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub enum EventClassification {
     Public,
     Private,
     Confidential,
+
+    /* iana-token or x-name, uppercased */
+    Other(String),
+}
+
+impl EventClassification {
+    pub fn from_ical(value: &str) -> Option<Self> {
+        let value = parse_token(value)?;
+        Some(match value.as_str() {
+            "PUBLIC" => EventClassification::Public,
+            "PRIVATE" => EventClassification::Private,
+            "CONFIDENTIAL" => EventClassification::Confidential,
+            _ => EventClassification::Other(value),
+        })
+    }
+
+    pub fn to_ical(&self) -> &str {
+        match self {
+            EventClassification::Public => "PUBLIC",
+            EventClassification::Private => "PRIVATE",
+            EventClassification::Confidential => "CONFIDENTIAL",
+            EventClassification::Other(value) => value,
+        }
+    }
+
+    pub fn get_effective(&self) -> Self {
+        /* Classification the access is granted by */
+        match self {
+            EventClassification::Other(_) => EventClassification::Private,
+            classification => classification.clone(),
+        }
+    }
+
+    pub fn is_public(&self) -> bool {
+        self.get_effective() == EventClassification::Public
+    }
 }
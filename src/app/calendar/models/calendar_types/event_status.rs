@@ -14,13 +14,54 @@ Format Definition:  This property is defined by the following
                        / "CONFIRMED"    ;Indicates event is definite.
                        / "CANCELLED"    ;Indicates event was cancelled.
        ;Status values for a "VEVENT"
+
+The values defined for other components are not valid, while other iana-token and x-name values are kept as they are.
 */
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::app::calendar::ical::values::parse_token;
+
+/* Values of statvalue-event, statvalue-todo and statvalue-jour */
+pub const STATUS_VALUES: [&str; 8] = [
+    "TENTATIVE",
+    "CONFIRMED",
+    "CANCELLED",
+    "NEEDS-ACTION",
+    "COMPLETED",
+    "IN-PROCESS",
+    "DRAFT",
+    "FINAL",
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub enum EventStatus {
     Tentative,
     Confirmed,
     Cancelled,
+
+    /* iana-token or x-name, uppercased */
+    Other(String),
+}
+
+impl EventStatus {
+    pub fn from_ical(value: &str) -> Option<Self> {
+        let value = parse_token(value)?;
+        Some(match value.as_str() {
+            "TENTATIVE" => EventStatus::Tentative,
+            "CONFIRMED" => EventStatus::Confirmed,
+            "CANCELLED" => EventStatus::Cancelled,
+            _ if STATUS_VALUES.contains(&value.as_str()) => return None,
+            _ => EventStatus::Other(value),
+        })
+    }
+
+    pub fn to_ical(&self) -> &str {
+        match self {
+            EventStatus::Tentative => "TENTATIVE",
+            EventStatus::Confirmed => "CONFIRMED",
+            EventStatus::Cancelled => "CANCELLED",
+            EventStatus::Other(value) => value,
+        }
+    }
 }
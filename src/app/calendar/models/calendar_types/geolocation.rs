@@ -12,12 +12,60 @@ Format Definition:  This property is defined by the following
    Example:  The following is an example of this property:
 
        GEO:37.386013;-122.082932
+
+   Description:  The longitude and latitude values MAY be specified up to six decimal places. The latitude is in the
+      range -90 to 90 degrees, positive north of the equator. The longitude is in the range -180 to 180 degrees,
+      positive east of the prime meridian.
 */
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(try_from = "RawGeoLocation")]
 pub struct GeoLocation {
     latitude: f64,
     longitude: f64,
 }
+
+/* Unchecked form of the stored documents */
+#[derive(Deserialize)]
+struct RawGeoLocation {
+    latitude: f64,
+    longitude: f64,
+}
+
+impl GeoLocation {
+    pub fn new(latitude: f64, longitude: f64) -> Option<Self> {
+        if (-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude) {
+            Some(Self { latitude, longitude })
+        } else {
+            None
+        }
+    }
+
+    pub fn get_latitude(&self) -> f64 {
+        self.latitude
+    }
+
+    pub fn get_longitude(&self) -> f64 {
+        self.longitude
+    }
+
+    pub fn from_ical(value: &str) -> Option<Self> {
+        let (latitude, longitude) = value.split_once(';')?;
+        Self::new(latitude.trim().parse().ok()?, longitude.trim().parse().ok()?)
+    }
+
+    pub fn to_ical(&self) -> String {
+        format!("{};{}", self.latitude, self.longitude)
+    }
+}
+
+impl TryFrom<RawGeoLocation> for GeoLocation {
+    type Error = String;
+
+    fn try_from(value: RawGeoLocation) -> Result<Self, Self::Error> {
+        Self::new(value.latitude, value.longitude)
+            .ok_or_else(|| format!("Invalid geographic position: {};{}", value.latitude, value.longitude))
+    }
+}
@@ -0,0 +1,50 @@
+/*
+Format Definition:  This property is defined by the following
+      notation:
+
+       status          = "STATUS" statparam ":" statvalue CRLF
+
+       statparam       = *(";" other-param)
+
+       statvalue-jour  = "DRAFT"        ;Indicates journal is draft.
+                       / "FINAL"        ;Indicates journal is final.
+                       / "CANCELLED"    ;Indicates journal is removed.
+      ;Status values for "VJOURNAL".
+*/
+
+use serde::{Deserialize, Serialize};
+
+use super::event_status::STATUS_VALUES;
+use crate::app::calendar::ical::values::parse_token;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum JournalStatus {
+    Draft,
+    Final,
+    Cancelled,
+
+    /* iana-token or x-name, uppercased */
+    Other(String),
+}
+
+impl JournalStatus {
+    pub fn from_ical(value: &str) -> Option<Self> {
+        let value = parse_token(value)?;
+        Some(match value.as_str() {
+            "DRAFT" => JournalStatus::Draft,
+            "FINAL" => JournalStatus::Final,
+            "CANCELLED" => JournalStatus::Cancelled,
+            _ if STATUS_VALUES.contains(&value.as_str()) => return None,
+            _ => JournalStatus::Other(value),
+        })
+    }
+
+    pub fn to_ical(&self) -> &str {
+        match self {
+            JournalStatus::Draft => "DRAFT",
+            JournalStatus::Final => "FINAL",
+            JournalStatus::Cancelled => "CANCELLED",
+            JournalStatus::Other(value) => value,
+        }
+    }
+}
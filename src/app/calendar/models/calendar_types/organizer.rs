@@ -0,0 +1,99 @@
+/*
+Format Definition:  This property is defined by the following
+      notation:
+
+       organizer  = "ORGANIZER" orgparam ":"
+                    cal-address CRLF
+
+       orgparam   = *(
+                  ;
+                  ; The following are OPTIONAL,
+                  ; but MUST NOT occur more than once.
+                  ;
+                  (";" cnparam) / (";" dirparam) / (";" sentbyparam) /
+                  (";" languageparam) /
+                  ;
+                  ; The following is OPTIONAL,
+                  ; and MAY occur more than once.
+                  ;
+                  (";" other-param)
+                  ;
+                  )
+
+   Example:  The following is an example of this property:
+
+       ORGANIZER;CN=John Smith:mailto:jsmith@example.com
+
+   The following is an example of this property with a pointer to the directory information associated with the
+   organizer:
+
+       ORGANIZER;CN=JohnSmith;DIR="ldap://example.com:6666/o=DC%20Associates,c=US???(cn=John%20Smith)"
+        :mailto:jsmith@example.com
+
+   The following is an example of this property used by another calendar user who is acting on behalf of the
+   organizer, with responses intended to be sent back to the organizer, not the other calendar user:
+
+       ORGANIZER;SENT-BY="mailto:jane_doe@example.com":mailto:jsmith@example.com
+*/
+
+use serde::{Deserialize, Serialize};
+
+use super::cal_address::{format_cal_address, get_cal_address, get_cal_address_parameter};
+use crate::app::calendar::ical::{property::ICalProperty, ICalError};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Organizer {
+    pub email: String,
+
+    /* Common name (CN) */
+    pub name: Option<String>,
+
+    /* Email of the calendar user acting on behalf of the organizer */
+    pub sent_by: Option<String>,
+
+    /* Directory entry URI (DIR) */
+    pub directory: Option<String>,
+
+    pub language: Option<String>,
+}
+
+impl Organizer {
+    pub fn new(email: &str) -> Self {
+        Self {
+            email: email.to_string(),
+            name: None,
+            sent_by: None,
+            directory: None,
+            language: None,
+        }
+    }
+
+    pub fn from_ical(property: &ICalProperty) -> Result<Self, ICalError> {
+        let parameter = |name: &str| property.get_parameter_value(name).map(str::to_string);
+
+        Ok(Self {
+            email: get_cal_address(property)?,
+            name: parameter("CN"),
+            sent_by: get_cal_address_parameter(property, "SENT-BY")?,
+            directory: parameter("DIR"),
+            language: parameter("LANGUAGE"),
+        })
+    }
+
+    pub fn to_ical(&self) -> ICalProperty {
+        let mut property = ICalProperty::new("ORGANIZER", &format_cal_address(&self.email));
+        if let Some(name) = &self.name {
+            property.set_parameter("CN", name);
+        }
+        if let Some(directory) = &self.directory {
+            property.set_parameter("DIR", directory);
+        }
+        if let Some(sent_by) = &self.sent_by {
+            property.set_parameter("SENT-BY", &format_cal_address(sent_by));
+        }
+        if let Some(language) = &self.language {
+            property.set_parameter("LANGUAGE", language);
+        }
+        property
+    }
+}
@@ -0,0 +1,107 @@
+/*
+Properties and parameters not interpreted by the server (RFC 5545 section 3.8.8): X- and IANA properties, and the
+standard ones a model does not hold, as RDATE, RELATED-TO, RESOURCES or RSTATUS. They are written back as they were
+received.
+
+The parameters of the properties a model holds are kept by property name and occurrence, but those it resolves: TZID
+and VALUE of the dates, written in UTC or in a standard timezone, and ENCODING of the attachments, stored apart.
+*/
+
+use serde::{Deserialize, Serialize};
+
+use super::attendee::OtherParameter;
+use crate::app::calendar::ical::{component::ICalComponent, property::ICalProperty};
+
+const RESOLVED_PARAMETERS: [&str; 3] = ["TZID", "VALUE", "ENCODING"];
+
+/* Properties whose model keeps their other parameters itself */
+const PARAMETERS_KEPT: [&str; 1] = ["ATTENDEE"];
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct OtherProperty {
+    pub name: String,
+    pub parameters: Vec<OtherParameter>,
+    pub values: Vec<String>,
+}
+
+/* Parameters not interpreted by the server, of the nth property with a name */
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct OtherPropertyParameters {
+    pub property: String,
+    pub index: usize,
+    pub parameters: Vec<OtherParameter>,
+}
+
+impl OtherProperty {
+    pub fn from_ical(property: &ICalProperty) -> Self {
+        Self {
+            name: property.name.clone(),
+            parameters: property.parameters.iter().map(OtherParameter::from_ical).collect(),
+            values: property.values.clone(),
+        }
+    }
+
+    pub fn to_ical(&self) -> ICalProperty {
+        let mut property = ICalProperty::with_values(&self.name, self.values.clone());
+        property.parameters = self.parameters.iter().map(OtherParameter::to_ical).collect();
+        property
+    }
+}
+
+pub fn get_other_properties(component: &ICalComponent, known: &[&str]) -> Vec<OtherProperty> {
+    component
+        .properties
+        .iter()
+        .filter(|property| !known.iter().any(|name| property.name.eq_ignore_ascii_case(name)))
+        .map(OtherProperty::from_ical)
+        .collect()
+}
+
+pub fn get_other_parameters(component: &ICalComponent, properties: &[&str]) -> Vec<OtherPropertyParameters> {
+    /* Parameters of the given properties, but the resolved ones */
+    let mut other_parameters = Vec::new();
+    for name in properties.iter().filter(|name| !PARAMETERS_KEPT.contains(name)) {
+        for (index, property) in component.get_properties(name).enumerate() {
+            let parameters: Vec<OtherParameter> = property
+                .parameters
+                .iter()
+                .filter(|parameter| !RESOLVED_PARAMETERS.contains(&parameter.name.as_str()))
+                .map(OtherParameter::from_ical)
+                .collect();
+            if !parameters.is_empty() {
+                other_parameters.push(OtherPropertyParameters {
+                    property: name.to_string(),
+                    index,
+                    parameters,
+                });
+            }
+        }
+    }
+    other_parameters
+}
+
+pub fn add_other_properties(
+    component: &mut ICalComponent,
+    properties: &[OtherProperty],
+    parameters: &[OtherPropertyParameters],
+) {
+    /* The parameters the model wrote are kept, the others are added back */
+    for other in parameters {
+        let Some(property) = component
+            .properties
+            .iter_mut()
+            .filter(|property| property.name.eq_ignore_ascii_case(&other.property))
+            .nth(other.index)
+        else {
+            continue;
+        };
+        for parameter in other.parameters.iter() {
+            if property.get_parameter(&parameter.name).is_none() {
+                property.parameters.push(parameter.to_ical());
+            }
+        }
+    }
+    for property in properties {
+        component.add_property(property.to_ical());
+    }
+}
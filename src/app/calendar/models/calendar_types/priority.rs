@@ -0,0 +1,91 @@
+/*
+Format Definition:  This property is defined by the following
+      notation:
+
+       priority   = "PRIORITY" prioparam ":" priovalue CRLF
+       ;Default is zero (i.e., undefined).
+
+       prioparam  = *(";" other-param)
+
+       priovalue   = integer       ;Must be in the range [0..9]
+          ; All other values are reserved for future use.
+
+   Description:  This priority is specified as an integer in the range 0 to 9. A value of 0 specifies an undefined
+      priority. A value of 1 is the highest priority. A value of 2 is the second highest priority. Subsequent
+      numbers specify a decreasing ordinal priority. A value of 9 is the lowest priority.
+
+      A CUA with a three-level priority scheme of "HIGH", "MEDIUM", and "LOW" is mapped into this property such that
+      a property value in the range of 1 to 4 specifies "HIGH" priority. A value of 5 is the normal or "MEDIUM"
+      priority. A value in the range of 6 to 9 is "LOW" priority.
+
+   Example:  The following is an example of a property with the highest priority:
+
+       PRIORITY:1
+*/
+
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[serde(try_from = "u8", into = "u8")]
+pub struct Priority(u8);
+
+impl Priority {
+    pub const UNDEFINED: Priority = Priority(0);
+    pub const HIGHEST: Priority = Priority(1);
+    pub const MEDIUM: Priority = Priority(5);
+    pub const LOWEST: Priority = Priority(9);
+
+    pub fn new(value: u8) -> Option<Self> {
+        if value <= 9 {
+            Some(Self(value))
+        } else {
+            None
+        }
+    }
+
+    pub fn get_value(&self) -> u8 {
+        self.0
+    }
+
+    pub fn is_undefined(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn is_high(&self) -> bool {
+        (1..=4).contains(&self.0)
+    }
+
+    pub fn is_low(&self) -> bool {
+        self.0 >= 6
+    }
+
+    pub fn from_ical(value: &str) -> Option<Self> {
+        Self::new(value.trim().parse().ok()?)
+    }
+
+    pub fn to_ical(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+impl TryFrom<u8> for Priority {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Self::new(value).ok_or_else(|| format!("Priority {} is not in the range 0 to 9", value))
+    }
+}
+
+impl From<Priority> for u8 {
+    fn from(priority: Priority) -> Self {
+        priority.0
+    }
+}
+
+impl Display for Priority {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
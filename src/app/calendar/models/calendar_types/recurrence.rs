@@ -83,35 +83,45 @@ If multiple BYxxx rule parts are specified, then after evaluating
 pub mod date;
 pub mod recurrence_builder;
 pub mod recurrence_frequency;
+mod recurrence_ical;
 pub mod recurrence_positions;
 pub mod recurrence_vec;
 pub mod weekday;
 use std::collections::HashSet;
 
+use chrono::{TimeZone, Utc};
 use serde::{Deserialize, Serialize};
 
 use self::{
     date::Date,
     recurrence_frequency::{
         recurrence_calculator::{RecurrenceCalculator, RecurrenceFrequencyCalculator},
+        recurrence_daily::DailyRecurrenceCalculator,
         recurrence_hourly::HourlyRecurrenceCalculator,
         recurrence_minutely::MinutelyRecurrenceCalculator,
+        recurrence_monthly::MonthlyRecurrenceCalculator,
         recurrence_secondly::SecondlyRecurrenceCalculator,
+        recurrence_weekly::WeeklyRecurrenceCalculator,
+        recurrence_yearly::YearlyRecurrenceCalculator,
         Frequency,
     },
     recurrence_positions::RecurrencePositions,
     recurrence_vec::RecurrenceVec,
     weekday::Weekday,
 };
+use super::timezone::ResolvedTimezone;
 
-#[derive(Debug, Serialize, Deserialize, Copy, Clone)]
+/* Ocurrences the server expands for a recurrence (CALDAV:max-instances) */
+pub const MAX_INSTANCES: u32 = 10000;
+
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, PartialEq, Eq)]
 pub struct RecurrenceDay {
-    // 20th monday or first sunday
-    pub recurence: u32,
+    // 20th monday or first sunday. Negative values count from the end: -1 is the last monday
+    pub recurence: i32,
     pub weekday: Weekday,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct Recurrence {
     pub frequency: Frequency,
 
@@ -135,11 +145,71 @@ pub struct Recurrence {
     pub year_days: RecurrenceVec<u32>,
     pub month_days: RecurrenceVec<u32>,
     pub months: RecurrenceVec<u32>,
+
+    /* Timezone of the start date, the recurrence being expanded in its local time */
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<ResolvedTimezone>,
 }
 
 impl Recurrence {
     pub fn calculate_ocurrences(&self, start_date: Date, end_date: Date) -> Vec<Date> {
         /* Returns all included dates in the recurrence, between start_date and end_date */
+        self.calculate(start_date, start_date, end_date, self.count.unwrap_or(1000), usize::MAX)
+    }
+
+    pub fn calculate_ocurrences_between(&self, start_date: Date, from_date: Date, end_date: Date) -> Vec<Date> {
+        /*
+        Returns the included dates of the recurrence starting at start_date, between from_date and end_date.
+        Only an explicit COUNT limits the number of ocurrences, the range bounds an unlimited recurrence, and no more
+        than MAX_INSTANCES are returned.
+        */
+        self.calculate(
+            start_date,
+            from_date,
+            end_date,
+            self.count.unwrap_or(u32::MAX),
+            MAX_INSTANCES as usize,
+        )
+    }
+
+    pub fn exceeds_max_instances(&self, start_date: Date) -> bool {
+        /* A recurrence limited by COUNT or UNTIL with more than MAX_INSTANCES ocurrences, unlimited ones being bounded */
+        match (self.count, self.until_date) {
+            (Some(count), _) if count <= MAX_INSTANCES => false,
+            (count, Some(until_date)) => {
+                let count = count.unwrap_or(u32::MAX);
+                self.calculate(start_date, start_date, until_date, count, MAX_INSTANCES as usize + 1)
+                    .len()
+                    > MAX_INSTANCES as usize
+            }
+            (Some(_), None) => true,
+            (None, None) => false,
+        }
+    }
+
+    fn calculate(&self, start_date: Date, from_date: Date, end_date: Date, count: u32, limit: usize) -> Vec<Date> {
+        if let Some(timezone) = &self.timezone {
+            /* The local times are expanded as if they were UTC, then each ocurrence is converted to UTC */
+            let to_local = |date: &Date| Date::new(Utc.from_utc_datetime(&timezone.to_local(&date.get_date_time())));
+            let local = Self {
+                until_date: self.until_date.as_ref().map(to_local),
+                excluded_dates: self.excluded_dates.iter().map(to_local).collect(),
+                timezone: None,
+                ..self.clone()
+            };
+            return local
+                .calculate(
+                    to_local(&start_date),
+                    to_local(&from_date),
+                    to_local(&end_date),
+                    count,
+                    limit,
+                )
+                .iter()
+                .map(|ocurrence| Date::new(timezone.to_utc(&ocurrence.get_date_time().naive_utc())))
+                .collect();
+        }
+
         let ending_date = match &self.until_date {
             Some(date) => {
                 if date > &end_date {
@@ -151,18 +221,22 @@ impl Recurrence {
             None => end_date,
         };
 
-        let count = self.count.unwrap_or(1000);
-
         let frequency_calculator: Box<dyn RecurrenceFrequencyCalculator> = match self.frequency {
             Frequency::Secondly => Box::new(SecondlyRecurrenceCalculator::new(self, start_date)),
             Frequency::Minutely => Box::new(MinutelyRecurrenceCalculator::new(self, start_date)),
             Frequency::Hourly => Box::new(HourlyRecurrenceCalculator::new(self, start_date)),
-            Frequency::Daily => unimplemented!(), // TODO: implement daily recurrence calculator
-            Frequency::Weekly => unimplemented!(), // TODO: implement weekly recurrence calculator
-            Frequency::Monthly => unimplemented!(), // TODO: implement monthly recurrence calculator
-            Frequency::Yearly => unimplemented!(), // TODO: implement yearly recurrence calculator
+            Frequency::Daily => Box::new(DailyRecurrenceCalculator::new(self, start_date)),
+            Frequency::Weekly => Box::new(WeeklyRecurrenceCalculator::new(self, start_date)),
+            Frequency::Monthly => Box::new(MonthlyRecurrenceCalculator::new(self, start_date)),
+            Frequency::Yearly => Box::new(YearlyRecurrenceCalculator::new(self, start_date)),
         };
 
-        RecurrenceCalculator::new(self, frequency_calculator).calculate(start_date, ending_date, count)
+        RecurrenceCalculator::new(self, frequency_calculator).calculate(
+            start_date,
+            from_date,
+            ending_date,
+            count,
+            limit,
+        )
     }
 }
@@ -1,4 +1,4 @@
-use chrono::{DateTime, Datelike, Timelike, Utc};
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Timelike, Utc};
 use serde::{Deserialize, Serialize};
 
 use super::{recurrence_vec::RecurrenceVec, weekday::Weekday};
@@ -15,6 +15,15 @@ impl Date {
         Self { date }
     }
 
+    pub fn from_ymd_hms(year: i32, month: u32, day: u32, hour: u32, minute: u32, second: u32) -> Option<Self> {
+        let naive = NaiveDate::from_ymd_opt(year, month, day)?.and_hms_opt(hour, minute, second)?;
+        Some(Self::new(Utc.from_utc_datetime(&naive)))
+    }
+
+    pub fn get_date_time(&self) -> DateTime<Utc> {
+        self.date
+    }
+
     pub fn set_month(&self, month: u32) -> Option<Self> {
         let mut current_date = self.date;
         let current_month = current_date.month();
@@ -78,6 +87,11 @@ impl Date {
         Some(Self::new(current_date))
     }
 
+    pub fn set_year_day_in_year(&self, day: u32) -> Option<Self> {
+        /* Unlike set_year_day, never moves to the next year */
+        Some(Self::new(self.date.with_ordinal(day)?))
+    }
+
     pub fn set_weekday(&self, weekday: Weekday) -> Option<Self> {
         let mut current_date = self.date;
 
@@ -251,20 +265,74 @@ impl Date {
     pub fn add_hours(&self, hours: u32) -> Self {
         Self::new(self.date + chrono::Duration::hours(hours as i64))
     }
+
+    pub fn add_days(&self, days: u32) -> Self {
+        /* Returns the first instant of the day, `days` days after this date */
+        let date = self.date.date_naive() + chrono::Duration::days(days as i64);
+        Self::new(Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap()))
+    }
+
+    pub fn add_weeks(&self, weeks: u32, week_start: Weekday) -> Self {
+        /* Returns the first instant of the week starting on `week_start`, `weeks` weeks after this date */
+        self.get_week_start(week_start).add_days(weeks * 7)
+    }
+
+    pub fn add_months(&self, months: u32) -> Self {
+        /* Returns the first instant of the month, `months` months after this date */
+        let month_index = self.get_month() - 1 + months;
+        let year = self.get_year() + (month_index / 12) as i32;
+        Self::from_ymd_hms(year, month_index % 12 + 1, 1, 0, 0, 0).unwrap()
+    }
+
+    pub fn add_years(&self, years: u32) -> Self {
+        /* Returns the first instant of the year, `years` years after this date */
+        Self::from_ymd_hms(self.get_year() + years as i32, 1, 1, 0, 0, 0).unwrap()
+    }
 }
 
 impl Date {
+    pub fn get_week_start(&self, week_start: Weekday) -> Self {
+        /* Returns the first instant of the week containing this date, for weeks starting on `week_start` */
+        let days_since_start = (7 - week_start.get_days_diff_from(&self.get_weekday())) % 7;
+        let date = self.date.date_naive() - chrono::Duration::days(days_since_start as i64);
+        Self::new(Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap()))
+    }
+
     pub fn seconds_to_date(&self, other: &Self) -> u32 {
-        (other.date - self.date).num_seconds() as u32
+        to_periods((other.date - self.date).num_seconds())
     }
 
     pub fn minutes_to_date(&self, other: &Self) -> u32 {
-        (other.date - self.date).num_minutes() as u32
+        to_periods((other.date - self.date).num_minutes())
     }
 
     pub fn hours_to_date(&self, other: &Self) -> u32 {
-        (other.date - self.date).num_hours() as u32
+        to_periods((other.date - self.date).num_hours())
+    }
+
+    pub fn days_to_date(&self, other: &Self) -> u32 {
+        to_periods((other.date.date_naive() - self.date.date_naive()).num_days())
+    }
+
+    pub fn weeks_to_date(&self, other: &Self, week_start: Weekday) -> u32 {
+        self.get_week_start(week_start)
+            .days_to_date(&other.get_week_start(week_start))
+            / 7
     }
+
+    pub fn months_to_date(&self, other: &Self) -> u32 {
+        let months = |date: &Self| date.get_year() as i64 * 12 + date.get_month() as i64;
+        to_periods(months(other) - months(self))
+    }
+
+    pub fn years_to_date(&self, other: &Self) -> u32 {
+        to_periods(other.get_year() as i64 - self.get_year() as i64)
+    }
+}
+
+fn to_periods(periods: i64) -> u32 {
+    /* Whole periods to a later date, none for an earlier one */
+    periods.clamp(0, u32::MAX as i64) as u32
 }
 
 impl std::cmp::PartialEq<DateTime<Utc>> for Date {
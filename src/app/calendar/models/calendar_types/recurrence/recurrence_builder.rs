@@ -138,6 +138,7 @@ impl RecurrenceBuilder {
             year_days: RecurrenceVec::new(self.year_days.clone().unwrap_or_default()),
             month_days: RecurrenceVec::new(self.month_days.clone().unwrap_or_default()),
             months: RecurrenceVec::new(self.months.clone().unwrap_or_default()),
+            timezone: None,
         }
     }
 }
@@ -1,11 +1,15 @@
 use serde::{Deserialize, Serialize};
 
 pub mod recurrence_calculator;
+pub mod recurrence_daily;
 pub mod recurrence_hourly;
 pub mod recurrence_minutely;
+pub mod recurrence_monthly;
 pub mod recurrence_secondly;
+pub mod recurrence_weekly;
+pub mod recurrence_yearly;
 
-#[derive(Debug, Serialize, Deserialize, Copy, Clone)]
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, PartialEq, Eq)]
 pub enum Frequency {
     Secondly,
     Minutely,
@@ -15,3 +19,30 @@ pub enum Frequency {
     Monthly,
     Yearly,
 }
+
+impl Frequency {
+    pub fn from_ical(value: &str) -> Option<Self> {
+        match value.to_uppercase().as_str() {
+            "SECONDLY" => Some(Frequency::Secondly),
+            "MINUTELY" => Some(Frequency::Minutely),
+            "HOURLY" => Some(Frequency::Hourly),
+            "DAILY" => Some(Frequency::Daily),
+            "WEEKLY" => Some(Frequency::Weekly),
+            "MONTHLY" => Some(Frequency::Monthly),
+            "YEARLY" => Some(Frequency::Yearly),
+            _ => None,
+        }
+    }
+
+    pub fn to_ical(&self) -> &'static str {
+        match self {
+            Frequency::Secondly => "SECONDLY",
+            Frequency::Minutely => "MINUTELY",
+            Frequency::Hourly => "HOURLY",
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        }
+    }
+}
@@ -1,4 +1,6 @@
-use crate::app::calendar::models::calendar_types::recurrence::{date::Date, Recurrence};
+use crate::app::calendar::models::calendar_types::recurrence::{
+    date::Date, recurrence_vec::RecurrenceVec, weekday::Weekday, Recurrence, RecurrenceDay,
+};
 
 pub trait RecurrenceFrequencyCalculator {
     fn use_positions(&self, recurrence: &Recurrence) -> bool;
@@ -7,6 +9,8 @@ pub trait RecurrenceFrequencyCalculator {
 
     fn add_time(&self, current_date: Date, time: u32) -> Date;
 
+    fn periods_to_date(&self, current_date: Date, date: Date) -> u32;
+
     fn check_date(&self, current_date: Date) -> bool;
 
     fn expand_date(&self, current_date: Date) -> Vec<Date>;
@@ -37,15 +41,29 @@ impl<'a> RecurrenceCalculator<'a> {
         }
     }
 
-    pub fn calculate(&self, start_date: Date, ending_date: Date, count: u32) -> Vec<Date> {
+    pub fn calculate(
+        &self,
+        start_date: Date,
+        from_date: Date,
+        ending_date: Date,
+        count: u32,
+        limit: usize,
+    ) -> Vec<Date> {
+        /*
+        The ocurrences before from_date are part of the COUNT, but they are not returned. Without COUNT, the calculation
+        starts at the period containing from_date, and at most `limit` ocurrences are returned.
+        */
         let mut ocurrences = Vec::new();
         let mut count = count;
-        let mut current_date = start_date;
+        let mut current_date = match self.recurrence.count {
+            Some(_) => start_date,
+            None => self.skip_to(start_date, from_date),
+        };
 
         let use_positions = self.frequency_calculator.use_positions(self.recurrence);
 
         loop {
-            if count == 0 {
+            if count == 0 || ocurrences.len() >= limit {
                 break;
             }
             if current_date > ending_date {
@@ -80,10 +98,13 @@ impl<'a> RecurrenceCalculator<'a> {
             for ocurrence in expanded_ocurrences_filtered.iter() {
                 if !self.recurrence.excluded_dates.contains(ocurrence)
                     && count > 0
+                    && ocurrences.len() < limit
                     && ocurrence <= &ending_date
                     && ocurrence >= &start_date
                 {
-                    ocurrences.push(*ocurrence);
+                    if ocurrence >= &from_date {
+                        ocurrences.push(*ocurrence);
+                    }
                     count -= 1;
                 }
             }
@@ -95,4 +116,78 @@ impl<'a> RecurrenceCalculator<'a> {
 
         ocurrences
     }
+
+    fn skip_to(&self, start_date: Date, from_date: Date) -> Date {
+        /* Skips the whole intervals before the period containing from_date, none of their ocurrences being returned */
+        let interval = self.recurrence.interval.max(1);
+        let periods = self.frequency_calculator.periods_to_date(start_date, from_date);
+        match periods - periods % interval {
+            0 => start_date,
+            periods => self.frequency_calculator.add_time(start_date, periods),
+        }
+    }
+}
+
+pub fn expand_day_times(
+    day: &Date,
+    hours: &RecurrenceVec<u32>,
+    minutes: &RecurrenceVec<u32>,
+    seconds: &RecurrenceVec<u32>,
+) -> Vec<Date> {
+    /* Expands a day with every combination of hours, minutes and seconds */
+    let mut day_ocurrences = Vec::new();
+    for hour in hours.iter() {
+        for minute in minutes.iter() {
+            for second in seconds.iter() {
+                let ocurrence = day
+                    .set_hour(*hour, false)
+                    .and_then(|ocurrence| ocurrence.set_minute(*minute, false))
+                    .and_then(|ocurrence| ocurrence.set_second(*second, false));
+                if let Some(ocurrence) = ocurrence {
+                    day_ocurrences.push(ocurrence);
+                }
+            }
+        }
+    }
+    day_ocurrences
+}
+
+pub fn expand_weekdays_in_period(
+    period: &[Date],
+    weekdays: &RecurrenceVec<Weekday>,
+    recurrences: &[RecurrenceDay],
+) -> Vec<Date> {
+    /* Every day of the period matching a weekday, plus the nth matching day for each ordinal weekday */
+    let mut days: Vec<Date> = period
+        .iter()
+        .filter(|day| weekdays.contains(&day.get_weekday()))
+        .copied()
+        .collect();
+
+    for recurrence in recurrences.iter() {
+        let matching: Vec<&Date> = period
+            .iter()
+            .filter(|day| day.get_weekday() == recurrence.weekday)
+            .collect();
+        let index = if recurrence.recurence > 0 {
+            recurrence.recurence - 1
+        } else {
+            matching.len() as i32 + recurrence.recurence
+        };
+        if index >= 0 {
+            if let Some(day) = matching.get(index as usize) {
+                days.push(**day);
+            }
+        }
+    }
+
+    days.sort();
+    days.dedup();
+    days
+}
+
+pub fn get_days_in_months(year: i32, months: std::ops::RangeInclusive<u32>) -> Vec<Date> {
+    months
+        .flat_map(|month| (1..=31).filter_map(move |day| Date::from_ymd_hms(year, month, day, 0, 0, 0)))
+        .collect()
 }
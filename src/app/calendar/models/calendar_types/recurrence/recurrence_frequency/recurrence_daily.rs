@@ -0,0 +1,81 @@
+use crate::app::calendar::models::calendar_types::recurrence::{
+    date::Date, recurrence_vec::RecurrenceVec, weekday::Weekday, Recurrence, RecurrenceDay,
+};
+
+use super::recurrence_calculator::{expand_day_times, RecurrenceFrequencyCalculator};
+
+pub struct DailyRecurrenceCalculator {
+    months: RecurrenceVec<u32>,
+    month_days: RecurrenceVec<u32>,
+    weekdays: RecurrenceVec<Weekday>,
+    recurrences: Vec<RecurrenceDay>,
+    hours: RecurrenceVec<u32>,
+    minutes: RecurrenceVec<u32>,
+    seconds: RecurrenceVec<u32>,
+}
+
+impl DailyRecurrenceCalculator {
+    pub fn new(recurrence: &Recurrence, start_date: Date) -> Self {
+        let months = recurrence.months.get_or_default_months();
+        let month_days = recurrence.month_days.get_or_default_month_days();
+        let hours = recurrence.hours.get_or_default(vec![start_date.get_hour()]);
+        let minutes = recurrence.minutes.get_or_default(vec![start_date.get_minute()]);
+        let seconds = recurrence.seconds.get_or_default(vec![start_date.get_second()]);
+
+        Self {
+            months,
+            month_days,
+            weekdays: recurrence.weekdays.clone(),
+            recurrences: recurrence.recurrences.clone(),
+            hours,
+            minutes,
+            seconds,
+        }
+    }
+
+    fn matches_weekday(&self, day: &Date) -> bool {
+        /* Ordinals have no meaning within a day, so they only limit by their weekday */
+        if self.weekdays.is_empty() && self.recurrences.is_empty() {
+            return true;
+        }
+        self.weekdays.contains(&day.get_weekday())
+            || self
+                .recurrences
+                .iter()
+                .any(|recurrence| recurrence.weekday == day.get_weekday())
+    }
+}
+
+impl RecurrenceFrequencyCalculator for DailyRecurrenceCalculator {
+    /* When freq is DAILY, we advance every `interval` days, but:
+                - Only in the months specified in `months`,
+                - Only in the days specified in `month_days` and `weekdays`,
+            Each day is expanded with `hours`, `minutes` and `seconds`, defaulting to the start date time.
+            If positions are specified, within a day only include those that match the positions in positions.
+    */
+    fn use_positions(&self, _recurrence: &Recurrence) -> bool {
+        true
+    }
+
+    fn get_skip_time(&self, _current_date: Date, _interval: u32) -> Option<u32> {
+        None
+    }
+
+    fn add_time(&self, current_date: Date, time: u32) -> Date {
+        current_date.add_days(time)
+    }
+
+    fn periods_to_date(&self, current_date: Date, date: Date) -> u32 {
+        current_date.days_to_date(&date)
+    }
+
+    fn check_date(&self, current_date: Date) -> bool {
+        self.months.contains(&current_date.get_month())
+            && self.month_days.contains(&current_date.get_month_day())
+            && self.matches_weekday(&current_date)
+    }
+
+    fn expand_date(&self, current_date: Date) -> Vec<Date> {
+        expand_day_times(&current_date, &self.hours, &self.minutes, &self.seconds)
+    }
+}
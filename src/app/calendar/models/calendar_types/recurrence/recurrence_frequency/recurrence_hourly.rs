@@ -72,6 +72,10 @@ impl RecurrenceFrequencyCalculator for HourlyRecurrenceCalculator {
         current_date.add_hours(time)
     }
 
+    fn periods_to_date(&self, current_date: Date, date: Date) -> u32 {
+        current_date.hours_to_date(&date)
+    }
+
     fn check_date(&self, current_date: Date) -> bool {
         self.hours.contains(&current_date.get_hour())
     }
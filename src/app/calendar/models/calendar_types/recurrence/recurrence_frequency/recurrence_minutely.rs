@@ -76,6 +76,10 @@ impl RecurrenceFrequencyCalculator for MinutelyRecurrenceCalculator {
         current_date.add_minutes(time)
     }
 
+    fn periods_to_date(&self, current_date: Date, date: Date) -> u32 {
+        current_date.minutes_to_date(&date)
+    }
+
     fn check_date(&self, current_date: Date) -> bool {
         self.minutes.contains(&current_date.get_minute())
     }
@@ -0,0 +1,103 @@
+use crate::app::calendar::models::calendar_types::recurrence::{
+    date::Date, recurrence_vec::RecurrenceVec, weekday::Weekday, Recurrence, RecurrenceDay,
+};
+
+use super::recurrence_calculator::{
+    expand_day_times, expand_weekdays_in_period, get_days_in_months, RecurrenceFrequencyCalculator,
+};
+
+pub struct MonthlyRecurrenceCalculator {
+    months: RecurrenceVec<u32>,
+    month_days: RecurrenceVec<u32>,
+    weekdays: RecurrenceVec<Weekday>,
+    recurrences: Vec<RecurrenceDay>,
+    hours: RecurrenceVec<u32>,
+    minutes: RecurrenceVec<u32>,
+    seconds: RecurrenceVec<u32>,
+    start_month_day: u32,
+}
+
+impl MonthlyRecurrenceCalculator {
+    pub fn new(recurrence: &Recurrence, start_date: Date) -> Self {
+        let hours = recurrence.hours.get_or_default(vec![start_date.get_hour()]);
+        let minutes = recurrence.minutes.get_or_default(vec![start_date.get_minute()]);
+        let seconds = recurrence.seconds.get_or_default(vec![start_date.get_second()]);
+
+        Self {
+            months: recurrence.months.get_or_default_months(),
+            month_days: recurrence.month_days.clone(),
+            weekdays: recurrence.weekdays.clone(),
+            recurrences: recurrence.recurrences.clone(),
+            hours,
+            minutes,
+            seconds,
+            start_month_day: start_date.get_month_day(),
+        }
+    }
+
+    fn has_weekdays(&self) -> bool {
+        !self.weekdays.is_empty() || !self.recurrences.is_empty()
+    }
+
+    fn expand_days(&self, year: i32, month: u32) -> Vec<Date> {
+        /* Returns the days of the month (at midnight) selected by the BYxxx rules, sorted */
+        let weekdays = self.has_weekdays().then(|| {
+            expand_weekdays_in_period(
+                &get_days_in_months(year, month..=month),
+                &self.weekdays,
+                &self.recurrences,
+            )
+        });
+
+        if !self.month_days.is_empty() {
+            self.month_days
+                .iter()
+                .filter_map(|month_day| Date::from_ymd_hms(year, month, *month_day, 0, 0, 0))
+                .filter(|day| weekdays.as_ref().map(|days| days.contains(day)).unwrap_or(true))
+                .collect()
+        } else if let Some(days) = weekdays {
+            days
+        } else {
+            /* Months without the start day are skipped */
+            Date::from_ymd_hms(year, month, self.start_month_day, 0, 0, 0)
+                .into_iter()
+                .collect()
+        }
+    }
+}
+
+impl RecurrenceFrequencyCalculator for MonthlyRecurrenceCalculator {
+    /* When freq is MONTHLY, we advance every `interval` months, but only in the months specified in `months`, and:
+                - `month_days` selects days of the month, limited by `weekdays` and `recurrences`,
+                - Else `weekdays` and `recurrences` select days within the month,
+                - Else the start date day is used.
+            Each day is expanded with `hours`, `minutes` and `seconds`, defaulting to the start date time.
+            If positions are specified, within a month only include those that match the positions in positions.
+    */
+    fn use_positions(&self, _recurrence: &Recurrence) -> bool {
+        true
+    }
+
+    fn get_skip_time(&self, _current_date: Date, _interval: u32) -> Option<u32> {
+        None
+    }
+
+    fn add_time(&self, current_date: Date, time: u32) -> Date {
+        current_date.add_months(time)
+    }
+
+    fn periods_to_date(&self, current_date: Date, date: Date) -> u32 {
+        current_date.months_to_date(&date)
+    }
+
+    fn check_date(&self, current_date: Date) -> bool {
+        self.months.contains(&current_date.get_month())
+    }
+
+    fn expand_date(&self, current_date: Date) -> Vec<Date> {
+        self.expand_days(current_date.get_year(), current_date.get_month())
+            .iter()
+            .flat_map(|day| expand_day_times(day, &self.hours, &self.minutes, &self.seconds))
+            .collect()
+    }
+}
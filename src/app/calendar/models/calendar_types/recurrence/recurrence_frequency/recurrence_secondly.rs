@@ -78,6 +78,10 @@ impl RecurrenceFrequencyCalculator for SecondlyRecurrenceCalculator {
         current_date.add_seconds(time)
     }
 
+    fn periods_to_date(&self, current_date: Date, date: Date) -> u32 {
+        current_date.seconds_to_date(&date)
+    }
+
     fn check_date(&self, current_date: Date) -> bool {
         self.seconds.contains(&current_date.get_second())
     }
@@ -0,0 +1,77 @@
+use crate::app::calendar::models::calendar_types::recurrence::{
+    date::Date, recurrence_vec::RecurrenceVec, weekday::Weekday, Recurrence,
+};
+
+use super::recurrence_calculator::{expand_day_times, RecurrenceFrequencyCalculator};
+
+pub struct WeeklyRecurrenceCalculator {
+    months: RecurrenceVec<u32>,
+    weekdays: RecurrenceVec<Weekday>,
+    week_start: Weekday,
+    hours: RecurrenceVec<u32>,
+    minutes: RecurrenceVec<u32>,
+    seconds: RecurrenceVec<u32>,
+}
+
+impl WeeklyRecurrenceCalculator {
+    pub fn new(recurrence: &Recurrence, start_date: Date) -> Self {
+        /* Ordinals have no meaning within a week, so they only select their weekday */
+        let mut weekdays: Vec<Weekday> = recurrence.weekdays.iter().copied().collect();
+        weekdays.extend(recurrence.recurrences.iter().map(|recurrence| recurrence.weekday));
+        if weekdays.is_empty() {
+            weekdays.push(start_date.get_weekday());
+        }
+        weekdays.sort();
+        weekdays.dedup();
+
+        let hours = recurrence.hours.get_or_default(vec![start_date.get_hour()]);
+        let minutes = recurrence.minutes.get_or_default(vec![start_date.get_minute()]);
+        let seconds = recurrence.seconds.get_or_default(vec![start_date.get_second()]);
+
+        Self {
+            months: recurrence.months.get_or_default_months(),
+            weekdays: RecurrenceVec::new(weekdays),
+            week_start: recurrence.week_start.unwrap_or(Weekday::Monday),
+            hours,
+            minutes,
+            seconds,
+        }
+    }
+}
+
+impl RecurrenceFrequencyCalculator for WeeklyRecurrenceCalculator {
+    /* When freq is WEEKLY, we advance every `interval` weeks, starting on `week_start` (monday by default), and:
+                - `weekdays` selects days of the week, defaulting to the start date weekday,
+                - Only days in the months specified in `months` are included.
+            Each day is expanded with `hours`, `minutes` and `seconds`, defaulting to the start date time.
+            If positions are specified, within a week only include those that match the positions in positions.
+    */
+    fn use_positions(&self, _recurrence: &Recurrence) -> bool {
+        true
+    }
+
+    fn get_skip_time(&self, _current_date: Date, _interval: u32) -> Option<u32> {
+        None
+    }
+
+    fn add_time(&self, current_date: Date, time: u32) -> Date {
+        current_date.add_weeks(time, self.week_start)
+    }
+
+    fn periods_to_date(&self, current_date: Date, date: Date) -> u32 {
+        current_date.weeks_to_date(&date, self.week_start)
+    }
+
+    fn check_date(&self, _current_date: Date) -> bool {
+        true
+    }
+
+    fn expand_date(&self, current_date: Date) -> Vec<Date> {
+        let week_start = current_date.get_week_start(self.week_start);
+        (0..7)
+            .map(|day| week_start.add_days(day))
+            .filter(|day| self.weekdays.contains(&day.get_weekday()) && self.months.contains(&day.get_month()))
+            .flat_map(|day| expand_day_times(&day, &self.hours, &self.minutes, &self.seconds))
+            .collect()
+    }
+}
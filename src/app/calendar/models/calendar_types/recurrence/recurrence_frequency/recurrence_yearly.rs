@@ -0,0 +1,139 @@
+use crate::app::calendar::models::calendar_types::recurrence::{
+    date::Date, recurrence_vec::RecurrenceVec, weekday::Weekday, Recurrence, RecurrenceDay,
+};
+
+use super::recurrence_calculator::{
+    expand_day_times, expand_weekdays_in_period, get_days_in_months, RecurrenceFrequencyCalculator,
+};
+
+pub struct YearlyRecurrenceCalculator {
+    months: RecurrenceVec<u32>,
+    year_days: RecurrenceVec<u32>,
+    month_days: RecurrenceVec<u32>,
+    weekdays: RecurrenceVec<Weekday>,
+    recurrences: Vec<RecurrenceDay>,
+    hours: RecurrenceVec<u32>,
+    minutes: RecurrenceVec<u32>,
+    seconds: RecurrenceVec<u32>,
+    start_month: u32,
+    start_month_day: u32,
+}
+
+impl YearlyRecurrenceCalculator {
+    pub fn new(recurrence: &Recurrence, start_date: Date) -> Self {
+        let hours = recurrence.hours.get_or_default(vec![start_date.get_hour()]);
+        let minutes = recurrence.minutes.get_or_default(vec![start_date.get_minute()]);
+        let seconds = recurrence.seconds.get_or_default(vec![start_date.get_second()]);
+
+        Self {
+            months: recurrence.months.clone(),
+            year_days: recurrence.year_days.clone(),
+            month_days: recurrence.month_days.clone(),
+            weekdays: recurrence.weekdays.clone(),
+            recurrences: recurrence.recurrences.clone(),
+            hours,
+            minutes,
+            seconds,
+            start_month: start_date.get_month(),
+            start_month_day: start_date.get_month_day(),
+        }
+    }
+
+    fn has_weekdays(&self) -> bool {
+        !self.weekdays.is_empty() || !self.recurrences.is_empty()
+    }
+
+    fn expand_days(&self, year: i32) -> Vec<Date> {
+        /* Returns the days of the year (at midnight) selected by the BYxxx rules, sorted */
+        let months = self.months.get_or_default_months();
+
+        let days: Vec<Date> = if !self.year_days.is_empty() {
+            self.year_days
+                .iter()
+                .filter_map(|year_day| Date::from_ymd_hms(year, 1, 1, 0, 0, 0)?.set_year_day_in_year(*year_day))
+                .filter(|day| months.contains(&day.get_month()))
+                .filter(|day| self.month_days.is_empty() || self.month_days.contains(&day.get_month_day()))
+                .filter(|day| !self.has_weekdays() || self.matches_weekday(day))
+                .collect()
+        } else if !self.month_days.is_empty() {
+            months
+                .iter()
+                .flat_map(|month| {
+                    self.month_days
+                        .iter()
+                        .filter_map(move |month_day| Date::from_ymd_hms(year, *month, *month_day, 0, 0, 0))
+                })
+                .filter(|day| !self.has_weekdays() || self.matches_weekday(day))
+                .collect()
+        } else if self.has_weekdays() {
+            if self.months.is_empty() {
+                self.expand_weekdays(get_days_in_months(year, 1..=12))
+            } else {
+                self.months
+                    .iter()
+                    .flat_map(|month| self.expand_weekdays(get_days_in_months(year, *month..=*month)))
+                    .collect()
+            }
+        } else {
+            self.months
+                .get_or_default(vec![self.start_month])
+                .iter()
+                .filter_map(|month| Date::from_ymd_hms(year, *month, self.start_month_day, 0, 0, 0))
+                .collect()
+        };
+
+        let mut days = days;
+        days.sort();
+        days.dedup();
+        days
+    }
+
+    fn expand_weekdays(&self, period: Vec<Date>) -> Vec<Date> {
+        expand_weekdays_in_period(&period, &self.weekdays, &self.recurrences)
+    }
+
+    fn matches_weekday(&self, day: &Date) -> bool {
+        self.weekdays.contains(&day.get_weekday())
+            || self
+                .recurrences
+                .iter()
+                .any(|recurrence| recurrence.weekday == day.get_weekday())
+    }
+}
+
+impl RecurrenceFrequencyCalculator for YearlyRecurrenceCalculator {
+    /* When freq is YEARLY, we advance every `interval` years, and every BYxxx rule expands the year:
+                - `year_days` selects days of the year, limited by `months`, `month_days` and `weekdays`,
+                - Else `month_days` selects those days in each of `months` (or every month), limited by `weekdays`,
+                - Else `weekdays` and `recurrences` select days within each of `months`, or within the whole year,
+                - Else the start date month and day are used.
+            Each day is expanded with `hours`, `minutes` and `seconds`, defaulting to the start date time.
+            If positions are specified, within a year only include those that match the positions in positions.
+    */
+    fn use_positions(&self, _recurrence: &Recurrence) -> bool {
+        true
+    }
+
+    fn get_skip_time(&self, _current_date: Date, _interval: u32) -> Option<u32> {
+        None
+    }
+
+    fn add_time(&self, current_date: Date, time: u32) -> Date {
+        current_date.add_years(time)
+    }
+
+    fn periods_to_date(&self, current_date: Date, date: Date) -> u32 {
+        current_date.years_to_date(&date)
+    }
+
+    fn check_date(&self, _current_date: Date) -> bool {
+        true
+    }
+
+    fn expand_date(&self, current_date: Date) -> Vec<Date> {
+        self.expand_days(current_date.get_year())
+            .iter()
+            .flat_map(|day| expand_day_times(day, &self.hours, &self.minutes, &self.seconds))
+            .collect()
+    }
+}
@@ -0,0 +1,206 @@
+/*
+Conversion between the RRULE value and Recurrence:
+
+       RRULE:FREQ=YEARLY;INTERVAL=2;BYMONTH=1;BYDAY=SU;BYHOUR=8,9;BYMINUTE=30
+
+A start date with a TZID is expanded in the local time of its timezone, so that the instances keep their local time
+across daylight saving changes (RFC 5545 section 3.3.10).
+
+BYWEEKNO is not supported by the recurrence engine: rules with it, or with unknown parts, are not valid, as they cannot
+be expanded or written back unchanged.
+*/
+
+use std::collections::HashSet;
+
+use crate::app::calendar::{
+    ical::{
+        component::ICalComponent,
+        property::ICalProperty,
+        values::{format_date_time, parse_date_time},
+        ICalError,
+    },
+    models::calendar_types::timezone::timezone_registry::TimezoneRegistry,
+};
+
+use super::{
+    date::Date, recurrence_builder::RecurrenceBuilder, recurrence_frequency::Frequency, weekday::Weekday, Recurrence,
+    RecurrenceDay,
+};
+
+impl Recurrence {
+    pub fn from_ical(value: &str) -> Result<Self, ICalError> {
+        let invalid = || ICalError::InvalidValue {
+            property: "RRULE".to_string(),
+            value: value.to_string(),
+        };
+
+        let parts: Vec<(String, &str)> = value
+            .split(';')
+            .filter(|part| !part.is_empty())
+            .map(|part| {
+                let (name, part_value) = part.split_once('=').ok_or_else(invalid)?;
+                Ok((name.trim().to_uppercase(), part_value.trim()))
+            })
+            .collect::<Result<_, ICalError>>()?;
+
+        let frequency = parts
+            .iter()
+            .find(|(name, _)| name == "FREQ")
+            .and_then(|(_, frequency)| Frequency::from_ical(frequency))
+            .ok_or_else(invalid)?;
+
+        let mut builder = RecurrenceBuilder::new(frequency);
+        for (name, part_value) in parts.iter() {
+            match name.as_str() {
+                "FREQ" => {}
+                "INTERVAL" => {
+                    builder.set_interval(part_value.parse().map_err(|_| invalid())?);
+                }
+                "COUNT" => {
+                    builder.set_count(part_value.parse().map_err(|_| invalid())?);
+                }
+                "UNTIL" => {
+                    builder.set_until_date(Date::new(parse_date_time(part_value).ok_or_else(invalid)?));
+                }
+                "WKST" => {
+                    builder.set_week_start(Weekday::from_ical(part_value).ok_or_else(invalid)?);
+                }
+                "BYDAY" => {
+                    let mut weekdays = Vec::new();
+                    let mut recurrences = Vec::new();
+                    for day in part_value.split(',') {
+                        let day = day.trim();
+                        let (ordinal, weekday) = day.split_at(day.len().saturating_sub(2));
+                        let weekday = Weekday::from_ical(weekday).ok_or_else(invalid)?;
+                        if ordinal.is_empty() {
+                            weekdays.push(weekday);
+                        } else {
+                            let recurence = ordinal.trim_start_matches('+').parse().map_err(|_| invalid())?;
+                            recurrences.push(RecurrenceDay { recurence, weekday });
+                        }
+                    }
+                    builder.set_weekdays(weekdays).set_recurrences(recurrences);
+                }
+                "BYSETPOS" => {
+                    builder.set_positions(parse_list(part_value).ok_or_else(invalid)?);
+                }
+                "BYSECOND" => {
+                    builder.set_seconds(parse_list(part_value).ok_or_else(invalid)?);
+                }
+                "BYMINUTE" => {
+                    builder.set_minutes(parse_list(part_value).ok_or_else(invalid)?);
+                }
+                "BYHOUR" => {
+                    builder.set_hours(parse_list(part_value).ok_or_else(invalid)?);
+                }
+                "BYMONTHDAY" => {
+                    builder.set_month_days(parse_list(part_value).ok_or_else(invalid)?);
+                }
+                "BYYEARDAY" => {
+                    builder.set_year_days(parse_list(part_value).ok_or_else(invalid)?);
+                }
+                "BYMONTH" => {
+                    builder.set_months(parse_list(part_value).ok_or_else(invalid)?);
+                }
+                _ => return Err(invalid()),
+            }
+        }
+
+        Ok(builder.build())
+    }
+
+    pub fn from_component(component: &ICalComponent, timezones: &TimezoneRegistry) -> Result<Option<Self>, ICalError> {
+        /*
+        Reads the RRULE of a component, with its EXDATEs as excluded dates. A DTSTART with a TZID keeps its timezone, for
+        the recurrence to be expanded in local time.
+        */
+        let property = match component.get_property("RRULE") {
+            Some(property) => property,
+            None => return Ok(None),
+        };
+
+        let mut recurrence = Self::from_ical(property.get_value())?;
+        recurrence.timezone = component
+            .get_property("DTSTART")
+            .filter(|dtstart| !dtstart.get_value().trim().ends_with('Z'))
+            .and_then(|dtstart| dtstart.get_parameter_value("TZID"))
+            .and_then(|tzid| timezones.resolve(tzid));
+        let mut excluded_dates = HashSet::new();
+        for exdate in component.get_properties("EXDATE") {
+            for value in exdate.values.iter() {
+                excluded_dates.insert(Date::new(timezones.get_value_date_time(exdate, value)?));
+            }
+        }
+        recurrence.excluded_dates = excluded_dates;
+        Ok(Some(recurrence))
+    }
+
+    pub fn add_to_component(&self, component: &mut ICalComponent) {
+        /* Adds the RRULE and the sorted EXDATEs, in UTC */
+        component.add_property(ICalProperty::new("RRULE", &self.to_ical()));
+
+        let mut excluded_dates: Vec<&Date> = self.excluded_dates.iter().collect();
+        excluded_dates.sort();
+        for excluded_date in excluded_dates {
+            component.add_property(ICalProperty::new(
+                "EXDATE",
+                &format_date_time(&excluded_date.get_date_time()),
+            ));
+        }
+    }
+
+    pub fn to_ical(&self) -> String {
+        let mut parts = vec![format!("FREQ={}", self.frequency.to_ical())];
+
+        if self.interval != 1 {
+            parts.push(format!("INTERVAL={}", self.interval));
+        }
+        if let Some(count) = self.count {
+            parts.push(format!("COUNT={}", count));
+        }
+        if let Some(until_date) = &self.until_date {
+            parts.push(format!("UNTIL={}", format_date_time(&until_date.get_date_time())));
+        }
+
+        add_list(&mut parts, "BYSECOND", self.seconds.iter());
+        add_list(&mut parts, "BYMINUTE", self.minutes.iter());
+        add_list(&mut parts, "BYHOUR", self.hours.iter());
+
+        let days: Vec<String> = self
+            .weekdays
+            .iter()
+            .map(|weekday| weekday.to_ical().to_string())
+            .chain(
+                self.recurrences
+                    .iter()
+                    .map(|recurrence| format!("{}{}", recurrence.recurence, recurrence.weekday.to_ical())),
+            )
+            .collect();
+        add_list(&mut parts, "BYDAY", days.iter());
+
+        add_list(&mut parts, "BYMONTHDAY", self.month_days.iter());
+        add_list(&mut parts, "BYYEARDAY", self.year_days.iter());
+        add_list(&mut parts, "BYMONTH", self.months.iter());
+        add_list(&mut parts, "BYSETPOS", self.positions.get_positions().iter());
+
+        if let Some(week_start) = &self.week_start {
+            parts.push(format!("WKST={}", week_start.to_ical()));
+        }
+
+        parts.join(";")
+    }
+}
+
+fn parse_list<T: std::str::FromStr>(value: &str) -> Option<Vec<T>> {
+    value
+        .split(',')
+        .map(|item| item.trim().trim_start_matches('+').parse::<T>().ok())
+        .collect()
+}
+
+fn add_list<T: ToString>(parts: &mut Vec<String>, name: &str, items: impl Iterator<Item = T>) {
+    let items: Vec<String> = items.map(|item| item.to_string()).collect();
+    if !items.is_empty() {
+        parts.push(format!("{}={}", name, items.join(",")));
+    }
+}
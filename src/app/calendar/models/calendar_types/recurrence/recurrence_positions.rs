@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 #[serde(transparent)]
 pub struct RecurrencePositions {
     positions: Vec<i32>,
@@ -11,6 +11,10 @@ impl RecurrencePositions {
         Self { positions }
     }
 
+    pub fn get_positions(&self) -> &[i32] {
+        &self.positions
+    }
+
     pub fn apply<T: PartialEq + Copy>(&self, data: Vec<T>) -> Vec<T> {
         if self.positions.is_empty() {
             return data;
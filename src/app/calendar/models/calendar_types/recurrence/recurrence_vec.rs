@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use super::weekday::Weekday;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct RecurrenceVec<T: PartialEq + Clone + Ord> {
     data: Vec<T>,
 }
@@ -23,7 +23,7 @@ impl<T: PartialEq + Clone + Ord> RecurrenceVec<T> {
     }
 
     pub fn contains(&self, item: &T) -> bool {
-        self.data.iter().any(|x| *x == *item)
+        self.data.contains(item)
     }
 
     pub fn length(&self) -> usize {
@@ -34,7 +34,7 @@ impl<T: PartialEq + Clone + Ord> RecurrenceVec<T> {
         self.data.is_empty()
     }
 
-    pub fn iter(&self) -> std::slice::Iter<T> {
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
         self.data.iter()
     }
 
@@ -24,6 +24,31 @@ impl Weekday {
         }
     }
 
+    pub fn from_ical(value: &str) -> Option<Weekday> {
+        match value.to_uppercase().as_str() {
+            "MO" => Some(Weekday::Monday),
+            "TU" => Some(Weekday::Tuesday),
+            "WE" => Some(Weekday::Wednesday),
+            "TH" => Some(Weekday::Thursday),
+            "FR" => Some(Weekday::Friday),
+            "SA" => Some(Weekday::Saturday),
+            "SU" => Some(Weekday::Sunday),
+            _ => None,
+        }
+    }
+
+    pub fn to_ical(&self) -> &'static str {
+        match self {
+            Weekday::Monday => "MO",
+            Weekday::Tuesday => "TU",
+            Weekday::Wednesday => "WE",
+            Weekday::Thursday => "TH",
+            Weekday::Friday => "FR",
+            Weekday::Saturday => "SA",
+            Weekday::Sunday => "SU",
+        }
+    }
+
     pub fn get_days_diff_from(&self, other: &Weekday) -> i32 {
         let mut diff = *self as i32 - *other as i32;
         if diff <= 0 {
@@ -0,0 +1,98 @@
+/*
+Format Definition:  This property is defined by the following
+      notation:
+
+       related    = "RELATED-TO" relparam ":" text CRLF
+
+       relparam   = *(
+                  ;
+                  ; The following is OPTIONAL,
+                  ; but MUST NOT occur more than once.
+                  ;
+                  (";" reltypeparam) /
+                  ;
+                  ; The following is OPTIONAL,
+                  ; and MAY occur more than once.
+                  ;
+                  (";" other-param)
+                  ;
+                  )
+
+       reltypeparam       = "RELTYPE" "="
+                           ("PARENT"    ; Parent relationship - Default
+                          / "CHILD"     ; Child relationship
+                          / "SIBLING"   ; Sibling relationship
+                          / iana-token  ; Some other IANA-registered
+                                        ; iCalendar relationship type
+                          / x-name)     ; A non-standard, experimental
+                                        ; relationship type
+
+   Example:  The following is an example of this property:
+
+       RELATED-TO;RELTYPE=PARENT:jsmith.part7.19960817T083000.xyzMail@example.com
+
+RFC 9074 registers the SNOOZE relationship, relating a snooze alarm to the UID of the alarm it snoozes:
+
+       RELATED-TO;RELTYPE=SNOOZE:8297C37D-BA2D-4476-91AE-C1EAA364F8E1
+*/
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::calendar::ical::{property::ICalProperty, ICalError};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum RelationType {
+    Parent,
+    Child,
+    Sibling,
+    Snooze,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Relation {
+    /* UID of the related component */
+    pub uid: String,
+    pub relation_type: RelationType,
+}
+
+impl RelationType {
+    pub fn from_ical(value: &str) -> Option<Self> {
+        match value.to_uppercase().as_str() {
+            "PARENT" => Some(RelationType::Parent),
+            "CHILD" => Some(RelationType::Child),
+            "SIBLING" => Some(RelationType::Sibling),
+            "SNOOZE" => Some(RelationType::Snooze),
+            _ => None,
+        }
+    }
+
+    pub fn to_ical(&self) -> &'static str {
+        match self {
+            RelationType::Parent => "PARENT",
+            RelationType::Child => "CHILD",
+            RelationType::Sibling => "SIBLING",
+            RelationType::Snooze => "SNOOZE",
+        }
+    }
+}
+
+impl Relation {
+    pub fn from_ical(property: &ICalProperty) -> Result<Self, ICalError> {
+        let relation_type = match property.get_parameter_value("RELTYPE") {
+            Some(value) => RelationType::from_ical(value).ok_or_else(|| ICalError::InvalidValue {
+                property: format!("{};RELTYPE", property.name),
+                value: value.to_string(),
+            })?,
+            None => RelationType::Parent,
+        };
+
+        Ok(Self {
+            uid: property.get_value().to_string(),
+            relation_type,
+        })
+    }
+
+    pub fn to_ical(&self) -> ICalProperty {
+        ICalProperty::new("RELATED-TO", &self.uid).with_parameter("RELTYPE", self.relation_type.to_ical())
+    }
+}
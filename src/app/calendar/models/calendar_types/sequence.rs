@@ -0,0 +1,26 @@
+/*
+Property Name:  SEQUENCE
+
+   Purpose:  This property defines the revision sequence number of the calendar component within a sequence of
+      revisions.
+
+   Description:  When a calendar component is created, its sequence number is 0. It is monotonically incremented by
+      the "Organizer's" CUA each time the "Organizer" makes a significant revision to the calendar component.
+
+      The "Organizer" includes this property in an iCalendar object that it sends to an "Attendee" to specify the
+      current version of the calendar component.
+
+      The "Attendee" includes this property in an iCalendar object that it sends to the "Organizer" to specify the
+      version of the calendar component to which the "Attendee" is referring.
+
+A change is significant when it changes when the component takes place, or whether it takes place at all: its dates,
+its recurrence and its status. Clients may already have incremented the number, which must never decrease.
+*/
+
+pub fn next_sequence(sequence: u32, previous: u32, significant_change: bool) -> u32 {
+    if significant_change {
+        sequence.max(previous.saturating_add(1))
+    } else {
+        sequence.max(previous)
+    }
+}
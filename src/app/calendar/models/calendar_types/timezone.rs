@@ -0,0 +1,304 @@
+/*
+BEGIN:VTIMEZONE
+TZID: ; (Required, MUST NOT occur more than once)
+LAST-MODIFIED: ; (Optional, MUST NOT occur more than once)
+TZURL: ; (Optional, MUST NOT occur more than once)
+BEGIN:STANDARD / BEGIN:DAYLIGHT (One of them is required, MAY occur more than once)
+    DTSTART: ; (Required, local time of the first onset)
+    TZOFFSETTO: ; (Required, offset in use during this observance)
+    TZOFFSETFROM: ; (Required, offset in use before the onset)
+    RRULE: ; (Optional, MUST NOT occur more than once)
+    RDATE: ; (Optional, MAY occur more than once)
+    TZNAME: ; (Optional, MAY occur more than once)
+END:STANDARD / END:DAYLIGHT
+END:VTIMEZONE
+
+Example:
+
+       BEGIN:VTIMEZONE
+       TZID:America/New_York
+       BEGIN:DAYLIGHT
+       DTSTART:20070311T020000
+       RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=2SU
+       TZOFFSETFROM:-0500
+       TZOFFSETTO:-0400
+       TZNAME:EDT
+       END:DAYLIGHT
+       BEGIN:STANDARD
+       DTSTART:20071104T020000
+       RRULE:FREQ=YEARLY;BYMONTH=11;BYDAY=1SU
+       TZOFFSETFROM:-0400
+       TZOFFSETTO:-0500
+       TZNAME:EST
+       END:STANDARD
+       END:VTIMEZONE
+
+The onsets of each observance are calculated with the recurrence engine, working on local times as if they were UTC.
+*/
+
+pub mod iana_timezone;
+pub mod timezone_registry;
+pub mod timezones_by_reference;
+
+use chrono::{DateTime, Duration, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::app::calendar::ical::{
+    component::ICalComponent,
+    property::ICalProperty,
+    values::{format_local_date_time, parse_date_time},
+    ICalError,
+};
+
+use self::iana_timezone::{get_iana_timezone, iana_to_local, iana_to_utc};
+use super::recurrence::{date::Date, Recurrence};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimezoneObservanceType {
+    Standard,
+    Daylight,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimezoneObservance {
+    pub observance_type: TimezoneObservanceType,
+
+    /* Local time of the first onset, expressed in offset_from */
+    pub start: NaiveDateTime,
+
+    /* Offsets in seconds east of UTC */
+    pub offset_from: i32,
+    pub offset_to: i32,
+
+    pub name: Option<String>,
+
+    pub recurrence: Option<Recurrence>,
+
+    /* Additional onsets (RDATE), in local time */
+    pub dates: Vec<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Timezone {
+    pub tzid: String,
+    pub observances: Vec<TimezoneObservance>,
+}
+
+/* Timezone a TZID resolves to: a VTIMEZONE of the calendar, or a standard one of the IANA database */
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResolvedTimezone {
+    Defined(Timezone),
+    Standard(String),
+}
+
+impl Timezone {
+    pub fn from_ical(component: &ICalComponent) -> Result<Self, ICalError> {
+        if !component.is("VTIMEZONE") {
+            return Err(ICalError::InvalidComponent(component.name.clone()));
+        }
+
+        let tzid = component.get_required_property("TZID")?.get_value().to_string();
+
+        let observances = component
+            .components
+            .iter()
+            .filter(|observance| observance.is("STANDARD") || observance.is("DAYLIGHT"))
+            .map(TimezoneObservance::from_ical)
+            .collect::<Result<Vec<TimezoneObservance>, ICalError>>()?;
+
+        if observances.is_empty() {
+            return Err(ICalError::InvalidComponent(format!(
+                "VTIMEZONE {} without observances",
+                tzid
+            )));
+        }
+
+        Ok(Self { tzid, observances })
+    }
+
+    pub fn get_offset_at(&self, date: DateTime<Utc>) -> i32 {
+        /* Returns the offset of the observance with the latest onset before the given instant */
+        let latest = self
+            .observances
+            .iter()
+            .filter_map(|observance| {
+                observance
+                    .get_last_onset_before(date)
+                    .map(|onset| (onset, observance.offset_to))
+            })
+            .max_by_key(|(onset, _)| *onset);
+
+        match latest {
+            Some((_, offset)) => offset,
+            None => self
+                .observances
+                .iter()
+                .min_by_key(|observance| observance.start)
+                .map(|observance| observance.offset_from)
+                .unwrap_or(0),
+        }
+    }
+
+    pub fn to_utc(&self, local: &NaiveDateTime) -> DateTime<Utc> {
+        let as_utc = Utc.from_utc_datetime(local);
+        let guess = as_utc - Duration::seconds(self.get_offset_at(as_utc) as i64);
+        as_utc - Duration::seconds(self.get_offset_at(guess) as i64)
+    }
+
+    pub fn to_local(&self, date: &DateTime<Utc>) -> NaiveDateTime {
+        (*date + Duration::seconds(self.get_offset_at(*date) as i64)).naive_utc()
+    }
+
+    pub fn to_ical(&self) -> ICalComponent {
+        let mut component = ICalComponent::new("VTIMEZONE");
+        component.add_property(ICalProperty::new("TZID", &self.tzid));
+        for observance in self.observances.iter() {
+            component.add_component(observance.to_ical());
+        }
+        component
+    }
+}
+
+impl ResolvedTimezone {
+    pub fn to_utc(&self, local: &NaiveDateTime) -> DateTime<Utc> {
+        match self {
+            Self::Defined(timezone) => timezone.to_utc(local),
+            Self::Standard(tzid) => match get_iana_timezone(tzid) {
+                Some(timezone) => iana_to_utc(&timezone, local),
+                None => Utc.from_utc_datetime(local),
+            },
+        }
+    }
+
+    pub fn to_local(&self, date: &DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            Self::Defined(timezone) => timezone.to_local(date),
+            Self::Standard(tzid) => match get_iana_timezone(tzid) {
+                Some(timezone) => iana_to_local(&timezone, date),
+                None => date.naive_utc(),
+            },
+        }
+    }
+}
+
+impl TimezoneObservance {
+    pub fn from_ical(component: &ICalComponent) -> Result<Self, ICalError> {
+        let observance_type = if component.is("DAYLIGHT") {
+            TimezoneObservanceType::Daylight
+        } else {
+            TimezoneObservanceType::Standard
+        };
+
+        let start = get_local_date_time(component.get_required_property("DTSTART")?)?;
+        let offset_from = get_utc_offset(component.get_required_property("TZOFFSETFROM")?)?;
+        let offset_to = get_utc_offset(component.get_required_property("TZOFFSETTO")?)?;
+
+        let recurrence = match component.get_property("RRULE") {
+            Some(property) => Some(Recurrence::from_ical(property.get_value())?),
+            None => None,
+        };
+
+        let mut dates = Vec::new();
+        for property in component.get_properties("RDATE") {
+            for value in property.values.iter() {
+                let date = parse_date_time(value).ok_or_else(|| property.invalid_value())?;
+                dates.push(date.naive_utc());
+            }
+        }
+
+        Ok(Self {
+            observance_type,
+            start,
+            offset_from,
+            offset_to,
+            name: component
+                .get_property("TZNAME")
+                .map(|property| property.get_value().to_string()),
+            recurrence,
+            dates,
+        })
+    }
+
+    pub fn to_ical(&self) -> ICalComponent {
+        let mut component = ICalComponent::new(match self.observance_type {
+            TimezoneObservanceType::Standard => "STANDARD",
+            TimezoneObservanceType::Daylight => "DAYLIGHT",
+        });
+
+        component.add_property(ICalProperty::new("DTSTART", &format_local_date_time(&self.start)));
+        component.add_property(ICalProperty::new("TZOFFSETFROM", &format_utc_offset(self.offset_from)));
+        component.add_property(ICalProperty::new("TZOFFSETTO", &format_utc_offset(self.offset_to)));
+        if let Some(name) = &self.name {
+            component.add_property(ICalProperty::new("TZNAME", name));
+        }
+        if let Some(recurrence) = &self.recurrence {
+            component.add_property(ICalProperty::new("RRULE", &recurrence.to_ical()));
+        }
+        for date in self.dates.iter() {
+            component.add_property(ICalProperty::new("RDATE", &format_local_date_time(date)));
+        }
+
+        component
+    }
+
+    fn get_last_onset_before(&self, date: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        /* Onsets are local times in offset_from, so they are compared against the instant in that same offset */
+        let local_limit = date + Duration::seconds(self.offset_from as i64);
+        let start = Utc.from_utc_datetime(&self.start);
+        if start > local_limit {
+            return None;
+        }
+
+        let mut onsets = vec![start];
+        onsets.extend(self.dates.iter().map(|date| Utc.from_utc_datetime(date)));
+        if let Some(recurrence) = &self.recurrence {
+            onsets.extend(
+                recurrence
+                    .calculate_ocurrences(Date::new(start), Date::new(local_limit))
+                    .iter()
+                    .map(|onset| onset.get_date_time()),
+            );
+        }
+
+        onsets
+            .into_iter()
+            .filter(|onset| *onset <= local_limit)
+            .max()
+            .map(|onset| onset - Duration::seconds(self.offset_from as i64))
+    }
+}
+
+fn get_local_date_time(property: &ICalProperty) -> Result<NaiveDateTime, ICalError> {
+    parse_date_time(property.get_value())
+        .map(|date| date.naive_utc())
+        .ok_or_else(|| property.invalid_value())
+}
+
+fn get_utc_offset(property: &ICalProperty) -> Result<i32, ICalError> {
+    /* utc-offset = time-numzone ; +HHMM[SS] or -HHMM[SS] */
+    let value = property.get_value().trim();
+    let (sign, digits) = match value.split_at_checked(1) {
+        Some(("+", digits)) => (1, digits),
+        Some(("-", digits)) => (-1, digits),
+        _ => return Err(property.invalid_value()),
+    };
+    if !(digits.len() == 4 || digits.len() == 6) || !digits.chars().all(|digit| digit.is_ascii_digit()) {
+        return Err(property.invalid_value());
+    }
+
+    let hours: i32 = digits[0..2].parse().unwrap();
+    let minutes: i32 = digits[2..4].parse().unwrap();
+    let seconds: i32 = digits.get(4..6).map(|seconds| seconds.parse().unwrap()).unwrap_or(0);
+    Ok(sign * (hours * 3600 + minutes * 60 + seconds))
+}
+
+fn format_utc_offset(offset: i32) -> String {
+    let sign = if offset < 0 { '-' } else { '+' };
+    let offset = offset.abs();
+    let (hours, minutes, seconds) = (offset / 3600, offset % 3600 / 60, offset % 60);
+    if seconds == 0 {
+        format!("{}{:02}{:02}", sign, hours, minutes)
+    } else {
+        format!("{}{:02}{:02}{:02}", sign, hours, minutes, seconds)
+    }
+}
@@ -0,0 +1,164 @@
+/*
+Standard timezones of the bundled IANA timezone database.
+
+Clients using timezones by reference (RFC 7809) send TZIDs like "Europe/Madrid" without their VTIMEZONE, so those are
+resolved against the database. The database has no recurrence rules, so a VTIMEZONE for a standard timezone is
+generated by finding every offset transition in a range of years, and listing them as RDATEs of its observances.
+*/
+
+use chrono::{DateTime, Duration, NaiveDateTime, Offset, TimeZone, Utc};
+use chrono_tz::{OffsetComponents, OffsetName, Tz};
+
+use crate::app::calendar::ical::{
+    property::ICalProperty,
+    values::{format_date_time, format_local_date_time},
+};
+
+use super::{Timezone, TimezoneObservance, TimezoneObservanceType};
+
+pub fn get_iana_timezone(tzid: &str) -> Option<Tz> {
+    tzid.parse::<Tz>().ok()
+}
+
+pub fn is_standard_tzid(tzid: &str) -> bool {
+    get_iana_timezone(tzid).is_some()
+}
+
+pub fn iana_to_utc(timezone: &Tz, local: &NaiveDateTime) -> DateTime<Utc> {
+    /* Local times skipped by a transition are moved forward, as RFC 5545 section 3.3.5 requires */
+    let date = timezone
+        .from_local_datetime(local)
+        .earliest()
+        .or_else(|| timezone.from_local_datetime(&(*local + Duration::hours(1))).earliest());
+
+    match date {
+        Some(date) => date.with_timezone(&Utc),
+        None => Utc.from_utc_datetime(local),
+    }
+}
+
+pub fn iana_to_local(timezone: &Tz, date: &DateTime<Utc>) -> NaiveDateTime {
+    timezone.from_utc_datetime(&date.naive_utc()).naive_local()
+}
+
+pub fn get_standard_tzid(property: &ICalProperty) -> Option<String> {
+    /* TZID of a date property, only when it is a standard one that can be stored by reference */
+    property
+        .get_parameter_value("TZID")
+        .filter(|tzid| is_standard_tzid(tzid))
+        .map(|tzid| tzid.to_string())
+}
+
+pub fn date_to_ical(name: &str, date: &DateTime<Utc>, tzid: Option<&str>) -> ICalProperty {
+    /* In local time with its TZID when a standard timezone is given, else in UTC */
+    match tzid.and_then(|tzid| get_iana_timezone(tzid).map(|timezone| (tzid, timezone))) {
+        Some((tzid, timezone)) => ICalProperty::new(name, &format_local_date_time(&iana_to_local(&timezone, date)))
+            .with_parameter("TZID", tzid),
+        None => ICalProperty::new(name, &format_date_time(date)),
+    }
+}
+
+impl Timezone {
+    pub fn from_iana(tzid: &str, from_year: i32, to_year: i32) -> Option<Self> {
+        /* Returns the definition of a standard timezone, with every transition from `from_year` until `to_year` */
+        let timezone = get_iana_timezone(tzid)?;
+        let start = Utc.with_ymd_and_hms(from_year, 1, 1, 0, 0, 0).single()?;
+        let end = Utc.with_ymd_and_hms(to_year + 1, 1, 1, 0, 0, 0).single()?;
+
+        let initial_offset = get_offset(&timezone, start);
+        let mut observances = vec![TimezoneObservance {
+            observance_type: get_observance_type(&timezone, start),
+            start: (start + Duration::seconds(initial_offset as i64)).naive_utc(),
+            offset_from: initial_offset,
+            offset_to: initial_offset,
+            name: Some(get_abbreviation(&timezone, start)),
+            recurrence: None,
+            dates: Vec::new(),
+        }];
+
+        let mut current = start;
+        while current < end {
+            let next = current + Duration::days(1);
+            if get_offset(&timezone, current) != get_offset(&timezone, next) {
+                let transition = find_transition(&timezone, current, next);
+                add_transition(&mut observances, &timezone, transition);
+            }
+            current = next;
+        }
+
+        Some(Self {
+            tzid: tzid.to_string(),
+            observances,
+        })
+    }
+}
+
+fn add_transition(observances: &mut Vec<TimezoneObservance>, timezone: &Tz, transition: DateTime<Utc>) {
+    /* Transitions with the same offsets and name are grouped in one observance */
+    let offset_from = get_offset(timezone, transition - Duration::seconds(1));
+    let offset_to = get_offset(timezone, transition);
+    let observance_type = get_observance_type(timezone, transition);
+    let name = get_abbreviation(timezone, transition);
+    let local_start = (transition + Duration::seconds(offset_from as i64)).naive_utc();
+
+    let existing = observances.iter_mut().find(|observance| {
+        observance.observance_type == observance_type
+            && observance.offset_from == offset_from
+            && observance.offset_to == offset_to
+            && observance.name.as_deref() == Some(name.as_str())
+    });
+
+    match existing {
+        Some(observance) => observance.dates.push(local_start),
+        None => observances.push(TimezoneObservance {
+            observance_type,
+            start: local_start,
+            offset_from,
+            offset_to,
+            name: Some(name),
+            recurrence: None,
+            dates: Vec::new(),
+        }),
+    }
+}
+
+fn find_transition(timezone: &Tz, before: DateTime<Utc>, after: DateTime<Utc>) -> DateTime<Utc> {
+    /* Binary search of the first second with the new offset */
+    let offset_before = get_offset(timezone, before);
+    let (mut low, mut high) = (before, after);
+    while (high - low).num_seconds() > 1 {
+        let middle = low + (high - low) / 2;
+        if get_offset(timezone, middle) == offset_before {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    high
+}
+
+fn get_offset(timezone: &Tz, date: DateTime<Utc>) -> i32 {
+    timezone
+        .offset_from_utc_datetime(&date.naive_utc())
+        .fix()
+        .local_minus_utc()
+}
+
+fn get_observance_type(timezone: &Tz, date: DateTime<Utc>) -> TimezoneObservanceType {
+    if timezone
+        .offset_from_utc_datetime(&date.naive_utc())
+        .dst_offset()
+        .is_zero()
+    {
+        TimezoneObservanceType::Standard
+    } else {
+        TimezoneObservanceType::Daylight
+    }
+}
+
+fn get_abbreviation(timezone: &Tz, date: DateTime<Utc>) -> String {
+    timezone
+        .offset_from_utc_datetime(&date.naive_utc())
+        .abbreviation()
+        .to_string()
+}
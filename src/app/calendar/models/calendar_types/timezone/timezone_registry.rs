@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+use crate::app::calendar::ical::{
+    component::ICalComponent, property::ICalProperty, values::parse_date_time, ICalError,
+};
+
+use super::{
+    iana_timezone::{get_iana_timezone, iana_to_utc, is_standard_tzid},
+    ResolvedTimezone, Timezone,
+};
+
+#[derive(Debug, Default, Clone)]
+pub struct TimezoneRegistry {
+    timezones: HashMap<String, Timezone>,
+}
+
+impl TimezoneRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_calendar(calendar: &ICalComponent) -> Result<Self, ICalError> {
+        /* Registers every VTIMEZONE defined in the calendar */
+        let mut registry = Self::new();
+        for component in calendar.get_components("VTIMEZONE") {
+            registry.add(Timezone::from_ical(component)?);
+        }
+        Ok(registry)
+    }
+
+    pub fn add(&mut self, timezone: Timezone) {
+        self.timezones.insert(timezone.tzid.clone(), timezone);
+    }
+
+    pub fn get(&self, tzid: &str) -> Option<&Timezone> {
+        self.timezones.get(tzid)
+    }
+
+    pub fn resolve(&self, tzid: &str) -> Option<ResolvedTimezone> {
+        /* The registered timezone, or the standard one of the IANA database without VTIMEZONE (RFC 7809) */
+        match self.get(tzid) {
+            Some(timezone) => Some(ResolvedTimezone::Defined(timezone.clone())),
+            None if is_standard_tzid(tzid) => Some(ResolvedTimezone::Standard(tzid.to_string())),
+            None => None,
+        }
+    }
+
+    pub fn get_date_time(&self, property: &ICalProperty) -> Result<DateTime<Utc>, ICalError> {
+        /*
+        Resolves a DATE-TIME property to its UTC instant.
+        UTC values are used as they are. Values with a TZID are converted with the registered timezone, or with the
+        IANA database if the TZID is a standard one without VTIMEZONE (RFC 7809).
+        Floating values and unknown timezones are interpreted as UTC.
+        */
+        self.get_value_date_time(property, property.get_value())
+    }
+
+    pub fn get_value_date_time(&self, property: &ICalProperty, value: &str) -> Result<DateTime<Utc>, ICalError> {
+        /* Same as get_date_time, for one of the values of a multi-valued property such as EXDATE */
+        let date = parse_date_time(value).ok_or_else(|| property.invalid_value())?;
+        if value.trim().ends_with('Z') {
+            return Ok(date);
+        }
+
+        let tzid = match property.get_parameter_value("TZID") {
+            Some(tzid) => tzid,
+            None => return Ok(date),
+        };
+
+        if let Some(timezone) = self.get(tzid) {
+            return Ok(timezone.to_utc(&date.naive_utc()));
+        }
+        match get_iana_timezone(tzid) {
+            Some(timezone) => Ok(iana_to_utc(&timezone, &date.naive_utc())),
+            None => Ok(date),
+        }
+    }
+}
@@ -0,0 +1,92 @@
+/*
+Timezones by reference (RFC 7809).
+
+The server advertises "calendar-no-timezone" in the DAV header, meaning it accepts calendar data referencing standard
+TZIDs without their VTIMEZONE. When returning calendar data, clients choose whether they want those VTIMEZONEs with:
+
+       CalDAV-Timezones: T   ; VTIMEZONEs are included (default)
+       CalDAV-Timezones: F   ; VTIMEZONEs of standard timezones are omitted
+
+Non standard timezones are always included, as the client could not resolve them.
+*/
+
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Utc};
+
+use crate::app::calendar::ical::{component::ICalComponent, values::parse_date_time};
+
+use super::{iana_timezone::is_standard_tzid, Timezone};
+
+pub const CALDAV_TIMEZONES_HEADER: &str = "CalDAV-Timezones";
+pub const CALENDAR_NO_TIMEZONE: &str = "calendar-no-timezone";
+
+pub fn should_include_timezones(header: Option<&str>) -> bool {
+    !matches!(header.map(|value| value.trim()), Some(value) if value.eq_ignore_ascii_case("F"))
+}
+
+pub fn apply_timezones_preference(calendar: &mut ICalComponent, include_timezones: bool) {
+    /* Adds the missing standard VTIMEZONEs referenced by the calendar, or removes them all */
+    if include_timezones {
+        add_missing_timezones(calendar);
+    } else {
+        calendar.components.retain(|component| {
+            !component.is("VTIMEZONE")
+                || !component
+                    .get_property("TZID")
+                    .map(|tzid| is_standard_tzid(tzid.get_value()))
+                    .unwrap_or(false)
+        });
+    }
+}
+
+fn add_missing_timezones(calendar: &mut ICalComponent) {
+    let mut referenced: BTreeMap<String, (i32, i32)> = BTreeMap::new();
+    for component in calendar
+        .components
+        .iter()
+        .filter(|component| !component.is("VTIMEZONE"))
+    {
+        collect_referenced_timezones(component, &mut referenced);
+    }
+
+    let current_year = Utc::now().year();
+    let mut timezones = Vec::new();
+    for (tzid, (from_year, to_year)) in referenced {
+        let defined = calendar.get_components("VTIMEZONE").any(|timezone| {
+            timezone
+                .get_property("TZID")
+                .map(|property| property.get_value() == tzid)
+                .unwrap_or(false)
+        });
+        if defined {
+            continue;
+        }
+
+        /* Recurring events keep happening after their last referenced date, so cover the coming years too */
+        if let Some(timezone) = Timezone::from_iana(&tzid, from_year, to_year.max(current_year) + 1) {
+            timezones.push(timezone.to_ical());
+        }
+    }
+
+    calendar.components.splice(0..0, timezones);
+}
+
+fn collect_referenced_timezones(component: &ICalComponent, referenced: &mut BTreeMap<String, (i32, i32)>) {
+    /* Maps each TZID to the range of years in which it is used */
+    for property in component.properties.iter() {
+        let tzid = match property.get_parameter_value("TZID") {
+            Some(tzid) => tzid,
+            None => continue,
+        };
+        for date in property.values.iter().filter_map(|value| parse_date_time(value)) {
+            let years = referenced.entry(tzid.to_string()).or_insert((date.year(), date.year()));
+            years.0 = years.0.min(date.year());
+            years.1 = years.1.max(date.year());
+        }
+    }
+
+    for child in component.components.iter() {
+        collect_referenced_timezones(child, referenced);
+    }
+}
@@ -0,0 +1,54 @@
+/*
+Format Definition:  This property is defined by the following
+      notation:
+
+       status          = "STATUS" statparam ":" statvalue CRLF
+
+       statparam       = *(";" other-param)
+
+       statvalue-todo  = "NEEDS-ACTION" ;Indicates to-do needs action.
+                       / "COMPLETED"    ;Indicates to-do completed.
+                       / "IN-PROCESS"   ;Indicates to-do in process of.
+                       / "CANCELLED"    ;Indicates to-do was cancelled.
+       ;Status values for "VTODO".
+*/
+
+use serde::{Deserialize, Serialize};
+
+use super::event_status::STATUS_VALUES;
+use crate::app::calendar::ical::values::parse_token;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum TodoStatus {
+    NeedsAction,
+    InProcess,
+    Completed,
+    Cancelled,
+
+    /* iana-token or x-name, uppercased */
+    Other(String),
+}
+
+impl TodoStatus {
+    pub fn from_ical(value: &str) -> Option<Self> {
+        let value = parse_token(value)?;
+        Some(match value.as_str() {
+            "NEEDS-ACTION" => TodoStatus::NeedsAction,
+            "IN-PROCESS" => TodoStatus::InProcess,
+            "COMPLETED" => TodoStatus::Completed,
+            "CANCELLED" => TodoStatus::Cancelled,
+            _ if STATUS_VALUES.contains(&value.as_str()) => return None,
+            _ => TodoStatus::Other(value),
+        })
+    }
+
+    pub fn to_ical(&self) -> &str {
+        match self {
+            TodoStatus::NeedsAction => "NEEDS-ACTION",
+            TodoStatus::InProcess => "IN-PROCESS",
+            TodoStatus::Completed => "COMPLETED",
+            TodoStatus::Cancelled => "CANCELLED",
+            TodoStatus::Other(value) => value,
+        }
+    }
+}
@@ -0,0 +1,106 @@
+/*
+Value Name:  URI
+
+   Purpose:  This value type is used to identify values that contain a uniform resource identifier (URI) type of
+      reference to the property value.
+
+   Format Definition:  This value type is defined by the following notation:
+
+       uri = <As defined in Section 3 of [RFC3986]>
+
+       URI         = scheme ":" hier-part [ "?" query ] [ "#" fragment ]
+       scheme      = ALPHA *( ALPHA / DIGIT / "+" / "-" / "." )
+
+   Example:  The following is a URI for a network file:
+
+       http://example.com/my-report.txt
+
+Only the scheme is checked in depth. The rest of the URI must be made of the characters allowed by RFC 3986, percent
+encoded octets included, so text with spaces or control characters is rejected.
+*/
+
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub struct Uri(String);
+
+impl Uri {
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let (scheme, rest) = value.split_once(':')?;
+        let mut scheme_chars = scheme.chars();
+        if !scheme_chars.next()?.is_ascii_alphabetic()
+            || !scheme_chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        {
+            return None;
+        }
+        if rest.is_empty() || !is_valid_uri_text(rest) {
+            return None;
+        }
+        Some(Self(value.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn get_scheme(&self) -> &str {
+        self.0.split_once(':').map(|(scheme, _)| scheme).unwrap_or_default()
+    }
+
+    pub fn from_ical(value: &str) -> Option<Self> {
+        Self::parse(value)
+    }
+
+    pub fn to_ical(&self) -> &str {
+        &self.0
+    }
+}
+
+fn is_valid_uri_text(text: &str) -> bool {
+    /* unreserved / reserved characters and pct-encoded octets */
+    let bytes = text.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'%' => {
+                let encoded = bytes.get(index + 1..index + 3);
+                if !encoded.is_some_and(|encoded| encoded.iter().all(u8::is_ascii_hexdigit)) {
+                    return false;
+                }
+                index += 3;
+                continue;
+            }
+            byte if byte.is_ascii_alphanumeric() => {}
+            b'-' | b'.' | b'_' | b'~' => {}
+            b':' | b'/' | b'?' | b'#' | b'[' | b']' | b'@' => {}
+            b'!' | b'$' | b'&' | b'\'' | b'(' | b')' | b'*' | b'+' | b',' | b';' | b'=' => {}
+            _ => return false,
+        }
+        index += 1;
+    }
+    true
+}
+
+impl TryFrom<String> for Uri {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value).ok_or_else(|| format!("Invalid URI: {}", value))
+    }
+}
+
+impl From<Uri> for String {
+    fn from(uri: Uri) -> Self {
+        uri.0
+    }
+}
+
+impl Display for Uri {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
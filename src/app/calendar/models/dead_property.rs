@@ -0,0 +1,79 @@
+/*
+Dead property of a WebDAV resource (RFC 4918 section 4.2).
+
+A property the server does not compute nor interpret, stored as set by a client: its XML element is kept whole, with
+its attributes and children, and returned as is. The dead properties of a resource are identified by their namespace
+and local name, and stored with the resource: in the document of a calendar collection, in the first component of a
+calendar object resource.
+*/
+
+use serde::{Deserialize, Serialize};
+
+use crate::architecture::xml::XmlElement;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct DeadProperty {
+    namespace: String,
+    name: String,
+
+    /* XML of the property element */
+    value: String,
+}
+
+impl DeadProperty {
+    pub fn from_xml(element: &XmlElement) -> Self {
+        Self {
+            namespace: element.get_namespace().to_string(),
+            name: element.name.clone(),
+            value: element.to_xml(),
+        }
+    }
+
+    pub fn get_namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is(&self, namespace: &str, name: &str) -> bool {
+        self.namespace == namespace && self.name == name
+    }
+
+    pub fn to_xml(&self) -> Option<XmlElement> {
+        XmlElement::parse(&self.value).ok()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+#[serde(transparent)]
+pub struct DeadProperties(Vec<DeadProperty>);
+
+impl DeadProperties {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, namespace: &str, name: &str) -> Option<&DeadProperty> {
+        self.0.iter().find(|property| property.is(namespace, name))
+    }
+
+    pub fn set(&mut self, property: DeadProperty) {
+        /* Replaces the value of the property */
+        self.remove(property.get_namespace(), property.get_name());
+        self.0.push(property);
+    }
+
+    pub fn remove(&mut self, namespace: &str, name: &str) {
+        self.0.retain(|property| !property.is(namespace, name));
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &DeadProperty> {
+        self.0.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
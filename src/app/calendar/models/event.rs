@@ -31,21 +31,57 @@ RELATED-TO: ; (Optional, MAY occur more than once)
 RESOURCES: ; (Optional, MAY occur more than once)
 RDATE: ; (Optional, MAY occur more than once)
 END:VEVENT
+
+The end of the event is given by DTEND or DURATION, but never both. Without any of them, an event starting on a date
+lasts the whole day, and one starting on a date-time has no duration.
 */
 
+mod event_ical;
+
 use bson::oid::ObjectId;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use super::calendar_types::{
-    attendee::Attendee, event_classification::EventClassification, event_status::EventStatus, geolocation::GeoLocation,
+    alarm::{calculate_alarm_times, calculate_next_alarm_time, Alarm, AlarmTime},
+    attachment::Attachment,
+    attendee::Attendee,
+    duration::Duration,
+    event_classification::EventClassification,
+    event_status::EventStatus,
+    geolocation::GeoLocation,
+    organizer::Organizer,
+    other_property::{OtherProperty, OtherPropertyParameters},
+    priority::Priority,
+    recurrence::Recurrence,
+    sequence::next_sequence,
+    uri::Uri,
 };
+use super::dead_property::DeadProperties;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Event {
     #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
     id: Option<ObjectId>,
 
+    /* Globally unique identifier of the event (UID) */
+    uid: String,
+
+    /* Calendar collection and name of the resource holding the event */
+    #[serde(default)]
+    calendar_id: Option<ObjectId>,
+
+    #[serde(default)]
+    resource_name: Option<String>,
+
+    /* Entity tag of the resource, changed on each write */
+    #[serde(default)]
+    etag: Option<String>,
+
+    /* Dead properties of the resource, held by its first component */
+    #[serde(default, skip_serializing_if = "DeadProperties::is_empty")]
+    dead_properties: DeadProperties,
+
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     created_at: DateTime<Utc>,
 
@@ -56,9 +92,30 @@ pub struct Event {
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     start: DateTime<Utc>,
 
-    /* Event end date */
-    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
-    end: DateTime<Utc>,
+    /* Event end date (DTEND), exclusive */
+    #[serde(
+        default,
+        with = "crate::architecture::mongodb::optional_chrono_datetime_as_bson_datetime"
+    )]
+    end: Option<DateTime<Utc>>,
+
+    /* Start of the instance of a recurring event overridden by this one (RECURRENCE-ID) */
+    #[serde(
+        default,
+        with = "crate::architecture::mongodb::optional_chrono_datetime_as_bson_datetime"
+    )]
+    recurrence_id: Option<DateTime<Utc>>,
+
+    /* Duration of the event, when given instead of the end date */
+    #[serde(default)]
+    duration: Option<Duration>,
+
+    /* Whether the dates are DATE values, without time */
+    #[serde(default)]
+    all_day: bool,
+
+    /* Standard (IANA) TZID of the start and end dates, used to return them in local time */
+    timezone: Option<String>,
 
     description: Option<String>,
 
@@ -70,10 +127,10 @@ pub struct Event {
 
     location: Option<String>,
 
-    organizer: Option<String>,
+    organizer: Option<Organizer>,
 
     /* Priority number, from 0 to 9 */
-    priority: Option<u8>,
+    priority: Option<Priority>,
 
     /* Event version, incremented on each update */
     version: u32,
@@ -82,9 +139,9 @@ pub struct Event {
 
     transparent: bool,
 
-    url: Option<String>,
+    url: Option<Uri>,
 
-    attachments: Option<Vec<String>>,
+    attachments: Option<Vec<Attachment>>,
 
     attendees: Option<Vec<Attendee>>,
 
@@ -93,5 +150,291 @@ pub struct Event {
     comments: Option<Vec<String>>,
 
     contacts: Option<Vec<String>>,
-    // recurrence
+
+    /* Recurrence rule (RRULE), with the excluded dates (EXDATE) */
+    recurrence: Option<Recurrence>,
+
+    /* Reminders (VALARM) */
+    alarms: Option<Vec<Alarm>>,
+
+    /* Properties and parameters not interpreted by the server, written back as received */
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    other_properties: Vec<OtherProperty>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    other_parameters: Vec<OtherPropertyParameters>,
+
+    /* Time of the next alarm, kept by the alarm scheduler to only load the events with upcoming alarms */
+    #[serde(
+        default,
+        with = "crate::architecture::mongodb::optional_chrono_datetime_as_bson_datetime"
+    )]
+    next_alarm_at: Option<DateTime<Utc>>,
+}
+
+impl Event {
+    /* MongoDB collection storing the events */
+    pub const COLLECTION: &'static str = "events";
+
+    pub fn get_id(&self) -> Option<ObjectId> {
+        self.id
+    }
+
+    pub fn set_id(&mut self, id: ObjectId) {
+        self.id = Some(id);
+    }
+
+    pub fn get_uid(&self) -> &str {
+        &self.uid
+    }
+
+    pub fn get_calendar_id(&self) -> Option<ObjectId> {
+        self.calendar_id
+    }
+
+    pub fn get_resource_name(&self) -> Option<&str> {
+        self.resource_name.as_deref()
+    }
+
+    pub fn set_resource(&mut self, calendar_id: ObjectId, resource_name: &str) {
+        self.calendar_id = Some(calendar_id);
+        self.resource_name = Some(resource_name.to_string());
+    }
+
+    pub fn get_etag(&self) -> Option<&str> {
+        self.etag.as_deref()
+    }
+
+    pub fn set_etag(&mut self, etag: &str) {
+        self.etag = Some(etag.to_string());
+    }
+
+    pub fn get_dead_properties(&self) -> &DeadProperties {
+        &self.dead_properties
+    }
+
+    pub fn get_dead_properties_mut(&mut self) -> &mut DeadProperties {
+        &mut self.dead_properties
+    }
+
+    pub fn get_created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn get_updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    pub fn touch(&mut self, previous: Option<&Self>, now: DateTime<Utc>) {
+        /* Written at `now`, created with its first version */
+        self.created_at = previous.map_or(now, |previous| previous.created_at);
+        self.updated_at = now;
+    }
+
+    pub fn get_start(&self) -> DateTime<Utc> {
+        self.start
+    }
+
+    pub fn get_end(&self) -> Option<DateTime<Utc>> {
+        self.end
+    }
+
+    pub fn get_recurrence_id(&self) -> Option<DateTime<Utc>> {
+        self.recurrence_id
+    }
+
+    pub fn is_override(&self) -> bool {
+        self.recurrence_id.is_some()
+    }
+
+    pub fn get_duration(&self) -> Option<&Duration> {
+        self.duration.as_ref()
+    }
+
+    pub fn is_all_day(&self) -> bool {
+        self.all_day
+    }
+
+    pub fn effective_end(&self) -> DateTime<Utc> {
+        match (self.end, &self.duration) {
+            (Some(end), _) => end,
+            (None, Some(duration)) => duration.add_to(self.start, self.get_timezone()),
+            (None, None) if self.all_day => Duration::days(1).add_to(self.start, None),
+            (None, None) => self.start,
+        }
+    }
+
+    pub fn get_timezone(&self) -> Option<&str> {
+        self.timezone.as_deref()
+    }
+
+    pub fn set_utc(&mut self) {
+        /* Writes the date-times in UTC instead of the timezone of the event */
+        self.timezone = None;
+    }
+
+    pub fn get_instance(&self, start: DateTime<Utc>) -> Self {
+        /* Instance of the recurring event starting at the date, identified by its RECURRENCE-ID */
+        let mut instance = self.clone();
+        instance.end = self.end.map(|end| start + (end - self.start));
+        instance.start = start;
+        instance.recurrence_id = Some(start);
+        instance.recurrence = None;
+        instance
+            .other_properties
+            .retain(|property| !property.name.eq_ignore_ascii_case("RDATE"));
+        instance
+    }
+
+    pub fn get_description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn get_summary(&self) -> Option<&str> {
+        self.summary.as_deref()
+    }
+
+    pub fn get_classification(&self) -> Option<&EventClassification> {
+        self.classification.as_ref()
+    }
+
+    pub fn get_geo_location(&self) -> Option<&GeoLocation> {
+        self.geo_location.as_ref()
+    }
+
+    pub fn get_location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
+
+    pub fn get_organizer(&self) -> Option<&Organizer> {
+        self.organizer.as_ref()
+    }
+
+    pub fn get_priority(&self) -> Option<Priority> {
+        self.priority
+    }
+
+    pub fn get_version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn has_significant_changes(&self, previous: &Self) -> bool {
+        self.start != previous.start
+            || self.end != previous.end
+            || self.duration != previous.duration
+            || self.all_day != previous.all_day
+            || self.recurrence != previous.recurrence
+            || self.status != previous.status
+    }
+
+    pub fn update_sequence(&mut self, previous: &Self) {
+        /* SEQUENCE of the event replacing `previous` */
+        self.version = next_sequence(self.version, previous.version, self.has_significant_changes(previous));
+    }
+
+    pub fn get_status(&self) -> Option<&EventStatus> {
+        self.status.as_ref()
+    }
+
+    pub fn is_transparent(&self) -> bool {
+        self.transparent
+    }
+
+    pub fn get_url(&self) -> Option<&Uri> {
+        self.url.as_ref()
+    }
+
+    pub fn get_attachments(&self) -> &[Attachment] {
+        self.attachments.as_deref().unwrap_or_default()
+    }
+
+    pub fn get_attachments_mut(&mut self) -> &mut [Attachment] {
+        self.attachments.as_deref_mut().unwrap_or_default()
+    }
+
+    pub fn add_attachment(&mut self, attachment: Attachment) {
+        self.attachments.get_or_insert_with(Vec::new).push(attachment);
+    }
+
+    pub fn remove_managed_attachment(&mut self, managed_id: &str) -> bool {
+        /* Whether the event had the attachment */
+        let Some(attachments) = &mut self.attachments else {
+            return false;
+        };
+        let count = attachments.len();
+        attachments.retain(|attachment| attachment.managed_id.as_deref() != Some(managed_id));
+        let removed = attachments.len() != count;
+        if attachments.is_empty() {
+            self.attachments = None;
+        }
+        removed
+    }
+
+    pub fn has_managed_attachment(&self, managed_id: &str) -> bool {
+        self.get_attachments()
+            .iter()
+            .any(|attachment| attachment.managed_id.as_deref() == Some(managed_id))
+    }
+
+    pub fn get_attendees(&self) -> &[Attendee] {
+        self.attendees.as_deref().unwrap_or_default()
+    }
+
+    pub fn get_categories(&self) -> &[String] {
+        self.categories.as_deref().unwrap_or_default()
+    }
+
+    pub fn get_comments(&self) -> &[String] {
+        self.comments.as_deref().unwrap_or_default()
+    }
+
+    pub fn get_contacts(&self) -> &[String] {
+        self.contacts.as_deref().unwrap_or_default()
+    }
+
+    pub fn get_recurrence(&self) -> Option<&Recurrence> {
+        self.recurrence.as_ref()
+    }
+
+    pub fn get_other_properties(&self) -> &[OtherProperty] {
+        &self.other_properties
+    }
+
+    pub fn get_alarms(&self) -> &[Alarm] {
+        self.alarms.as_deref().unwrap_or_default()
+    }
+
+    pub fn get_alarm_times(
+        &self,
+        overridden: &[DateTime<Utc>],
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Vec<AlarmTime> {
+        /* Alarms firing between `from` and `to`, for every instance of the event but the overridden ones */
+        calculate_alarm_times(
+            self.get_alarms(),
+            Some(self.start),
+            Some(self.effective_end()),
+            self.get_recurrence().filter(|_| !self.is_override()),
+            overridden,
+            from,
+            to,
+        )
+    }
+
+    pub fn get_next_alarm_at(&self) -> Option<DateTime<Utc>> {
+        self.next_alarm_at
+    }
+
+    pub fn update_next_alarm(&mut self, overridden: &[DateTime<Utc>], after: DateTime<Utc>) {
+        /* First alarm firing after `after`, for the instances of the event but the overridden ones */
+        self.next_alarm_at = calculate_next_alarm_time(
+            self.get_alarms(),
+            Some(self.start),
+            Some(self.effective_end()),
+            self.get_recurrence().filter(|_| !self.is_override()),
+            overridden,
+            after,
+        );
+    }
 }
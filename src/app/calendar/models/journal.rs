@@ -0,0 +1,313 @@
+/*
+BEGIN:VJOURNAL
+DTSTAMP: ; (Required, MUST NOT occur more than once)
+UID: ; (Required, MUST NOT occur more than once)
+CLASS: ; (Optional, MUST NOT occur more than once)
+CREATED: ; (Optional, MUST NOT occur more than once)
+DTSTART: ; (Optional, MUST NOT occur more than once)
+LAST-MODIFIED: ; (Optional, MUST NOT occur more than once)
+ORGANIZER: ; (Optional, MUST NOT occur more than once)
+RECURRENCE-ID: ; (Optional, MUST NOT occur more than once)
+SEQUENCE: ; (Optional, MUST NOT occur more than once)
+STATUS: ; (Optional, MUST NOT occur more than once)
+SUMMARY: ; (Optional, MUST NOT occur more than once)
+URL: ; (Optional, MUST NOT occur more than once)
+RRULE: ; (Optional, SHOULD NOT occur more than once)
+ATTACH: ; (Optional, MAY occur more than once)
+ATTENDEE: ; (Optional, MAY occur more than once)
+CATEGORIES: ; (Optional, MAY occur more than once)
+COMMENT: ; (Optional, MAY occur more than once)
+CONTACT: ; (Optional, MAY occur more than once)
+DESCRIPTION: ; (Optional, MAY occur more than once)
+EXDATE: ; (Optional, MAY occur more than once)
+RELATED-TO: ; (Optional, MAY occur more than once)
+RDATE: ; (Optional, MAY occur more than once)
+RSTATUS: ; (Optional, MAY occur more than once)
+END:VJOURNAL
+
+A journal entry has no duration: it is associated with the date or date-time of its start. The instances of a recurring
+entry are anchored at its start.
+*/
+
+mod journal_ical;
+
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::calendar_types::{
+    attachment::Attachment,
+    attendee::Attendee,
+    event_classification::EventClassification,
+    journal_status::JournalStatus,
+    organizer::Organizer,
+    other_property::{OtherProperty, OtherPropertyParameters},
+    recurrence::Recurrence,
+    relation::Relation,
+    sequence::next_sequence,
+    uri::Uri,
+};
+use super::dead_property::DeadProperties;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Journal {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    id: Option<ObjectId>,
+
+    /* Globally unique identifier of the journal entry (UID) */
+    uid: String,
+
+    /* Calendar collection and name of the resource holding the journal entry */
+    #[serde(default)]
+    calendar_id: Option<ObjectId>,
+
+    #[serde(default)]
+    resource_name: Option<String>,
+
+    /* Entity tag of the resource, changed on each write */
+    #[serde(default)]
+    etag: Option<String>,
+
+    /* Dead properties of the resource, held by its first component */
+    #[serde(default, skip_serializing_if = "DeadProperties::is_empty")]
+    dead_properties: DeadProperties,
+
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    created_at: DateTime<Utc>,
+
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    updated_at: DateTime<Utc>,
+
+    /* Date the journal entry is associated with */
+    #[serde(
+        default,
+        with = "crate::architecture::mongodb::optional_chrono_datetime_as_bson_datetime"
+    )]
+    start: Option<DateTime<Utc>>,
+
+    /* Start of the instance of a recurring journal entry overridden by this one (RECURRENCE-ID) */
+    #[serde(
+        default,
+        with = "crate::architecture::mongodb::optional_chrono_datetime_as_bson_datetime"
+    )]
+    recurrence_id: Option<DateTime<Utc>>,
+
+    /* Whether the start date is a DATE value, without time */
+    #[serde(default)]
+    all_day: bool,
+
+    /* Standard (IANA) TZID of the start date, used to return it in local time */
+    timezone: Option<String>,
+
+    /* A journal entry can have several descriptions, e.g. one per day of notes */
+    descriptions: Option<Vec<String>>,
+
+    summary: Option<String>,
+
+    classification: Option<EventClassification>,
+
+    organizer: Option<Organizer>,
+
+    /* Journal entry version, incremented on each update */
+    version: u32,
+
+    status: Option<JournalStatus>,
+
+    url: Option<Uri>,
+
+    attachments: Option<Vec<Attachment>>,
+
+    attendees: Option<Vec<Attendee>>,
+
+    categories: Option<Vec<String>>,
+
+    comments: Option<Vec<String>>,
+
+    contacts: Option<Vec<String>>,
+
+    /* Parent, child and sibling components (RELATED-TO) */
+    relations: Option<Vec<Relation>>,
+
+    /* Recurrence rule (RRULE), with the excluded dates (EXDATE) */
+    recurrence: Option<Recurrence>,
+
+    /* Properties and parameters not interpreted by the server, written back as received */
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    other_properties: Vec<OtherProperty>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    other_parameters: Vec<OtherPropertyParameters>,
+}
+
+impl Journal {
+    /* MongoDB collection storing the journal entries */
+    pub const COLLECTION: &'static str = "journals";
+
+    pub fn get_id(&self) -> Option<ObjectId> {
+        self.id
+    }
+
+    pub fn set_id(&mut self, id: ObjectId) {
+        self.id = Some(id);
+    }
+
+    pub fn get_uid(&self) -> &str {
+        &self.uid
+    }
+
+    pub fn get_calendar_id(&self) -> Option<ObjectId> {
+        self.calendar_id
+    }
+
+    pub fn get_resource_name(&self) -> Option<&str> {
+        self.resource_name.as_deref()
+    }
+
+    pub fn set_resource(&mut self, calendar_id: ObjectId, resource_name: &str) {
+        self.calendar_id = Some(calendar_id);
+        self.resource_name = Some(resource_name.to_string());
+    }
+
+    pub fn get_etag(&self) -> Option<&str> {
+        self.etag.as_deref()
+    }
+
+    pub fn set_etag(&mut self, etag: &str) {
+        self.etag = Some(etag.to_string());
+    }
+
+    pub fn get_dead_properties(&self) -> &DeadProperties {
+        &self.dead_properties
+    }
+
+    pub fn get_dead_properties_mut(&mut self) -> &mut DeadProperties {
+        &mut self.dead_properties
+    }
+
+    pub fn get_created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn get_updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    pub fn touch(&mut self, previous: Option<&Self>, now: DateTime<Utc>) {
+        /* Written at `now`, created with its first version */
+        self.created_at = previous.map_or(now, |previous| previous.created_at);
+        self.updated_at = now;
+    }
+
+    pub fn get_start(&self) -> Option<DateTime<Utc>> {
+        self.start
+    }
+
+    pub fn get_recurrence_id(&self) -> Option<DateTime<Utc>> {
+        self.recurrence_id
+    }
+
+    pub fn is_override(&self) -> bool {
+        self.recurrence_id.is_some()
+    }
+
+    pub fn is_all_day(&self) -> bool {
+        self.all_day
+    }
+
+    pub fn get_timezone(&self) -> Option<&str> {
+        self.timezone.as_deref()
+    }
+
+    pub fn set_utc(&mut self) {
+        /* Writes the date-times in UTC instead of the timezone of the journal entry */
+        self.timezone = None;
+    }
+
+    pub fn get_instance(&self, start: DateTime<Utc>) -> Self {
+        /* Instance of the recurring journal entry starting at the date, identified by its RECURRENCE-ID */
+        let mut instance = self.clone();
+        instance.start = Some(start);
+        instance.recurrence_id = Some(start);
+        instance.recurrence = None;
+        instance
+            .other_properties
+            .retain(|property| !property.name.eq_ignore_ascii_case("RDATE"));
+        instance
+    }
+
+    pub fn get_descriptions(&self) -> &[String] {
+        self.descriptions.as_deref().unwrap_or_default()
+    }
+
+    pub fn get_summary(&self) -> Option<&str> {
+        self.summary.as_deref()
+    }
+
+    pub fn get_classification(&self) -> Option<&EventClassification> {
+        self.classification.as_ref()
+    }
+
+    pub fn get_organizer(&self) -> Option<&Organizer> {
+        self.organizer.as_ref()
+    }
+
+    pub fn get_version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn has_significant_changes(&self, previous: &Self) -> bool {
+        self.start != previous.start
+            || self.all_day != previous.all_day
+            || self.recurrence != previous.recurrence
+            || self.status != previous.status
+    }
+
+    pub fn update_sequence(&mut self, previous: &Self) {
+        /* SEQUENCE of the journal entry replacing `previous` */
+        self.version = next_sequence(self.version, previous.version, self.has_significant_changes(previous));
+    }
+
+    pub fn get_status(&self) -> Option<&JournalStatus> {
+        self.status.as_ref()
+    }
+
+    pub fn get_url(&self) -> Option<&Uri> {
+        self.url.as_ref()
+    }
+
+    pub fn get_attachments(&self) -> &[Attachment] {
+        self.attachments.as_deref().unwrap_or_default()
+    }
+
+    pub fn get_attachments_mut(&mut self) -> &mut [Attachment] {
+        self.attachments.as_deref_mut().unwrap_or_default()
+    }
+
+    pub fn get_attendees(&self) -> &[Attendee] {
+        self.attendees.as_deref().unwrap_or_default()
+    }
+
+    pub fn get_categories(&self) -> &[String] {
+        self.categories.as_deref().unwrap_or_default()
+    }
+
+    pub fn get_comments(&self) -> &[String] {
+        self.comments.as_deref().unwrap_or_default()
+    }
+
+    pub fn get_contacts(&self) -> &[String] {
+        self.contacts.as_deref().unwrap_or_default()
+    }
+
+    pub fn get_relations(&self) -> &[Relation] {
+        self.relations.as_deref().unwrap_or_default()
+    }
+
+    pub fn get_recurrence(&self) -> Option<&Recurrence> {
+        self.recurrence.as_ref()
+    }
+
+    pub fn get_other_properties(&self) -> &[OtherProperty] {
+        &self.other_properties
+    }
+}
@@ -0,0 +1,455 @@
+/*
+BEGIN:VTODO
+DTSTAMP: ; (Required, MUST NOT occur more than once)
+UID: ; (Required, MUST NOT occur more than once)
+CLASS: ; (Optional, MUST NOT occur more than once)
+COMPLETED: ; (Optional, MUST NOT occur more than once)
+CREATED: ; (Optional, MUST NOT occur more than once)
+DESCRIPTION: ; (Optional, MUST NOT occur more than once)
+DTSTART: ; (Optional, MUST NOT occur more than once)
+GEO: ; (Optional, MUST NOT occur more than once)
+LAST-MODIFIED: ; (Optional, MUST NOT occur more than once)
+LOCATION: ; (Optional, MUST NOT occur more than once)
+ORGANIZER: ; (Optional, MUST NOT occur more than once)
+PERCENT-COMPLETE: ; (Optional, MUST NOT occur more than once)
+PRIORITY: ; (Optional, MUST NOT occur more than once)
+RECURRENCE-ID: ; (Optional, MUST NOT occur more than once)
+SEQUENCE: ; (Optional, MUST NOT occur more than once)
+STATUS: ; (Optional, MUST NOT occur more than once)
+SUMMARY: ; (Optional, MUST NOT occur more than once)
+URL: ; (Optional, MUST NOT occur more than once)
+RRULE: ; (Optional, SHOULD NOT occur more than once)
+DUE: ; (Optional, MUST NOT occur more than once if DURATION is present)
+DURATION: ; (Optional, MUST NOT occur more than once if DUE is present)
+ATTACH: ; (Optional, MAY occur more than once)
+ATTENDEE: ; (Optional, MAY occur more than once)
+CATEGORIES: ; (Optional, MAY occur more than once)
+COMMENT: ; (Optional, MAY occur more than once)
+CONTACT: ; (Optional, MAY occur more than once)
+EXDATE: ; (Optional, MAY occur more than once)
+RSTATUS: ; (Optional, MAY occur more than once)
+RELATED-TO: ; (Optional, MAY occur more than once)
+RESOURCES: ; (Optional, MAY occur more than once)
+RDATE: ; (Optional, MAY occur more than once)
+END:VTODO
+
+The to-do is due at DUE, or after DURATION from its start, but never both. The instances of a recurring to-do are
+anchored at its start.
+*/
+
+mod todo_ical;
+
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::calendar_types::{
+    alarm::{calculate_alarm_times, calculate_next_alarm_time, Alarm, AlarmTime},
+    attachment::Attachment,
+    attendee::Attendee,
+    duration::Duration,
+    event_classification::EventClassification,
+    geolocation::GeoLocation,
+    organizer::Organizer,
+    other_property::{OtherProperty, OtherPropertyParameters},
+    priority::Priority,
+    recurrence::Recurrence,
+    relation::{Relation, RelationType},
+    sequence::next_sequence,
+    todo_status::TodoStatus,
+    uri::Uri,
+};
+use super::dead_property::DeadProperties;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Todo {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    id: Option<ObjectId>,
+
+    /* Globally unique identifier of the to-do (UID) */
+    uid: String,
+
+    /* Calendar collection and name of the resource holding the to-do */
+    #[serde(default)]
+    calendar_id: Option<ObjectId>,
+
+    #[serde(default)]
+    resource_name: Option<String>,
+
+    /* Entity tag of the resource, changed on each write */
+    #[serde(default)]
+    etag: Option<String>,
+
+    /* Dead properties of the resource, held by its first component */
+    #[serde(default, skip_serializing_if = "DeadProperties::is_empty")]
+    dead_properties: DeadProperties,
+
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    created_at: DateTime<Utc>,
+
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    updated_at: DateTime<Utc>,
+
+    /* To-do start date */
+    #[serde(
+        default,
+        with = "crate::architecture::mongodb::optional_chrono_datetime_as_bson_datetime"
+    )]
+    start: Option<DateTime<Utc>>,
+
+    /* Date by which the to-do is expected to be completed */
+    #[serde(
+        default,
+        with = "crate::architecture::mongodb::optional_chrono_datetime_as_bson_datetime"
+    )]
+    due: Option<DateTime<Utc>>,
+
+    /* Date at which the to-do was actually completed */
+    #[serde(
+        default,
+        with = "crate::architecture::mongodb::optional_chrono_datetime_as_bson_datetime"
+    )]
+    completed: Option<DateTime<Utc>>,
+
+    /* Start of the instance of a recurring to-do overridden by this one (RECURRENCE-ID) */
+    #[serde(
+        default,
+        with = "crate::architecture::mongodb::optional_chrono_datetime_as_bson_datetime"
+    )]
+    recurrence_id: Option<DateTime<Utc>>,
+
+    /* Duration of the to-do, when given instead of the due date */
+    #[serde(default)]
+    duration: Option<Duration>,
+
+    /* Whether the start and due dates are DATE values, without time */
+    #[serde(default)]
+    all_day: bool,
+
+    /* Standard (IANA) TZID of the start and due dates, used to return them in local time */
+    timezone: Option<String>,
+
+    /* Percent of completion, from 0 to 100 */
+    percent_complete: Option<u8>,
+
+    description: Option<String>,
+
+    summary: Option<String>,
+
+    classification: Option<EventClassification>,
+
+    geo_location: Option<GeoLocation>,
+
+    location: Option<String>,
+
+    organizer: Option<Organizer>,
+
+    /* Priority number, from 0 to 9 */
+    priority: Option<Priority>,
+
+    /* To-do version, incremented on each update */
+    version: u32,
+
+    status: Option<TodoStatus>,
+
+    url: Option<Uri>,
+
+    attachments: Option<Vec<Attachment>>,
+
+    attendees: Option<Vec<Attendee>>,
+
+    categories: Option<Vec<String>>,
+
+    comments: Option<Vec<String>>,
+
+    contacts: Option<Vec<String>>,
+
+    /* Parent, child and sibling components (RELATED-TO) */
+    relations: Option<Vec<Relation>>,
+
+    /* Recurrence rule (RRULE), with the excluded dates (EXDATE) */
+    recurrence: Option<Recurrence>,
+
+    /* Reminders (VALARM) */
+    alarms: Option<Vec<Alarm>>,
+
+    /* Time of the next alarm, kept by the alarm scheduler to only load the to-dos with upcoming alarms */
+    #[serde(
+        default,
+        with = "crate::architecture::mongodb::optional_chrono_datetime_as_bson_datetime"
+    )]
+    next_alarm_at: Option<DateTime<Utc>>,
+
+    /* Properties and parameters not interpreted by the server, written back as received */
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    other_properties: Vec<OtherProperty>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    other_parameters: Vec<OtherPropertyParameters>,
+}
+
+impl Todo {
+    /* MongoDB collection storing the to-dos */
+    pub const COLLECTION: &'static str = "todos";
+
+    pub fn get_id(&self) -> Option<ObjectId> {
+        self.id
+    }
+
+    pub fn set_id(&mut self, id: ObjectId) {
+        self.id = Some(id);
+    }
+
+    pub fn get_uid(&self) -> &str {
+        &self.uid
+    }
+
+    pub fn get_calendar_id(&self) -> Option<ObjectId> {
+        self.calendar_id
+    }
+
+    pub fn get_resource_name(&self) -> Option<&str> {
+        self.resource_name.as_deref()
+    }
+
+    pub fn set_resource(&mut self, calendar_id: ObjectId, resource_name: &str) {
+        self.calendar_id = Some(calendar_id);
+        self.resource_name = Some(resource_name.to_string());
+    }
+
+    pub fn get_etag(&self) -> Option<&str> {
+        self.etag.as_deref()
+    }
+
+    pub fn set_etag(&mut self, etag: &str) {
+        self.etag = Some(etag.to_string());
+    }
+
+    pub fn get_dead_properties(&self) -> &DeadProperties {
+        &self.dead_properties
+    }
+
+    pub fn get_dead_properties_mut(&mut self) -> &mut DeadProperties {
+        &mut self.dead_properties
+    }
+
+    pub fn get_created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn get_updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    pub fn touch(&mut self, previous: Option<&Self>, now: DateTime<Utc>) {
+        /* Written at `now`, created with its first version */
+        self.created_at = previous.map_or(now, |previous| previous.created_at);
+        self.updated_at = now;
+    }
+
+    pub fn get_start(&self) -> Option<DateTime<Utc>> {
+        self.start
+    }
+
+    pub fn get_due(&self) -> Option<DateTime<Utc>> {
+        self.due
+    }
+
+    pub fn get_completed(&self) -> Option<DateTime<Utc>> {
+        self.completed
+    }
+
+    pub fn get_recurrence_id(&self) -> Option<DateTime<Utc>> {
+        self.recurrence_id
+    }
+
+    pub fn is_override(&self) -> bool {
+        self.recurrence_id.is_some()
+    }
+
+    pub fn get_duration(&self) -> Option<&Duration> {
+        self.duration.as_ref()
+    }
+
+    pub fn is_all_day(&self) -> bool {
+        self.all_day
+    }
+
+    pub fn effective_due(&self) -> Option<DateTime<Utc>> {
+        match (self.due, self.start, &self.duration) {
+            (Some(due), _, _) => Some(due),
+            (None, Some(start), Some(duration)) => Some(duration.add_to(start, self.get_timezone())),
+            _ => None,
+        }
+    }
+
+    pub fn get_timezone(&self) -> Option<&str> {
+        self.timezone.as_deref()
+    }
+
+    pub fn set_utc(&mut self) {
+        /* Writes the date-times in UTC instead of the timezone of the to-do */
+        self.timezone = None;
+    }
+
+    pub fn get_instance(&self, start: DateTime<Utc>) -> Self {
+        /* Instance of the recurring to-do starting at the date, identified by its RECURRENCE-ID */
+        let mut instance = self.clone();
+        if let Some(master_start) = self.start {
+            instance.due = self.due.map(|due| start + (due - master_start));
+        }
+        instance.start = Some(start);
+        instance.recurrence_id = Some(start);
+        instance.recurrence = None;
+        instance
+            .other_properties
+            .retain(|property| !property.name.eq_ignore_ascii_case("RDATE"));
+        instance
+    }
+
+    pub fn get_percent_complete(&self) -> Option<u8> {
+        self.percent_complete
+    }
+
+    pub fn get_description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn get_summary(&self) -> Option<&str> {
+        self.summary.as_deref()
+    }
+
+    pub fn get_classification(&self) -> Option<&EventClassification> {
+        self.classification.as_ref()
+    }
+
+    pub fn get_geo_location(&self) -> Option<&GeoLocation> {
+        self.geo_location.as_ref()
+    }
+
+    pub fn get_location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
+
+    pub fn get_organizer(&self) -> Option<&Organizer> {
+        self.organizer.as_ref()
+    }
+
+    pub fn get_priority(&self) -> Option<Priority> {
+        self.priority
+    }
+
+    pub fn get_version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn has_significant_changes(&self, previous: &Self) -> bool {
+        self.start != previous.start
+            || self.due != previous.due
+            || self.duration != previous.duration
+            || self.all_day != previous.all_day
+            || self.recurrence != previous.recurrence
+            || self.status != previous.status
+    }
+
+    pub fn update_sequence(&mut self, previous: &Self) {
+        /* SEQUENCE of the to-do replacing `previous` */
+        self.version = next_sequence(self.version, previous.version, self.has_significant_changes(previous));
+    }
+
+    pub fn get_status(&self) -> Option<&TodoStatus> {
+        self.status.as_ref()
+    }
+
+    pub fn get_url(&self) -> Option<&Uri> {
+        self.url.as_ref()
+    }
+
+    pub fn get_attachments(&self) -> &[Attachment] {
+        self.attachments.as_deref().unwrap_or_default()
+    }
+
+    pub fn get_attachments_mut(&mut self) -> &mut [Attachment] {
+        self.attachments.as_deref_mut().unwrap_or_default()
+    }
+
+    pub fn get_attendees(&self) -> &[Attendee] {
+        self.attendees.as_deref().unwrap_or_default()
+    }
+
+    pub fn get_categories(&self) -> &[String] {
+        self.categories.as_deref().unwrap_or_default()
+    }
+
+    pub fn get_comments(&self) -> &[String] {
+        self.comments.as_deref().unwrap_or_default()
+    }
+
+    pub fn get_contacts(&self) -> &[String] {
+        self.contacts.as_deref().unwrap_or_default()
+    }
+
+    pub fn get_relations(&self) -> &[Relation] {
+        self.relations.as_deref().unwrap_or_default()
+    }
+
+    pub fn get_parent_uid(&self) -> Option<&str> {
+        self.get_relations()
+            .iter()
+            .find(|relation| relation.relation_type == RelationType::Parent)
+            .map(|relation| relation.uid.as_str())
+    }
+
+    pub fn get_recurrence(&self) -> Option<&Recurrence> {
+        self.recurrence.as_ref()
+    }
+
+    pub fn get_other_properties(&self) -> &[OtherProperty] {
+        &self.other_properties
+    }
+
+    pub fn get_alarms(&self) -> &[Alarm] {
+        self.alarms.as_deref().unwrap_or_default()
+    }
+
+    pub fn get_alarm_times(
+        &self,
+        overridden: &[DateTime<Utc>],
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Vec<AlarmTime> {
+        /* Alarms firing between `from` and `to`, for every instance of the to-do but the overridden ones */
+        calculate_alarm_times(
+            self.get_alarms(),
+            self.start,
+            self.effective_due(),
+            self.get_recurrence().filter(|_| !self.is_override()),
+            overridden,
+            from,
+            to,
+        )
+    }
+
+    pub fn get_next_alarm_at(&self) -> Option<DateTime<Utc>> {
+        self.next_alarm_at
+    }
+
+    pub fn update_next_alarm(&mut self, overridden: &[DateTime<Utc>], after: DateTime<Utc>) {
+        /* Completed to-dos need no reminder */
+        self.next_alarm_at = match self.is_completed() {
+            true => None,
+            false => calculate_next_alarm_time(
+                self.get_alarms(),
+                self.start,
+                self.effective_due(),
+                self.get_recurrence().filter(|_| !self.is_override()),
+                overridden,
+                after,
+            ),
+        };
+    }
+
+    pub fn is_completed(&self) -> bool {
+        matches!(self.status, Some(TodoStatus::Completed)) || self.completed.is_some()
+    }
+}
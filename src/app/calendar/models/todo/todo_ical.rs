@@ -0,0 +1,348 @@
+use chrono::{DateTime, Utc};
+
+use crate::app::calendar::{
+    ical::{
+        component::ICalComponent,
+        property::ICalProperty,
+        values::{format_date, format_date_time, get_date_time, get_integer, is_date_property},
+        ICalError,
+    },
+    models::{
+        calendar_types::{
+            alarm::Alarm,
+            attachment::Attachment,
+            attendee::Attendee,
+            duration::Duration,
+            event_classification::EventClassification,
+            geolocation::GeoLocation,
+            organizer::Organizer,
+            other_property::{add_other_properties, get_other_parameters, get_other_properties},
+            priority::Priority,
+            recurrence::Recurrence,
+            relation::Relation,
+            timezone::{
+                iana_timezone::{date_to_ical, get_standard_tzid},
+                timezone_registry::TimezoneRegistry,
+            },
+            todo_status::TodoStatus,
+            uri::Uri,
+        },
+        dead_property::DeadProperties,
+    },
+};
+
+use super::Todo;
+
+/* Properties held by the to-do, the others are kept verbatim */
+const KNOWN_PROPERTIES: [&str; 28] = [
+    "UID",
+    "DTSTAMP",
+    "DTSTART",
+    "DUE",
+    "DURATION",
+    "COMPLETED",
+    "PERCENT-COMPLETE",
+    "RECURRENCE-ID",
+    "CREATED",
+    "LAST-MODIFIED",
+    "CLASS",
+    "STATUS",
+    "GEO",
+    "PRIORITY",
+    "URL",
+    "SEQUENCE",
+    "RRULE",
+    "EXDATE",
+    "ATTACH",
+    "ATTENDEE",
+    "DESCRIPTION",
+    "SUMMARY",
+    "LOCATION",
+    "ORGANIZER",
+    "CATEGORIES",
+    "COMMENT",
+    "CONTACT",
+    "RELATED-TO",
+];
+
+impl Todo {
+    pub fn from_calendar(calendar: &ICalComponent) -> Result<Vec<Self>, ICalError> {
+        /* Returns every VTODO of the VCALENDAR, resolving their dates with the VTIMEZONEs it defines */
+        let timezones = TimezoneRegistry::from_calendar(calendar)?;
+        calendar
+            .get_components("VTODO")
+            .map(|component| Self::from_ical(component, &timezones))
+            .collect()
+    }
+
+    pub fn from_ical(component: &ICalComponent, timezones: &TimezoneRegistry) -> Result<Self, ICalError> {
+        if !component.is("VTODO") {
+            return Err(ICalError::InvalidComponent(component.name.clone()));
+        }
+
+        let uid = component.get_required_property("UID")?.get_value().to_string();
+        let dtstart = component.get_property("DTSTART");
+        let start = match dtstart {
+            Some(property) => Some(timezones.get_date_time(property)?),
+            None => None,
+        };
+        let all_day = dtstart
+            .or_else(|| component.get_property("DUE"))
+            .is_some_and(is_date_property);
+        let due = match component.get_property("DUE") {
+            Some(property) => {
+                /* DUE MUST be later than or equal to DTSTART, and have its same value type */
+                let due = timezones.get_date_time(property)?;
+                if start.is_some_and(|start| due < start) || is_date_property(property) != all_day {
+                    return Err(property.invalid_value());
+                }
+                Some(due)
+            }
+            None => None,
+        };
+        let recurrence_id = match component.get_property("RECURRENCE-ID") {
+            Some(property) => Some(timezones.get_date_time(property)?),
+            None => None,
+        };
+        let duration = match component.get_property("DURATION") {
+            Some(property) => {
+                /* DURATION requires DTSTART, and to-dos starting on a date MUST last a whole number of days */
+                let duration = Duration::from_ical(property.get_value()).ok_or_else(|| property.invalid_value())?;
+                if start.is_none() || duration.is_negative() || (all_day && duration.has_time()) {
+                    return Err(property.invalid_value());
+                }
+                Some(duration)
+            }
+            None => None,
+        };
+        if due.is_some() && duration.is_some() {
+            return Err(ICalError::InvalidComponent(
+                "VTODO with both DUE and DURATION".to_string(),
+            ));
+        }
+        let timezone = component
+            .get_property("DTSTART")
+            .or_else(|| component.get_property("DUE"))
+            .and_then(get_standard_tzid);
+
+        /* COMPLETED MUST be specified in UTC */
+        let completed = match component.get_property("COMPLETED") {
+            Some(property) => Some(get_date_time(property)?),
+            None => None,
+        };
+        let percent_complete = match component.get_property("PERCENT-COMPLETE") {
+            Some(property) => {
+                let percent = get_integer::<u8>(property)?;
+                if percent > 100 {
+                    return Err(property.invalid_value());
+                }
+                Some(percent)
+            }
+            None => None,
+        };
+
+        let dtstamp = match component.get_property("DTSTAMP") {
+            Some(property) => get_date_time(property)?,
+            None => Utc::now(),
+        };
+        let created_at = match component.get_property("CREATED") {
+            Some(property) => get_date_time(property)?,
+            None => dtstamp,
+        };
+        let updated_at = match component.get_property("LAST-MODIFIED") {
+            Some(property) => get_date_time(property)?,
+            None => dtstamp,
+        };
+
+        let classification = match component.get_property("CLASS") {
+            Some(property) => {
+                Some(EventClassification::from_ical(property.get_value()).ok_or_else(|| property.invalid_value())?)
+            }
+            None => None,
+        };
+        let status = match component.get_property("STATUS") {
+            Some(property) => {
+                Some(TodoStatus::from_ical(property.get_value()).ok_or_else(|| property.invalid_value())?)
+            }
+            None => None,
+        };
+        let geo_location = match component.get_property("GEO") {
+            Some(property) => {
+                Some(GeoLocation::from_ical(property.get_value()).ok_or_else(|| property.invalid_value())?)
+            }
+            None => None,
+        };
+        let priority = match component.get_property("PRIORITY") {
+            Some(property) => Some(Priority::from_ical(property.get_value()).ok_or_else(|| property.invalid_value())?),
+            None => None,
+        };
+        let url = match component.get_property("URL") {
+            Some(property) => Some(Uri::from_ical(property.get_value()).ok_or_else(|| property.invalid_value())?),
+            None => None,
+        };
+        let version = match component.get_property("SEQUENCE") {
+            Some(property) => get_integer::<u32>(property)?,
+            None => 0,
+        };
+
+        /* Without DTSTART, to anchor the instances, the to-do is a single instance */
+        let recurrence = Recurrence::from_component(component, timezones)?;
+
+        let attachments = component
+            .get_properties("ATTACH")
+            .map(Attachment::from_ical)
+            .collect::<Result<Vec<Attachment>, ICalError>>()?;
+
+        let attendees = component
+            .get_properties("ATTENDEE")
+            .map(Attendee::from_ical)
+            .collect::<Result<Vec<Attendee>, ICalError>>()?;
+        let relations = component
+            .get_properties("RELATED-TO")
+            .map(Relation::from_ical)
+            .collect::<Result<Vec<Relation>, ICalError>>()?;
+
+        let alarms = component
+            .get_components("VALARM")
+            .map(Alarm::from_ical)
+            .collect::<Result<Vec<Alarm>, ICalError>>()?;
+
+        Ok(Self {
+            id: None,
+            uid,
+            calendar_id: None,
+            resource_name: None,
+            etag: None,
+            dead_properties: DeadProperties::new(),
+            created_at,
+            updated_at,
+            start,
+            due,
+            completed,
+            recurrence_id,
+            duration,
+            all_day,
+            timezone,
+            percent_complete,
+            description: component.get_text("DESCRIPTION"),
+            summary: component.get_text("SUMMARY"),
+            classification,
+            geo_location,
+            location: component.get_text("LOCATION"),
+            organizer: component
+                .get_property("ORGANIZER")
+                .map(Organizer::from_ical)
+                .transpose()?,
+            priority,
+            version,
+            status,
+            url,
+            attachments: if attachments.is_empty() {
+                None
+            } else {
+                Some(attachments)
+            },
+            attendees: if attendees.is_empty() { None } else { Some(attendees) },
+            categories: component.get_texts("CATEGORIES"),
+            comments: component.get_texts("COMMENT"),
+            contacts: component.get_texts("CONTACT"),
+            relations: if relations.is_empty() { None } else { Some(relations) },
+            recurrence,
+            alarms: if alarms.is_empty() { None } else { Some(alarms) },
+            next_alarm_at: None,
+            other_properties: get_other_properties(component, &KNOWN_PROPERTIES),
+            other_parameters: get_other_parameters(component, &KNOWN_PROPERTIES),
+        })
+    }
+
+    pub fn to_ical(&self) -> ICalComponent {
+        let mut component = ICalComponent::new("VTODO");
+
+        component.add_property(ICalProperty::new("UID", &self.uid));
+        component.add_property(ICalProperty::new("DTSTAMP", &format_date_time(&self.updated_at)));
+        if let Some(start) = &self.start {
+            component.add_property(self.date_to_ical("DTSTART", start));
+        }
+        if let Some(due) = &self.due {
+            component.add_property(self.date_to_ical("DUE", due));
+        }
+        if let Some(duration) = &self.duration {
+            component.add_property(ICalProperty::new("DURATION", &duration.to_ical()));
+        }
+        if let Some(recurrence_id) = &self.recurrence_id {
+            component.add_property(self.date_to_ical("RECURRENCE-ID", recurrence_id));
+        }
+        if let Some(completed) = &self.completed {
+            component.add_property(ICalProperty::new("COMPLETED", &format_date_time(completed)));
+        }
+        if let Some(percent_complete) = self.percent_complete {
+            component.add_property(ICalProperty::new("PERCENT-COMPLETE", &percent_complete.to_string()));
+        }
+        component.add_property(ICalProperty::new("CREATED", &format_date_time(&self.created_at)));
+        component.add_property(ICalProperty::new("LAST-MODIFIED", &format_date_time(&self.updated_at)));
+        component.add_property(ICalProperty::new("SEQUENCE", &self.version.to_string()));
+
+        component.add_text("SUMMARY", &self.summary);
+        component.add_text("DESCRIPTION", &self.description);
+        component.add_text("LOCATION", &self.location);
+
+        if let Some(geo_location) = &self.geo_location {
+            component.add_property(ICalProperty::new("GEO", &geo_location.to_ical()));
+        }
+        if let Some(classification) = &self.classification {
+            component.add_property(ICalProperty::new("CLASS", classification.to_ical()));
+        }
+        if let Some(status) = &self.status {
+            component.add_property(ICalProperty::new("STATUS", status.to_ical()));
+        }
+        if let Some(priority) = self.priority {
+            component.add_property(ICalProperty::new("PRIORITY", &priority.to_ical()));
+        }
+
+        if let Some(url) = &self.url {
+            component.add_property(ICalProperty::new("URL", url.to_ical()));
+        }
+        if let Some(organizer) = &self.organizer {
+            component.add_property(organizer.to_ical());
+        }
+
+        for attendee in self.get_attendees() {
+            component.add_property(attendee.to_ical());
+        }
+
+        if !self.get_categories().is_empty() {
+            component.add_property(ICalProperty::with_values("CATEGORIES", self.get_categories().to_vec()));
+        }
+        component.add_texts("COMMENT", &self.comments);
+        component.add_texts("CONTACT", &self.contacts);
+        for attachment in self.get_attachments() {
+            if let Some(property) = attachment.to_ical() {
+                component.add_property(property);
+            }
+        }
+
+        for relation in self.get_relations() {
+            component.add_property(relation.to_ical());
+        }
+
+        if let Some(recurrence) = &self.recurrence {
+            recurrence.add_to_component(&mut component);
+        }
+
+        add_other_properties(&mut component, &self.other_properties, &self.other_parameters);
+
+        for alarm in self.get_alarms() {
+            component.add_component(alarm.to_ical());
+        }
+
+        component
+    }
+
+    fn date_to_ical(&self, name: &str, date: &DateTime<Utc>) -> ICalProperty {
+        if self.all_day {
+            ICalProperty::new(name, &format_date(date)).with_parameter("VALUE", "DATE")
+        } else {
+            date_to_ical(name, date, self.get_timezone())
+        }
+    }
+}
@@ -0,0 +1,14 @@
+pub mod calendar_data;
+pub mod calendar_filter;
+pub mod calendar_properties;
+pub mod dav_error;
+pub mod dav_path;
+pub mod dav_resource;
+pub mod free_busy;
+pub mod mkcol;
+pub mod multistatus;
+pub mod preconditions;
+pub mod property_name;
+pub mod propfind;
+pub mod proppatch;
+pub mod report;
@@ -0,0 +1,321 @@
+/*
+Calendar data requested by a report (RFC 4791 section 9.6), as the value of the CALDAV:calendar-data property.
+
+       <!ELEMENT calendar-data (comp?, (expand | limit-recurrence-set)?, limit-freebusy-set?)>
+       <!ATTLIST calendar-data content-type CDATA "text/calendar" version CDATA "2.0">
+
+       <!ELEMENT comp ((allprop | prop*), (allcomp | comp*))>
+       <!ATTLIST comp name CDATA #REQUIRED>
+
+       <!ELEMENT prop EMPTY>
+       <!ATTLIST prop name CDATA #REQUIRED novalue (yes | no) "no">
+
+       <!ELEMENT expand EMPTY>
+       <!ATTLIST expand start CDATA #REQUIRED end CDATA #REQUIRED>
+
+       <!ELEMENT limit-recurrence-set EMPTY>
+       <!ATTLIST limit-recurrence-set start CDATA #REQUIRED end CDATA #REQUIRED>
+
+The comp element selects the properties and components returned, starting with the VCALENDAR: a component without
+allprop returns the listed properties only, and without allcomp the listed components only.
+
+RFC 4791 section 9.6.5: the expand element "specifies that for a given calendaring REPORT request, the server MUST
+expand the recurrence set into calendar components that define exactly one recurrence instance, and MUST return only
+those whose scheduled time intersect a specified time range". The instances have a RECURRENCE-ID and no RRULE, and
+their date-times are in UTC.
+
+The data is returned as text/calendar, or as application/calendar+xml (xCal, RFC 6321) when requested by the
+content-type attribute.
+
+RFC 4791 section 9.6.6: the limit-recurrence-set element limits the overridden components returned to those that
+override an instance overlapping the time range, or that were moved into it. The master component is returned.
+*/
+
+use chrono::Duration;
+
+use super::calendar_filter::{
+    get_instances, get_journal_instances, get_todo_instances, journal_overlaps, todo_overlaps, FilterError, TimeRange,
+};
+use crate::{
+    app::calendar::{
+        ical::{component::ICalComponent, ICalFormat},
+        models::{
+            calendar_object::CalendarObject,
+            calendar_types::timezone::timezones_by_reference::apply_timezones_preference, event::Event,
+            journal::Journal, todo::Todo,
+        },
+    },
+    architecture::xml::{XmlElement, CALDAV_NAMESPACE, DAV_NAMESPACE},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CalendarDataError {
+    Invalid(String),
+
+    /* Media type the server cannot return (CALDAV:supported-calendar-data) */
+    UnsupportedType(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertySelection {
+    pub name: String,
+
+    /* Returns the property with an empty value */
+    pub novalue: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentSelection {
+    pub name: String,
+
+    /* None for every property (CALDAV:allprop) */
+    pub properties: Option<Vec<PropertySelection>>,
+
+    /* None for every component (CALDAV:allcomp) */
+    pub components: Option<Vec<ComponentSelection>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecurrenceSet {
+    /* One component per instance overlapping the range */
+    Expand(TimeRange),
+
+    /* The master component, and the overrides of the instances overlapping the range */
+    Limit(TimeRange),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CalendarData {
+    pub format: ICalFormat,
+
+    /* The whole calendar when None */
+    pub component: Option<ComponentSelection>,
+
+    /* Every component as stored when None */
+    pub recurrence_set: Option<RecurrenceSet>,
+}
+
+impl CalendarData {
+    pub fn parse(element: &XmlElement) -> Result<Self, CalendarDataError> {
+        let content_type = element.get_attribute("content-type").unwrap_or("text/calendar");
+        let version = element.get_attribute("version").unwrap_or("2.0");
+        let format = match ICalFormat::from_content_type(content_type) {
+            Some(format) if version == "2.0" => format,
+            _ => {
+                return Err(CalendarDataError::UnsupportedType(format!(
+                    "{} {}",
+                    content_type, version
+                )))
+            }
+        };
+
+        let component = match element.child(CALDAV_NAMESPACE, "comp") {
+            Some(comp) => {
+                let component = ComponentSelection::parse(comp)?;
+                if !component.name.eq_ignore_ascii_case("VCALENDAR") {
+                    return Err(CalendarDataError::Invalid(component.name));
+                }
+                Some(component)
+            }
+            None => None,
+        };
+        let recurrence_set = match (
+            element.child(CALDAV_NAMESPACE, "expand"),
+            element.child(CALDAV_NAMESPACE, "limit-recurrence-set"),
+        ) {
+            (Some(_), Some(_)) => {
+                return Err(CalendarDataError::Invalid(
+                    "both expand and limit-recurrence-set".to_string(),
+                ))
+            }
+            (Some(expand), None) => Some(RecurrenceSet::Expand(parse_time_range(expand)?)),
+            (None, Some(limit)) => Some(RecurrenceSet::Limit(parse_time_range(limit)?)),
+            (None, None) => None,
+        };
+        Ok(Self {
+            format,
+            component,
+            recurrence_set,
+        })
+    }
+
+    pub fn from_properties(element: &XmlElement) -> Result<Self, CalendarDataError> {
+        /* The CALDAV:calendar-data element of the DAV:prop of a report, the whole data when absent */
+        let calendar_data = element
+            .child(DAV_NAMESPACE, "prop")
+            .and_then(|prop| prop.child(CALDAV_NAMESPACE, "calendar-data"));
+        match calendar_data {
+            Some(calendar_data) => Self::parse(calendar_data),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn write(&self, object: &CalendarObject, include_timezones: bool) -> String {
+        let mut calendar = match &self.recurrence_set {
+            None => object.to_ical(),
+            Some(RecurrenceSet::Expand(time_range)) => {
+                let events = get_instances(object, time_range).into_iter().map(|mut instance| {
+                    instance.set_utc();
+                    instance.to_ical()
+                });
+                let todos = get_todo_instances(object, time_range).into_iter().map(|mut instance| {
+                    instance.set_utc();
+                    instance.to_ical()
+                });
+                let journals = get_journal_instances(object, time_range)
+                    .into_iter()
+                    .map(|mut instance| {
+                        instance.set_utc();
+                        instance.to_ical()
+                    });
+                ICalComponent::calendar(events.chain(todos).chain(journals).collect())
+            }
+            Some(RecurrenceSet::Limit(time_range)) => {
+                let events = object
+                    .get_events()
+                    .iter()
+                    .filter(|event| !event.is_override() || overrides_in_range(object, event, time_range))
+                    .map(Event::to_ical);
+                let todos = object
+                    .get_todos()
+                    .iter()
+                    .filter(|todo| !todo.is_override() || todo_overrides_in_range(object, todo, time_range))
+                    .map(Todo::to_ical);
+                let journals = object
+                    .get_journals()
+                    .iter()
+                    .filter(|journal| !journal.is_override() || journal_overrides_in_range(journal, time_range))
+                    .map(Journal::to_ical);
+                ICalComponent::calendar(events.chain(todos).chain(journals).collect())
+            }
+        };
+        apply_timezones_preference(&mut calendar, include_timezones);
+        if let Some(component) = &self.component {
+            calendar = component.select(&calendar);
+        }
+        self.format.write(&calendar)
+    }
+}
+
+impl ComponentSelection {
+    pub fn parse(element: &XmlElement) -> Result<Self, CalendarDataError> {
+        let name = get_name(element)?;
+        let properties = match element.child(CALDAV_NAMESPACE, "allprop") {
+            Some(_) => None,
+            None => Some(
+                element
+                    .children_named(CALDAV_NAMESPACE, "prop")
+                    .map(|prop| {
+                        Ok(PropertySelection {
+                            name: get_name(prop)?,
+                            novalue: prop.get_attribute("novalue") == Some("yes"),
+                        })
+                    })
+                    .collect::<Result<Vec<_>, CalendarDataError>>()?,
+            ),
+        };
+        let components = match element.child(CALDAV_NAMESPACE, "allcomp") {
+            Some(_) => None,
+            None => Some(
+                element
+                    .children_named(CALDAV_NAMESPACE, "comp")
+                    .map(Self::parse)
+                    .collect::<Result<Vec<_>, CalendarDataError>>()?,
+            ),
+        };
+        Ok(Self {
+            name,
+            properties,
+            components,
+        })
+    }
+
+    pub fn select(&self, component: &ICalComponent) -> ICalComponent {
+        /* Copy of the component with the selected properties and subcomponents */
+        let mut selected = ICalComponent::new(&component.name);
+        for property in component.properties.iter() {
+            let selection = match &self.properties {
+                None => Some(false),
+                Some(properties) => properties
+                    .iter()
+                    .find(|selection| property.name.eq_ignore_ascii_case(&selection.name))
+                    .map(|selection| selection.novalue),
+            };
+            match selection {
+                Some(false) => selected.add_property(property.clone()),
+                Some(true) => {
+                    let mut property = property.clone();
+                    property.values = vec![String::new()];
+                    selected.add_property(property);
+                }
+                None => {}
+            }
+        }
+        for subcomponent in component.components.iter() {
+            match &self.components {
+                None => selected.add_component(subcomponent.clone()),
+                Some(components) => {
+                    if let Some(selection) = components.iter().find(|selection| subcomponent.is(&selection.name)) {
+                        selected.add_component(selection.select(subcomponent));
+                    }
+                }
+            }
+        }
+        selected
+    }
+}
+
+fn get_name(element: &XmlElement) -> Result<String, CalendarDataError> {
+    element
+        .get_attribute("name")
+        .map(str::to_uppercase)
+        .ok_or_else(|| CalendarDataError::Invalid(format!("{} without name", element.name)))
+}
+
+fn overrides_in_range(object: &CalendarObject, event: &Event, time_range: &TimeRange) -> bool {
+    /* The override, or the instance it overrides, overlaps the range */
+    let duration = object
+        .get_master()
+        .map(|master| master.effective_end() - master.get_start())
+        .unwrap_or_else(Duration::zero);
+    time_range.overlaps(event.get_start(), event.effective_end())
+        || event
+            .get_recurrence_id()
+            .is_some_and(|recurrence_id| time_range.overlaps(recurrence_id, recurrence_id + duration))
+}
+
+fn todo_overrides_in_range(object: &CalendarObject, todo: &Todo, time_range: &TimeRange) -> bool {
+    /* As for the events, the instance overridden starting at its RECURRENCE-ID */
+    let duration = object
+        .get_todos()
+        .iter()
+        .find(|master| !master.is_override())
+        .and_then(|master| Some(master.effective_due()? - master.get_start()?))
+        .unwrap_or_else(Duration::zero);
+    todo_overlaps(todo, time_range)
+        || todo
+            .get_recurrence_id()
+            .is_some_and(|recurrence_id| time_range.overlaps(recurrence_id, recurrence_id + duration))
+}
+
+fn journal_overrides_in_range(journal: &Journal, time_range: &TimeRange) -> bool {
+    /* Journal entries last no time, the instance overridden is the one starting at the RECURRENCE-ID */
+    journal_overlaps(journal, time_range)
+        || journal
+            .get_recurrence_id()
+            .is_some_and(|recurrence_id| time_range.contains(recurrence_id))
+}
+
+fn parse_time_range(element: &XmlElement) -> Result<TimeRange, CalendarDataError> {
+    /* Both bounds are required */
+    match TimeRange::parse(element) {
+        Ok(time_range) if time_range.start.is_some() && time_range.end.is_some() => Ok(time_range),
+        Ok(_) => Err(CalendarDataError::Invalid(format!(
+            "{} without start or end",
+            element.name
+        ))),
+        Err(FilterError::Invalid(message) | FilterError::UnsupportedCollation(message)) => {
+            Err(CalendarDataError::Invalid(message))
+        }
+    }
+}
@@ -0,0 +1,649 @@
+/*
+Filter of the calendar-query REPORT (RFC 4791 section 9.7).
+
+       <!ELEMENT filter (comp-filter)>
+       <!ELEMENT comp-filter (is-not-defined | (time-range?, prop-filter*, comp-filter*))>
+       <!ELEMENT prop-filter (is-not-defined | ((time-range | text-match)?, param-filter*))>
+       <!ELEMENT param-filter (is-not-defined | text-match)?>
+       <!ELEMENT text-match (#PCDATA)>
+       <!ATTLIST text-match collation CDATA "i;ascii-casemap" negate-condition (yes | no) "no">
+       <!ELEMENT time-range EMPTY>
+       <!ATTLIST time-range start CDATA #IMPLIED end CDATA #IMPLIED>
+
+A comp-filter is true when a component with its name matches all its conditions, and a prop-filter when a property of
+the component does. The time range of an event, to-do or journal entry is matched against each of its instances,
+computed from its recurrence rule: the instances overridden by another component are matched by the overriding
+component instead.
+
+RFC 4791 section 9.9: a VTODO overlaps a time range depending on the properties it has.
+
+       DTSTART + DURATION    (start <= DTSTART+DURATION) AND ((end > DTSTART) OR (end >= DTSTART+DURATION))
+       DTSTART               (start <= DTSTART) AND (end > DTSTART)
+       DUE                   (start < DUE) AND (end >= DUE)
+       DTSTART + DUE         ((start < DUE) OR (start <= DTSTART)) AND ((end > DTSTART) OR (end >= DUE))
+       COMPLETED + CREATED   ((start <= CREATED) OR (start <= COMPLETED)) AND ((end >= CREATED) OR (end >= COMPLETED))
+       COMPLETED             (start <= COMPLETED) AND (end >= COMPLETED)
+       CREATED               (end > CREATED)
+
+A VJOURNAL overlaps it when starting within the range, its whole day for a DATE start. Without DTSTART it never does.
+*/
+
+use chrono::{DateTime, Duration, Utc};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{
+    app::calendar::{
+        ical::{component::ICalComponent, property::ICalProperty, values::parse_date_time},
+        models::{
+            calendar_object::CalendarObject,
+            calendar_types::{
+                recurrence::{date::Date, Recurrence},
+                timezone::timezone_registry::TimezoneRegistry,
+            },
+            event::Event,
+            journal::Journal,
+            todo::Todo,
+        },
+    },
+    architecture::xml::{XmlElement, CALDAV_NAMESPACE},
+};
+
+/* Days searched for instances on the open side of a time range, the expansion stopping at MAX_INSTANCES */
+const OPEN_RANGE_DAYS: i64 = 36525;
+
+/* Component of a resource matched by a comp-filter */
+#[derive(Debug, Clone, Copy)]
+enum ObjectComponent<'a> {
+    Event(&'a Event),
+    Todo(&'a Todo),
+    Journal(&'a Journal),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterError {
+    /* Filter not following the rules of the CALDAV:filter element (CALDAV:valid-filter) */
+    Invalid(String),
+
+    /* Collation of a text-match the server does not support (CALDAV:supported-collation) */
+    UnsupportedCollation(String),
+}
+
+impl FilterError {
+    pub fn get_condition(&self) -> &'static str {
+        match self {
+            Self::Invalid(_) => "valid-filter",
+            Self::UnsupportedCollation(_) => "supported-collation",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeRange {
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl TimeRange {
+    pub fn parse(element: &XmlElement) -> Result<Self, FilterError> {
+        /* UTC date-times, at least one of them, the start before the end (RFC 4791 section 9.9) */
+        let parse = |name: &str| match element.get_attribute(name).map(str::trim) {
+            None => Ok(None),
+            Some(value) if value.len() == 16 && value.ends_with('Z') => parse_date_time(value)
+                .map(Some)
+                .ok_or_else(|| FilterError::Invalid(format!("time-range {}", name))),
+            Some(_) => Err(FilterError::Invalid(format!("time-range {} not in UTC", name))),
+        };
+        let time_range = Self {
+            start: parse("start")?,
+            end: parse("end")?,
+        };
+        match (time_range.start, time_range.end) {
+            (None, None) => Err(FilterError::Invalid("time-range without start nor end".to_string())),
+            (Some(start), Some(end)) if start >= end => {
+                Err(FilterError::Invalid("time-range ending before its start".to_string()))
+            }
+            _ => Ok(time_range),
+        }
+    }
+
+    pub fn overlaps(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> bool {
+        /* Instances without duration match when they start within the range */
+        let starts_before_end = self.end.is_none_or(|range_end| start < range_end);
+        match end > start {
+            true => starts_before_end && self.start.is_none_or(|range_start| end > range_start),
+            false => starts_before_end && self.start.is_none_or(|range_start| start >= range_start),
+        }
+    }
+
+    pub fn contains(&self, date: DateTime<Utc>) -> bool {
+        self.overlaps(date, date)
+    }
+
+    pub fn get_bounds(&self, around: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+        /* Open sides bounded a century away from the date */
+        (
+            self.start.unwrap_or(around - Duration::days(OPEN_RANGE_DAYS)),
+            self.end.unwrap_or(around + Duration::days(OPEN_RANGE_DAYS)),
+        )
+    }
+}
+
+/* Collations of RFC 4790 and RFC 5051 */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Collation {
+    Octet,
+    AsciiCasemap,
+    UnicodeCasemap,
+}
+
+impl Collation {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "i;octet" => Some(Self::Octet),
+            "i;ascii-casemap" => Some(Self::AsciiCasemap),
+            "i;unicode-casemap" => Some(Self::UnicodeCasemap),
+            _ => None,
+        }
+    }
+
+    fn normalize(&self, text: &str) -> String {
+        match self {
+            Self::Octet => text.to_string(),
+            Self::AsciiCasemap => text.to_ascii_uppercase(),
+            /* Characters case mapped, then decomposed in compatibility mode (NFKD) */
+            Self::UnicodeCasemap => text.to_uppercase().nfkd().collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextMatch {
+    pub text: String,
+    pub collation: Collation,
+
+    /* negate-condition="yes" */
+    pub negate: bool,
+}
+
+impl TextMatch {
+    pub fn parse(element: &XmlElement) -> Result<Self, FilterError> {
+        let collation = element.get_attribute("collation").unwrap_or("i;ascii-casemap");
+        Ok(Self {
+            text: element.text.clone(),
+            collation: Collation::parse(collation)
+                .ok_or_else(|| FilterError::UnsupportedCollation(collation.to_string()))?,
+            negate: element.get_attribute("negate-condition") == Some("yes"),
+        })
+    }
+
+    pub fn matches(&self, value: &str) -> bool {
+        /* Substring match */
+        let contains = self
+            .collation
+            .normalize(value)
+            .contains(&self.collation.normalize(&self.text));
+        contains != self.negate
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamFilter {
+    pub name: String,
+    pub is_not_defined: bool,
+    pub text_match: Option<TextMatch>,
+}
+
+impl ParamFilter {
+    pub fn parse(element: &XmlElement) -> Result<Self, FilterError> {
+        let filter = Self {
+            name: get_name(element)?,
+            is_not_defined: element.child(CALDAV_NAMESPACE, "is-not-defined").is_some(),
+            text_match: parse_text_match(element)?,
+        };
+        if filter.is_not_defined && filter.text_match.is_some() {
+            return Err(FilterError::Invalid(format!("param-filter {}", filter.name)));
+        }
+        Ok(filter)
+    }
+
+    pub fn matches(&self, property: &ICalProperty) -> bool {
+        match property.get_parameter(&self.name) {
+            None => self.is_not_defined,
+            Some(_) if self.is_not_defined => false,
+            Some(parameter) => self
+                .text_match
+                .as_ref()
+                .is_none_or(|text_match| text_match.matches(&parameter.values.join(","))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropFilter {
+    pub name: String,
+    pub is_not_defined: bool,
+    pub time_range: Option<TimeRange>,
+    pub text_match: Option<TextMatch>,
+    pub param_filters: Vec<ParamFilter>,
+}
+
+impl PropFilter {
+    pub fn parse(element: &XmlElement) -> Result<Self, FilterError> {
+        let filter = Self {
+            name: get_name(element)?,
+            is_not_defined: element.child(CALDAV_NAMESPACE, "is-not-defined").is_some(),
+            time_range: parse_time_range(element)?,
+            text_match: parse_text_match(element)?,
+            param_filters: element
+                .children_named(CALDAV_NAMESPACE, "param-filter")
+                .map(ParamFilter::parse)
+                .collect::<Result<_, _>>()?,
+        };
+        let has_conditions =
+            filter.time_range.is_some() || filter.text_match.is_some() || !filter.param_filters.is_empty();
+        if (filter.is_not_defined && has_conditions) || (filter.time_range.is_some() && filter.text_match.is_some()) {
+            return Err(FilterError::Invalid(format!("prop-filter {}", filter.name)));
+        }
+        Ok(filter)
+    }
+
+    pub fn matches(&self, component: &ICalComponent, timezones: &TimezoneRegistry) -> bool {
+        /* Date values are resolved with their TZID, as when the events are parsed */
+        let mut properties = component.get_properties(&self.name);
+        if self.is_not_defined {
+            return properties.next().is_none();
+        }
+        properties.any(|property| {
+            self.time_range.as_ref().is_none_or(|time_range| {
+                timezones
+                    .get_date_time(property)
+                    .is_ok_and(|date| time_range.contains(date))
+            }) && self
+                .text_match
+                .as_ref()
+                .is_none_or(|text_match| text_match.matches(&property.values.join(",")))
+                && self.param_filters.iter().all(|filter| filter.matches(property))
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompFilter {
+    pub name: String,
+    pub is_not_defined: bool,
+    pub time_range: Option<TimeRange>,
+    pub prop_filters: Vec<PropFilter>,
+    pub comp_filters: Vec<CompFilter>,
+}
+
+impl CompFilter {
+    pub fn parse_filter(filter: &XmlElement) -> Result<Self, FilterError> {
+        /* <C:filter><C:comp-filter name="VCALENDAR">...</C:comp-filter></C:filter> */
+        let comp_filters: Vec<_> = filter.children_named(CALDAV_NAMESPACE, "comp-filter").collect();
+        let [comp_filter] = comp_filters.as_slice() else {
+            return Err(FilterError::Invalid("expected a single comp-filter".to_string()));
+        };
+        let filter = Self::parse(comp_filter)?;
+        if filter.name != "VCALENDAR" || filter.is_not_defined || filter.time_range.is_some() {
+            return Err(FilterError::Invalid("expected a VCALENDAR comp-filter".to_string()));
+        }
+        Ok(filter)
+    }
+
+    pub fn parse(element: &XmlElement) -> Result<Self, FilterError> {
+        let filter = Self {
+            name: get_name(element)?,
+            is_not_defined: element.child(CALDAV_NAMESPACE, "is-not-defined").is_some(),
+            time_range: parse_time_range(element)?,
+            prop_filters: element
+                .children_named(CALDAV_NAMESPACE, "prop-filter")
+                .map(PropFilter::parse)
+                .collect::<Result<_, _>>()?,
+            comp_filters: element
+                .children_named(CALDAV_NAMESPACE, "comp-filter")
+                .map(CompFilter::parse)
+                .collect::<Result<_, _>>()?,
+        };
+        let has_conditions =
+            filter.time_range.is_some() || !filter.prop_filters.is_empty() || !filter.comp_filters.is_empty();
+        if filter.is_not_defined && has_conditions {
+            return Err(FilterError::Invalid(format!("comp-filter {}", filter.name)));
+        }
+        Ok(filter)
+    }
+
+    pub fn get_event_time_range(&self) -> Option<&TimeRange> {
+        /* Time range some event of every matching resource overlaps, used to preselect the resources */
+        self.comp_filters
+            .iter()
+            .filter(|filter| filter.name == "VEVENT" && !filter.is_not_defined)
+            .find_map(|filter| filter.time_range.as_ref())
+    }
+
+    pub fn matches(&self, object: &CalendarObject) -> bool {
+        /* VCALENDAR filter, on the whole resource */
+        let calendar = object.to_ical();
+        let timezones = TimezoneRegistry::from_calendar(&calendar).unwrap_or_default();
+        self.prop_filters
+            .iter()
+            .all(|filter| filter.matches(&calendar, &timezones))
+            && self
+                .comp_filters
+                .iter()
+                .all(|filter| filter.matches_components(object, &calendar, &timezones))
+    }
+
+    fn matches_components(
+        &self,
+        object: &CalendarObject,
+        calendar: &ICalComponent,
+        timezones: &TimezoneRegistry,
+    ) -> bool {
+        /* The components of the VCALENDAR, one per event, to-do or journal entry, written in this order */
+        let overridden = object.get_overridden();
+        let mut candidates = object
+            .get_events()
+            .iter()
+            .map(ObjectComponent::Event)
+            .chain(object.get_todos().iter().map(ObjectComponent::Todo))
+            .chain(object.get_journals().iter().map(ObjectComponent::Journal))
+            .zip(calendar.components.iter())
+            .filter(|(_, component)| component.is(&self.name));
+        if self.is_not_defined {
+            return candidates.next().is_none();
+        }
+
+        candidates.any(|(object_component, component)| {
+            self.time_range
+                .as_ref()
+                .is_none_or(|time_range| object_component.overlaps(&overridden, time_range))
+                && self.matches_content(object_component, &overridden, component, timezones)
+        })
+    }
+
+    fn matches_subcomponents(
+        &self,
+        object_component: ObjectComponent,
+        overridden: &[DateTime<Utc>],
+        parent: &ICalComponent,
+        timezones: &TimezoneRegistry,
+    ) -> bool {
+        /* Components nested in an event or to-do: its alarms */
+        let mut candidates = parent
+            .components
+            .iter()
+            .filter(|component| component.is(&self.name))
+            .enumerate();
+        if self.is_not_defined {
+            return candidates.next().is_none();
+        }
+
+        candidates.any(|(index, component)| {
+            self.time_range.as_ref().is_none_or(|time_range| {
+                component.is("VALARM") && object_component.alarm_fires(overridden, index, time_range)
+            }) && self.matches_content(object_component, overridden, component, timezones)
+        })
+    }
+
+    fn matches_content(
+        &self,
+        object_component: ObjectComponent,
+        overridden: &[DateTime<Utc>],
+        component: &ICalComponent,
+        timezones: &TimezoneRegistry,
+    ) -> bool {
+        self.prop_filters
+            .iter()
+            .all(|filter| filter.matches(component, timezones))
+            && self
+                .comp_filters
+                .iter()
+                .all(|filter| filter.matches_subcomponents(object_component, overridden, component, timezones))
+    }
+}
+
+impl ObjectComponent<'_> {
+    fn overlaps(&self, overridden: &[DateTime<Utc>], time_range: &TimeRange) -> bool {
+        match self {
+            Self::Event(event) => !get_instance_starts(event, overridden, time_range).is_empty(),
+            Self::Todo(todo) => !get_todo_instance_starts(todo, overridden, time_range).is_empty(),
+            Self::Journal(journal) => !get_journal_instance_starts(journal, overridden, time_range).is_empty(),
+        }
+    }
+
+    fn alarm_fires(&self, overridden: &[DateTime<Utc>], alarm_index: usize, time_range: &TimeRange) -> bool {
+        /* RFC 4791 section 9.9: the alarm triggers within the range, for any instance of the component */
+        let times = match self {
+            Self::Event(event) => {
+                let (from, to) = time_range.get_bounds(event.get_start());
+                event.get_alarm_times(overridden, from, to)
+            }
+            Self::Todo(todo) => {
+                let around = todo
+                    .get_start()
+                    .or(todo.effective_due())
+                    .unwrap_or(todo.get_created_at());
+                let (from, to) = time_range.get_bounds(around);
+                todo.get_alarm_times(overridden, from, to)
+            }
+            /* Journal entries have no alarms */
+            Self::Journal(_) => Vec::new(),
+        };
+        times
+            .iter()
+            .any(|time| time.alarm_index == alarm_index && time_range.contains(time.fire_at))
+    }
+}
+
+pub fn get_instances(object: &CalendarObject, time_range: &TimeRange) -> Vec<Event> {
+    /* Instances of the resource overlapping the range, by start: the overrides replace the instances they override */
+    let overridden = object.get_overridden();
+    let mut instances: Vec<Event> = Vec::new();
+    for event in object.get_events() {
+        let starts = get_instance_starts(event, &overridden, time_range);
+        match event.get_recurrence().filter(|_| !event.is_override()) {
+            Some(_) => instances.extend(starts.into_iter().map(|start| event.get_instance(start))),
+            None if !starts.is_empty() => instances.push(event.clone()),
+            None => {}
+        }
+    }
+    instances.sort_by_key(Event::get_start);
+    instances
+}
+
+pub fn get_todo_instances(object: &CalendarObject, time_range: &TimeRange) -> Vec<Todo> {
+    /* Instances of the to-dos of the resource overlapping the range, as get_instances for the events */
+    let overridden = object.get_overridden();
+    let mut instances: Vec<Todo> = Vec::new();
+    for todo in object.get_todos() {
+        let starts = get_todo_instance_starts(todo, &overridden, time_range);
+        match todo.get_recurrence().filter(|_| !todo.is_override()) {
+            Some(_) => instances.extend(starts.into_iter().map(|start| todo.get_instance(start))),
+            None if !starts.is_empty() => instances.push(todo.clone()),
+            None => {}
+        }
+    }
+    instances.sort_by_key(Todo::get_start);
+    instances
+}
+
+pub fn get_journal_instances(object: &CalendarObject, time_range: &TimeRange) -> Vec<Journal> {
+    /* Instances of the journal entries of the resource overlapping the range, as get_instances for the events */
+    let overridden = object.get_overridden();
+    let mut instances: Vec<Journal> = Vec::new();
+    for journal in object.get_journals() {
+        let starts = get_journal_instance_starts(journal, &overridden, time_range);
+        match journal.get_recurrence().filter(|_| !journal.is_override()) {
+            Some(_) => instances.extend(starts.into_iter().map(|start| journal.get_instance(start))),
+            None if !starts.is_empty() => instances.push(journal.clone()),
+            None => {}
+        }
+    }
+    instances.sort_by_key(Journal::get_start);
+    instances
+}
+
+pub fn journal_overlaps(journal: &Journal, time_range: &TimeRange) -> bool {
+    /* The journal entry itself, not its other instances */
+    journal
+        .get_start()
+        .is_some_and(|start| journal_instance_overlaps(journal, start, time_range))
+}
+
+pub fn todo_overlaps(todo: &Todo, time_range: &TimeRange) -> bool {
+    /* The to-do itself, not its other instances */
+    todo_instance_overlaps(todo, todo.get_start(), todo.effective_due(), time_range)
+}
+
+fn get_instance_starts(event: &Event, overridden: &[DateTime<Utc>], time_range: &TimeRange) -> Vec<DateTime<Utc>> {
+    /* Starts of the instances of the event overlapping the range, but those overridden */
+    let start = event.get_start();
+    let duration = event.effective_end() - start;
+    get_recurrence_starts(
+        start,
+        duration,
+        event.get_recurrence().filter(|_| !event.is_override()),
+        overridden,
+        time_range,
+        |instance| time_range.overlaps(instance, instance + duration),
+    )
+}
+
+fn get_todo_instance_starts(todo: &Todo, overridden: &[DateTime<Utc>], time_range: &TimeRange) -> Vec<DateTime<Utc>> {
+    /* A to-do without start has a single instance, the recurrence requiring DTSTART */
+    let Some(start) = todo.get_start() else {
+        return match todo_overlaps(todo, time_range) {
+            true => vec![todo.get_created_at()],
+            false => Vec::new(),
+        };
+    };
+    let duration = todo.effective_due().map_or_else(Duration::zero, |due| due - start);
+    get_recurrence_starts(
+        start,
+        duration,
+        todo.get_recurrence().filter(|_| !todo.is_override()),
+        overridden,
+        time_range,
+        |instance| {
+            let due = todo.effective_due().map(|_| instance + duration);
+            todo_instance_overlaps(todo, Some(instance), due, time_range)
+        },
+    )
+}
+
+fn get_journal_instance_starts(
+    journal: &Journal,
+    overridden: &[DateTime<Utc>],
+    time_range: &TimeRange,
+) -> Vec<DateTime<Utc>> {
+    let Some(start) = journal.get_start() else {
+        return Vec::new();
+    };
+    let duration = match journal.is_all_day() {
+        true => Duration::days(1),
+        false => Duration::zero(),
+    };
+    get_recurrence_starts(
+        start,
+        duration,
+        journal.get_recurrence().filter(|_| !journal.is_override()),
+        overridden,
+        time_range,
+        |instance| journal_instance_overlaps(journal, instance, time_range),
+    )
+}
+
+fn journal_instance_overlaps(journal: &Journal, start: DateTime<Utc>, time_range: &TimeRange) -> bool {
+    /* RFC 4791 section 9.9: a DATE start lasts the whole day, a DATE-TIME one matches when within the range */
+    match journal.is_all_day() {
+        true => time_range.overlaps(start, start + Duration::days(1)),
+        false => {
+            time_range.start.is_none_or(|range_start| range_start <= start)
+                && time_range.end.is_none_or(|range_end| range_end > start)
+        }
+    }
+}
+
+fn get_recurrence_starts(
+    start: DateTime<Utc>,
+    duration: Duration,
+    recurrence: Option<&Recurrence>,
+    overridden: &[DateTime<Utc>],
+    time_range: &TimeRange,
+    overlaps: impl Fn(DateTime<Utc>) -> bool,
+) -> Vec<DateTime<Utc>> {
+    /* Starts of the instances overlapping the range, but those overridden */
+    let Some(recurrence) = recurrence else {
+        return match overlaps(start) {
+            true => vec![start],
+            false => Vec::new(),
+        };
+    };
+
+    /* Instances ending after the start of the range and starting before its end, at most MAX_INSTANCES of them */
+    let (from, until) = time_range.get_bounds(start);
+    recurrence
+        .calculate_ocurrences_between(Date::new(start), Date::new(from - duration), Date::new(until))
+        .iter()
+        .map(Date::get_date_time)
+        .filter(|instance| !overridden.contains(instance))
+        .filter(|instance| overlaps(*instance))
+        .collect()
+}
+
+fn todo_instance_overlaps(
+    todo: &Todo,
+    start: Option<DateTime<Utc>>,
+    due: Option<DateTime<Utc>>,
+    time_range: &TimeRange,
+) -> bool {
+    /* RFC 4791 section 9.9, the open sides of the range always matching */
+    let after_start = |condition: &dyn Fn(DateTime<Utc>) -> bool| time_range.start.is_none_or(condition);
+    let before_end = |condition: &dyn Fn(DateTime<Utc>) -> bool| time_range.end.is_none_or(condition);
+    match (start, due) {
+        (Some(start), Some(due)) if todo.get_due().is_none() => {
+            after_start(&|range_start| range_start <= due)
+                && before_end(&|range_end| range_end > start || range_end >= due)
+        }
+        (Some(start), Some(due)) => {
+            after_start(&|range_start| range_start < due || range_start <= start)
+                && before_end(&|range_end| range_end > start || range_end >= due)
+        }
+        (Some(start), None) => {
+            after_start(&|range_start| range_start <= start) && before_end(&|range_end| range_end > start)
+        }
+        (None, Some(due)) => after_start(&|range_start| range_start < due) && before_end(&|range_end| range_end >= due),
+        (None, None) => {
+            let created = todo.get_created_at();
+            match todo.get_completed() {
+                Some(completed) => {
+                    after_start(&|range_start| range_start <= created || range_start <= completed)
+                        && before_end(&|range_end| range_end >= created || range_end >= completed)
+                }
+                None => before_end(&|range_end| range_end > created),
+            }
+        }
+    }
+}
+
+fn get_name(element: &XmlElement) -> Result<String, FilterError> {
+    element
+        .get_attribute("name")
+        .map(|name| name.trim().to_uppercase())
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| FilterError::Invalid(format!("{} without name", element.name)))
+}
+
+fn parse_time_range(element: &XmlElement) -> Result<Option<TimeRange>, FilterError> {
+    element
+        .child(CALDAV_NAMESPACE, "time-range")
+        .map(TimeRange::parse)
+        .transpose()
+}
+
+fn parse_text_match(element: &XmlElement) -> Result<Option<TextMatch>, FilterError> {
+    element
+        .child(CALDAV_NAMESPACE, "text-match")
+        .map(TextMatch::parse)
+        .transpose()
+}
@@ -0,0 +1,163 @@
+/*
+Properties of a calendar collection set by clients, when creating it (MKCALENDAR, extended MKCOL) or updating it.
+
+       DAV:displayname
+       CALDAV:calendar-description
+       CALDAV:calendar-timezone                 ; VCALENDAR with a single VTIMEZONE (RFC 4791 section 5.2.2)
+       CALDAV:supported-calendar-component-set  ; <C:comp name="VEVENT"/>, of VEVENT, VTODO or VJOURNAL
+       ICAL:calendar-color                      ; #RRGGBB or #RRGGBBAA
+
+The other live properties are computed by the server and cannot be set. The properties unknown to the server are
+stored as dead properties.
+
+RFC 4791 section 5.2.3: [supported-calendar-component-set] MAY be defined on any calendar collection. If defined, it
+MUST be protected [...]. It is only set when creating the collection, not updated by PROPPATCH.
+*/
+
+use actix_web::http::StatusCode;
+
+use super::{dav_resource::is_live_property, property_name::PropertyName, proppatch::PropertyUpdate};
+use crate::{
+    app::calendar::{
+        ical::ics,
+        models::{calendar::Calendar, calendar_types::timezone::Timezone, dead_property::DeadProperty},
+    },
+    architecture::xml::{XmlElement, APPLE_ICAL_NAMESPACE, CALDAV_NAMESPACE, DAV_NAMESPACE},
+};
+
+/* Components the server stores */
+pub const SUPPORTED_COMPONENTS: [&str; 3] = ["VEVENT", "VTODO", "VJOURNAL"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertyError {
+    /* Computed by the server */
+    Protected,
+
+    /* Value with semantics not appropriate for the property */
+    InvalidValue,
+
+    /* calendar-timezone not holding a single valid VTIMEZONE */
+    InvalidTimezone,
+
+    /* Component types the server does not store */
+    UnsupportedComponent,
+
+    /* Properties the server does not store */
+    Unsupported,
+}
+
+impl PropertyError {
+    pub fn get_status(&self) -> StatusCode {
+        match self {
+            Self::InvalidValue => StatusCode::CONFLICT,
+            _ => StatusCode::FORBIDDEN,
+        }
+    }
+
+    pub fn get_condition(&self) -> Option<XmlElement> {
+        /* RFC 4918 section 16, RFC 4791 section 5.3.2.1 */
+        match self {
+            Self::Protected => Some(XmlElement::new(DAV_NAMESPACE, "cannot-modify-protected-property")),
+            Self::InvalidTimezone => Some(XmlElement::new(CALDAV_NAMESPACE, "valid-calendar-data")),
+            Self::UnsupportedComponent => Some(XmlElement::new(CALDAV_NAMESPACE, "supported-calendar-component")),
+            Self::InvalidValue | Self::Unsupported => None,
+        }
+    }
+}
+
+pub fn set_calendar_property(calendar: &mut Calendar, property: &XmlElement) -> Result<(), PropertyError> {
+    let text = property.text.trim();
+    match (property.get_namespace(), property.name.as_str()) {
+        (DAV_NAMESPACE, "displayname") => calendar.set_display_name(Some(property.text.clone())),
+        (CALDAV_NAMESPACE, "calendar-description") => calendar.set_description(Some(property.text.clone())),
+        (CALDAV_NAMESPACE, "calendar-timezone") => {
+            validate_timezone(text)?;
+            calendar.set_timezone(Some(property.text.clone()));
+        }
+        (CALDAV_NAMESPACE, "supported-calendar-component-set") => {
+            calendar.set_supported_components(get_components(property)?);
+        }
+        (APPLE_ICAL_NAMESPACE, "calendar-color") => {
+            if !is_color(text) {
+                return Err(PropertyError::InvalidValue);
+            }
+            calendar.set_color(Some(text.to_string()));
+        }
+        _ if is_protected(&PropertyName::from_xml(property)) => return Err(PropertyError::Protected),
+        _ => calendar.get_dead_properties_mut().set(DeadProperty::from_xml(property)),
+    }
+    Ok(())
+}
+
+pub fn remove_calendar_property(calendar: &mut Calendar, property: &PropertyName) -> Result<(), PropertyError> {
+    /* Removing a property without value is not an error (RFC 4918 section 14.23) */
+    match (property.get_namespace(), property.get_name()) {
+        (DAV_NAMESPACE, "displayname") => calendar.set_display_name(None),
+        (CALDAV_NAMESPACE, "calendar-description") => calendar.set_description(None),
+        (CALDAV_NAMESPACE, "calendar-timezone") => calendar.set_timezone(None),
+        (APPLE_ICAL_NAMESPACE, "calendar-color") => calendar.set_color(None),
+        _ if is_live_property(property) => return Err(PropertyError::Protected),
+        (namespace, name) => calendar.get_dead_properties_mut().remove(namespace, name),
+    }
+    Ok(())
+}
+
+pub fn update_calendar_property(calendar: &mut Calendar, update: &PropertyUpdate) -> Result<(), PropertyError> {
+    /* PROPPATCH instruction on an existing calendar */
+    if update
+        .get_property_name()
+        .is(CALDAV_NAMESPACE, "supported-calendar-component-set")
+    {
+        return Err(PropertyError::Protected);
+    }
+    match update {
+        PropertyUpdate::Set(property) => set_calendar_property(calendar, property),
+        PropertyUpdate::Remove(property) => remove_calendar_property(calendar, property),
+    }
+}
+
+pub fn is_protected(property: &PropertyName) -> bool {
+    let settable = [
+        (DAV_NAMESPACE, "displayname"),
+        (CALDAV_NAMESPACE, "calendar-description"),
+        (CALDAV_NAMESPACE, "calendar-timezone"),
+        (CALDAV_NAMESPACE, "supported-calendar-component-set"),
+        (APPLE_ICAL_NAMESPACE, "calendar-color"),
+    ];
+    is_live_property(property) && !settable.iter().any(|(namespace, name)| property.is(namespace, name))
+}
+
+fn validate_timezone(text: &str) -> Result<(), PropertyError> {
+    let calendar = ics::parse(text).map_err(|_| PropertyError::InvalidTimezone)?;
+    let timezones: Vec<_> = calendar.get_components("VTIMEZONE").collect();
+    match timezones.as_slice() {
+        [timezone] if calendar.is("VCALENDAR") && Timezone::from_ical(timezone).is_ok() => Ok(()),
+        _ => Err(PropertyError::InvalidTimezone),
+    }
+}
+
+fn get_components(property: &XmlElement) -> Result<Vec<String>, PropertyError> {
+    let mut components = Vec::new();
+    for comp in property.children_named(CALDAV_NAMESPACE, "comp") {
+        let name = comp
+            .get_attribute("name")
+            .ok_or(PropertyError::InvalidValue)?
+            .to_uppercase();
+        if !SUPPORTED_COMPONENTS.contains(&name.as_str()) {
+            return Err(PropertyError::UnsupportedComponent);
+        }
+        if !components.contains(&name) {
+            components.push(name);
+        }
+    }
+    if components.is_empty() {
+        return Err(PropertyError::InvalidValue);
+    }
+    Ok(components)
+}
+
+fn is_color(text: &str) -> bool {
+    /* #RRGGBB or #RRGGBBAA */
+    text.strip_prefix('#')
+        .is_some_and(|hex| matches!(hex.len(), 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
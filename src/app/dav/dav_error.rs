@@ -0,0 +1,24 @@
+/*
+Body of the error responses of the WebDAV methods, naming the precondition or postcondition that failed (RFC 4918
+section 16):
+
+       <D:error xmlns:D="DAV:"><D:propfind-finite-depth/></D:error>
+*/
+
+use actix_web::{http::StatusCode, HttpResponse};
+
+use crate::architecture::xml::{XmlElement, DAV_NAMESPACE};
+
+pub fn error_xml(condition: XmlElement) -> XmlElement {
+    XmlElement::new(DAV_NAMESPACE, "error").with_child(condition)
+}
+
+pub fn error_response(status: StatusCode, namespace: &str, condition: &str) -> HttpResponse {
+    condition_response(status, XmlElement::new(namespace, condition))
+}
+
+pub fn condition_response(status: StatusCode, condition: XmlElement) -> HttpResponse {
+    HttpResponse::build(status)
+        .content_type("application/xml; charset=utf-8")
+        .body(error_xml(condition).to_xml())
+}
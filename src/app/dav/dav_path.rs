@@ -0,0 +1,94 @@
+/*
+Paths of the WebDAV resources:
+
+       /calendars/                         ; collection of the calendars
+       /calendars/{calendar}/              ; calendar collection
+       /calendars/{calendar}/{resource}    ; calendar object resource
+
+Segments are percent-decoded when parsing and percent-encoded when writing the href of a resource (RFC 4918 section
+8.3.1).
+*/
+
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
+
+pub const CALENDARS_PATH: &str = "/calendars/";
+
+/* Characters escaped in a path segment: everything but unreserved and sub-delims characters (RFC 3986 section 3.3) */
+const SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'[')
+    .add(b'\\')
+    .add(b']')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DavPath {
+    Root,
+    Calendar(String),
+    Object { calendar: String, name: String },
+}
+
+impl DavPath {
+    pub fn parse(path: &str) -> Option<Self> {
+        let path = path.strip_prefix(CALENDARS_PATH.trim_end_matches('/'))?;
+        if !path.is_empty() && !path.starts_with('/') {
+            return None;
+        }
+
+        let mut segments = Vec::new();
+        for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+            let segment = percent_decode_str(segment).decode_utf8().ok()?;
+            if segment == "." || segment == ".." || segment.contains('/') {
+                return None;
+            }
+            segments.push(segment.to_string());
+        }
+
+        match segments.as_slice() {
+            [] => Some(Self::Root),
+            [calendar] => Some(Self::Calendar(calendar.clone())),
+            [calendar, name] => Some(Self::Object {
+                calendar: calendar.clone(),
+                name: name.clone(),
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn parse_href(href: &str) -> Option<Self> {
+        /* Absolute URI or absolute path */
+        let path = match href.split_once("://") {
+            Some((_, rest)) => rest.find('/').map(|index| &rest[index..]).unwrap_or("/"),
+            None => href,
+        };
+        Self::parse(path)
+    }
+
+    pub fn get_href(&self) -> String {
+        match self {
+            Self::Root => CALENDARS_PATH.to_string(),
+            Self::Calendar(calendar) => format!("{}{}/", CALENDARS_PATH, encode_segment(calendar)),
+            Self::Object { calendar, name } => format!(
+                "{}{}/{}",
+                CALENDARS_PATH,
+                encode_segment(calendar),
+                encode_segment(name)
+            ),
+        }
+    }
+}
+
+pub fn encode_segment(segment: &str) -> String {
+    utf8_percent_encode(segment, SEGMENT).to_string()
+}
@@ -0,0 +1,298 @@
+/*
+Live properties of the WebDAV resources (RFC 4918 section 15, RFC 4791 sections 4.2 and 5.2, RFC 8607 section 6.1).
+
+CalDAV properties are only returned when requested by name, not by an allprop request: they may be expensive to
+compute, as the calendar data of a resource. The dead properties of the calendars and their resources are returned
+with the live ones.
+*/
+
+use actix_web::http::StatusCode;
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use super::{
+    calendar_data::CalendarData,
+    calendar_properties::{update_calendar_property, PropertyError},
+    dav_path::DavPath,
+    multistatus::DavResponse,
+    property_name::PropertyName,
+    propfind::PropfindRequest,
+    proppatch::PropertyUpdate,
+};
+use crate::{
+    app::calendar::{
+        ical::ICalFormat,
+        models::{
+            calendar::Calendar,
+            calendar_object::CalendarObject,
+            calendar_types::recurrence::MAX_INSTANCES,
+            dead_property::{DeadProperties, DeadProperty},
+        },
+    },
+    architecture::xml::{XmlElement, APPLE_ICAL_NAMESPACE, CALDAV_NAMESPACE, CALENDARSERVER_NAMESPACE, DAV_NAMESPACE},
+};
+
+const ROOT_DISPLAY_NAME: &str = "Calendars";
+
+const ROOT_PROPERTIES: [(&str, &str); 2] = [(DAV_NAMESPACE, "resourcetype"), (DAV_NAMESPACE, "displayname")];
+
+const CALENDAR_PROPERTIES: [(&str, &str); 12] = [
+    (DAV_NAMESPACE, "resourcetype"),
+    (DAV_NAMESPACE, "displayname"),
+    (DAV_NAMESPACE, "creationdate"),
+    (DAV_NAMESPACE, "getlastmodified"),
+    (CALENDARSERVER_NAMESPACE, "getctag"),
+    (APPLE_ICAL_NAMESPACE, "calendar-color"),
+    (CALDAV_NAMESPACE, "calendar-description"),
+    (CALDAV_NAMESPACE, "calendar-timezone"),
+    (CALDAV_NAMESPACE, "supported-calendar-component-set"),
+    (CALDAV_NAMESPACE, "supported-calendar-data"),
+    (CALDAV_NAMESPACE, "max-attachment-size"),
+    (CALDAV_NAMESPACE, "max-instances"),
+];
+
+const OBJECT_PROPERTIES: [(&str, &str); 7] = [
+    (DAV_NAMESPACE, "resourcetype"),
+    (DAV_NAMESPACE, "creationdate"),
+    (DAV_NAMESPACE, "getlastmodified"),
+    (DAV_NAMESPACE, "getetag"),
+    (DAV_NAMESPACE, "getcontenttype"),
+    (DAV_NAMESPACE, "getcontentlength"),
+    (CALDAV_NAMESPACE, "calendar-data"),
+];
+
+pub fn is_live_property(property: &PropertyName) -> bool {
+    ROOT_PROPERTIES
+        .iter()
+        .chain(CALENDAR_PROPERTIES.iter())
+        .chain(OBJECT_PROPERTIES.iter())
+        .any(|(namespace, name)| property.is(namespace, name))
+}
+
+/* Values of the request used to compute the properties */
+#[derive(Debug, Clone)]
+pub struct DavContext {
+    pub max_attachment_size: u64,
+
+    /* Whether calendar data includes the standard VTIMEZONEs (CalDAV-Timezones header) */
+    pub include_timezones: bool,
+
+    /* Parts of the calendar data returned by a report */
+    pub calendar_data: CalendarData,
+}
+
+#[derive(Debug)]
+pub enum DavResource {
+    Root,
+    Calendar(Calendar),
+    Object { calendar: String, object: CalendarObject },
+}
+
+impl DavResource {
+    pub fn get_path(&self) -> DavPath {
+        match self {
+            Self::Root => DavPath::Root,
+            Self::Calendar(calendar) => DavPath::Calendar(calendar.get_name().to_string()),
+            Self::Object { calendar, object } => DavPath::Object {
+                calendar: calendar.clone(),
+                name: object.get_name().to_string(),
+            },
+        }
+    }
+
+    pub fn get_dead_properties(&self) -> Option<&DeadProperties> {
+        match self {
+            Self::Root => None,
+            Self::Calendar(calendar) => Some(calendar.get_dead_properties()),
+            Self::Object { object, .. } => object.get_dead_properties(),
+        }
+    }
+
+    pub fn get_property_names(&self) -> Vec<PropertyName> {
+        let mut names = self.get_live_property_names();
+        names.extend(self.get_dead_property_names());
+        names
+    }
+
+    pub fn get_property(&self, property: &PropertyName, context: &DavContext) -> Option<XmlElement> {
+        let live = match self {
+            Self::Root => get_root_property(property),
+            Self::Calendar(calendar) => get_calendar_property(calendar, property, context),
+            Self::Object { object, .. } => get_object_property(object, property, context),
+        };
+        live.or_else(|| {
+            self.get_dead_properties()?
+                .get(property.get_namespace(), property.get_name())?
+                .to_xml()
+        })
+    }
+
+    pub fn update_property(&mut self, update: &PropertyUpdate) -> Result<(), PropertyError> {
+        /* Instruction of a PROPPATCH request, applied to the resource in memory */
+        let dead_properties = match self {
+            Self::Calendar(calendar) => return update_calendar_property(calendar, update),
+            _ if is_live_property(&update.get_property_name()) => return Err(PropertyError::Protected),
+            Self::Root => None,
+            Self::Object { object, .. } => object.get_dead_properties_mut(),
+        };
+        let Some(dead_properties) = dead_properties else {
+            return Err(PropertyError::Unsupported);
+        };
+        match update {
+            PropertyUpdate::Set(property) => dead_properties.set(DeadProperty::from_xml(property)),
+            PropertyUpdate::Remove(property) => dead_properties.remove(property.get_namespace(), property.get_name()),
+        }
+        Ok(())
+    }
+
+    pub fn propfind(&self, request: &PropfindRequest, context: &DavContext) -> DavResponse {
+        let mut response = DavResponse::new(&self.get_path().get_href());
+
+        if *request == PropfindRequest::PropName {
+            for property in self.get_property_names() {
+                response.add_property(StatusCode::OK, property.to_xml());
+            }
+            return response;
+        }
+
+        let properties = match request {
+            PropfindRequest::AllProp { include } => {
+                let mut properties: Vec<PropertyName> = self
+                    .get_live_property_names()
+                    .into_iter()
+                    .filter(|property| property.get_namespace() != CALDAV_NAMESPACE)
+                    .chain(self.get_dead_property_names())
+                    .collect();
+                for property in include {
+                    if !properties.contains(property) {
+                        properties.push(property.clone());
+                    }
+                }
+                properties
+            }
+            PropfindRequest::Prop(properties) => properties.clone(),
+            PropfindRequest::PropName => Vec::new(),
+        };
+
+        for property in properties {
+            match self.get_property(&property, context) {
+                Some(value) => response.add_property(StatusCode::OK, value),
+                None => response.add_property(StatusCode::NOT_FOUND, property.to_xml()),
+            }
+        }
+        response
+    }
+
+    fn get_live_property_names(&self) -> Vec<PropertyName> {
+        let names: &[(&str, &str)] = match self {
+            Self::Root => &ROOT_PROPERTIES,
+            Self::Calendar(_) => &CALENDAR_PROPERTIES,
+            Self::Object { .. } => &OBJECT_PROPERTIES,
+        };
+        names
+            .iter()
+            .map(|(namespace, name)| PropertyName::new(namespace, name))
+            .collect()
+    }
+
+    fn get_dead_property_names(&self) -> Vec<PropertyName> {
+        self.get_dead_properties()
+            .map(|properties| {
+                properties
+                    .iter()
+                    .map(|property| PropertyName::new(property.get_namespace(), property.get_name()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+fn get_root_property(property: &PropertyName) -> Option<XmlElement> {
+    let value = property.to_xml();
+    match (property.get_namespace(), property.get_name()) {
+        (DAV_NAMESPACE, "resourcetype") => Some(value.with_child(XmlElement::new(DAV_NAMESPACE, "collection"))),
+        (DAV_NAMESPACE, "displayname") => Some(value.with_text(ROOT_DISPLAY_NAME)),
+        _ => None,
+    }
+}
+
+fn get_calendar_property(calendar: &Calendar, property: &PropertyName, context: &DavContext) -> Option<XmlElement> {
+    let value = property.to_xml();
+    match (property.get_namespace(), property.get_name()) {
+        (DAV_NAMESPACE, "resourcetype") => Some(
+            value
+                .with_child(XmlElement::new(DAV_NAMESPACE, "collection"))
+                .with_child(XmlElement::new(CALDAV_NAMESPACE, "calendar")),
+        ),
+        (DAV_NAMESPACE, "displayname") => calendar.get_display_name().map(|name| value.with_text(name)),
+        (DAV_NAMESPACE, "creationdate") => Some(value.with_text(&format_creation_date(&calendar.get_created_at()))),
+        (DAV_NAMESPACE, "getlastmodified") => Some(value.with_text(&format_http_date(&calendar.get_updated_at()))),
+        (CALENDARSERVER_NAMESPACE, "getctag") => Some(value.with_text(&calendar.get_ctag())),
+        (CALDAV_NAMESPACE, "calendar-description") => calendar
+            .get_description()
+            .map(|description| value.with_text(description)),
+        (CALDAV_NAMESPACE, "calendar-timezone") => calendar.get_timezone().map(|timezone| value.with_text(timezone)),
+        (APPLE_ICAL_NAMESPACE, "calendar-color") => calendar.get_color().map(|color| value.with_text(color)),
+        (CALDAV_NAMESPACE, "supported-calendar-component-set") => Some(
+            value.with_children(
+                calendar
+                    .get_supported_components()
+                    .iter()
+                    .map(|name| XmlElement::new(CALDAV_NAMESPACE, "comp").with_attribute("name", name))
+                    .collect(),
+            ),
+        ),
+        (CALDAV_NAMESPACE, "supported-calendar-data") => Some(
+            value.with_children(
+                ICalFormat::ALL
+                    .iter()
+                    .map(|format| {
+                        XmlElement::new(CALDAV_NAMESPACE, "calendar-data")
+                            .with_attribute("content-type", format.get_content_type())
+                            .with_attribute("version", "2.0")
+                    })
+                    .collect(),
+            ),
+        ),
+        (CALDAV_NAMESPACE, "max-attachment-size") => Some(value.with_text(&context.max_attachment_size.to_string())),
+        (CALDAV_NAMESPACE, "max-instances") => Some(value.with_text(&MAX_INSTANCES.to_string())),
+        _ => None,
+    }
+}
+
+fn get_object_property(object: &CalendarObject, property: &PropertyName, context: &DavContext) -> Option<XmlElement> {
+    let value = property.to_xml();
+    match (property.get_namespace(), property.get_name()) {
+        (DAV_NAMESPACE, "resourcetype") => Some(value),
+        (DAV_NAMESPACE, "creationdate") => object
+            .get_created_at()
+            .map(|date| value.with_text(&format_creation_date(&date))),
+        (DAV_NAMESPACE, "getlastmodified") => object
+            .get_last_modified()
+            .map(|date| value.with_text(&format_http_date(&date))),
+        (DAV_NAMESPACE, "getetag") => Some(value.with_text(&object.get_etag())),
+        (DAV_NAMESPACE, "getcontenttype") => Some(value.with_text(&object.get_content_type())),
+        (DAV_NAMESPACE, "getcontentlength") => {
+            Some(value.with_text(&object.to_ics(context.include_timezones).len().to_string()))
+        }
+        (CALDAV_NAMESPACE, "calendar-data") => {
+            let calendar_data = &context.calendar_data;
+            let value = value.with_text(&calendar_data.write(object, context.include_timezones));
+            /* text/calendar unless told otherwise */
+            match calendar_data.format {
+                ICalFormat::Ics => Some(value),
+                format => Some(value.with_attribute("content-type", format.get_content_type())),
+            }
+        }
+        _ => None,
+    }
+}
+
+pub fn format_creation_date(date: &DateTime<Utc>) -> String {
+    /* RFC 3339: 1997-12-01T17:42:21Z */
+    date.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+pub fn format_http_date(date: &DateTime<Utc>) -> String {
+    /* RFC 1123: Mon, 12 Jan 1998 09:25:56 GMT */
+    date.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
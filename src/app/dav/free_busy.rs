@@ -0,0 +1,179 @@
+/*
+Free busy time of a calendar collection (RFC 4791 section 7.10), as a VFREEBUSY component.
+
+       freebusy   = "FREEBUSY" fbparam ":" fbvalue CRLF
+       fbparam    = *(";" fbtypeparam / other-param)
+       fbtype     = "FREE" / "BUSY" / "BUSY-UNAVAILABLE" / "BUSY-TENTATIVE"
+       fbvalue    = period *("," period)
+
+The instances of the events overlapping the time range are busy, but the transparent and cancelled ones: tentative
+events are BUSY-TENTATIVE, the others BUSY. Overlapping and adjacent periods of a type are merged, and the time of a
+period is only reported once, with its busiest type: BUSY-UNAVAILABLE, then BUSY, then BUSY-TENTATIVE.
+
+BUSY-UNAVAILABLE is not reported from the calendars: RFC 4791 section 7.10 only derives it from VFREEBUSY and
+VAVAILABILITY components, which are not supported by the collections. It is only merged when added as a period.
+*/
+
+use chrono::{DateTime, Utc};
+
+use super::calendar_filter::{get_instances, TimeRange};
+use crate::app::calendar::{
+    ical::{component::ICalComponent, property::ICalProperty, values::format_date_time},
+    models::{calendar_object::CalendarObject, calendar_types::event_status::EventStatus, event::Event},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FreeBusyType {
+    /* Busiest first */
+    BusyUnavailable,
+    Busy,
+    BusyTentative,
+}
+
+impl FreeBusyType {
+    pub fn from_event(event: &Event) -> Option<Self> {
+        if event.is_transparent() {
+            return None;
+        }
+        match event.get_status() {
+            Some(EventStatus::Cancelled) => None,
+            Some(EventStatus::Tentative) => Some(Self::BusyTentative),
+            _ => Some(Self::Busy),
+        }
+    }
+
+    pub fn to_ical(&self) -> &str {
+        match self {
+            Self::BusyUnavailable => "BUSY-UNAVAILABLE",
+            Self::Busy => "BUSY",
+            Self::BusyTentative => "BUSY-TENTATIVE",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FreeBusyPeriod {
+    pub free_busy_type: FreeBusyType,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FreeBusy {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    periods: Vec<FreeBusyPeriod>,
+}
+
+impl FreeBusy {
+    pub fn new(start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self {
+            start,
+            end,
+            periods: Vec::new(),
+        }
+    }
+
+    pub fn add_object(&mut self, object: &CalendarObject) {
+        let time_range = TimeRange {
+            start: Some(self.start),
+            end: Some(self.end),
+        };
+        for instance in get_instances(object, &time_range) {
+            if let Some(free_busy_type) = FreeBusyType::from_event(&instance) {
+                self.add_period(free_busy_type, instance.get_start(), instance.effective_end());
+            }
+        }
+    }
+
+    pub fn add_period(&mut self, free_busy_type: FreeBusyType, start: DateTime<Utc>, end: DateTime<Utc>) {
+        /* Limited to the time range, events without duration are not busy */
+        let start = start.max(self.start);
+        let end = end.min(self.end);
+        if start < end {
+            self.periods.push(FreeBusyPeriod {
+                free_busy_type,
+                start,
+                end,
+            });
+        }
+    }
+
+    pub fn get_periods(&self) -> Vec<FreeBusyPeriod> {
+        /* Coalesced periods, by start */
+        let mut periods: Vec<FreeBusyPeriod> = Vec::new();
+        for free_busy_type in [
+            FreeBusyType::BusyUnavailable,
+            FreeBusyType::Busy,
+            FreeBusyType::BusyTentative,
+        ] {
+            let busier: Vec<FreeBusyPeriod> = periods.clone();
+            for period in merge_periods(&self.periods, free_busy_type) {
+                periods.extend(subtract_periods(period, &busier));
+            }
+        }
+        periods.sort_by_key(|period| period.start);
+        periods
+    }
+
+    pub fn to_ical(&self) -> ICalComponent {
+        let mut free_busy = ICalComponent::new("VFREEBUSY");
+        free_busy.add_property(ICalProperty::new("DTSTAMP", &format_date_time(&Utc::now())));
+        free_busy.add_property(ICalProperty::new("DTSTART", &format_date_time(&self.start)));
+        free_busy.add_property(ICalProperty::new("DTEND", &format_date_time(&self.end)));
+        for period in self.get_periods() {
+            let value = format!("{}/{}", format_date_time(&period.start), format_date_time(&period.end));
+            free_busy.add_property(
+                ICalProperty::new("FREEBUSY", &value).with_parameter("FBTYPE", period.free_busy_type.to_ical()),
+            );
+        }
+        ICalComponent::calendar(vec![free_busy])
+    }
+}
+
+fn merge_periods(periods: &[FreeBusyPeriod], free_busy_type: FreeBusyType) -> Vec<FreeBusyPeriod> {
+    /* Periods of the type, overlapping and adjacent ones merged */
+    let mut sorted: Vec<FreeBusyPeriod> = periods
+        .iter()
+        .filter(|period| period.free_busy_type == free_busy_type)
+        .copied()
+        .collect();
+    sorted.sort_by_key(|period| period.start);
+
+    let mut merged: Vec<FreeBusyPeriod> = Vec::new();
+    for period in sorted {
+        match merged.last_mut() {
+            Some(last) if period.start <= last.end => last.end = last.end.max(period.end),
+            _ => merged.push(period),
+        }
+    }
+    merged
+}
+
+fn subtract_periods(period: FreeBusyPeriod, busier: &[FreeBusyPeriod]) -> Vec<FreeBusyPeriod> {
+    /* Parts of the period not covered by the busier periods */
+    let mut parts = vec![period];
+    for other in busier {
+        parts = parts
+            .into_iter()
+            .flat_map(|part| {
+                if other.end <= part.start || other.start >= part.end {
+                    return vec![part];
+                }
+                let before = FreeBusyPeriod {
+                    end: other.start,
+                    ..part
+                };
+                let after = FreeBusyPeriod {
+                    start: other.end,
+                    ..part
+                };
+                [before, after]
+                    .into_iter()
+                    .filter(|part| part.start < part.end)
+                    .collect()
+            })
+            .collect();
+    }
+    parts
+}
@@ -0,0 +1,67 @@
+/*
+Bodies of the requests creating a calendar collection with its initial properties.
+
+MKCALENDAR (RFC 4791 section 5.3.1):
+
+       <!ELEMENT mkcalendar (DAV:set)>
+
+Extended MKCOL (RFC 5689 section 5.1), whose resourcetype selects the type of the collection:
+
+       <!ELEMENT mkcol (set+)>
+
+       <!ELEMENT set (prop)>
+*/
+
+use crate::architecture::xml::{XmlElement, XmlError, CALDAV_NAMESPACE, DAV_NAMESPACE};
+
+/* Compliance class advertised in the DAV header (RFC 5689 section 3) */
+pub const EXTENDED_MKCOL: &str = "extended-mkcol";
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MkcolRequest {
+    /* Properties to set, in request order */
+    pub properties: Vec<XmlElement>,
+}
+
+impl MkcolRequest {
+    pub fn parse_mkcalendar(body: &str) -> Result<Self, XmlError> {
+        Self::parse(body, CALDAV_NAMESPACE, "mkcalendar")
+    }
+
+    pub fn parse_mkcol(body: &str) -> Result<Self, XmlError> {
+        Self::parse(body, DAV_NAMESPACE, "mkcol")
+    }
+
+    fn parse(body: &str, namespace: &str, name: &str) -> Result<Self, XmlError> {
+        if body.trim().is_empty() {
+            return Ok(Self::default());
+        }
+
+        let root = XmlElement::parse(body)?;
+        if !root.is(namespace, name) {
+            return Err(XmlError::new(&format!("expected a {}{} element", namespace, name)));
+        }
+
+        let properties = root
+            .children_named(DAV_NAMESPACE, "set")
+            .filter_map(|set| set.child(DAV_NAMESPACE, "prop"))
+            .flat_map(|prop| prop.children.iter().cloned())
+            .collect();
+        Ok(Self { properties })
+    }
+
+    pub fn get_resource_type(&self) -> Option<&XmlElement> {
+        self.properties
+            .iter()
+            .find(|property| property.is(DAV_NAMESPACE, "resourcetype"))
+    }
+
+    pub fn is_calendar(&self) -> bool {
+        /* <D:resourcetype><D:collection/><C:calendar/></D:resourcetype> */
+        self.get_resource_type().is_some_and(|resource_type| {
+            resource_type.child(DAV_NAMESPACE, "collection").is_some()
+                && resource_type.child(CALDAV_NAMESPACE, "calendar").is_some()
+                && resource_type.children.len() == 2
+        })
+    }
+}
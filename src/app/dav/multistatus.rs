@@ -0,0 +1,147 @@
+/*
+Multi-Status response body (RFC 4918 section 13).
+
+       <!ELEMENT multistatus (response*, responsedescription?) >
+       <!ELEMENT response (href, ((href*, status)|(propstat+)), error?, responsedescription? , location?) >
+       <!ELEMENT propstat (prop, status, error?, responsedescription?) >
+
+The properties of a resource are grouped by the status of their retrieval or update, one propstat each.
+*/
+
+use actix_web::http::StatusCode;
+
+use crate::architecture::xml::{XmlElement, DAV_NAMESPACE};
+
+#[derive(Debug, Clone)]
+pub struct PropStat {
+    status: StatusCode,
+    properties: Vec<XmlElement>,
+
+    /* Precondition or postcondition that failed for the properties */
+    error: Option<XmlElement>,
+}
+
+impl PropStat {
+    pub fn get_status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn get_properties(&self) -> &[XmlElement] {
+        &self.properties
+    }
+
+    pub fn get_error(&self) -> Option<&XmlElement> {
+        self.error.as_ref()
+    }
+
+    pub fn to_xml(&self) -> XmlElement {
+        let propstat = XmlElement::new(DAV_NAMESPACE, "propstat")
+            .with_child(XmlElement::new(DAV_NAMESPACE, "prop").with_children(self.properties.clone()))
+            .with_child(status_xml(self.status));
+        match &self.error {
+            Some(error) => propstat.with_child(XmlElement::new(DAV_NAMESPACE, "error").with_child(error.clone())),
+            None => propstat,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DavResponse {
+    href: String,
+
+    /* Status of the resource itself, when it has no propstat */
+    status: Option<StatusCode>,
+
+    propstats: Vec<PropStat>,
+}
+
+impl DavResponse {
+    pub fn new(href: &str) -> Self {
+        Self {
+            href: href.to_string(),
+            status: None,
+            propstats: Vec::new(),
+        }
+    }
+
+    pub fn with_status(href: &str, status: StatusCode) -> Self {
+        Self {
+            status: Some(status),
+            ..Self::new(href)
+        }
+    }
+
+    pub fn get_href(&self) -> &str {
+        &self.href
+    }
+
+    pub fn get_status(&self) -> Option<StatusCode> {
+        self.status
+    }
+
+    pub fn get_propstats(&self) -> &[PropStat] {
+        &self.propstats
+    }
+
+    pub fn add_property(&mut self, status: StatusCode, property: XmlElement) {
+        self.add_property_with_error(status, property, None);
+    }
+
+    pub fn add_property_with_error(&mut self, status: StatusCode, property: XmlElement, error: Option<XmlElement>) {
+        match self
+            .propstats
+            .iter_mut()
+            .find(|propstat| propstat.status == status && propstat.error == error)
+        {
+            Some(propstat) => propstat.properties.push(property),
+            None => self.propstats.push(PropStat {
+                status,
+                properties: vec![property],
+                error,
+            }),
+        }
+    }
+
+    fn to_xml(&self) -> XmlElement {
+        let response = XmlElement::new(DAV_NAMESPACE, "response")
+            .with_child(XmlElement::new(DAV_NAMESPACE, "href").with_text(&self.href));
+        match self.status {
+            Some(status) => response.with_child(status_xml(status)),
+            None => response.with_children(self.propstats.iter().map(PropStat::to_xml).collect()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Multistatus {
+    responses: Vec<DavResponse>,
+}
+
+impl Multistatus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_response(&mut self, response: DavResponse) {
+        self.responses.push(response);
+    }
+
+    pub fn get_responses(&self) -> &[DavResponse] {
+        &self.responses
+    }
+
+    pub fn to_xml(&self) -> XmlElement {
+        XmlElement::new(DAV_NAMESPACE, "multistatus")
+            .with_children(self.responses.iter().map(DavResponse::to_xml).collect())
+    }
+}
+
+fn status_xml(status: StatusCode) -> XmlElement {
+    /* HTTP/1.1 200 OK */
+    let status_line = format!(
+        "HTTP/1.1 {} {}",
+        status.as_u16(),
+        status.canonical_reason().unwrap_or_default()
+    );
+    XmlElement::new(DAV_NAMESPACE, "status").with_text(&status_line)
+}
@@ -0,0 +1,221 @@
+/*
+Conditional requests (RFC 7232 section 3, RFC 4918 section 10.4).
+
+       If-Match: "xyzzy", "r2d2xxxx"         ; the resource has one of the entity tags, "*" any
+       If-None-Match: *                      ; the resource does not exist, create only
+
+       If = "If" ":" ( 1*No-tag-list | 1*Tagged-list )
+       No-tag-list = List
+       Tagged-list = Resource-Tag 1*List
+       List = "(" 1*Condition ")"
+       Condition = ["Not"] (State-token | "[" entity-tag "]")
+
+The If header is true when any of its lists is true, a list being true when all its conditions are. Lists tagged with
+another resource are not evaluated. The server does not support locks, so no state token matches the resource.
+*/
+
+use super::dav_path::DavPath;
+
+pub const IF_HEADER: &str = "If";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntityTags {
+    Any,
+    List(Vec<String>),
+}
+
+impl EntityTags {
+    pub fn parse(header: &str) -> Self {
+        if header.trim() == "*" {
+            return Self::Any;
+        }
+        Self::List(
+            header
+                .split(',')
+                .map(str::trim)
+                .filter(|etag| !etag.is_empty())
+                .map(str::to_string)
+                .collect(),
+        )
+    }
+
+    fn matches(&self, etag: Option<&str>, weak: bool) -> bool {
+        match (self, etag) {
+            (Self::List(etags), Some(etag)) => etags.iter().any(|candidate| compare_etags(candidate, etag, weak)),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IfCondition {
+    StateToken(String),
+    EntityTag(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IfList {
+    /* Resource of a tagged list */
+    pub resource: Option<String>,
+
+    /* Conditions, negated by Not */
+    pub conditions: Vec<(bool, IfCondition)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Preconditions {
+    if_match: Option<EntityTags>,
+    if_none_match: Option<EntityTags>,
+    if_lists: Vec<IfList>,
+}
+
+impl Preconditions {
+    pub fn parse(if_match: Option<&str>, if_none_match: Option<&str>, if_header: Option<&str>) -> Option<Self> {
+        /* None when the If header is not valid */
+        Some(Self {
+            if_match: if_match.map(EntityTags::parse),
+            if_none_match: if_none_match.map(EntityTags::parse),
+            if_lists: match if_header {
+                Some(header) => parse_if_header(header)?,
+                None => Vec::new(),
+            },
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.if_match.is_none() && self.if_none_match.is_none() && self.if_lists.is_empty()
+    }
+
+    pub fn evaluate(&self, href: &str, etag: Option<&str>, exists: bool) -> bool {
+        /* The resource with the href, with its entity tag when it exists */
+        let etag = etag.filter(|_| exists);
+
+        /* If-Match uses the strong comparison, If-None-Match the weak one (RFC 7232 section 2.3.2) */
+        if let Some(if_match) = &self.if_match {
+            let matches = match if_match {
+                EntityTags::Any => exists,
+                etags => etags.matches(etag, false),
+            };
+            if !matches {
+                return false;
+            }
+        }
+        if let Some(if_none_match) = &self.if_none_match {
+            let matches = match if_none_match {
+                EntityTags::Any => exists,
+                etags => etags.matches(etag, true),
+            };
+            if matches {
+                return false;
+            }
+        }
+        self.evaluate_if_header(href, etag)
+    }
+
+    pub fn is_not_modified(&self, href: &str, etag: Option<&str>, exists: bool) -> bool {
+        /* Only If-None-Match fails: a GET or HEAD is then answered with 304 (RFC 7232 section 3.2) */
+        let others = Self {
+            if_none_match: None,
+            ..self.clone()
+        };
+        self.if_none_match.is_some() && !self.evaluate(href, etag, exists) && others.evaluate(href, etag, exists)
+    }
+
+    fn evaluate_if_header(&self, href: &str, etag: Option<&str>) -> bool {
+        let lists: Vec<&IfList> = self
+            .if_lists
+            .iter()
+            .filter(|list| {
+                list.resource
+                    .as_deref()
+                    .is_none_or(|resource| is_same_resource(resource, href))
+            })
+            .collect();
+        if lists.is_empty() {
+            return true;
+        }
+
+        lists.iter().any(|list| {
+            list.conditions.iter().all(|(not, condition)| {
+                let matches = match condition {
+                    IfCondition::StateToken(_) => false,
+                    IfCondition::EntityTag(candidate) => etag.is_some_and(|etag| compare_etags(candidate, etag, false)),
+                };
+                matches != *not
+            })
+        })
+    }
+}
+
+fn compare_etags(candidate: &str, etag: &str, weak: bool) -> bool {
+    /* Weak entity tags (W/"xyzzy") never match with the strong comparison */
+    let strip = |tag: &str| tag.strip_prefix("W/").map(|tag| (true, tag.to_string()));
+    let (candidate_weak, candidate) = strip(candidate).unwrap_or((false, candidate.to_string()));
+    let (etag_weak, etag) = strip(etag).unwrap_or((false, etag.to_string()));
+    candidate == etag && (weak || (!candidate_weak && !etag_weak))
+}
+
+fn is_same_resource(resource: &str, href: &str) -> bool {
+    match (DavPath::parse_href(resource), DavPath::parse(href)) {
+        (Some(resource), Some(href)) => resource == href,
+        _ => resource == href,
+    }
+}
+
+fn parse_if_header(header: &str) -> Option<Vec<IfList>> {
+    let mut lists = Vec::new();
+    let mut resource: Option<String> = None;
+    let mut rest = header.trim_start();
+
+    while !rest.is_empty() {
+        if let Some(tagged) = rest.strip_prefix('<') {
+            let (tag, remaining) = tagged.split_once('>')?;
+            resource = Some(tag.to_string());
+            rest = remaining.trim_start();
+            if !rest.starts_with('(') {
+                return None;
+            }
+        } else if let Some(list) = rest.strip_prefix('(') {
+            let (list, remaining) = list.split_once(')')?;
+            lists.push(IfList {
+                resource: resource.clone(),
+                conditions: parse_conditions(list)?,
+            });
+            rest = remaining.trim_start();
+        } else {
+            return None;
+        }
+    }
+
+    /* No-tag-list and Tagged-list cannot be mixed */
+    let tagged = lists.iter().filter(|list| list.resource.is_some()).count();
+    (!lists.is_empty() && (tagged == 0 || tagged == lists.len())).then_some(lists)
+}
+
+fn parse_conditions(list: &str) -> Option<Vec<(bool, IfCondition)>> {
+    let mut conditions = Vec::new();
+    let mut rest = list.trim_start();
+
+    while !rest.is_empty() {
+        let not = match rest.strip_prefix("Not") {
+            Some(remaining) => {
+                rest = remaining.trim_start();
+                true
+            }
+            None => false,
+        };
+        let (condition, remaining) = if let Some(token) = rest.strip_prefix('<') {
+            let (token, remaining) = token.split_once('>')?;
+            (IfCondition::StateToken(token.to_string()), remaining)
+        } else if let Some(etag) = rest.strip_prefix('[') {
+            let (etag, remaining) = etag.split_once(']')?;
+            (IfCondition::EntityTag(etag.trim().to_string()), remaining)
+        } else {
+            return None;
+        };
+        conditions.push((not, condition));
+        rest = remaining.trim_start();
+    }
+
+    (!conditions.is_empty()).then_some(conditions)
+}
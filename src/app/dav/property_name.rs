@@ -0,0 +1,41 @@
+/*
+Name of a WebDAV property: its XML namespace and local name (RFC 4918 section 4.3).
+*/
+
+use crate::architecture::xml::XmlElement;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PropertyName {
+    namespace: String,
+    name: String,
+}
+
+impl PropertyName {
+    pub fn new(namespace: &str, name: &str) -> Self {
+        Self {
+            namespace: namespace.to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    pub fn from_xml(element: &XmlElement) -> Self {
+        Self::new(element.get_namespace(), &element.name)
+    }
+
+    pub fn get_namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is(&self, namespace: &str, name: &str) -> bool {
+        self.namespace == namespace && self.name == name
+    }
+
+    pub fn to_xml(&self) -> XmlElement {
+        /* Empty element, as listed by a propname request or a property without value */
+        XmlElement::new(&self.namespace, &self.name)
+    }
+}
@@ -0,0 +1,82 @@
+/*
+PROPFIND request (RFC 4918 section 9.1).
+
+       <!ELEMENT propfind ( propname | (allprop, include?) | prop ) >
+
+An empty body is an allprop request. The Depth header selects the resource alone ("0") or the resource and its
+members ("1"); "infinity", the default, is not supported.
+*/
+
+use crate::architecture::xml::{XmlElement, XmlError, DAV_NAMESPACE};
+
+use super::property_name::PropertyName;
+
+pub const DEPTH_HEADER: &str = "Depth";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PropfindRequest {
+    /* Every property but those only returned on request, plus the included ones */
+    AllProp { include: Vec<PropertyName> },
+
+    /* Names of the properties, without their values */
+    PropName,
+
+    Prop(Vec<PropertyName>),
+}
+
+impl PropfindRequest {
+    pub fn parse(body: &str) -> Result<Self, XmlError> {
+        if body.trim().is_empty() {
+            return Ok(Self::AllProp { include: Vec::new() });
+        }
+
+        let root = XmlElement::parse(body)?;
+        if !root.is(DAV_NAMESPACE, "propfind") {
+            return Err(XmlError::new("expected a DAV:propfind element"));
+        }
+
+        Self::from_xml(&root)
+    }
+
+    pub fn from_xml(element: &XmlElement) -> Result<Self, XmlError> {
+        /* Properties selected by the children of a PROPFIND or REPORT body */
+        if element.child(DAV_NAMESPACE, "propname").is_some() {
+            Ok(Self::PropName)
+        } else if element.child(DAV_NAMESPACE, "allprop").is_some() {
+            let include = element
+                .child(DAV_NAMESPACE, "include")
+                .map(get_property_names)
+                .unwrap_or_default();
+            Ok(Self::AllProp { include })
+        } else if let Some(prop) = element.child(DAV_NAMESPACE, "prop") {
+            Ok(Self::Prop(get_property_names(prop)))
+        } else {
+            Err(XmlError::new(
+                "expected a DAV:propname, DAV:allprop or DAV:prop element",
+            ))
+        }
+    }
+}
+
+pub fn get_property_names(element: &XmlElement) -> Vec<PropertyName> {
+    element.children.iter().map(PropertyName::from_xml).collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Depth {
+    Zero,
+    One,
+    Infinity,
+}
+
+impl Depth {
+    pub fn parse(header: Option<&str>) -> Option<Self> {
+        match header.map(str::trim) {
+            None => Some(Self::Infinity),
+            Some("0") => Some(Self::Zero),
+            Some("1") => Some(Self::One),
+            Some(value) if value.eq_ignore_ascii_case("infinity") => Some(Self::Infinity),
+            Some(_) => None,
+        }
+    }
+}
@@ -0,0 +1,67 @@
+/*
+PROPPATCH request (RFC 4918 section 9.2).
+
+       <!ELEMENT propertyupdate (remove | set)+ >
+       <!ELEMENT remove (prop) >
+       <!ELEMENT set (prop) >
+
+The instructions are applied in document order, all of them or none: when one fails, the others fail with
+424 (Failed Dependency) and the resource is left unchanged.
+*/
+
+use crate::architecture::xml::{XmlElement, XmlError, DAV_NAMESPACE};
+
+use super::property_name::PropertyName;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PropertyUpdate {
+    /* Property element with its new value */
+    Set(XmlElement),
+
+    Remove(PropertyName),
+}
+
+impl PropertyUpdate {
+    pub fn get_property_name(&self) -> PropertyName {
+        match self {
+            Self::Set(property) => PropertyName::from_xml(property),
+            Self::Remove(name) => name.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProppatchRequest {
+    pub updates: Vec<PropertyUpdate>,
+}
+
+impl ProppatchRequest {
+    pub fn parse(body: &str) -> Result<Self, XmlError> {
+        let root = XmlElement::parse(body)?;
+        if !root.is(DAV_NAMESPACE, "propertyupdate") {
+            return Err(XmlError::new("expected a DAV:propertyupdate element"));
+        }
+
+        let mut updates = Vec::new();
+        for instruction in root.children.iter() {
+            let Some(prop) = instruction.child(DAV_NAMESPACE, "prop") else {
+                return Err(XmlError::new("expected a DAV:prop element"));
+            };
+            if instruction.is(DAV_NAMESPACE, "set") {
+                updates.extend(prop.children.iter().cloned().map(PropertyUpdate::Set));
+            } else if instruction.is(DAV_NAMESPACE, "remove") {
+                updates.extend(
+                    prop.children
+                        .iter()
+                        .map(|property| PropertyUpdate::Remove(PropertyName::from_xml(property))),
+                );
+            } else {
+                return Err(XmlError::new("expected a DAV:set or DAV:remove element"));
+            }
+        }
+        if updates.is_empty() {
+            return Err(XmlError::new("expected a property to update"));
+        }
+        Ok(Self { updates })
+    }
+}
@@ -0,0 +1,159 @@
+/*
+REPORT request (RFC 3253 section 3.6), whose body names the report and holds its arguments.
+
+calendar-query (RFC 4791 section 7.8): the resources matching the filter, with the requested properties.
+
+       <!ELEMENT calendar-query ((DAV:allprop | DAV:propname | DAV:prop)?, filter, timezone?)>
+
+The timezone element, giving the timezone of the floating times, is ignored: floating times are stored as UTC.
+
+calendar-multiget (RFC 4791 section 7.9): the resources with the hrefs, with the requested properties. The Depth header
+is ignored.
+
+       <!ELEMENT calendar-multiget ((DAV:allprop | DAV:propname | DAV:prop)?, DAV:href+)>
+
+free-busy-query (RFC 4791 section 7.10): the free busy time of the calendar in the time range, bounded on both sides.
+
+       <!ELEMENT free-busy-query (time-range)>
+*/
+
+use super::{
+    calendar_data::{CalendarData, CalendarDataError},
+    calendar_filter::{CompFilter, FilterError, TimeRange},
+    propfind::PropfindRequest,
+};
+use chrono::{DateTime, Utc};
+
+use crate::architecture::xml::{XmlElement, XmlError, CALDAV_NAMESPACE, DAV_NAMESPACE};
+
+#[derive(Debug)]
+pub enum ReportError {
+    InvalidXml(XmlError),
+
+    /* Report the server does not implement (DAV:supported-report) */
+    Unsupported(String),
+
+    Filter(FilterError),
+
+    CalendarData(CalendarDataError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalendarQuery {
+    pub properties: PropfindRequest,
+    pub calendar_data: CalendarData,
+    pub filter: CompFilter,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalendarMultiget {
+    pub properties: PropfindRequest,
+    pub calendar_data: CalendarData,
+    pub hrefs: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FreeBusyQuery {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReportRequest {
+    CalendarQuery(CalendarQuery),
+    CalendarMultiget(CalendarMultiget),
+    FreeBusyQuery(FreeBusyQuery),
+}
+
+impl ReportRequest {
+    pub fn get_calendar_data(&self) -> Option<&CalendarData> {
+        match self {
+            Self::CalendarQuery(query) => Some(&query.calendar_data),
+            Self::CalendarMultiget(multiget) => Some(&multiget.calendar_data),
+            Self::FreeBusyQuery(_) => None,
+        }
+    }
+
+    pub fn parse(body: &str) -> Result<Self, ReportError> {
+        let root = XmlElement::parse(body).map_err(ReportError::InvalidXml)?;
+        if root.is(CALDAV_NAMESPACE, "calendar-query") {
+            return Ok(Self::CalendarQuery(CalendarQuery::parse(&root)?));
+        }
+        if root.is(CALDAV_NAMESPACE, "calendar-multiget") {
+            return Ok(Self::CalendarMultiget(CalendarMultiget::parse(&root)?));
+        }
+        if root.is(CALDAV_NAMESPACE, "free-busy-query") {
+            return Ok(Self::FreeBusyQuery(FreeBusyQuery::parse(&root)?));
+        }
+        Err(ReportError::Unsupported(format!(
+            "{}{}",
+            root.get_namespace(),
+            root.name
+        )))
+    }
+}
+
+impl CalendarQuery {
+    fn parse(root: &XmlElement) -> Result<Self, ReportError> {
+        let Some(filter) = root.child(CALDAV_NAMESPACE, "filter") else {
+            return Err(ReportError::InvalidXml(XmlError::new(
+                "expected a CALDAV:filter element",
+            )));
+        };
+        Ok(Self {
+            properties: get_properties(root)?,
+            calendar_data: get_calendar_data(root)?,
+            filter: CompFilter::parse_filter(filter).map_err(ReportError::Filter)?,
+        })
+    }
+}
+
+impl CalendarMultiget {
+    fn parse(root: &XmlElement) -> Result<Self, ReportError> {
+        let hrefs: Vec<String> = root
+            .children_named(DAV_NAMESPACE, "href")
+            .map(|href| href.text.trim().to_string())
+            .collect();
+        if hrefs.is_empty() {
+            return Err(ReportError::InvalidXml(XmlError::new("expected a DAV:href element")));
+        }
+        Ok(Self {
+            properties: get_properties(root)?,
+            calendar_data: get_calendar_data(root)?,
+            hrefs,
+        })
+    }
+}
+
+impl FreeBusyQuery {
+    fn parse(root: &XmlElement) -> Result<Self, ReportError> {
+        let Some(element) = root.child(CALDAV_NAMESPACE, "time-range") else {
+            return Err(ReportError::InvalidXml(XmlError::new(
+                "expected a CALDAV:time-range element",
+            )));
+        };
+        match TimeRange::parse(element) {
+            Ok(TimeRange {
+                start: Some(start),
+                end: Some(end),
+            }) => Ok(Self { start, end }),
+            _ => Err(ReportError::InvalidXml(XmlError::new("expected a bounded time range"))),
+        }
+    }
+}
+
+fn get_properties(root: &XmlElement) -> Result<PropfindRequest, ReportError> {
+    /* Every property when none is selected */
+    if root
+        .children
+        .iter()
+        .all(|child| child.get_namespace() != DAV_NAMESPACE || child.name == "href")
+    {
+        return Ok(PropfindRequest::AllProp { include: Vec::new() });
+    }
+    PropfindRequest::from_xml(root).map_err(ReportError::InvalidXml)
+}
+
+fn get_calendar_data(root: &XmlElement) -> Result<CalendarData, ReportError> {
+    CalendarData::from_properties(root).map_err(ReportError::CalendarData)
+}
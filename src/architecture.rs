@@ -1,3 +1,4 @@
 pub mod app_config;
 pub mod dependency_injection;
 pub mod mongodb;
+pub mod xml;
@@ -3,20 +3,51 @@ use std::env;
 pub struct AppConfig {
     pub environment: String,
     pub server_port: u16,
+    pub public_url: String,
     pub mongodb: String,
     pub mongo_database: String,
+    pub alarm_webhook_url: Option<String>,
+    pub smtp_host: Option<String>,
+    pub smtp_port: u16,
+    pub smtp_from: String,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+    pub smtp_implicit_tls: bool,
+    pub max_attachment_size: u64,
 }
 
 impl AppConfig {
     pub fn new() -> Self {
+        let server_port = env::var("SERVER_PORT")
+            .unwrap_or_else(|_| "8080".to_string())
+            .parse::<u16>()
+            .unwrap();
+        let smtp_implicit_tls = env::var("SMTP_TLS").is_ok_and(|tls| tls == "implicit");
         Self {
             environment: env::var("RUST_ENV").unwrap_or_else(|_| "local".to_string()),
             mongodb: env::var("MONGO_DB").expect("MongoDB env var is needed"),
             mongo_database: env::var("MONGO_DB_NAME").unwrap_or_else(|_| "caldav".to_string()),
-            server_port: env::var("SERVER_PORT")
-                .unwrap_or_else(|_| "8080".to_string())
+            server_port,
+            /* Base URL of the server as seen by clients, used in the URLs it generates */
+            public_url: env::var("PUBLIC_URL")
+                .unwrap_or_else(|_| format!("http://localhost:{}", server_port))
+                .trim_end_matches('/')
+                .to_string(),
+            alarm_webhook_url: env::var("ALARM_WEBHOOK_URL").ok(),
+            smtp_host: env::var("SMTP_HOST").ok(),
+            /* Email alarms are sent over STARTTLS (port 587), or over TLS from the start (port 465) */
+            smtp_port: env::var("SMTP_PORT")
+                .unwrap_or_else(|_| if smtp_implicit_tls { "465" } else { "587" }.to_string())
                 .parse::<u16>()
                 .unwrap(),
+            smtp_from: env::var("SMTP_FROM").unwrap_or_else(|_| "caldav@localhost".to_string()),
+            smtp_username: env::var("SMTP_USERNAME").ok(),
+            smtp_password: env::var("SMTP_PASSWORD").ok(),
+            smtp_implicit_tls,
+            max_attachment_size: env::var("MAX_ATTACHMENT_SIZE")
+                .unwrap_or_else(|_| "10485760".to_string())
+                .parse::<u64>()
+                .unwrap(),
         }
     }
 }
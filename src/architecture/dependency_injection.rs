@@ -1,5 +1,12 @@
+use crate::app::alarms::alarm_scheduler::AlarmScheduler;
+use crate::app::attachments::attachment_store::AttachmentStore;
+use crate::app::attachments::managed_attachments::ManagedAttachments;
 use crate::architecture::app_config::AppConfig;
 use crate::architecture::mongodb::MongoDb;
+use crate::controllers::attachments_controller::AttachmentsController;
+use crate::controllers::calendar_object_controller::CalendarObjectController;
+use crate::controllers::dav_controller::DavController;
+use crate::controllers::options_controller::OptionsController;
 use ddi::*;
 
 /*
@@ -10,6 +17,33 @@ pub async fn di_container(client: &mongodb::Client, db_name: String) -> DDIProvi
     let mut services = ServiceCollection::new();
     services.service(Service::new(AppConfig::new()));
     services.service(Service::new(MongoDb::new(client, db_name)));
+    services.service(Service::new(OptionsController::new()));
+    services.service_factory(|config: &Service<AppConfig>, mongo: &Service<MongoDb>| {
+        Ok(Service::new(AlarmScheduler::from_config(config, mongo)))
+    });
+    services.service_factory(|config: &Service<AppConfig>, mongo: &Service<MongoDb>| {
+        Ok(Service::new(AttachmentStore::from_config(config, mongo)))
+    });
+    services.service_factory(|config: &Service<AppConfig>, mongo: &Service<MongoDb>| {
+        Ok(Service::new(ManagedAttachments::from_config(config, mongo)))
+    });
+    services.service_factory(|managed_attachments: &Service<ManagedAttachments>| {
+        Ok(Service::new(AttachmentsController::new(managed_attachments.clone())))
+    });
+    services.service_factory(|config: &Service<AppConfig>, mongo: &Service<MongoDb>| {
+        Ok(Service::new(DavController::from_config(config, mongo)))
+    });
+    services.service_factory(
+        |mongo: &Service<MongoDb>,
+         attachments: &Service<AttachmentStore>,
+         managed_attachments: &Service<AttachmentsController>| {
+            Ok(Service::new(CalendarObjectController::from_mongo(
+                mongo,
+                attachments.clone(),
+                managed_attachments.clone(),
+            )))
+        },
+    );
     /*services.service_factory(|config: &Service<AppConfig>| {
         Ok(Service::new(HelloController::new(config.clone())))
     });*/
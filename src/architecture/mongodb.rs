@@ -17,3 +17,24 @@ impl Clone for MongoDb {
         Self { db: self.db.clone() }
     }
 }
+
+/*
+Serializes an optional chrono date as a BSON date, like bson::serde_helpers::chrono_datetime_as_bson_datetime.
+Usage: #[serde(default, with = "crate::architecture::mongodb::optional_chrono_datetime_as_bson_datetime")]
+*/
+pub mod optional_chrono_datetime_as_bson_datetime {
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let date = Option::<bson::DateTime>::deserialize(deserializer)?;
+        Ok(date.map(|date| date.to_chrono()))
+    }
+
+    pub fn serialize<S: Serializer>(date: &Option<DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error> {
+        date.map(bson::DateTime::from_chrono).serialize(serializer)
+    }
+}
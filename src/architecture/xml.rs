@@ -0,0 +1,257 @@
+/*
+Minimal namespace aware XML tree.
+
+Used to read and write the XML bodies of the WebDAV/CalDAV protocol and the xCal (RFC 6321) representation of
+iCalendar objects. Mixed content is not supported: the text of an element is the concatenation of all its text nodes.
+*/
+
+use std::fmt;
+
+use xml::reader::{EventReader, XmlEvent};
+
+pub const DAV_NAMESPACE: &str = "DAV:";
+pub const CALDAV_NAMESPACE: &str = "urn:ietf:params:xml:ns:caldav";
+pub const CALENDARSERVER_NAMESPACE: &str = "http://calendarserver.org/ns/";
+pub const APPLE_ICAL_NAMESPACE: &str = "http://apple.com/ns/ical/";
+
+const KNOWN_PREFIXES: [(&str, &str); 4] = [
+    (DAV_NAMESPACE, "D"),
+    (CALDAV_NAMESPACE, "C"),
+    (CALENDARSERVER_NAMESPACE, "CS"),
+    (APPLE_ICAL_NAMESPACE, "ICAL"),
+];
+
+#[derive(Debug)]
+pub struct XmlError {
+    message: String,
+}
+
+impl XmlError {
+    pub fn new(message: &str) -> Self {
+        Self {
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for XmlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid XML: {}", self.message)
+    }
+}
+
+impl std::error::Error for XmlError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XmlElement {
+    pub namespace: Option<String>,
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<XmlElement>,
+    pub text: String,
+}
+
+impl XmlElement {
+    pub fn new(namespace: &str, name: &str) -> Self {
+        Self {
+            namespace: if namespace.is_empty() {
+                None
+            } else {
+                Some(namespace.to_string())
+            },
+            name: name.to_string(),
+            attributes: Vec::new(),
+            children: Vec::new(),
+            text: String::new(),
+        }
+    }
+
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.text = text.to_string();
+        self
+    }
+
+    pub fn with_child(mut self, child: XmlElement) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    pub fn with_children(mut self, children: Vec<XmlElement>) -> Self {
+        self.children.extend(children);
+        self
+    }
+
+    pub fn with_attribute(mut self, name: &str, value: &str) -> Self {
+        self.attributes.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn is(&self, namespace: &str, name: &str) -> bool {
+        self.namespace.as_deref().unwrap_or("") == namespace && self.name == name
+    }
+
+    pub fn get_namespace(&self) -> &str {
+        self.namespace.as_deref().unwrap_or("")
+    }
+
+    pub fn get_attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn child(&self, namespace: &str, name: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|child| child.is(namespace, name))
+    }
+
+    pub fn children_named<'a>(&'a self, namespace: &'a str, name: &'a str) -> impl Iterator<Item = &'a XmlElement> {
+        self.children.iter().filter(move |child| child.is(namespace, name))
+    }
+}
+
+impl XmlElement {
+    pub fn parse(xml: &str) -> Result<Self, XmlError> {
+        let mut stack: Vec<XmlElement> = Vec::new();
+
+        for event in EventReader::from_str(xml) {
+            match event.map_err(|error| XmlError::new(&error.to_string()))? {
+                XmlEvent::StartElement { name, attributes, .. } => {
+                    let mut element = XmlElement::new(name.namespace.as_deref().unwrap_or(""), &name.local_name);
+                    for attribute in attributes {
+                        element.attributes.push((attribute.name.local_name, attribute.value));
+                    }
+                    stack.push(element);
+                }
+                XmlEvent::EndElement { .. } => {
+                    let element = stack.pop().ok_or_else(|| XmlError::new("unexpected closing tag"))?;
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(element),
+                        None => return Ok(element),
+                    }
+                }
+                XmlEvent::Characters(text) | XmlEvent::CData(text) => {
+                    if let Some(element) = stack.last_mut() {
+                        element.text.push_str(&text);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Err(XmlError::new("missing root element"))
+    }
+
+    pub fn to_xml(&self) -> String {
+        let mut namespaces = Vec::new();
+        self.collect_namespaces(&mut namespaces);
+
+        let default_namespace = self
+            .namespace
+            .clone()
+            .filter(|namespace| get_known_prefix(namespace).is_none());
+
+        let mut prefixes = Vec::new();
+        let mut unknown_count = 0;
+        for namespace in namespaces {
+            if Some(&namespace) == default_namespace.as_ref() {
+                continue;
+            }
+            let prefix = match get_known_prefix(&namespace) {
+                Some(prefix) => prefix.to_string(),
+                None => {
+                    unknown_count += 1;
+                    format!("X{}", unknown_count)
+                }
+            };
+            prefixes.push((namespace, prefix));
+        }
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        let writer = XmlWriter {
+            default_namespace: default_namespace.as_deref(),
+            prefixes: &prefixes,
+        };
+        writer.write(self, true, &mut xml);
+        xml
+    }
+
+    fn collect_namespaces(&self, namespaces: &mut Vec<String>) {
+        if let Some(namespace) = &self.namespace {
+            if !namespaces.contains(namespace) {
+                namespaces.push(namespace.clone());
+            }
+        }
+        for child in self.children.iter() {
+            child.collect_namespaces(namespaces);
+        }
+    }
+}
+
+struct XmlWriter<'a> {
+    default_namespace: Option<&'a str>,
+    prefixes: &'a Vec<(String, String)>,
+}
+
+impl<'a> XmlWriter<'a> {
+    fn write(&self, element: &XmlElement, is_root: bool, xml: &mut String) {
+        let tag = self.get_tag(element);
+
+        xml.push('<');
+        xml.push_str(&tag);
+        if is_root {
+            if let Some(namespace) = self.default_namespace {
+                xml.push_str(&format!(" xmlns=\"{}\"", escape(namespace)));
+            }
+            for (namespace, prefix) in self.prefixes.iter() {
+                xml.push_str(&format!(" xmlns:{}=\"{}\"", prefix, escape(namespace)));
+            }
+        }
+        if element.namespace.is_none() && self.default_namespace.is_some() {
+            xml.push_str(" xmlns=\"\"");
+        }
+        for (name, value) in element.attributes.iter() {
+            xml.push_str(&format!(" {}=\"{}\"", name, escape(value)));
+        }
+
+        if element.children.is_empty() && element.text.is_empty() {
+            xml.push_str("/>");
+            return;
+        }
+
+        xml.push('>');
+        xml.push_str(&escape(&element.text));
+        for child in element.children.iter() {
+            self.write(child, false, xml);
+        }
+        xml.push_str(&format!("</{}>", tag));
+    }
+
+    fn get_tag(&self, element: &XmlElement) -> String {
+        let prefix = element.namespace.as_ref().and_then(|namespace| {
+            self.prefixes
+                .iter()
+                .find(|(known, _)| known == namespace)
+                .map(|(_, prefix)| prefix)
+        });
+
+        match prefix {
+            Some(prefix) => format!("{}:{}", prefix, element.name),
+            None => element.name.clone(),
+        }
+    }
+}
+
+fn get_known_prefix(namespace: &str) -> Option<&'static str> {
+    KNOWN_PREFIXES
+        .iter()
+        .find(|(known, _)| *known == namespace)
+        .map(|(_, prefix)| *prefix)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -1 +1,4 @@
-
+pub mod attachments_controller;
+pub mod calendar_object_controller;
+pub mod dav_controller;
+pub mod options_controller;
@@ -0,0 +1,153 @@
+use actix_web::{
+    http::{header, StatusCode},
+    HttpRequest, HttpResponse,
+};
+use ddi::Service;
+use serde::Deserialize;
+
+use crate::{
+    app::{
+        attachments::{
+            attachment_store::AttachmentError,
+            managed_attachments::{
+                AttachmentUpload, ManagedAttachmentAction, ManagedAttachmentError, ManagedAttachments,
+            },
+        },
+        calendar::models::event::Event,
+        dav::dav_error::error_response,
+    },
+    architecture::xml::CALDAV_NAMESPACE,
+};
+
+/* Header returning the MANAGED-ID of an added or updated attachment (RFC 8607 section 5.1) */
+pub const CAL_MANAGED_ID_HEADER: &str = "Cal-Managed-ID";
+
+/* Query of a POST request on a calendar resource managing its attachments */
+#[derive(Debug, Clone, Deserialize)]
+pub struct ManagedAttachmentQuery {
+    pub action: String,
+
+    #[serde(rename = "managed-id")]
+    pub managed_id: Option<String>,
+
+    pub rid: Option<String>,
+}
+
+pub struct AttachmentsController {
+    managed_attachments: Service<ManagedAttachments>,
+}
+
+impl AttachmentsController {
+    pub fn new(managed_attachments: Service<ManagedAttachments>) -> Self {
+        Self { managed_attachments }
+    }
+
+    pub async fn get(&self, managed_id: &str) -> HttpResponse {
+        let info = match self.managed_attachments.get_info(managed_id).await {
+            Ok(Some(info)) => info,
+            Ok(None) => return HttpResponse::NotFound().finish(),
+            Err(error) => return attachment_error_response(&error),
+        };
+        let data = match self.managed_attachments.download(managed_id).await {
+            Ok(data) => data,
+            Err(error) => return attachment_error_response(&error),
+        };
+
+        let mut response = HttpResponse::Ok();
+        response.content_type(
+            info.content_type
+                .unwrap_or_else(|| "application/octet-stream".to_string()),
+        );
+        if let Some(filename) = &info.filename {
+            response.insert_header((
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename.replace('"', "")),
+            ));
+        }
+        response.body(data)
+    }
+
+    pub async fn action(
+        &self,
+        events: &mut [Event],
+        query: &ManagedAttachmentQuery,
+        request: &HttpRequest,
+        body: &[u8],
+    ) -> HttpResponse {
+        /* Applies the action to the components of the resource, which the caller saves on success */
+        let Some(action) = ManagedAttachmentAction::from_query(&query.action) else {
+            return HttpResponse::BadRequest().finish();
+        };
+        let managed_id = query.managed_id.as_deref().unwrap_or_default();
+        let rid = query.rid.as_deref();
+
+        let result = match action {
+            ManagedAttachmentAction::Add => {
+                self.managed_attachments
+                    .add(events, rid, &get_upload(request, body))
+                    .await
+            }
+            ManagedAttachmentAction::Update => {
+                self.managed_attachments
+                    .update(events, managed_id, &get_upload(request, body))
+                    .await
+            }
+            ManagedAttachmentAction::Remove => self
+                .managed_attachments
+                .remove(events, managed_id, rid)
+                .await
+                .map(|_| String::new()),
+        };
+
+        match (action, result) {
+            (ManagedAttachmentAction::Add, Ok(managed_id)) => HttpResponse::Created()
+                .insert_header((CAL_MANAGED_ID_HEADER, managed_id.as_str()))
+                .insert_header((header::LOCATION, self.managed_attachments.get_url(&managed_id)))
+                .finish(),
+            (ManagedAttachmentAction::Update, Ok(managed_id)) => HttpResponse::NoContent()
+                .insert_header((CAL_MANAGED_ID_HEADER, managed_id))
+                .finish(),
+            (ManagedAttachmentAction::Remove, Ok(_)) => HttpResponse::NoContent().finish(),
+            (_, Err(error)) => attachment_error_response(&error),
+        }
+    }
+
+    pub async fn release(&self, events: &[Event]) -> Result<(), ManagedAttachmentError> {
+        /* Deletes the managed attachments of a deleted resource */
+        self.managed_attachments.release(events).await
+    }
+}
+
+fn get_upload(request: &HttpRequest, body: &[u8]) -> AttachmentUpload {
+    let header_value = |name| request.headers().get(name).and_then(|value| value.to_str().ok());
+    AttachmentUpload {
+        data: body.to_vec(),
+        format_type: header_value(header::CONTENT_TYPE).map(str::to_string),
+        filename: header_value(header::CONTENT_DISPOSITION).and_then(get_filename),
+    }
+}
+
+fn get_filename(content_disposition: &str) -> Option<String> {
+    /* attachment;filename=myfile.txt */
+    content_disposition.split(';').skip(1).find_map(|parameter| {
+        let (name, value) = parameter.split_once('=')?;
+        if !name.trim().eq_ignore_ascii_case("filename") {
+            return None;
+        }
+        let value = value.trim().trim_matches('"');
+        (!value.is_empty()).then(|| value.to_string())
+    })
+}
+
+fn attachment_error_response(error: &ManagedAttachmentError) -> HttpResponse {
+    /* Preconditions of RFC 8607 section 3.8, in a DAV:error body */
+    let precondition = match error {
+        ManagedAttachmentError::InvalidManagedId(_) => "valid-managed-id",
+        ManagedAttachmentError::InvalidRecurrenceId(_) => "valid-rid",
+        ManagedAttachmentError::Attachment(AttachmentError::TooLarge { .. }) => "max-attachment-size",
+        ManagedAttachmentError::Attachment(AttachmentError::Storage(_)) => {
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    error_response(StatusCode::FORBIDDEN, CALDAV_NAMESPACE, precondition)
+}
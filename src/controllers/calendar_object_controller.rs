@@ -0,0 +1,347 @@
+use std::sync::Arc;
+
+use actix_web::{
+    http::{header, StatusCode},
+    HttpRequest, HttpResponse,
+};
+use chrono::Utc;
+use ddi::Service;
+
+use super::{
+    attachments_controller::{AttachmentsController, ManagedAttachmentQuery},
+    dav_controller::{check_preconditions, get_header},
+};
+use crate::{
+    app::{
+        attachments::attachment_store::{AttachmentError, AttachmentStore},
+        calendar::{
+            calendar_store::{CalendarStore, MongoCalendarStore},
+            ical::ICalFormat,
+            models::{
+                calendar::Calendar,
+                calendar_object::{CalendarObject, CalendarObjectError},
+                calendar_types::timezone::timezones_by_reference::{should_include_timezones, CALDAV_TIMEZONES_HEADER},
+            },
+        },
+        dav::{
+            dav_error::{condition_response, error_response},
+            dav_path::DavPath,
+            dav_resource::format_http_date,
+        },
+    },
+    architecture::{
+        mongodb::MongoDb,
+        xml::{XmlElement, CALDAV_NAMESPACE, DAV_NAMESPACE},
+    },
+};
+
+/* Calendar object resources: GET, PUT and DELETE of their iCalendar data, POST of their managed attachments */
+pub struct CalendarObjectController {
+    calendars: Arc<dyn CalendarStore>,
+    attachments: Service<AttachmentStore>,
+    managed_attachments: Service<AttachmentsController>,
+}
+
+impl CalendarObjectController {
+    pub fn new(
+        calendars: Arc<dyn CalendarStore>,
+        attachments: Service<AttachmentStore>,
+        managed_attachments: Service<AttachmentsController>,
+    ) -> Self {
+        Self {
+            calendars,
+            attachments,
+            managed_attachments,
+        }
+    }
+
+    pub fn from_mongo(
+        mongo: &MongoDb,
+        attachments: Service<AttachmentStore>,
+        managed_attachments: Service<AttachmentsController>,
+    ) -> Self {
+        Self::new(
+            Arc::new(MongoCalendarStore::new(mongo.db.clone())),
+            attachments,
+            managed_attachments,
+        )
+    }
+
+    pub async fn get(&self, request: &HttpRequest) -> HttpResponse {
+        let mut object = match self.get_object(request).await {
+            Ok((_, object)) => object,
+            Err(response) => return response,
+        };
+        let etag = object.get_etag();
+        for attachments in object.get_attachments_mut() {
+            if self.attachments.load(attachments).await.is_err() {
+                return HttpResponse::InternalServerError().finish();
+            }
+        }
+
+        let mut response = HttpResponse::Ok();
+        response
+            .content_type(object.get_content_type())
+            .insert_header((header::ETAG, etag));
+        if let Some(last_modified) = object.get_last_modified() {
+            response.insert_header((header::LAST_MODIFIED, format_http_date(&last_modified)));
+        }
+        response.body(object.to_ics(should_include_timezones(get_header(request, CALDAV_TIMEZONES_HEADER))))
+    }
+
+    pub async fn put(&self, request: &HttpRequest, body: &str) -> HttpResponse {
+        let (calendar, name) = match self.get_location(request, StatusCode::CONFLICT).await {
+            Ok(location) => location,
+            Err(response) => return response,
+        };
+        let Some(calendar_id) = calendar.get_id() else {
+            return HttpResponse::InternalServerError().finish();
+        };
+
+        let previous = match self.calendars.get_object(calendar_id, &name).await {
+            Ok(previous) => previous,
+            Err(_) => return HttpResponse::InternalServerError().finish(),
+        };
+        let href = DavPath::Object {
+            calendar: calendar.get_name().to_string(),
+            name: name.clone(),
+        }
+        .get_href();
+        if let Err(response) = check_preconditions(
+            request,
+            &href,
+            previous.as_ref().map(|previous| previous.get_etag()).as_deref(),
+            previous.is_some(),
+        ) {
+            return response;
+        }
+
+        /* RFC 4791 section 5.3.2.1: preconditions of PUT */
+        let format = match get_header(request, "Content-Type").map(ICalFormat::from_content_type) {
+            None => ICalFormat::Ics,
+            Some(Some(format)) => format,
+            Some(None) => return error_response(StatusCode::FORBIDDEN, CALDAV_NAMESPACE, "supported-calendar-data"),
+        };
+        let parsed = format.parse(body).map_err(CalendarObjectError::InvalidData);
+        let mut object = match parsed
+            .and_then(|ical| CalendarObject::from_ical(&name, &ical, calendar.get_supported_components()))
+        {
+            Ok(object) => object,
+            Err(error) => return object_error_response(&error),
+        };
+
+        let uid = object.get_uid().unwrap_or_default().to_string();
+        match self.calendars.find_uid(calendar_id, &uid).await {
+            Ok(Some(other)) if other != name => {
+                /* The resource already holding the UID */
+                let href = DavPath::Object {
+                    calendar: calendar.get_name().to_string(),
+                    name: other,
+                }
+                .get_href();
+                return condition_response(
+                    StatusCode::FORBIDDEN,
+                    XmlElement::new(CALDAV_NAMESPACE, "no-uid-conflict")
+                        .with_child(XmlElement::new(DAV_NAMESPACE, "href").with_text(&href)),
+                );
+            }
+            Ok(_) => {}
+            Err(_) => return HttpResponse::InternalServerError().finish(),
+        }
+
+        let now = Utc::now();
+        for event in object.get_events_mut() {
+            event.set_resource(calendar_id, &name);
+            let previous_event = previous.as_ref().and_then(|previous| {
+                previous
+                    .get_events()
+                    .iter()
+                    .find(|previous| previous.get_recurrence_id() == event.get_recurrence_id())
+            });
+            if let Some(previous_event) = previous_event {
+                event.update_sequence(previous_event);
+            }
+            /* Last-Modified is the time of the write, whatever LAST-MODIFIED the client sent */
+            event.touch(previous_event, now);
+        }
+        for todo in object.get_todos_mut() {
+            todo.set_resource(calendar_id, &name);
+            let previous_todo = previous.as_ref().and_then(|previous| {
+                previous
+                    .get_todos()
+                    .iter()
+                    .find(|previous| previous.get_recurrence_id() == todo.get_recurrence_id())
+            });
+            if let Some(previous_todo) = previous_todo {
+                todo.update_sequence(previous_todo);
+            }
+            todo.touch(previous_todo, now);
+        }
+        for journal in object.get_journals_mut() {
+            journal.set_resource(calendar_id, &name);
+            let previous_journal = previous.as_ref().and_then(|previous| {
+                previous
+                    .get_journals()
+                    .iter()
+                    .find(|previous| previous.get_recurrence_id() == journal.get_recurrence_id())
+            });
+            if let Some(previous_journal) = previous_journal {
+                journal.update_sequence(previous_journal);
+            }
+            journal.touch(previous_journal, now);
+        }
+        /* The dead properties of the resource are kept when its content changes */
+        if let Some(properties) = previous.as_ref().and_then(CalendarObject::get_dead_properties) {
+            object.set_dead_properties(properties.clone());
+        }
+        object.update_next_alarms(now);
+        object.update_etag();
+        /* RFC 4791 section 5.3.4: no strong ETag when the stored data is not the one sent */
+        let unchanged = format.write(&object.to_ical()) == body;
+
+        for attachments in object.get_attachments_mut() {
+            match self.attachments.save(attachments).await {
+                Ok(()) => {}
+                Err(AttachmentError::TooLarge { .. }) => {
+                    return error_response(StatusCode::FORBIDDEN, CALDAV_NAMESPACE, "max-attachment-size")
+                }
+                Err(AttachmentError::Storage(_)) => return HttpResponse::InternalServerError().finish(),
+            }
+        }
+
+        match self
+            .calendars
+            .save_object(calendar_id, &object, previous.as_ref())
+            .await
+        {
+            Ok(true) => {}
+            result => {
+                for attachments in object.get_attachments() {
+                    self.attachments.delete(attachments).await.ok();
+                }
+                return match result {
+                    /* Changed by another request since read */
+                    Ok(_) => HttpResponse::PreconditionFailed().finish(),
+                    Err(_) => HttpResponse::InternalServerError().finish(),
+                };
+            }
+        }
+        if let Some(previous) = &previous {
+            /* Inline attachments are stored again on each write */
+            for attachments in previous.get_attachments() {
+                self.attachments.delete(attachments).await.ok();
+            }
+        }
+        self.calendars.touch_calendar(calendar_id, now).await.ok();
+
+        let mut response = match previous {
+            Some(_) => HttpResponse::NoContent(),
+            None => HttpResponse::Created(),
+        };
+        if unchanged {
+            response.insert_header((header::ETAG, object.get_etag()));
+        }
+        response.finish()
+    }
+
+    pub async fn delete(&self, request: &HttpRequest) -> HttpResponse {
+        let (calendar_id, object) = match self.get_object(request).await {
+            Ok(found) => found,
+            Err(response) => return response,
+        };
+        match self.calendars.delete_object(calendar_id, &object).await {
+            Ok(true) => {}
+            Ok(false) => return HttpResponse::PreconditionFailed().finish(),
+            Err(_) => return HttpResponse::InternalServerError().finish(),
+        }
+
+        for attachments in object.get_attachments() {
+            self.attachments.delete(attachments).await.ok();
+        }
+        self.managed_attachments.release(object.get_events()).await.ok();
+        self.calendars.touch_calendar(calendar_id, Utc::now()).await.ok();
+        HttpResponse::NoContent().finish()
+    }
+
+    pub async fn post(&self, request: &HttpRequest, query: &ManagedAttachmentQuery, body: &[u8]) -> HttpResponse {
+        /* Managed attachments actions (RFC 8607 section 3.3) */
+        let (calendar_id, mut object) = match self.get_object(request).await {
+            Ok(found) => found,
+            Err(response) => return response,
+        };
+        let previous = object.clone();
+
+        let mut response = self
+            .managed_attachments
+            .action(object.get_events_mut(), query, request, body)
+            .await;
+        if !response.status().is_success() {
+            return response;
+        }
+
+        let now = Utc::now();
+        for (event, previous_event) in object.get_events_mut().iter_mut().zip(previous.get_events()) {
+            event.touch(Some(previous_event), now);
+        }
+        object.update_next_alarms(now);
+        object.update_etag();
+        match self.calendars.save_object(calendar_id, &object, Some(&previous)).await {
+            Ok(true) => {}
+            Ok(false) => return HttpResponse::PreconditionFailed().finish(),
+            Err(_) => return HttpResponse::InternalServerError().finish(),
+        }
+        self.calendars.touch_calendar(calendar_id, now).await.ok();
+        if let Ok(etag) = object.get_etag().parse() {
+            response.headers_mut().insert(header::ETAG, etag);
+        }
+        response
+    }
+
+    async fn get_location(
+        &self,
+        request: &HttpRequest,
+        missing: StatusCode,
+    ) -> Result<(Calendar, String), HttpResponse> {
+        /* Calendar and name of the resource addressed by the request */
+        let (calendar, name) = match DavPath::parse(request.path()) {
+            Some(DavPath::Object { calendar, name }) => (calendar, name),
+            Some(_) => return Err(HttpResponse::MethodNotAllowed().finish()),
+            None => return Err(HttpResponse::build(missing).finish()),
+        };
+        match self.calendars.get_calendar(&calendar).await {
+            Ok(Some(calendar)) => Ok((calendar, name)),
+            Ok(None) => Err(HttpResponse::build(missing).finish()),
+            Err(_) => Err(HttpResponse::InternalServerError().finish()),
+        }
+    }
+
+    async fn get_object(&self, request: &HttpRequest) -> Result<(bson::oid::ObjectId, CalendarObject), HttpResponse> {
+        /* Resource addressed by the request, if its preconditions hold */
+        let (calendar, name) = self.get_location(request, StatusCode::NOT_FOUND).await?;
+        let Some(calendar_id) = calendar.get_id() else {
+            return Err(HttpResponse::InternalServerError().finish());
+        };
+        let object = match self.calendars.get_object(calendar_id, &name).await {
+            Ok(Some(object)) => object,
+            Ok(None) => return Err(HttpResponse::NotFound().finish()),
+            Err(_) => return Err(HttpResponse::InternalServerError().finish()),
+        };
+        let href = DavPath::Object {
+            calendar: calendar.get_name().to_string(),
+            name,
+        }
+        .get_href();
+        check_preconditions(request, &href, Some(&object.get_etag()), true)?;
+        Ok((calendar_id, object))
+    }
+}
+
+fn object_error_response(error: &CalendarObjectError) -> HttpResponse {
+    let condition = match error {
+        CalendarObjectError::InvalidData(_) => "valid-calendar-data",
+        CalendarObjectError::UnsupportedComponent(_) => "supported-calendar-component",
+        CalendarObjectError::InvalidResource(_) => "valid-calendar-object-resource",
+        CalendarObjectError::TooManyInstances => "max-instances",
+    };
+    error_response(StatusCode::FORBIDDEN, CALDAV_NAMESPACE, condition)
+}
@@ -0,0 +1,538 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use actix_web::{
+    http::{header, Method, StatusCode},
+    HttpRequest, HttpResponse,
+};
+use chrono::Utc;
+
+use crate::{
+    app::{
+        calendar::{
+            calendar_store::{CalendarStore, MongoCalendarStore},
+            ical::ics,
+            models::calendar_types::timezone::timezones_by_reference::{
+                should_include_timezones, CALDAV_TIMEZONES_HEADER,
+            },
+            models::{
+                calendar::Calendar,
+                calendar_object::{CalendarObject, CALENDAR_CONTENT_TYPE},
+            },
+        },
+        dav::{
+            calendar_data::{CalendarData, CalendarDataError},
+            calendar_filter::TimeRange,
+            calendar_properties::{set_calendar_property, PropertyError},
+            dav_error::error_response,
+            dav_path::DavPath,
+            dav_resource::{DavContext, DavResource},
+            free_busy::FreeBusy,
+            mkcol::MkcolRequest,
+            multistatus::{DavResponse, Multistatus, PropStat},
+            preconditions::{Preconditions, IF_HEADER},
+            property_name::PropertyName,
+            propfind::{Depth, PropfindRequest, DEPTH_HEADER},
+            proppatch::ProppatchRequest,
+            report::{CalendarMultiget, CalendarQuery, FreeBusyQuery, ReportError, ReportRequest},
+        },
+    },
+    architecture::{
+        app_config::AppConfig,
+        mongodb::MongoDb,
+        xml::{XmlElement, CALDAV_NAMESPACE, DAV_NAMESPACE},
+    },
+};
+
+pub struct DavController {
+    calendars: Arc<dyn CalendarStore>,
+
+    /* Advertised by the CALDAV:max-attachment-size property */
+    max_attachment_size: u64,
+}
+
+impl DavController {
+    pub fn new(calendars: Arc<dyn CalendarStore>, max_attachment_size: u64) -> Self {
+        Self {
+            calendars,
+            max_attachment_size,
+        }
+    }
+
+    pub fn from_config(config: &AppConfig, mongo: &MongoDb) -> Self {
+        Self::new(
+            Arc::new(MongoCalendarStore::new(mongo.db.clone())),
+            config.max_attachment_size,
+        )
+    }
+
+    pub async fn propfind(&self, request: &HttpRequest, body: &str) -> HttpResponse {
+        let Some(depth) = Depth::parse(get_header(request, DEPTH_HEADER)) else {
+            return HttpResponse::BadRequest().finish();
+        };
+        if depth == Depth::Infinity {
+            /* RFC 4918 section 9.1: the server may reject infinite depth requests */
+            return error_response(StatusCode::FORBIDDEN, DAV_NAMESPACE, "propfind-finite-depth");
+        }
+        let Ok(propfind) = PropfindRequest::parse(body) else {
+            return HttpResponse::BadRequest().finish();
+        };
+        let Some(path) = DavPath::parse(request.path()) else {
+            return HttpResponse::NotFound().finish();
+        };
+
+        let resources = match self.get_resources(&path, depth).await {
+            Ok(Some(resources)) => resources,
+            Ok(None) => return HttpResponse::NotFound().finish(),
+            Err(_) => return HttpResponse::InternalServerError().finish(),
+        };
+
+        let context = self.get_context(request);
+        let mut multistatus = Multistatus::new();
+        for resource in resources.iter() {
+            multistatus.add_response(resource.propfind(&propfind, &context));
+        }
+        multistatus_response(&multistatus)
+    }
+
+    pub async fn report(&self, request: &HttpRequest, body: &str) -> HttpResponse {
+        let Some(depth) = Depth::parse(get_header(request, DEPTH_HEADER)) else {
+            return HttpResponse::BadRequest().finish();
+        };
+        let report = match ReportRequest::parse(body) {
+            Ok(report) => report,
+            Err(ReportError::InvalidXml(_)) => return HttpResponse::BadRequest().finish(),
+            Err(ReportError::Unsupported(_)) => {
+                return error_response(StatusCode::FORBIDDEN, DAV_NAMESPACE, "supported-report")
+            }
+            Err(ReportError::Filter(error)) => {
+                return error_response(StatusCode::FORBIDDEN, CALDAV_NAMESPACE, error.get_condition())
+            }
+            Err(ReportError::CalendarData(CalendarDataError::Invalid(_))) => {
+                return HttpResponse::BadRequest().finish()
+            }
+            Err(ReportError::CalendarData(CalendarDataError::UnsupportedType(_))) => {
+                return error_response(StatusCode::FORBIDDEN, CALDAV_NAMESPACE, "supported-calendar-data")
+            }
+        };
+        let Some(path) = DavPath::parse(request.path()) else {
+            return HttpResponse::NotFound().finish();
+        };
+
+        let context = DavContext {
+            calendar_data: report.get_calendar_data().cloned().unwrap_or_default(),
+            ..self.get_context(request)
+        };
+        match report {
+            ReportRequest::CalendarQuery(query) => self.calendar_query(&path, depth, &query, &context).await,
+            ReportRequest::CalendarMultiget(multiget) => self.calendar_multiget(&path, &multiget, &context).await,
+            ReportRequest::FreeBusyQuery(query) => self.free_busy_query(&path, &query).await,
+        }
+    }
+
+    async fn calendar_query(
+        &self,
+        path: &DavPath,
+        depth: Depth,
+        query: &CalendarQuery,
+        context: &DavContext,
+    ) -> HttpResponse {
+        let objects = match self
+            .find_objects(path, depth, query.filter.get_event_time_range())
+            .await
+        {
+            Ok(Some(objects)) => objects,
+            Ok(None) => return HttpResponse::NotFound().finish(),
+            Err(_) => return HttpResponse::InternalServerError().finish(),
+        };
+
+        let mut multistatus = Multistatus::new();
+        for (calendar, object) in objects {
+            if query.filter.matches(&object) {
+                let resource = DavResource::Object { calendar, object };
+                multistatus.add_response(resource.propfind(&query.properties, context));
+            }
+        }
+        multistatus_response(&multistatus)
+    }
+
+    async fn calendar_multiget(
+        &self,
+        path: &DavPath,
+        multiget: &CalendarMultiget,
+        context: &DavContext,
+    ) -> HttpResponse {
+        match self.get_resources(path, Depth::Zero).await {
+            Ok(Some(_)) => {}
+            Ok(None) => return HttpResponse::NotFound().finish(),
+            Err(_) => return HttpResponse::InternalServerError().finish(),
+        }
+
+        let paths: Vec<Option<DavPath>> = multiget.hrefs.iter().map(|href| DavPath::parse_href(href)).collect();
+        let objects = match self.get_objects_by_paths(&paths).await {
+            Ok(objects) => objects,
+            Err(_) => return HttpResponse::InternalServerError().finish(),
+        };
+
+        /* Responses in the order of the hrefs, 404 (Not Found) for those that are not calendar object resources */
+        let mut multistatus = Multistatus::new();
+        for (href, path) in multiget.hrefs.iter().zip(paths) {
+            let resource = match path {
+                Some(DavPath::Object { calendar, name }) => {
+                    objects
+                        .get(&(calendar.clone(), name))
+                        .map(|object| DavResource::Object {
+                            calendar,
+                            object: object.clone(),
+                        })
+                }
+                _ => None,
+            };
+            match resource {
+                Some(resource) => multistatus.add_response(resource.propfind(&multiget.properties, context)),
+                None => multistatus.add_response(DavResponse::with_status(href, StatusCode::NOT_FOUND)),
+            }
+        }
+        multistatus_response(&multistatus)
+    }
+
+    async fn free_busy_query(&self, path: &DavPath, query: &FreeBusyQuery) -> HttpResponse {
+        /* Reported by the calendar collections, from their resources whatever the depth */
+        if !matches!(path, DavPath::Calendar(_)) {
+            return error_response(StatusCode::FORBIDDEN, DAV_NAMESPACE, "supported-report");
+        }
+        let time_range = TimeRange {
+            start: Some(query.start),
+            end: Some(query.end),
+        };
+        let objects = match self.find_objects(path, Depth::One, Some(&time_range)).await {
+            Ok(Some(objects)) => objects,
+            Ok(None) => return HttpResponse::NotFound().finish(),
+            Err(_) => return HttpResponse::InternalServerError().finish(),
+        };
+
+        let mut free_busy = FreeBusy::new(query.start, query.end);
+        for (_, object) in objects.iter() {
+            free_busy.add_object(object);
+        }
+        HttpResponse::Ok()
+            .content_type(CALENDAR_CONTENT_TYPE)
+            .body(ics::write(&free_busy.to_ical()))
+    }
+
+    pub async fn proppatch(&self, request: &HttpRequest, body: &str) -> HttpResponse {
+        let Ok(proppatch) = ProppatchRequest::parse(body) else {
+            return HttpResponse::BadRequest().finish();
+        };
+        let Some(path) = DavPath::parse(request.path()) else {
+            return HttpResponse::NotFound().finish();
+        };
+        let mut resource = match self.get_resources(&path, Depth::Zero).await {
+            Ok(Some(mut resources)) => resources.remove(0),
+            Ok(None) => return HttpResponse::NotFound().finish(),
+            Err(_) => return HttpResponse::InternalServerError().finish(),
+        };
+
+        let href = path.get_href();
+        let etag = match &resource {
+            DavResource::Object { object, .. } => Some(object.get_etag()),
+            _ => None,
+        };
+        if let Err(response) = check_preconditions(request, &href, etag.as_deref(), true) {
+            return response;
+        }
+
+        let results: Vec<_> = proppatch
+            .updates
+            .iter()
+            .map(|update| (update.get_property_name(), resource.update_property(update)))
+            .collect();
+
+        /* The instructions are applied all together, or not at all */
+        if results.iter().all(|(_, result)| result.is_ok()) {
+            let saved = match &mut resource {
+                DavResource::Calendar(calendar) => {
+                    calendar.touch(Utc::now());
+                    self.calendars.update_calendar(calendar).await.map(|_| true)
+                }
+                DavResource::Object { object, .. } => self.calendars.update_object_properties(object).await,
+                DavResource::Root => Ok(true),
+            };
+            match saved {
+                Ok(true) => {}
+                /* Changed by another request since read */
+                Ok(false) => return HttpResponse::PreconditionFailed().finish(),
+                Err(_) => return HttpResponse::InternalServerError().finish(),
+            }
+        }
+
+        let mut multistatus = Multistatus::new();
+        multistatus.add_response(property_results(&href, results));
+        multistatus_response(&multistatus)
+    }
+
+    pub async fn mkcalendar(&self, request: &HttpRequest, body: &str) -> HttpResponse {
+        let Ok(mkcalendar) = MkcolRequest::parse_mkcalendar(body) else {
+            return HttpResponse::BadRequest().finish();
+        };
+        self.create_calendar(request, &mkcalendar, (CALDAV_NAMESPACE, "mkcalendar-response"), false)
+            .await
+    }
+
+    pub async fn mkcol(&self, request: &HttpRequest, body: &str) -> HttpResponse {
+        let Ok(mkcol) = MkcolRequest::parse_mkcol(body) else {
+            return HttpResponse::BadRequest().finish();
+        };
+        /* Only calendar collections can be created */
+        if !mkcol.is_calendar() {
+            return error_response(StatusCode::FORBIDDEN, DAV_NAMESPACE, "valid-resourcetype");
+        }
+        self.create_calendar(request, &mkcol, (DAV_NAMESPACE, "mkcol-response"), true)
+            .await
+    }
+
+    async fn create_calendar(
+        &self,
+        request: &HttpRequest,
+        mkcol: &MkcolRequest,
+        response_name: (&str, &str),
+        sets_resource_type: bool,
+    ) -> HttpResponse {
+        let name = match DavPath::parse(request.path()) {
+            Some(DavPath::Calendar(name)) => name,
+            Some(DavPath::Root) => {
+                return error_response(StatusCode::METHOD_NOT_ALLOWED, DAV_NAMESPACE, "resource-must-be-null")
+            }
+            /* Calendar collections cannot be nested */
+            Some(DavPath::Object { calendar, .. }) => {
+                return match self.calendars.get_calendar(&calendar).await {
+                    Ok(Some(_)) => error_response(
+                        StatusCode::FORBIDDEN,
+                        CALDAV_NAMESPACE,
+                        "calendar-collection-location-ok",
+                    ),
+                    Ok(None) => HttpResponse::Conflict().finish(),
+                    Err(_) => HttpResponse::InternalServerError().finish(),
+                };
+            }
+            None => return HttpResponse::Conflict().finish(),
+        };
+
+        let mut calendar = Calendar::new(&name);
+        let results: Vec<_> = mkcol
+            .properties
+            .iter()
+            .map(|property| {
+                /* The resource type of MKCALENDAR is set by the method, the one of MKCOL was validated */
+                let result = match property.is(DAV_NAMESPACE, "resourcetype") {
+                    true if sets_resource_type => Ok(()),
+                    true => Err(PropertyError::Protected),
+                    false => set_calendar_property(&mut calendar, property),
+                };
+                (PropertyName::from_xml(property), result)
+            })
+            .collect();
+
+        if results.iter().any(|(_, result)| result.is_err()) {
+            /* Nothing is created when a property cannot be set (RFC 5689 section 3) */
+            let response = property_results("", results);
+            let body = XmlElement::new(response_name.0, response_name.1)
+                .with_children(response.get_propstats().iter().map(PropStat::to_xml).collect());
+            return HttpResponse::Forbidden()
+                .content_type("application/xml; charset=utf-8")
+                .body(body.to_xml());
+        }
+
+        match self.calendars.create_calendar(&calendar).await {
+            Ok(Some(_)) => HttpResponse::Created().finish(),
+            Ok(None) => error_response(StatusCode::METHOD_NOT_ALLOWED, DAV_NAMESPACE, "resource-must-be-null"),
+            Err(_) => HttpResponse::InternalServerError().finish(),
+        }
+    }
+
+    fn get_context(&self, request: &HttpRequest) -> DavContext {
+        DavContext {
+            max_attachment_size: self.max_attachment_size,
+            include_timezones: should_include_timezones(get_header(request, CALDAV_TIMEZONES_HEADER)),
+            calendar_data: CalendarData::default(),
+        }
+    }
+
+    async fn find_objects(
+        &self,
+        path: &DavPath,
+        depth: Depth,
+        time_range: Option<&TimeRange>,
+    ) -> mongodb::error::Result<Option<Vec<(String, CalendarObject)>>> {
+        /* Calendar object resources a report applies to: the resource, or the members of the collection */
+        let calendars = match path {
+            DavPath::Root if depth == Depth::Infinity => self.calendars.get_calendars().await?,
+            DavPath::Root => Vec::new(),
+            DavPath::Calendar(name) => match self.calendars.get_calendar(name).await? {
+                Some(_) if depth == Depth::Zero => Vec::new(),
+                Some(calendar) => vec![calendar],
+                None => return Ok(None),
+            },
+            DavPath::Object { .. } => {
+                let resources = self.get_resources(path, Depth::Zero).await?;
+                return Ok(resources.map(|resources| {
+                    resources
+                        .into_iter()
+                        .filter_map(|resource| match resource {
+                            DavResource::Object { calendar, object } => Some((calendar, object)),
+                            _ => None,
+                        })
+                        .collect()
+                }));
+            }
+        };
+
+        let mut objects = Vec::new();
+        for calendar in calendars {
+            let Some(calendar_id) = calendar.get_id() else {
+                continue;
+            };
+            let found = match time_range {
+                Some(time_range) => {
+                    self.calendars
+                        .get_objects_in_range(calendar_id, time_range.start, time_range.end)
+                        .await?
+                }
+                None => self.calendars.get_objects(calendar_id).await?,
+            };
+            objects.extend(
+                found
+                    .into_iter()
+                    .map(|object| (calendar.get_name().to_string(), object)),
+            );
+        }
+        Ok(Some(objects))
+    }
+
+    async fn get_objects_by_paths(
+        &self,
+        paths: &[Option<DavPath>],
+    ) -> mongodb::error::Result<HashMap<(String, String), CalendarObject>> {
+        /* Calendar object resources at the paths, loaded with a single query per calendar */
+        let mut names_by_calendar: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for path in paths.iter().flatten() {
+            if let DavPath::Object { calendar, name } = path {
+                names_by_calendar.entry(calendar).or_default().push(name.clone());
+            }
+        }
+
+        let mut objects = HashMap::new();
+        for (calendar, names) in names_by_calendar {
+            let Some(calendar_id) = self
+                .calendars
+                .get_calendar(calendar)
+                .await?
+                .and_then(|calendar| calendar.get_id())
+            else {
+                continue;
+            };
+            for object in self.calendars.get_objects_by_names(calendar_id, &names).await? {
+                objects.insert((calendar.to_string(), object.get_name().to_string()), object);
+            }
+        }
+        Ok(objects)
+    }
+
+    async fn get_resources(&self, path: &DavPath, depth: Depth) -> mongodb::error::Result<Option<Vec<DavResource>>> {
+        /* The resource, followed by its members when the depth is 1 */
+        match path {
+            DavPath::Root => {
+                let mut resources = vec![DavResource::Root];
+                if depth == Depth::One {
+                    let calendars = self.calendars.get_calendars().await?;
+                    resources.extend(calendars.into_iter().map(DavResource::Calendar));
+                }
+                Ok(Some(resources))
+            }
+            DavPath::Calendar(name) => {
+                let Some(calendar) = self.calendars.get_calendar(name).await? else {
+                    return Ok(None);
+                };
+                let objects = match (depth, calendar.get_id()) {
+                    (Depth::One, Some(calendar_id)) => self.calendars.get_objects(calendar_id).await?,
+                    _ => Vec::new(),
+                };
+                let mut resources = vec![DavResource::Calendar(calendar)];
+                resources.extend(objects.into_iter().map(|object| DavResource::Object {
+                    calendar: name.clone(),
+                    object,
+                }));
+                Ok(Some(resources))
+            }
+            DavPath::Object { calendar, name } => {
+                let Some(calendar_id) = self
+                    .calendars
+                    .get_calendar(calendar)
+                    .await?
+                    .and_then(|calendar| calendar.get_id())
+                else {
+                    return Ok(None);
+                };
+                let object = self.calendars.get_object(calendar_id, name).await?;
+                Ok(object.map(|object| {
+                    vec![DavResource::Object {
+                        calendar: calendar.clone(),
+                        object,
+                    }]
+                }))
+            }
+        }
+    }
+}
+
+pub fn get_header<'a>(request: &'a HttpRequest, name: &str) -> Option<&'a str> {
+    request.headers().get(name).and_then(|value| value.to_str().ok())
+}
+
+pub fn check_preconditions(
+    request: &HttpRequest,
+    href: &str,
+    etag: Option<&str>,
+    exists: bool,
+) -> Result<(), HttpResponse> {
+    /* If-Match, If-None-Match and If headers, a GET or HEAD of an unchanged resource returning 304 */
+    let Some(preconditions) = Preconditions::parse(
+        get_header(request, "If-Match"),
+        get_header(request, "If-None-Match"),
+        get_header(request, IF_HEADER),
+    ) else {
+        return Err(HttpResponse::BadRequest().finish());
+    };
+    if preconditions.evaluate(href, etag, exists) {
+        return Ok(());
+    }
+    let read = request.method() == Method::GET || request.method() == Method::HEAD;
+    match (etag, read && preconditions.is_not_modified(href, etag, exists)) {
+        (Some(etag), true) => Err(HttpResponse::NotModified().insert_header((header::ETAG, etag)).finish()),
+        _ => Err(HttpResponse::PreconditionFailed().finish()),
+    }
+}
+
+fn property_results(href: &str, results: Vec<(PropertyName, Result<(), PropertyError>)>) -> DavResponse {
+    /* When a property cannot be set, the others fail with 424 (Failed Dependency) as none is set */
+    let failed = results.iter().any(|(_, result)| result.is_err());
+    let mut response = DavResponse::new(href);
+    for (property, result) in results {
+        match result {
+            Ok(()) if failed => response.add_property(StatusCode::FAILED_DEPENDENCY, property.to_xml()),
+            Ok(()) => response.add_property(StatusCode::OK, property.to_xml()),
+            Err(error) => {
+                response.add_property_with_error(error.get_status(), property.to_xml(), error.get_condition())
+            }
+        }
+    }
+    response
+}
+
+fn multistatus_response(multistatus: &Multistatus) -> HttpResponse {
+    HttpResponse::build(StatusCode::MULTI_STATUS)
+        .content_type("application/xml; charset=utf-8")
+        .body(multistatus.to_xml().to_xml())
+}
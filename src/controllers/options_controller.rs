@@ -0,0 +1,51 @@
+use actix_web::HttpResponse;
+
+use crate::app::{
+    attachments::managed_attachments::CALENDAR_MANAGED_ATTACHMENTS,
+    calendar::models::calendar_types::timezone::timezones_by_reference::CALENDAR_NO_TIMEZONE,
+    dav::mkcol::EXTENDED_MKCOL,
+};
+
+/* Compliance classes advertised in the DAV header (RFC 4918 section 10.1) */
+pub const DAV_COMPLIANCE_CLASSES: [&str; 5] = [
+    "1",
+    "calendar-access",
+    EXTENDED_MKCOL,
+    CALENDAR_NO_TIMEZONE,
+    CALENDAR_MANAGED_ATTACHMENTS,
+];
+
+pub const ALLOWED_METHODS: [&str; 11] = [
+    "OPTIONS",
+    "GET",
+    "HEAD",
+    "PUT",
+    "DELETE",
+    "POST",
+    "PROPFIND",
+    "PROPPATCH",
+    "REPORT",
+    "MKCALENDAR",
+    "MKCOL",
+];
+
+pub struct OptionsController {}
+
+impl OptionsController {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub async fn options(&self) -> HttpResponse {
+        HttpResponse::Ok()
+            .insert_header(("DAV", DAV_COMPLIANCE_CLASSES.join(", ")))
+            .insert_header(("Allow", ALLOWED_METHODS.join(", ")))
+            .finish()
+    }
+}
+
+impl Default for OptionsController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
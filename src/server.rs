@@ -1,21 +1,108 @@
-use actix_web::{middleware, web::Data, App, HttpServer};
+use actix_web::{delete, get, middleware, post, put, route, web, web::Data, App, HttpRequest, HttpServer, Responder};
 
-use crate::architecture::{app_config::AppConfig, dependency_injection::di_container};
+use crate::{
+    app::alarms::alarm_scheduler::AlarmScheduler,
+    architecture::{
+        app_config::AppConfig,
+        dependency_injection::{di_container, DDIProvider},
+    },
+    controllers::{
+        attachments_controller::{AttachmentsController, ManagedAttachmentQuery},
+        calendar_object_controller::CalendarObjectController,
+        dav_controller::DavController,
+        options_controller::OptionsController,
+    },
+};
 
 /*#[post("/")]
 async fn hello(provider: web::Data<DDIProvider>, request: Xml<XMLRequest>) -> impl Responder {
     provider.get::<HelloController>().hello(request.0).await
 }*/
 
+#[route("/{path:.*}", method = "OPTIONS")]
+async fn options(provider: web::Data<DDIProvider>) -> impl Responder {
+    provider.get::<OptionsController>().options().await
+}
+
+#[get("/attachments/{managed_id}")]
+async fn get_attachment(provider: web::Data<DDIProvider>, managed_id: web::Path<String>) -> impl Responder {
+    provider.get::<AttachmentsController>().get(&managed_id).await
+}
+
+#[route("/calendars{path:.*}", method = "PROPFIND")]
+async fn propfind(provider: web::Data<DDIProvider>, request: HttpRequest, body: String) -> impl Responder {
+    provider.get::<DavController>().propfind(&request, &body).await
+}
+
+#[route("/calendars{path:.*}", method = "PROPPATCH")]
+async fn proppatch(provider: web::Data<DDIProvider>, request: HttpRequest, body: String) -> impl Responder {
+    provider.get::<DavController>().proppatch(&request, &body).await
+}
+
+#[route("/calendars{path:.*}", method = "REPORT")]
+async fn report(provider: web::Data<DDIProvider>, request: HttpRequest, body: String) -> impl Responder {
+    provider.get::<DavController>().report(&request, &body).await
+}
+
+#[route("/calendars{path:.*}", method = "MKCALENDAR")]
+async fn mkcalendar(provider: web::Data<DDIProvider>, request: HttpRequest, body: String) -> impl Responder {
+    provider.get::<DavController>().mkcalendar(&request, &body).await
+}
+
+#[route("/calendars{path:.*}", method = "MKCOL")]
+async fn mkcol(provider: web::Data<DDIProvider>, request: HttpRequest, body: String) -> impl Responder {
+    provider.get::<DavController>().mkcol(&request, &body).await
+}
+
+#[route("/calendars{path:.*}", method = "GET", method = "HEAD")]
+async fn get_object(provider: web::Data<DDIProvider>, request: HttpRequest) -> impl Responder {
+    provider.get::<CalendarObjectController>().get(&request).await
+}
+
+#[put("/calendars{path:.*}")]
+async fn put_object(provider: web::Data<DDIProvider>, request: HttpRequest, body: String) -> impl Responder {
+    provider.get::<CalendarObjectController>().put(&request, &body).await
+}
+
+#[delete("/calendars{path:.*}")]
+async fn delete_object(provider: web::Data<DDIProvider>, request: HttpRequest) -> impl Responder {
+    provider.get::<CalendarObjectController>().delete(&request).await
+}
+
+#[post("/calendars{path:.*}")]
+async fn post_object(
+    provider: web::Data<DDIProvider>,
+    request: HttpRequest,
+    query: web::Query<ManagedAttachmentQuery>,
+    body: web::Bytes,
+) -> impl Responder {
+    provider
+        .get::<CalendarObjectController>()
+        .post(&request, &query, &body)
+        .await
+}
+
 pub async fn start_server() -> Result<(), std::io::Error> {
     let app_config = AppConfig::new();
     let client = mongodb::Client::with_uri_str(&app_config.mongodb).await.unwrap();
     let di_provider = di_container(&client, app_config.mongo_database).await;
+    di_provider.get::<AlarmScheduler>().clone().start();
 
     HttpServer::new(move || {
         App::new()
             .wrap(middleware::Logger::default())
             .app_data(Data::new(di_provider.clone()))
+            .service(options)
+            .service(get_attachment)
+            .service(propfind)
+            .service(proppatch)
+            .service(report)
+            .service(mkcalendar)
+            .service(mkcol)
+            .service(get_object)
+            .service(put_object)
+            .service(delete_object)
+            .service(post_object)
         //.service(hello)
     })
     .bind(("127.0.0.1", app_config.server_port))?
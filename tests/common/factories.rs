@@ -1,2 +1,4 @@
+#![allow(unused_imports)]
+
 pub mod datetime_factory;
 pub use datetime_factory::*;
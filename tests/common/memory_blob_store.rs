@@ -0,0 +1,44 @@
+#![allow(dead_code)]
+
+use std::{collections::HashMap, sync::Mutex};
+
+use async_trait::async_trait;
+use bson::oid::ObjectId;
+use cal_dav_fast::app::attachments::blob_store::{BlobInfo, BlobStore};
+
+#[derive(Default)]
+pub struct MemoryBlobStore {
+    pub blobs: Mutex<HashMap<ObjectId, (BlobInfo, Vec<u8>)>>,
+}
+
+#[async_trait]
+impl BlobStore for MemoryBlobStore {
+    async fn upload(
+        &self,
+        filename: &str,
+        content_type: Option<&str>,
+        data: &[u8],
+    ) -> mongodb::error::Result<ObjectId> {
+        let id = ObjectId::new();
+        let info = BlobInfo {
+            filename: Some(filename.to_string()).filter(|filename| !filename.is_empty()),
+            content_type: content_type.map(str::to_string),
+            size: data.len() as u64,
+        };
+        self.blobs.lock().unwrap().insert(id, (info, data.to_vec()));
+        Ok(id)
+    }
+
+    async fn download(&self, id: ObjectId) -> mongodb::error::Result<Vec<u8>> {
+        Ok(self.blobs.lock().unwrap()[&id].1.clone())
+    }
+
+    async fn get_info(&self, id: ObjectId) -> mongodb::error::Result<Option<BlobInfo>> {
+        Ok(self.blobs.lock().unwrap().get(&id).map(|blob| blob.0.clone()))
+    }
+
+    async fn delete(&self, id: ObjectId) -> mongodb::error::Result<()> {
+        self.blobs.lock().unwrap().remove(&id);
+        Ok(())
+    }
+}
@@ -0,0 +1,335 @@
+#![allow(dead_code)]
+
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use bson::oid::ObjectId;
+use cal_dav_fast::app::calendar::{
+    calendar_store::{group_objects, CalendarStore},
+    ical::ics,
+    models::{
+        calendar::Calendar,
+        calendar_object::{CalendarObject, ComponentRevision},
+        event::Event,
+        journal::Journal,
+        todo::Todo,
+    },
+};
+use chrono::{DateTime, Utc};
+
+#[derive(Default)]
+pub struct MemoryCalendarStore {
+    pub calendars: Mutex<Vec<Calendar>>,
+    pub events: Mutex<Vec<Event>>,
+    pub todos: Mutex<Vec<Todo>>,
+    pub journals: Mutex<Vec<Journal>>,
+}
+
+impl MemoryCalendarStore {
+    pub fn add_calendar(&self, mut calendar: Calendar) -> ObjectId {
+        let id = ObjectId::new();
+        calendar.set_id(id);
+        self.calendars.lock().unwrap().push(calendar);
+        id
+    }
+
+    pub fn add_object(&self, calendar_id: ObjectId, name: &str, text: &str) {
+        let calendar = ics::parse(text).unwrap();
+        let mut events = Event::from_calendar(&calendar).unwrap();
+        for event in events.iter_mut() {
+            event.set_id(ObjectId::new());
+            event.set_resource(calendar_id, name);
+        }
+        self.events.lock().unwrap().extend(events);
+        let mut todos = Todo::from_calendar(&calendar).unwrap();
+        for todo in todos.iter_mut() {
+            todo.set_id(ObjectId::new());
+            todo.set_resource(calendar_id, name);
+        }
+        self.todos.lock().unwrap().extend(todos);
+        let mut journals = Journal::from_calendar(&calendar).unwrap();
+        for journal in journals.iter_mut() {
+            journal.set_id(ObjectId::new());
+            journal.set_resource(calendar_id, name);
+        }
+        self.journals.lock().unwrap().extend(journals);
+    }
+
+    fn remove_components(&self, calendar_id: ObjectId, name: &str) {
+        self.events
+            .lock()
+            .unwrap()
+            .retain(|event| event.get_calendar_id() != Some(calendar_id) || event.get_resource_name() != Some(name));
+        self.todos
+            .lock()
+            .unwrap()
+            .retain(|todo| todo.get_calendar_id() != Some(calendar_id) || todo.get_resource_name() != Some(name));
+        self.journals.lock().unwrap().retain(|journal| {
+            journal.get_calendar_id() != Some(calendar_id) || journal.get_resource_name() != Some(name)
+        });
+    }
+
+    fn get_todos(&self, calendar_id: ObjectId, name: Option<&str>) -> Vec<Todo> {
+        let mut todos: Vec<Todo> = self
+            .todos
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|todo| todo.get_calendar_id() == Some(calendar_id))
+            .filter(|todo| name.is_none() || todo.get_resource_name() == name)
+            .cloned()
+            .collect();
+        todos.sort_by(|a, b| a.get_resource_name().cmp(&b.get_resource_name()));
+        todos
+    }
+
+    fn get_journals(&self, calendar_id: ObjectId, name: Option<&str>) -> Vec<Journal> {
+        let mut journals: Vec<Journal> = self
+            .journals
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|journal| journal.get_calendar_id() == Some(calendar_id))
+            .filter(|journal| name.is_none() || journal.get_resource_name() == name)
+            .cloned()
+            .collect();
+        journals.sort_by(|a, b| a.get_resource_name().cmp(&b.get_resource_name()));
+        journals
+    }
+}
+
+fn get_revision(object: Option<&CalendarObject>) -> Option<ComponentRevision> {
+    /* First component, version and entity tag, compared like the MongoDB store */
+    object?.get_revision()
+}
+
+#[async_trait]
+impl CalendarStore for MemoryCalendarStore {
+    async fn get_calendars(&self) -> mongodb::error::Result<Vec<Calendar>> {
+        let mut calendars = self.calendars.lock().unwrap().clone();
+        calendars.sort_by(|a, b| a.get_name().cmp(b.get_name()));
+        Ok(calendars)
+    }
+
+    async fn get_calendar(&self, name: &str) -> mongodb::error::Result<Option<Calendar>> {
+        Ok(self
+            .calendars
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|calendar| calendar.get_name() == name)
+            .cloned())
+    }
+
+    async fn create_calendar(&self, calendar: &Calendar) -> mongodb::error::Result<Option<ObjectId>> {
+        if self.get_calendar(calendar.get_name()).await?.is_some() {
+            return Ok(None);
+        }
+        Ok(Some(self.add_calendar(calendar.clone())))
+    }
+
+    async fn update_calendar(&self, calendar: &Calendar) -> mongodb::error::Result<()> {
+        for stored in self.calendars.lock().unwrap().iter_mut() {
+            if stored.get_id() == calendar.get_id() {
+                *stored = calendar.clone();
+            }
+        }
+        Ok(())
+    }
+
+    async fn get_objects(&self, calendar_id: ObjectId) -> mongodb::error::Result<Vec<CalendarObject>> {
+        let mut events: Vec<Event> = self
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|event| event.get_calendar_id() == Some(calendar_id))
+            .cloned()
+            .collect();
+        events.sort_by(|a, b| a.get_resource_name().cmp(&b.get_resource_name()));
+        Ok(group_objects(
+            events,
+            self.get_todos(calendar_id, None),
+            self.get_journals(calendar_id, None),
+        ))
+    }
+
+    async fn get_objects_in_range(
+        &self,
+        calendar_id: ObjectId,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> mongodb::error::Result<Vec<CalendarObject>> {
+        let in_range = |event: &Event| {
+            event.get_recurrence().is_some()
+                || (end.is_none_or(|end| event.get_start() < end)
+                    && start.is_none_or(|start| event.get_end().is_none_or(|event_end| event_end >= start)))
+        };
+        /* To-dos and journal entries are all matched by the query filters */
+        let mut names: Vec<String> = self
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|event| event.get_calendar_id() == Some(calendar_id) && in_range(event))
+            .filter_map(|event| event.get_resource_name().map(str::to_string))
+            .collect();
+        names.extend(
+            self.get_todos(calendar_id, None)
+                .iter()
+                .filter_map(|todo| todo.get_resource_name().map(str::to_string)),
+        );
+        names.extend(
+            self.get_journals(calendar_id, None)
+                .iter()
+                .filter_map(|journal| journal.get_resource_name().map(str::to_string)),
+        );
+        let objects = self.get_objects(calendar_id).await?;
+        Ok(objects
+            .into_iter()
+            .filter(|object| names.iter().any(|name| name == object.get_name()))
+            .collect())
+    }
+
+    async fn get_object(&self, calendar_id: ObjectId, name: &str) -> mongodb::error::Result<Option<CalendarObject>> {
+        let events: Vec<Event> = self
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|event| event.get_calendar_id() == Some(calendar_id) && event.get_resource_name() == Some(name))
+            .cloned()
+            .collect();
+        let todos = self.get_todos(calendar_id, Some(name));
+        let journals = self.get_journals(calendar_id, Some(name));
+        Ok(group_objects(events, todos, journals).pop())
+    }
+
+    async fn get_objects_by_names(
+        &self,
+        calendar_id: ObjectId,
+        names: &[String],
+    ) -> mongodb::error::Result<Vec<CalendarObject>> {
+        let objects = self.get_objects(calendar_id).await?;
+        Ok(objects
+            .into_iter()
+            .filter(|object| names.iter().any(|name| name == object.get_name()))
+            .collect())
+    }
+
+    async fn find_uid(&self, calendar_id: ObjectId, uid: &str) -> mongodb::error::Result<Option<String>> {
+        let event = self
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|event| event.get_calendar_id() == Some(calendar_id) && event.get_uid() == uid)
+            .and_then(|event| event.get_resource_name().map(str::to_string));
+        Ok(event
+            .or_else(|| {
+                self.get_todos(calendar_id, None)
+                    .iter()
+                    .find(|todo| todo.get_uid() == uid)
+                    .and_then(|todo| todo.get_resource_name().map(str::to_string))
+            })
+            .or_else(|| {
+                self.get_journals(calendar_id, None)
+                    .iter()
+                    .find(|journal| journal.get_uid() == uid)
+                    .and_then(|journal| journal.get_resource_name().map(str::to_string))
+            }))
+    }
+
+    async fn save_object(
+        &self,
+        calendar_id: ObjectId,
+        object: &CalendarObject,
+        previous: Option<&CalendarObject>,
+    ) -> mongodb::error::Result<bool> {
+        let current = self.get_object(calendar_id, object.get_name()).await?;
+        if get_revision(current.as_ref()) != get_revision(previous) {
+            return Ok(false);
+        }
+        self.remove_components(calendar_id, object.get_name());
+        self.events
+            .lock()
+            .unwrap()
+            .extend(object.get_events().iter().cloned().map(|mut event| {
+                event.set_id(ObjectId::new());
+                event
+            }));
+        self.todos
+            .lock()
+            .unwrap()
+            .extend(object.get_todos().iter().cloned().map(|mut todo| {
+                todo.set_id(ObjectId::new());
+                todo
+            }));
+        self.journals
+            .lock()
+            .unwrap()
+            .extend(object.get_journals().iter().cloned().map(|mut journal| {
+                journal.set_id(ObjectId::new());
+                journal
+            }));
+        Ok(true)
+    }
+
+    async fn update_object_properties(&self, object: &CalendarObject) -> mongodb::error::Result<bool> {
+        let (Some(revision), Some(properties)) = (object.get_revision(), object.get_dead_properties()) else {
+            return Ok(false);
+        };
+        let matches = |id: Option<ObjectId>, etag: Option<&str>| id == revision.id && etag == revision.etag.as_deref();
+        if let Some(event) = self
+            .events
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|event| matches(event.get_id(), event.get_etag()))
+        {
+            *event.get_dead_properties_mut() = properties.clone();
+            return Ok(true);
+        }
+        if let Some(todo) = self
+            .todos
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|todo| matches(todo.get_id(), todo.get_etag()))
+        {
+            *todo.get_dead_properties_mut() = properties.clone();
+            return Ok(true);
+        }
+        match self
+            .journals
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|journal| matches(journal.get_id(), journal.get_etag()))
+        {
+            Some(journal) => {
+                *journal.get_dead_properties_mut() = properties.clone();
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn delete_object(&self, calendar_id: ObjectId, object: &CalendarObject) -> mongodb::error::Result<bool> {
+        let current = self.get_object(calendar_id, object.get_name()).await?;
+        if get_revision(current.as_ref()) != get_revision(Some(object)) {
+            return Ok(false);
+        }
+        self.remove_components(calendar_id, object.get_name());
+        Ok(true)
+    }
+
+    async fn touch_calendar(&self, calendar_id: ObjectId, now: DateTime<Utc>) -> mongodb::error::Result<()> {
+        for calendar in self.calendars.lock().unwrap().iter_mut() {
+            if calendar.get_id() == Some(calendar_id) {
+                calendar.touch(now);
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,3 +1,5 @@
+#![allow(unused_imports, dead_code)]
+
 pub mod test_runner;
 pub use test_runner::*;
 
@@ -5,3 +7,70 @@ pub mod factories;
 pub use factories::*;
 
 pub use factori::*;
+
+pub mod memory_calendar_store;
+pub use memory_calendar_store::*;
+
+pub mod memory_blob_store;
+pub use memory_blob_store::*;
+
+use std::sync::Arc;
+
+use actix_web::{body::to_bytes, HttpResponse};
+use cal_dav_fast::{
+    app::{
+        attachments::{attachment_store::AttachmentStore, managed_attachments::ManagedAttachments},
+        calendar::models::calendar::Calendar,
+    },
+    controllers::{
+        attachments_controller::AttachmentsController, calendar_object_controller::CalendarObjectController,
+        dav_controller::DavController,
+    },
+};
+
+pub async fn read_body(response: HttpResponse) -> String {
+    String::from_utf8(to_bytes(response.into_body()).await.unwrap().to_vec()).unwrap()
+}
+
+pub fn event(uid: &str, properties: &str) -> String {
+    /* Calendar with a single event, starting on 2021-01-04 at 10:00 UTC */
+    format!(
+        "BEGIN:VCALENDAR\r\n\
+         VERSION:2.0\r\n\
+         BEGIN:VEVENT\r\n\
+         UID:{}\r\n\
+         DTSTAMP:20210101T000000Z\r\n\
+         DTSTART:20210104T100000Z\r\n\
+         {}END:VEVENT\r\n\
+         END:VCALENDAR\r\n",
+        uid, properties
+    )
+}
+
+pub fn setup() -> (Arc<MemoryCalendarStore>, DavController) {
+    /* Empty work calendar */
+    setup_calendar(Calendar::new("work"), &[])
+}
+
+pub fn setup_calendar(calendar: Calendar, objects: &[(&str, &str)]) -> (Arc<MemoryCalendarStore>, DavController) {
+    /* Store holding the calendar and its resources, by name and content */
+    let store = Arc::new(MemoryCalendarStore::default());
+    let calendar_id = store.add_calendar(calendar);
+    for (name, text) in objects {
+        store.add_object(calendar_id, name, text);
+    }
+    (store.clone(), DavController::new(store, 1024))
+}
+
+pub fn object_controller(
+    store: Arc<MemoryCalendarStore>,
+    blobs: Arc<MemoryBlobStore>,
+    max_attachment_size: u64,
+) -> CalendarObjectController {
+    let managed = ManagedAttachments::new(blobs.clone(), max_attachment_size, "https://cal.example.com");
+    CalendarObjectController::new(
+        store,
+        Arc::new(AttachmentStore::new(blobs, max_attachment_size)),
+        Arc::new(AttachmentsController::new(Arc::new(managed))),
+    )
+}
@@ -0,0 +1,562 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use cal_dav_fast::app::{
+    alarms::{
+        alarm_queue::AlarmQueue,
+        alarm_scheduler::{AlarmScheduler, AlarmSchedulerState},
+        alarm_store::AlarmStore,
+        notifier::{
+            log_notifier::LogNotifier, smtp_notifier::SmtpNotifier, webhook_notifier::WebhookNotifier,
+            AlarmNotification, Notifier, NotifierError,
+        },
+    },
+    calendar::{
+        ical::ics,
+        models::{calendar_object::CalendarObject, calendar_types::alarm::AlarmAction, event::Event, todo::Todo},
+    },
+};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use pretty_assertions::assert_eq;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::TcpListener,
+};
+
+fn utc(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2021, 1, day, hour, minute, 0).unwrap()
+}
+
+fn parse_event(uid: &str, properties: &str) -> Event {
+    let text = format!(
+        "BEGIN:VCALENDAR\r\n\
+         VERSION:2.0\r\n\
+         BEGIN:VEVENT\r\n\
+         UID:{}\r\n\
+         DTSTAMP:20210101T000000Z\r\n\
+         DTSTART:20210104T100000Z\r\n\
+         DTEND:20210104T110000Z\r\n\
+         SUMMARY:Team meeting\r\n\
+         {}END:VEVENT\r\n\
+         END:VCALENDAR\r\n",
+        uid, properties
+    );
+    Event::from_calendar(&ics::parse(&text).unwrap()).unwrap().remove(0)
+}
+
+fn object(events: Vec<Event>) -> CalendarObject {
+    let name = format!("{}.ics", events[0].get_uid());
+    CalendarObject::new(&name, events)
+}
+
+const DISPLAY_ALARM: &str = "BEGIN:VALARM\r\n\
+                             ACTION:DISPLAY\r\n\
+                             TRIGGER:-PT15M\r\n\
+                             DESCRIPTION:Meeting soon\r\n\
+                             END:VALARM\r\n";
+
+fn notification(action: AlarmAction, recipients: Vec<&str>) -> AlarmNotification {
+    AlarmNotification {
+        uid: "event-1".to_string(),
+        alarm_uid: None,
+        action,
+        summary: Some("Team meeting".to_string()),
+        description: Some("Meeting soon".to_string()),
+        recipients: recipients.into_iter().map(str::to_string).collect(),
+        fire_at: utc(4, 9, 45),
+        instance_start: utc(4, 10, 0),
+        alarm_index: 0,
+    }
+}
+
+#[derive(Default)]
+struct MemoryStore {
+    cursor: Mutex<Option<DateTime<Utc>>>,
+
+    /* One resource per event */
+    objects: Mutex<Vec<CalendarObject>>,
+
+    /* UIDs of the events whose next alarm was saved, the others never had it computed */
+    computed: Mutex<HashSet<String>>,
+
+    /* Time up to which the alarms were loaded, and number of resources loaded, by each call */
+    loads: Mutex<Vec<(DateTime<Utc>, usize)>>,
+}
+
+impl MemoryStore {
+    fn new(cursor: Option<DateTime<Utc>>, events: Vec<(&str, String)>) -> Self {
+        Self {
+            cursor: Mutex::new(cursor),
+            objects: Mutex::new(
+                events
+                    .into_iter()
+                    .map(|(uid, properties)| object(vec![parse_event(uid, &properties)]))
+                    .collect(),
+            ),
+            ..Default::default()
+        }
+    }
+
+    fn get_next_alarms(&self) -> Vec<Option<DateTime<Utc>>> {
+        self.objects
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|object| object.get_events()[0].get_next_alarm_at())
+            .collect()
+    }
+}
+
+#[async_trait]
+impl AlarmStore for MemoryStore {
+    async fn get_cursor(&self) -> mongodb::error::Result<Option<DateTime<Utc>>> {
+        Ok(*self.cursor.lock().unwrap())
+    }
+
+    async fn save_cursor(&self, cursor: DateTime<Utc>) -> mongodb::error::Result<()> {
+        *self.cursor.lock().unwrap() = Some(cursor);
+        Ok(())
+    }
+
+    async fn get_objects_with_alarms(&self, until: DateTime<Utc>) -> mongodb::error::Result<Vec<CalendarObject>> {
+        let objects: Vec<CalendarObject> = self
+            .objects
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|object| {
+                object.get_events().iter().any(|event| {
+                    let next_alarm_at = match self.computed.lock().unwrap().contains(event.get_uid()) {
+                        true => event.get_next_alarm_at(),
+                        false => Some(until),
+                    };
+                    !event.get_alarms().is_empty() && next_alarm_at.is_some_and(|next| next <= until)
+                })
+            })
+            .cloned()
+            .collect();
+        self.loads.lock().unwrap().push((until, objects.len()));
+        Ok(objects)
+    }
+
+    async fn save_event_next_alarm(&self, event: &Event) -> mongodb::error::Result<()> {
+        self.computed.lock().unwrap().insert(event.get_uid().to_string());
+        for object in self.objects.lock().unwrap().iter_mut() {
+            for stored in object.get_events_mut() {
+                if stored.get_uid() == event.get_uid() && stored.get_recurrence_id() == event.get_recurrence_id() {
+                    *stored = event.clone();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn save_todo_next_alarm(&self, _todo: &Todo) -> mongodb::error::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct RecordingNotifier {
+    notifications: Mutex<Vec<AlarmNotification>>,
+}
+
+#[async_trait]
+impl Notifier for RecordingNotifier {
+    async fn notify(&self, notification: &AlarmNotification) -> Result<(), NotifierError> {
+        self.notifications.lock().unwrap().push(notification.clone());
+        Ok(())
+    }
+}
+
+impl RecordingNotifier {
+    fn get_fire_times(&self) -> Vec<DateTime<Utc>> {
+        self.notifications
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|notification| notification.fire_at)
+            .collect()
+    }
+}
+
+#[test]
+fn test_queue_orders_by_fire_time() {
+    let daily = parse_event("daily", &format!("RRULE:FREQ=DAILY;COUNT=3\r\n{}", DISPLAY_ALARM));
+    let single = parse_event(
+        "single",
+        "BEGIN:VALARM\r\n\
+         ACTION:DISPLAY\r\n\
+         TRIGGER;RELATED=END:PT0S\r\n\
+         DESCRIPTION:Meeting over\r\n\
+         END:VALARM\r\n",
+    );
+    let mut queue = AlarmQueue::new();
+
+    queue.add_object(&object(vec![daily]), utc(4, 0, 0), utc(10, 0, 0));
+    queue.add_object(&object(vec![single]), utc(4, 0, 0), utc(10, 0, 0));
+
+    assert_eq!(queue.len(), 4);
+    assert_eq!(queue.get_next_time(), Some(utc(4, 9, 45)));
+
+    let due = queue.pop_due(utc(5, 12, 0));
+    let due: Vec<(&str, DateTime<Utc>)> = due
+        .iter()
+        .map(|notification| (notification.uid.as_str(), notification.fire_at))
+        .collect();
+    assert_eq!(
+        due,
+        vec![
+            ("daily", utc(4, 9, 45)),
+            ("single", utc(4, 11, 0)),
+            ("daily", utc(5, 9, 45))
+        ]
+    );
+    assert_eq!(queue.get_next_time(), Some(utc(6, 9, 45)));
+}
+
+#[test]
+fn test_queue_skips_overridden_instances() {
+    let daily = parse_event("daily", &format!("RRULE:FREQ=DAILY;COUNT=3\r\n{}", DISPLAY_ALARM));
+    let moved = parse_event(
+        "daily",
+        "RECURRENCE-ID:20210105T100000Z\r\n\
+         BEGIN:VALARM\r\n\
+         ACTION:DISPLAY\r\n\
+         TRIGGER:-PT1H\r\n\
+         DESCRIPTION:Moved meeting soon\r\n\
+         END:VALARM\r\n",
+    );
+    let mut queue = AlarmQueue::new();
+
+    /* The override starts at 10:00 on the 4th, its own alarm replaces the one of the instance of the 5th */
+    queue.add_object(&object(vec![daily, moved]), utc(4, 0, 0), utc(10, 0, 0));
+
+    let fire_times: Vec<DateTime<Utc>> = queue
+        .pop_due(utc(10, 0, 0))
+        .iter()
+        .map(|notification| notification.fire_at)
+        .collect();
+    assert_eq!(fire_times, vec![utc(4, 9, 0), utc(4, 9, 45), utc(6, 9, 45)]);
+}
+
+#[test]
+fn test_queue_skips_completed_todo_instances() {
+    let text = format!(
+        "BEGIN:VCALENDAR\r\n\
+         VERSION:2.0\r\n\
+         BEGIN:VTODO\r\n\
+         UID:chores\r\n\
+         DTSTAMP:20210101T000000Z\r\n\
+         DTSTART:20210104T100000Z\r\n\
+         DUE:20210104T110000Z\r\n\
+         RRULE:FREQ=DAILY;COUNT=3\r\n\
+         {}END:VTODO\r\n\
+         BEGIN:VTODO\r\n\
+         UID:chores\r\n\
+         DTSTAMP:20210101T000000Z\r\n\
+         RECURRENCE-ID:20210105T100000Z\r\n\
+         DTSTART:20210105T100000Z\r\n\
+         DUE:20210105T110000Z\r\n\
+         STATUS:COMPLETED\r\n\
+         {}END:VTODO\r\n\
+         END:VCALENDAR\r\n",
+        DISPLAY_ALARM, DISPLAY_ALARM
+    );
+    let todos = Todo::from_calendar(&ics::parse(&text).unwrap()).unwrap();
+    let mut queue = AlarmQueue::new();
+
+    /* The instance of the 5th is replaced by a completed override, which needs no reminder */
+    queue.add_object(
+        &CalendarObject::from_todos("chores.ics", todos),
+        utc(4, 0, 0),
+        utc(10, 0, 0),
+    );
+
+    let due: Vec<(String, DateTime<Utc>)> = queue
+        .pop_due(utc(10, 0, 0))
+        .into_iter()
+        .map(|notification| (notification.uid, notification.fire_at))
+        .collect();
+    assert_eq!(
+        due,
+        vec![
+            ("chores".to_string(), utc(4, 9, 45)),
+            ("chores".to_string(), utc(6, 9, 45))
+        ]
+    );
+}
+
+#[test]
+fn test_notification_falls_back_to_event_texts() {
+    let event = parse_event(
+        "event-1",
+        "DESCRIPTION:Weekly sync\r\n\
+         BEGIN:VALARM\r\n\
+         ACTION:AUDIO\r\n\
+         TRIGGER:-PT5M\r\n\
+         END:VALARM\r\n",
+    );
+    let mut queue = AlarmQueue::new();
+
+    queue.add_object(&object(vec![event]), utc(1, 0, 0), utc(10, 0, 0));
+    let notification = queue.pop_due(utc(10, 0, 0)).remove(0);
+
+    assert_eq!(notification.summary.as_deref(), Some("Team meeting"));
+    assert_eq!(notification.description.as_deref(), Some("Weekly sync"));
+    assert_eq!(notification.fire_at, utc(4, 9, 55));
+}
+
+#[actix_web::test]
+async fn test_scheduler_fires_and_persists_cursor() {
+    let store = Arc::new(MemoryStore::new(
+        None,
+        vec![("event-1", format!("RRULE:FREQ=DAILY;COUNT=3\r\n{}", DISPLAY_ALARM))],
+    ));
+    let notifier = Arc::new(RecordingNotifier::default());
+    let scheduler = AlarmScheduler::new(store.clone(), vec![notifier.clone()]).with_lookahead(Duration::days(1));
+    let mut state = AlarmSchedulerState::default();
+
+    /* The first run starts from now, waking up for the next alarm */
+    let wake_at = scheduler.tick(&mut state, utc(4, 9, 0)).await.unwrap();
+    assert_eq!(wake_at, utc(4, 9, 1));
+    assert_eq!(*store.cursor.lock().unwrap(), Some(utc(4, 9, 0)));
+
+    /* The cursor is the time up to which the alarms were processed, even without any alarm */
+    scheduler.tick(&mut state, utc(4, 9, 30)).await.unwrap();
+    assert_eq!(*store.cursor.lock().unwrap(), Some(utc(4, 9, 30)));
+
+    let wake_at = scheduler.tick(&mut state, utc(4, 9, 45)).await.unwrap();
+    assert_eq!(notifier.get_fire_times(), vec![utc(4, 9, 45)]);
+    assert_eq!(*store.cursor.lock().unwrap(), Some(utc(4, 9, 45)));
+    assert_eq!(wake_at, utc(4, 9, 46));
+
+    /* After a restart, the alarms missed while down are fired once */
+    let mut state = AlarmSchedulerState::default();
+    scheduler.tick(&mut state, utc(5, 10, 0)).await.unwrap();
+    scheduler.tick(&mut state, utc(5, 10, 0)).await.unwrap();
+    assert_eq!(notifier.get_fire_times(), vec![utc(4, 9, 45), utc(5, 9, 45)]);
+    assert_eq!(*store.cursor.lock().unwrap(), Some(utc(5, 10, 0)));
+}
+
+#[actix_web::test]
+async fn test_scheduler_drops_stale_alarms() {
+    let store = Arc::new(MemoryStore::new(
+        Some(utc(4, 0, 0)),
+        vec![("event-1", format!("RRULE:FREQ=DAILY;COUNT=3\r\n{}", DISPLAY_ALARM))],
+    ));
+    let notifier = Arc::new(RecordingNotifier::default());
+    let scheduler = AlarmScheduler::new(store.clone(), vec![notifier.clone()]).with_max_delay(Duration::hours(2));
+
+    /* Down for two days, only the alarm of the last two hours is still fired */
+    scheduler
+        .tick(&mut AlarmSchedulerState::default(), utc(6, 11, 0))
+        .await
+        .unwrap();
+
+    assert_eq!(notifier.get_fire_times(), vec![utc(6, 9, 45)]);
+}
+
+#[actix_web::test]
+async fn test_scheduler_loads_upcoming_alarms_only() {
+    let store = Arc::new(MemoryStore::new(
+        Some(utc(4, 0, 0)),
+        vec![
+            ("daily", format!("RRULE:FREQ=DAILY\r\n{}", DISPLAY_ALARM)),
+            ("single", DISPLAY_ALARM.to_string()),
+        ],
+    ));
+    let notifier = Arc::new(RecordingNotifier::default());
+    let scheduler = AlarmScheduler::new(store.clone(), vec![notifier.clone()]);
+    let mut state = AlarmSchedulerState::default();
+
+    /* The events stored without their next alarm get it on the first load */
+    scheduler.tick(&mut state, utc(4, 9, 0)).await.unwrap();
+    assert_eq!(store.get_next_alarms(), vec![Some(utc(4, 9, 45)), Some(utc(4, 9, 45))]);
+
+    /* Once fired, the next alarm moves to the following instance, or to none */
+    scheduler.tick(&mut state, utc(4, 9, 45)).await.unwrap();
+    assert_eq!(notifier.get_fire_times(), vec![utc(4, 9, 45), utc(4, 9, 45)]);
+    assert_eq!(store.get_next_alarms(), vec![Some(utc(5, 9, 45)), None]);
+
+    /* Far from their next alarm, the events are not loaded */
+    let mut state = AlarmSchedulerState::default();
+    scheduler.tick(&mut state, utc(4, 12, 0)).await.unwrap();
+    assert_eq!(store.loads.lock().unwrap().last(), Some(&(utc(4, 13, 0), 0)));
+
+    /* More than 1000 instances later, the alarms still fire */
+    let now = Utc.with_ymd_and_hms(2026, 3, 1, 9, 0, 0).unwrap();
+    scheduler.tick(&mut AlarmSchedulerState::default(), now).await.unwrap();
+    scheduler
+        .tick(&mut AlarmSchedulerState::default(), now + Duration::minutes(45))
+        .await
+        .unwrap();
+    assert_eq!(
+        notifier.get_fire_times().last(),
+        Some(&Utc.with_ymd_and_hms(2026, 3, 1, 9, 45, 0).unwrap())
+    );
+}
+
+#[actix_web::test]
+async fn test_scheduler_skips_notifiers_not_handling_alarm() {
+    let store = Arc::new(MemoryStore::new(
+        Some(utc(4, 0, 0)),
+        vec![("event-1", DISPLAY_ALARM.to_string())],
+    ));
+    let smtp = Arc::new(SmtpNotifier::insecure("127.0.0.1", 1, "caldav@localhost", None).unwrap());
+    let notifier = Arc::new(RecordingNotifier::default());
+    let scheduler = AlarmScheduler::new(store, vec![smtp, Arc::new(LogNotifier::new()), notifier.clone()]);
+
+    scheduler
+        .tick(&mut AlarmSchedulerState::default(), utc(4, 10, 0))
+        .await
+        .unwrap();
+
+    assert_eq!(notifier.get_fire_times(), vec![utc(4, 9, 45)]);
+}
+
+#[actix_web::test]
+async fn test_scheduler_respects_acknowledgements() {
+    /* The first alarm was snoozed on a device, firing again at 09:55 */
+    let store = Arc::new(MemoryStore::new(
+        Some(utc(4, 0, 0)),
+        vec![(
+            "event-1",
+            "BEGIN:VALARM\r\n\
+             UID:alarm-1\r\n\
+             ACTION:DISPLAY\r\n\
+             TRIGGER:-PT15M\r\n\
+             ACKNOWLEDGED:20210104T094600Z\r\n\
+             DESCRIPTION:Meeting soon\r\n\
+             END:VALARM\r\n\
+             BEGIN:VALARM\r\n\
+             UID:alarm-2\r\n\
+             ACTION:DISPLAY\r\n\
+             TRIGGER;VALUE=DATE-TIME:20210104T095500Z\r\n\
+             RELATED-TO;RELTYPE=SNOOZE:alarm-1\r\n\
+             DESCRIPTION:Meeting soon\r\n\
+             END:VALARM\r\n"
+                .to_string(),
+        )],
+    ));
+    let notifier = Arc::new(RecordingNotifier::default());
+    let scheduler = AlarmScheduler::new(store, vec![notifier.clone()]);
+
+    scheduler
+        .tick(&mut AlarmSchedulerState::default(), utc(4, 10, 0))
+        .await
+        .unwrap();
+
+    let notifications = notifier.notifications.lock().unwrap();
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(notifications[0].fire_at, utc(4, 9, 55));
+    assert_eq!(notifications[0].alarm_uid.as_deref(), Some("alarm-2"));
+}
+
+async fn run_smtp_sink(listener: TcpListener) -> String {
+    /* Minimal SMTP server accepting one message and returning its data */
+    let (socket, _) = listener.accept().await.unwrap();
+    let (reader, mut writer) = socket.into_split();
+    let mut reader = BufReader::new(reader);
+    writer.write_all(b"220 localhost ESMTP\r\n").await.unwrap();
+
+    let mut data = String::new();
+    let mut in_data = false;
+    let mut line = String::new();
+    while reader.read_line(&mut line).await.unwrap() > 0 {
+        if in_data {
+            if line == ".\r\n" {
+                in_data = false;
+                writer.write_all(b"250 OK\r\n").await.unwrap();
+            } else {
+                data.push_str(&line);
+            }
+        } else {
+            let command = line.to_uppercase();
+            let reply: &[u8] = if command.starts_with("EHLO") {
+                b"250 localhost\r\n"
+            } else if command.starts_with("DATA") {
+                in_data = true;
+                b"354 Start mail input\r\n"
+            } else if command.starts_with("QUIT") {
+                writer.write_all(b"221 Bye\r\n").await.unwrap();
+                break;
+            } else {
+                b"250 OK\r\n"
+            };
+            writer.write_all(reply).await.unwrap();
+        }
+        line.clear();
+    }
+    data
+}
+
+#[actix_web::test]
+async fn test_smtp_notifier_sends_email() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let sink = tokio::spawn(run_smtp_sink(listener));
+    let notifier = SmtpNotifier::insecure("127.0.0.1", port, "caldav@localhost", None).unwrap();
+
+    assert!(!notifier.handles(&notification(AlarmAction::Display, vec!["john@example.com"])));
+    assert!(!notifier.handles(&notification(AlarmAction::Email, vec![])));
+
+    let email = notification(AlarmAction::Email, vec!["mailto:john@example.com"]);
+    assert!(notifier.handles(&email));
+    notifier.notify(&email).await.unwrap();
+    drop(notifier);
+
+    let data = sink.await.unwrap();
+    assert!(data.contains("To: john@example.com"));
+    assert!(data.contains("Subject: Team meeting"));
+    assert!(data.contains("Meeting soon"));
+}
+
+#[actix_web::test]
+async fn test_smtp_notifier_requires_starttls() {
+    /* The sink does not offer STARTTLS: the message is not sent over the plain connection */
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let sink = tokio::spawn(run_smtp_sink(listener));
+    let notifier = SmtpNotifier::new("127.0.0.1", port, "caldav@localhost", None, false).unwrap();
+
+    let email = notification(AlarmAction::Email, vec!["mailto:john@example.com"]);
+    assert!(notifier.notify(&email).await.is_err());
+    drop(notifier);
+
+    assert_eq!(sink.await.unwrap(), "");
+}
+
+#[actix_web::test]
+async fn test_webhook_notifier_posts_json() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/alarms", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        let mut buffer = [0; 1024];
+        while !String::from_utf8_lossy(&request).contains("\"alarm_index\":0}") {
+            let read = socket.read(&mut buffer).await.unwrap();
+            request.extend_from_slice(&buffer[..read]);
+        }
+        socket
+            .write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n")
+            .await
+            .unwrap();
+        String::from_utf8(request).unwrap()
+    });
+
+    WebhookNotifier::new(&url)
+        .notify(&notification(AlarmAction::Display, vec![]))
+        .await
+        .unwrap();
+
+    let request = server.await.unwrap();
+    assert!(request.starts_with("POST /alarms HTTP/1.1"));
+    assert!(request.contains("\"uid\":\"event-1\""));
+    assert!(request.contains("\"action\":\"Display\""));
+    assert!(request.contains("\"fire_at\":\"2021-01-04T09:45:00Z\""));
+}
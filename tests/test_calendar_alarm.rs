@@ -0,0 +1,363 @@
+use cal_dav_fast::app::calendar::{
+    ical::{ics, values::parse_duration, ICalError},
+    models::{
+        calendar_types::alarm::{Alarm, AlarmAction, AlarmTrigger, AlarmTriggerRelation},
+        event::Event,
+        todo::Todo,
+    },
+};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use pretty_assertions::assert_eq;
+
+fn calendar_with(components: &str) -> String {
+    format!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{}END:VCALENDAR\r\n", components)
+}
+
+fn event_with(properties: &str) -> Event {
+    let text = calendar_with(&format!(
+        "BEGIN:VEVENT\r\n\
+         UID:event-1\r\n\
+         DTSTAMP:20210101T000000Z\r\n\
+         DTSTART:20210104T100000Z\r\n\
+         DTEND:20210104T110000Z\r\n\
+         {}END:VEVENT\r\n",
+        properties
+    ));
+    Event::from_calendar(&ics::parse(&text).unwrap()).unwrap().remove(0)
+}
+
+fn parse_alarm(properties: &str) -> Result<Alarm, ICalError> {
+    let text = format!("BEGIN:VALARM\r\n{}END:VALARM\r\n", properties);
+    Alarm::from_ical(&ics::parse(&text).unwrap())
+}
+
+fn utc(month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2021, month, day, hour, minute, 0).unwrap()
+}
+
+#[test]
+fn test_parse_duration() {
+    assert_eq!(parse_duration("PT15M"), Some(Duration::minutes(15)));
+    assert_eq!(parse_duration("-PT15M"), Some(Duration::minutes(-15)));
+    assert_eq!(
+        parse_duration("P15DT5H0M20S"),
+        Some(Duration::days(15) + Duration::hours(5) + Duration::seconds(20))
+    );
+    assert_eq!(parse_duration("+P7W"), Some(Duration::weeks(7)));
+    assert_eq!(parse_duration("PT"), None);
+    assert_eq!(parse_duration("P5H"), None);
+    assert_eq!(parse_duration("15M"), None);
+}
+
+#[test]
+fn test_alarm_from_ical() {
+    let alarm = parse_alarm(
+        "ACTION:DISPLAY\r\n\
+         TRIGGER;RELATED=END:-P2D\r\n\
+         REPEAT:4\r\n\
+         DURATION:PT15M\r\n\
+         DESCRIPTION:Breakfast meeting\r\n",
+    )
+    .unwrap();
+
+    assert_eq!(alarm.action, AlarmAction::Display);
+    assert_eq!(
+        alarm.trigger,
+        AlarmTrigger::Relative {
+            offset: -2 * 24 * 3600,
+            related: AlarmTriggerRelation::End
+        }
+    );
+    assert_eq!(alarm.repeat, 4);
+    assert_eq!(alarm.repeat_interval, Some(15 * 60));
+
+    let component = alarm.to_ical();
+    let trigger = component.get_property("TRIGGER").unwrap();
+    assert_eq!(trigger.get_value(), "-P2D");
+    assert_eq!(trigger.get_parameter_value("RELATED"), Some("END"));
+    assert_eq!(component.get_property("DURATION").unwrap().get_value(), "PT15M");
+}
+
+#[test]
+fn test_alarm_absolute_trigger() {
+    let alarm = parse_alarm("ACTION:AUDIO\r\nTRIGGER;VALUE=DATE-TIME:19970317T133000Z\r\n").unwrap();
+
+    let date = Utc.with_ymd_and_hms(1997, 3, 17, 13, 30, 0).unwrap();
+    assert_eq!(alarm.trigger, AlarmTrigger::Absolute(date));
+    assert_eq!(alarm.get_trigger_times(None, None), vec![date]);
+}
+
+#[test]
+fn test_alarm_invalid() {
+    assert!(matches!(
+        parse_alarm("ACTION:DISPLAY\r\nTRIGGER:-PT15M\r\n"),
+        Err(ICalError::MissingProperty(name)) if name == "DESCRIPTION"
+    ));
+    assert!(matches!(
+        parse_alarm("ACTION:EMAIL\r\nTRIGGER:-PT15M\r\nDESCRIPTION:Text\r\nSUMMARY:Subject\r\n"),
+        Err(ICalError::MissingProperty(name)) if name == "ATTENDEE"
+    ));
+    assert!(matches!(
+        parse_alarm("ACTION:AUDIO\r\nTRIGGER:-PT15M\r\nREPEAT:2\r\n"),
+        Err(ICalError::MissingProperty(name)) if name == "DURATION"
+    ));
+    assert!(matches!(
+        parse_alarm("ACTION:SMOKE\r\nTRIGGER:-PT15M\r\n"),
+        Err(ICalError::InvalidValue { .. })
+    ));
+    assert!(matches!(
+        parse_alarm("ACTION:AUDIO\r\nTRIGGER:soon\r\n"),
+        Err(ICalError::InvalidValue { .. })
+    ));
+}
+
+#[test]
+fn test_event_alarm_times() {
+    let event = event_with(
+        "BEGIN:VALARM\r\n\
+         ACTION:DISPLAY\r\n\
+         TRIGGER:-PT15M\r\n\
+         REPEAT:1\r\n\
+         DURATION:PT5M\r\n\
+         DESCRIPTION:Starting soon\r\n\
+         END:VALARM\r\n\
+         BEGIN:VALARM\r\n\
+         ACTION:EMAIL\r\n\
+         TRIGGER;RELATED=END:PT0S\r\n\
+         SUMMARY:Meeting ended\r\n\
+         DESCRIPTION:Write the minutes\r\n\
+         ATTENDEE:mailto:minutes@example.com\r\n\
+         END:VALARM\r\n",
+    );
+
+    assert_eq!(event.get_alarms().len(), 2);
+    let times = event.get_alarm_times(&[], utc(1, 1, 0, 0), utc(2, 1, 0, 0));
+
+    let fire_times: Vec<(DateTime<Utc>, usize)> = times.iter().map(|time| (time.fire_at, time.alarm_index)).collect();
+    assert_eq!(
+        fire_times,
+        vec![(utc(1, 4, 9, 45), 0), (utc(1, 4, 9, 50), 0), (utc(1, 4, 11, 0), 1)]
+    );
+
+    /* The alarms are written back */
+    assert_eq!(event.to_ical().get_components("VALARM").count(), 2);
+}
+
+#[test]
+fn test_recurring_event_alarm_times() {
+    let event = event_with(
+        "RRULE:FREQ=WEEKLY;UNTIL=20210126T000000Z\r\n\
+         EXDATE:20210111T100000Z\r\n\
+         BEGIN:VALARM\r\n\
+         ACTION:AUDIO\r\n\
+         TRIGGER:-PT10M\r\n\
+         END:VALARM\r\n",
+    );
+
+    let times = event.get_alarm_times(&[], utc(1, 5, 0, 0), utc(3, 1, 0, 0));
+
+    let fire_times: Vec<DateTime<Utc>> = times.iter().map(|time| time.fire_at).collect();
+    assert_eq!(fire_times, vec![utc(1, 18, 9, 50), utc(1, 25, 9, 50)]);
+    assert_eq!(times[0].instance_start, utc(1, 18, 10, 0));
+
+    /* Alarms of instances after the window are not included, even if the instance is */
+    let times = event.get_alarm_times(&[], utc(1, 18, 0, 0), utc(1, 18, 9, 55));
+    assert_eq!(times.len(), 1);
+}
+
+#[test]
+fn test_recurring_event_alarm_times_far_from_start() {
+    let event = event_with(
+        "RRULE:FREQ=DAILY\r\n\
+         BEGIN:VALARM\r\n\
+         ACTION:AUDIO\r\n\
+         TRIGGER:-PT10M\r\n\
+         END:VALARM\r\n\
+         BEGIN:VALARM\r\n\
+         ACTION:AUDIO\r\n\
+         TRIGGER;VALUE=DATE-TIME:20210104T090000Z\r\n\
+         END:VALARM\r\n",
+    );
+
+    /* More than 1000 instances after DTSTART, absolute triggers only fire for the first instance */
+    let from = Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap();
+    let times = event.get_alarm_times(&[], from, from + Duration::days(2));
+
+    let fire_times: Vec<DateTime<Utc>> = times.iter().map(|time| time.fire_at).collect();
+    assert_eq!(
+        fire_times,
+        vec![
+            Utc.with_ymd_and_hms(2026, 3, 1, 9, 50, 0).unwrap(),
+            Utc.with_ymd_and_hms(2026, 3, 2, 9, 50, 0).unwrap()
+        ]
+    );
+    assert_eq!(
+        times[0].instance_start,
+        Utc.with_ymd_and_hms(2026, 3, 1, 10, 0, 0).unwrap()
+    );
+
+    let times = event.get_alarm_times(&[], utc(1, 1, 0, 0), utc(1, 5, 0, 0));
+    let fire_times: Vec<DateTime<Utc>> = times.iter().map(|time| time.fire_at).collect();
+    assert_eq!(fire_times, vec![utc(1, 4, 9, 0), utc(1, 4, 9, 50)]);
+}
+
+#[test]
+fn test_next_alarm_far_from_start() {
+    let mut event = event_with(
+        "RRULE:FREQ=MINUTELY;INTERVAL=7\r\n\
+         BEGIN:VALARM\r\n\
+         ACTION:AUDIO\r\n\
+         TRIGGER:-PT1M\r\n\
+         END:VALARM\r\n",
+    );
+
+    /* A century of instances before, only those around the date being expanded */
+    let after = Utc.with_ymd_and_hms(2121, 1, 1, 0, 0, 0).unwrap();
+    event.update_next_alarm(&[], after);
+    assert_eq!(
+        event.get_next_alarm_at(),
+        Some(Utc.with_ymd_and_hms(2121, 1, 1, 0, 1, 0).unwrap())
+    );
+
+    /* Searched up to a century later */
+    let mut event = event_with(
+        "RRULE:FREQ=YEARLY;INTERVAL=50\r\n\
+         BEGIN:VALARM\r\n\
+         ACTION:AUDIO\r\n\
+         TRIGGER:-PT10M\r\n\
+         END:VALARM\r\n",
+    );
+    event.update_next_alarm(&[], utc(2, 1, 0, 0));
+    assert_eq!(
+        event.get_next_alarm_at(),
+        Some(Utc.with_ymd_and_hms(2071, 1, 4, 9, 50, 0).unwrap())
+    );
+    event.update_next_alarm(&[], Utc.with_ymd_and_hms(2121, 1, 5, 0, 0, 0).unwrap());
+    assert_eq!(
+        event.get_next_alarm_at(),
+        Some(Utc.with_ymd_and_hms(2171, 1, 4, 9, 50, 0).unwrap())
+    );
+}
+
+#[test]
+fn test_overridden_instance_alarm_times() {
+    let event = event_with(
+        "RRULE:FREQ=DAILY;COUNT=3\r\n\
+         BEGIN:VALARM\r\n\
+         ACTION:AUDIO\r\n\
+         TRIGGER:-PT10M\r\n\
+         END:VALARM\r\n",
+    );
+
+    /* The alarms of an overridden instance are those of its override */
+    let times = event.get_alarm_times(&[utc(1, 5, 10, 0)], utc(1, 1, 0, 0), utc(2, 1, 0, 0));
+
+    let fire_times: Vec<DateTime<Utc>> = times.iter().map(|time| time.fire_at).collect();
+    assert_eq!(fire_times, vec![utc(1, 4, 9, 50), utc(1, 6, 9, 50)]);
+}
+
+#[test]
+fn test_todo_alarm_times() {
+    let text = calendar_with(
+        "BEGIN:VTODO\r\n\
+         UID:todo-1\r\n\
+         DTSTAMP:20210101T000000Z\r\n\
+         DUE:20210110T180000Z\r\n\
+         BEGIN:VALARM\r\n\
+         ACTION:DISPLAY\r\n\
+         TRIGGER;RELATED=END:-PT1H\r\n\
+         DESCRIPTION:Due in an hour\r\n\
+         END:VALARM\r\n\
+         BEGIN:VALARM\r\n\
+         ACTION:DISPLAY\r\n\
+         TRIGGER:-PT1H\r\n\
+         DESCRIPTION:Has no start\r\n\
+         END:VALARM\r\n\
+         END:VTODO\r\n",
+    );
+    let todo = Todo::from_calendar(&ics::parse(&text).unwrap()).unwrap().remove(0);
+
+    let times = todo.get_alarm_times(&[], utc(1, 1, 0, 0), utc(2, 1, 0, 0));
+
+    assert_eq!(times.len(), 1);
+    assert_eq!(times[0].fire_at, utc(1, 10, 17, 0));
+}
+
+#[test]
+fn test_snooze_alarm_from_ical() {
+    let alarm = parse_alarm(
+        "UID:DE7B5C34-83FF-47FE-BE9E-FF41AE6DD097\r\n\
+         TRIGGER;VALUE=DATE-TIME:20210104T095500Z\r\n\
+         RELATED-TO;RELTYPE=SNOOZE:8297C37D-BA2D-4476-91AE-C1EAA364F8E1\r\n\
+         ACKNOWLEDGED:20210104T095600Z\r\n\
+         ACTION:DISPLAY\r\n\
+         DESCRIPTION:Breakfast meeting\r\n",
+    )
+    .unwrap();
+
+    assert_eq!(alarm.uid.as_deref(), Some("DE7B5C34-83FF-47FE-BE9E-FF41AE6DD097"));
+    assert_eq!(
+        alarm.snoozed_alarm_uid.as_deref(),
+        Some("8297C37D-BA2D-4476-91AE-C1EAA364F8E1")
+    );
+    assert_eq!(alarm.acknowledged, Some(utc(1, 4, 9, 56)));
+    assert!(alarm.is_snooze());
+
+    let component = alarm.to_ical();
+    assert_eq!(
+        component.get_property("UID").unwrap().get_value(),
+        "DE7B5C34-83FF-47FE-BE9E-FF41AE6DD097"
+    );
+    assert_eq!(
+        component.get_property("ACKNOWLEDGED").unwrap().get_value(),
+        "20210104T095600Z"
+    );
+    let related_to = component.get_property("RELATED-TO").unwrap();
+    assert_eq!(related_to.get_value(), "8297C37D-BA2D-4476-91AE-C1EAA364F8E1");
+    assert_eq!(related_to.get_parameter_value("RELTYPE"), Some("SNOOZE"));
+}
+
+#[test]
+fn test_acknowledged_alarm_times() {
+    let event = event_with(
+        "RRULE:FREQ=DAILY;COUNT=3\r\n\
+         BEGIN:VALARM\r\n\
+         UID:alarm-1\r\n\
+         ACTION:DISPLAY\r\n\
+         TRIGGER:-PT15M\r\n\
+         ACKNOWLEDGED:20210105T094600Z\r\n\
+         DESCRIPTION:Starting soon\r\n\
+         END:VALARM\r\n",
+    );
+
+    /* Triggers up to the acknowledgement are not fired again */
+    let times = event.get_alarm_times(&[], utc(1, 1, 0, 0), utc(2, 1, 0, 0));
+
+    let fire_times: Vec<DateTime<Utc>> = times.iter().map(|time| time.fire_at).collect();
+    assert_eq!(fire_times, vec![utc(1, 6, 9, 45)]);
+}
+
+#[test]
+fn test_snooze_alarm() {
+    let mut alarm = parse_alarm(
+        "ACTION:DISPLAY\r\n\
+         TRIGGER:-PT15M\r\n\
+         DESCRIPTION:Starting soon\r\n",
+    )
+    .unwrap();
+
+    let mut snooze = alarm.snooze(utc(1, 4, 9, 55), utc(1, 4, 9, 45));
+
+    /* The snoozed alarm gets a UID for the snooze to relate to */
+    assert!(alarm.uid.is_some());
+    assert_eq!(alarm.acknowledged, Some(utc(1, 4, 9, 45)));
+    assert_eq!(snooze.trigger, AlarmTrigger::Absolute(utc(1, 4, 9, 55)));
+    assert_eq!(snooze.snoozed_alarm_uid, alarm.uid);
+    assert_eq!(snooze.description.as_deref(), Some("Starting soon"));
+    assert_ne!(snooze.uid, alarm.uid);
+
+    /* Snoozing again relates to the original alarm */
+    let second = snooze.snooze(utc(1, 4, 10, 5), utc(1, 4, 9, 55));
+    assert_eq!(snooze.acknowledged, Some(utc(1, 4, 9, 55)));
+    assert_eq!(second.snoozed_alarm_uid, alarm.uid);
+}
@@ -0,0 +1,199 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use bson::oid::ObjectId;
+use cal_dav_fast::app::{
+    attachments::{
+        attachment_store::{AttachmentError, AttachmentStore},
+        blob_store::{BlobInfo, BlobStore},
+    },
+    calendar::{
+        ical::{ics, property::ICalProperty, ICalError},
+        models::{
+            calendar_types::attachment::{Attachment, AttachmentContent},
+            event::Event,
+        },
+    },
+};
+use pretty_assertions::assert_eq;
+
+fn parse_property(line: &str) -> ICalProperty {
+    let text = format!("BEGIN:VEVENT\r\n{}\r\nEND:VEVENT\r\n", line);
+    ics::parse(&text).unwrap().properties.remove(0)
+}
+
+fn parse_event(properties: &str) -> Event {
+    let text = format!(
+        "BEGIN:VCALENDAR\r\n\
+         VERSION:2.0\r\n\
+         BEGIN:VEVENT\r\n\
+         UID:event-1\r\n\
+         DTSTAMP:20210101T000000Z\r\n\
+         DTSTART:20210104T100000Z\r\n\
+         {}END:VEVENT\r\n\
+         END:VCALENDAR\r\n",
+        properties
+    );
+    Event::from_calendar(&ics::parse(&text).unwrap()).unwrap().remove(0)
+}
+
+#[derive(Default)]
+struct MemoryBlobStore {
+    blobs: Mutex<HashMap<ObjectId, Vec<u8>>>,
+}
+
+#[async_trait]
+impl BlobStore for MemoryBlobStore {
+    async fn upload(
+        &self,
+        _filename: &str,
+        _content_type: Option<&str>,
+        data: &[u8],
+    ) -> mongodb::error::Result<ObjectId> {
+        let id = ObjectId::new();
+        self.blobs.lock().unwrap().insert(id, data.to_vec());
+        Ok(id)
+    }
+
+    async fn download(&self, id: ObjectId) -> mongodb::error::Result<Vec<u8>> {
+        Ok(self.blobs.lock().unwrap()[&id].clone())
+    }
+
+    async fn get_info(&self, id: ObjectId) -> mongodb::error::Result<Option<BlobInfo>> {
+        Ok(self.blobs.lock().unwrap().get(&id).map(|data| BlobInfo {
+            filename: None,
+            content_type: None,
+            size: data.len() as u64,
+        }))
+    }
+
+    async fn delete(&self, id: ObjectId) -> mongodb::error::Result<()> {
+        self.blobs.lock().unwrap().remove(&id);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_uri_attachment() {
+    let attachment = Attachment::from_ical(&parse_property(
+        "ATTACH;FMTTYPE=application/postscript:ftp://example.com/r.ps",
+    ))
+    .unwrap();
+
+    assert_eq!(attachment.get_uri(), Some("ftp://example.com/r.ps"));
+    assert_eq!(attachment.format_type.as_deref(), Some("application/postscript"));
+    assert_eq!(attachment.get_size(), None);
+
+    let property = attachment.to_ical().unwrap();
+    assert_eq!(property.get_value(), "ftp://example.com/r.ps");
+    assert_eq!(property.get_parameter_value("ENCODING"), None);
+}
+
+#[test]
+fn test_binary_attachment() {
+    let attachment = Attachment::from_ical(&parse_property(
+        "ATTACH;FMTTYPE=text/plain;FILENAME=notes.txt;ENCODING=BASE64;VALUE=BINARY:SGVsbG8gd29ybGQ=",
+    ))
+    .unwrap();
+
+    assert_eq!(attachment.content, AttachmentContent::Binary(b"Hello world".to_vec()));
+    assert_eq!(attachment.filename.as_deref(), Some("notes.txt"));
+    assert_eq!(attachment.get_size(), Some(11));
+
+    let property = attachment.to_ical().unwrap();
+    assert_eq!(property.get_value(), "SGVsbG8gd29ybGQ=");
+    assert_eq!(property.get_parameter_value("ENCODING"), Some("BASE64"));
+    assert_eq!(property.get_parameter_value("VALUE"), Some("BINARY"));
+    assert_eq!(property.get_parameter_value("FMTTYPE"), Some("text/plain"));
+}
+
+#[test]
+fn test_managed_attachment() {
+    let attachment = Attachment::from_ical(&parse_property(
+        "ATTACH;MANAGED-ID=97S;FMTTYPE=text/plain;SIZE=234;FILENAME=f.txt:https://example.com/abcd.txt",
+    ))
+    .unwrap();
+
+    assert!(attachment.is_managed());
+    assert_eq!(attachment.managed_id.as_deref(), Some("97S"));
+    assert_eq!(attachment.get_size(), Some(234));
+
+    let property = attachment.to_ical().unwrap();
+    assert_eq!(property.get_parameter_value("MANAGED-ID"), Some("97S"));
+    assert_eq!(property.get_parameter_value("SIZE"), Some("234"));
+
+    assert!(matches!(
+        Attachment::from_ical(&parse_property("ATTACH;SIZE=big:https://example.com/abcd.txt")),
+        Err(ICalError::InvalidValue { property, .. }) if property == "ATTACH;SIZE"
+    ));
+}
+
+#[test]
+fn test_invalid_binary_attachment() {
+    assert!(matches!(
+        Attachment::from_ical(&parse_property("ATTACH;VALUE=BINARY:SGVsbG8=")),
+        Err(ICalError::InvalidValue { property, .. }) if property == "ATTACH;ENCODING"
+    ));
+    assert!(matches!(
+        Attachment::from_ical(&parse_property("ATTACH;ENCODING=BASE64;VALUE=BINARY:not base64!")),
+        Err(ICalError::InvalidValue { property, .. }) if property == "ATTACH"
+    ));
+}
+
+#[actix_web::test]
+async fn test_store_inline_attachments() {
+    let blobs = Arc::new(MemoryBlobStore::default());
+    let store = AttachmentStore::new(blobs.clone(), 1024);
+    let mut event = parse_event(
+        "ATTACH:https://example.com/agenda.pdf\r\n\
+         ATTACH;FILENAME=notes.txt;ENCODING=BASE64;VALUE=BINARY:SGVsbG8gd29ybGQ=\r\n",
+    );
+
+    store.save(event.get_attachments_mut()).await.unwrap();
+
+    /* Only the reference to the stored file is kept in the event */
+    assert!(matches!(
+        event.get_attachments()[1].content,
+        AttachmentContent::Stored { size: 11, .. }
+    ));
+    assert_eq!(
+        event.get_attachments()[0].get_uri(),
+        Some("https://example.com/agenda.pdf")
+    );
+    assert_eq!(blobs.blobs.lock().unwrap().len(), 1);
+    assert_eq!(event.to_ical().get_properties("ATTACH").count(), 1);
+
+    let document = bson::to_document(&event).unwrap();
+    let mut event: Event = bson::from_document(document).unwrap();
+    let stored = event.get_attachments().to_vec();
+    store.load(event.get_attachments_mut()).await.unwrap();
+    assert_eq!(
+        event.get_attachments()[1].content,
+        AttachmentContent::Binary(b"Hello world".to_vec())
+    );
+    assert_eq!(event.to_ical().get_properties("ATTACH").count(), 2);
+
+    store.delete(&stored).await.unwrap();
+    assert!(blobs.blobs.lock().unwrap().is_empty());
+}
+
+#[actix_web::test]
+async fn test_attachment_size_limit() {
+    let blobs = Arc::new(MemoryBlobStore::default());
+    let store = AttachmentStore::new(blobs.clone(), 8);
+    let mut event = parse_event(
+        "ATTACH;ENCODING=BASE64;VALUE=BINARY:SGk=\r\n\
+         ATTACH;ENCODING=BASE64;VALUE=BINARY:SGVsbG8gd29ybGQ=\r\n",
+    );
+
+    assert!(matches!(
+        store.save(event.get_attachments_mut()).await,
+        Err(AttachmentError::TooLarge { size: 11, max_size: 8 })
+    ));
+
+    /* Nothing is stored when an attachment is rejected */
+    assert!(blobs.blobs.lock().unwrap().is_empty());
+}
@@ -0,0 +1,161 @@
+use cal_dav_fast::app::calendar::{
+    ical::{ics, property::ICalProperty, ICalError},
+    models::calendar_types::attendee::{
+        Attendee, AttendeeRole, AttendeeStatus, AttendeeType, OtherParameter, ScheduleAgent,
+    },
+};
+use pretty_assertions::assert_eq;
+
+fn parse_property(line: &str) -> ICalProperty {
+    let text = format!("BEGIN:VEVENT\r\n{}\r\nEND:VEVENT\r\n", line);
+    ics::parse(&text).unwrap().properties.remove(0)
+}
+
+fn parse_attendee(line: &str) -> Result<Attendee, ICalError> {
+    Attendee::from_ical(&parse_property(line))
+}
+
+#[test]
+fn test_attendee_defaults() {
+    let attendee = parse_attendee("ATTENDEE:mailto:jsmith@example.com").unwrap();
+
+    assert_eq!(attendee.email, "jsmith@example.com");
+    assert!(matches!(attendee.status, AttendeeStatus::NeedsAction));
+    assert!(!attendee.rsvp);
+    assert!(attendee.delegated_to.is_empty());
+    assert_eq!(attendee.schedule_agent, ScheduleAgent::Server);
+    assert!(attendee.other_parameters.is_empty());
+}
+
+#[test]
+fn test_attendee_parameters() {
+    let attendee = parse_attendee(
+        "ATTENDEE;CN=John Smith;RSVP=TRUE;MEMBER=\"mailto:projectA@example.com\",\"mailto:projectB@example.com\";\
+         SENT-BY=\"mailto:sray@example.com\";DIR=\"ldap://example.com:6666/o=ABC,c=US???(cn=John)\";\
+         SCHEDULE-AGENT=CLIENT;SCHEDULE-STATUS=2.0;X-EXAMPLE=\"a:b\",c:mailto:jsmith@example.com",
+    )
+    .unwrap();
+
+    assert!(attendee.rsvp);
+    assert_eq!(attendee.member_of, vec!["projectA@example.com", "projectB@example.com"]);
+    assert_eq!(attendee.sent_by.as_deref(), Some("sray@example.com"));
+    assert_eq!(
+        attendee.directory.as_deref(),
+        Some("ldap://example.com:6666/o=ABC,c=US???(cn=John)")
+    );
+    assert_eq!(attendee.schedule_agent, ScheduleAgent::Client);
+    assert_eq!(attendee.schedule_status, vec!["2.0"]);
+    assert_eq!(
+        attendee.other_parameters,
+        vec![OtherParameter {
+            name: "X-EXAMPLE".to_string(),
+            values: vec!["a:b".to_string(), "c".to_string()],
+        }]
+    );
+
+    /* Every parameter is written back */
+    let written = Attendee::from_ical(&attendee.to_ical()).unwrap();
+    assert_eq!(written.member_of, attendee.member_of);
+    assert_eq!(written.sent_by, attendee.sent_by);
+    assert_eq!(written.directory, attendee.directory);
+    assert_eq!(written.schedule_agent, attendee.schedule_agent);
+    assert_eq!(written.schedule_status, attendee.schedule_status);
+    assert_eq!(written.other_parameters, attendee.other_parameters);
+    assert_eq!(
+        attendee.to_ical().get_parameter("MEMBER").unwrap().values,
+        vec!["mailto:projectA@example.com", "mailto:projectB@example.com"]
+    );
+}
+
+#[test]
+fn test_attendee_delegation() {
+    let delegator = parse_attendee(
+        "ATTENDEE;ROLE=CHAIR;PARTSTAT=DELEGATED;DELEGATED-TO=\"mailto:jdoe@example.com\":mailto:jsmith@example.com",
+    )
+    .unwrap();
+    assert!(delegator.is_delegated());
+    assert_eq!(delegator.delegated_to, vec!["jdoe@example.com"]);
+
+    let delegate =
+        parse_attendee("ATTENDEE;DELEGATED-FROM=\"mailto:jsmith@example.com\":mailto:jdoe@example.com").unwrap();
+    assert_eq!(delegate.delegated_from, vec!["jsmith@example.com"]);
+
+    /* A delegated attendee must say who it delegated to */
+    assert!(matches!(
+        parse_attendee("ATTENDEE;PARTSTAT=DELEGATED:mailto:jsmith@example.com"),
+        Err(ICalError::MissingProperty(name)) if name == "ATTENDEE;DELEGATED-TO"
+    ));
+    assert!(matches!(
+        parse_attendee("ATTENDEE;DELEGATED-TO=\"mailto:jdoe\";PARTSTAT=DELEGATED:mailto:jsmith@example.com"),
+        Err(ICalError::InvalidValue { property, .. }) if property == "ATTENDEE;DELEGATED-TO"
+    ));
+}
+
+#[test]
+fn test_delegate_attendee() {
+    let mut attendee = parse_attendee("ATTENDEE;ROLE=OPT-PARTICIPANT:mailto:jsmith@example.com").unwrap();
+
+    let delegate = attendee.delegate("jdoe@example.com");
+
+    assert!(attendee.is_delegated());
+    assert_eq!(attendee.delegated_to, vec!["jdoe@example.com"]);
+    assert_eq!(delegate.email, "jdoe@example.com");
+    assert_eq!(delegate.delegated_from, vec!["jsmith@example.com"]);
+    assert!(matches!(delegate.attendee_role, AttendeeRole::OptParticipant));
+    assert!(delegate.rsvp);
+
+    let property = attendee.to_ical();
+    assert_eq!(property.get_parameter_value("PARTSTAT"), Some("DELEGATED"));
+    assert_eq!(
+        property.get_parameter_value("DELEGATED-TO"),
+        Some("mailto:jdoe@example.com")
+    );
+}
+
+#[test]
+fn test_attendee_extension_values() {
+    let attendee =
+        parse_attendee("ATTENDEE;PARTSTAT=x-waitlisted;CUTYPE=UNKNOWN;ROLE=X-OBSERVER:mailto:jsmith@example.com")
+            .unwrap();
+
+    assert_eq!(attendee.status, AttendeeStatus::Other("X-WAITLISTED".to_string()));
+    assert_eq!(attendee.attendee_type, AttendeeType::Unknown);
+    assert_eq!(attendee.attendee_role, AttendeeRole::Other("X-OBSERVER".to_string()));
+
+    /* Unrecognized values are treated as the default ones */
+    assert_eq!(attendee.status.get_effective(), AttendeeStatus::NeedsAction);
+    assert_eq!(attendee.attendee_role.get_effective(), AttendeeRole::ReqParticipant);
+    assert_eq!(
+        AttendeeType::Other("X-ROBOT".to_string()).get_effective(),
+        AttendeeType::Unknown
+    );
+
+    let property = attendee.to_ical();
+    assert_eq!(property.get_parameter_value("PARTSTAT"), Some("X-WAITLISTED"));
+    assert_eq!(property.get_parameter_value("CUTYPE"), Some("UNKNOWN"));
+    assert_eq!(property.get_parameter_value("ROLE"), Some("X-OBSERVER"));
+
+    let document = bson::to_document(&attendee).unwrap();
+    let attendee: Attendee = bson::from_document(document).unwrap();
+    assert_eq!(attendee.status, AttendeeStatus::Other("X-WAITLISTED".to_string()));
+}
+
+#[test]
+fn test_invalid_attendee_parameters() {
+    assert!(matches!(
+        parse_attendee("ATTENDEE;PARTSTAT=\"NOT A TOKEN\":mailto:jsmith@example.com"),
+        Err(ICalError::InvalidValue { property, .. }) if property == "ATTENDEE;PARTSTAT"
+    ));
+    assert!(matches!(
+        parse_attendee("ATTENDEE;RSVP=MAYBE:mailto:jsmith@example.com"),
+        Err(ICalError::InvalidValue { property, .. }) if property == "ATTENDEE;RSVP"
+    ));
+    assert!(matches!(
+        parse_attendee("ATTENDEE;SCHEDULE-AGENT=ROBOT:mailto:jsmith@example.com"),
+        Err(ICalError::InvalidValue { property, .. }) if property == "ATTENDEE;SCHEDULE-AGENT"
+    ));
+    assert!(matches!(
+        parse_attendee("ATTENDEE;SCHEDULE-STATUS=OK:mailto:jsmith@example.com"),
+        Err(ICalError::InvalidValue { property, .. }) if property == "ATTENDEE;SCHEDULE-STATUS"
+    ));
+}
@@ -0,0 +1,133 @@
+use cal_dav_fast::app::calendar::{
+    ical::{ics, ICalError},
+    models::{calendar_types::duration::Duration, event::Event},
+};
+use chrono::{DateTime, TimeZone, Utc};
+use pretty_assertions::assert_eq;
+
+fn utc(month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2021, month, day, hour, minute, 0).unwrap()
+}
+
+fn parse_event(properties: &str) -> Result<Event, ICalError> {
+    let text = format!(
+        "BEGIN:VCALENDAR\r\n\
+         VERSION:2.0\r\n\
+         BEGIN:VEVENT\r\n\
+         UID:event-1\r\n\
+         DTSTAMP:20210101T000000Z\r\n\
+         {}END:VEVENT\r\n\
+         END:VCALENDAR\r\n",
+        properties
+    );
+    Event::from_calendar(&ics::parse(&text)?).map(|mut events| events.remove(0))
+}
+
+#[test]
+fn test_duration_from_ical() {
+    let duration = Duration::from_ical("P15DT5H0M20S").unwrap();
+    assert_eq!(duration.get_nominal_days(), 15);
+    assert_eq!(duration.get_exact_seconds(), 5 * 3600 + 20);
+    assert_eq!(duration.to_ical(), "P15DT5H20S");
+
+    let duration = Duration::from_ical("-P7W").unwrap();
+    assert!(duration.is_negative());
+    assert_eq!(duration.get_nominal_days(), -49);
+    assert_eq!(duration.to_ical(), "-P7W");
+
+    assert_eq!(Duration::from_ical("PT0S").unwrap().to_ical(), "PT0S");
+    assert!(!Duration::from_ical("-PT0S").unwrap().is_negative());
+    assert_eq!(Duration::from_ical("P1DT"), None);
+    assert_eq!(Duration::from_ical("P1H"), None);
+    assert_eq!(Duration::from_ical("PT1D"), None);
+}
+
+#[test]
+fn test_duration_from_exact() {
+    assert_eq!(Duration::from_exact(&chrono::Duration::days(14)).to_ical(), "P2W");
+    assert_eq!(Duration::from_exact(&chrono::Duration::hours(-26)).to_ical(), "-P1DT2H");
+    assert_eq!(
+        Duration::from_exact(&chrono::Duration::minutes(90)).to_ical(),
+        "PT1H30M"
+    );
+}
+
+#[test]
+fn test_nominal_days_across_daylight_saving() {
+    /* Europe/Madrid moved to summer time on 2021-03-28, so that day lasted 23 hours */
+    let start = utc(3, 27, 9, 0);
+    let day = Duration::from_ical("P1D").unwrap();
+    let hours = Duration::from_ical("PT24H").unwrap();
+
+    assert_eq!(day.add_to(start, Some("Europe/Madrid")), utc(3, 28, 8, 0));
+    assert_eq!(hours.add_to(start, Some("Europe/Madrid")), utc(3, 28, 9, 0));
+    assert_eq!(day.add_to(start, None), utc(3, 28, 9, 0));
+}
+
+#[test]
+fn test_event_end() {
+    let event = parse_event("DTSTART:20210104T100000Z\r\nDTEND:20210104T113000Z\r\n").unwrap();
+    assert_eq!(event.get_end(), Some(utc(1, 4, 11, 30)));
+    assert_eq!(event.get_duration(), None);
+    assert_eq!(event.effective_end(), utc(1, 4, 11, 30));
+
+    /* Without end nor duration, the event has no duration */
+    let event = parse_event("DTSTART:20210104T100000Z\r\n").unwrap();
+    assert_eq!(event.effective_end(), utc(1, 4, 10, 0));
+    assert!(event.to_ical().get_property("DTEND").is_none());
+}
+
+#[test]
+fn test_event_duration() {
+    let event = parse_event("DTSTART;TZID=Europe/Madrid:20210327T100000\r\nDURATION:P1DT1H\r\n").unwrap();
+
+    assert_eq!(event.get_end(), None);
+    assert_eq!(event.get_duration().unwrap().to_ical(), "P1DT1H");
+    assert_eq!(event.effective_end(), utc(3, 28, 9, 0));
+
+    let component = event.to_ical();
+    assert_eq!(component.get_property("DURATION").unwrap().get_value(), "P1DT1H");
+    assert!(component.get_property("DTEND").is_none());
+}
+
+#[test]
+fn test_all_day_event() {
+    let event = parse_event("DTSTART;VALUE=DATE:20210104\r\n").unwrap();
+    assert!(event.is_all_day());
+    assert_eq!(event.effective_end(), utc(1, 5, 0, 0));
+
+    let dtstart = event.to_ical().get_property("DTSTART").unwrap().clone();
+    assert_eq!(dtstart.get_value(), "20210104");
+    assert_eq!(dtstart.get_parameter_value("VALUE"), Some("DATE"));
+
+    let event = parse_event("DTSTART;VALUE=DATE:20210104\r\nDTEND;VALUE=DATE:20210107\r\n").unwrap();
+    assert_eq!(event.effective_end(), utc(1, 7, 0, 0));
+    assert_eq!(event.to_ical().get_property("DTEND").unwrap().get_value(), "20210107");
+
+    let event = parse_event("DTSTART;VALUE=DATE:20210104\r\nDURATION:P1W\r\n").unwrap();
+    assert_eq!(event.effective_end(), utc(1, 11, 0, 0));
+}
+
+#[test]
+fn test_invalid_event_end() {
+    assert!(matches!(
+        parse_event("DTSTART:20210104T100000Z\r\nDTEND:20210104T113000Z\r\nDURATION:PT1H\r\n"),
+        Err(ICalError::InvalidComponent(_))
+    ));
+    assert!(matches!(
+        parse_event("DTSTART:20210104T100000Z\r\nDTEND:20210104T090000Z\r\n"),
+        Err(ICalError::InvalidValue { property, .. }) if property == "DTEND"
+    ));
+    assert!(matches!(
+        parse_event("DTSTART;VALUE=DATE:20210104\r\nDTEND:20210105T090000Z\r\n"),
+        Err(ICalError::InvalidValue { property, .. }) if property == "DTEND"
+    ));
+    assert!(matches!(
+        parse_event("DTSTART;VALUE=DATE:20210104\r\nDURATION:PT12H\r\n"),
+        Err(ICalError::InvalidValue { property, .. }) if property == "DURATION"
+    ));
+    assert!(matches!(
+        parse_event("DTSTART:20210104T100000Z\r\nDURATION:-PT1H\r\n"),
+        Err(ICalError::InvalidValue { property, .. }) if property == "DURATION"
+    ));
+}
@@ -0,0 +1,73 @@
+use cal_dav_fast::app::calendar::ical::{component::ICalComponent, ics, property::ICalProperty};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_parse_ics_calendar() {
+    let text = "BEGIN:VCALENDAR\r\n\
+                VERSION:2.0\r\n\
+                PRODID:-//Example Corp.//CalDAV Client//EN\r\n\
+                BEGIN:VEVENT\r\n\
+                UID:20010712T182145Z-123401@example.com\r\n\
+                DTSTART;TZID=America/New_York:20060206T001102\r\n\
+                SUMMARY:Meeting with John\\, Paul\\; and George\r\n\
+                CATEGORIES:WORK,MEETING\r\n\
+                ATTENDEE;CN=\"Smith, John\";PARTSTAT=ACCEPTED:mailto:jsmith@example.com\r\n\
+                END:VEVENT\r\n\
+                END:VCALENDAR\r\n";
+
+    let calendar = ics::parse(text).unwrap();
+    assert!(calendar.is("VCALENDAR"));
+
+    let event = &calendar.components[0];
+    assert_eq!(
+        event.get_property("DTSTART").unwrap().get_parameter_value("TZID"),
+        Some("America/New_York")
+    );
+    assert_eq!(
+        event.get_property("SUMMARY").unwrap().get_value(),
+        "Meeting with John, Paul; and George"
+    );
+    assert_eq!(
+        event.get_property("CATEGORIES").unwrap().values,
+        vec!["WORK", "MEETING"]
+    );
+
+    let attendee = event.get_property("ATTENDEE").unwrap();
+    assert_eq!(attendee.get_parameter_value("CN"), Some("Smith, John"));
+    assert_eq!(attendee.get_value(), "mailto:jsmith@example.com");
+}
+
+#[test]
+fn test_parse_ics_folded_lines() {
+    let text = "BEGIN:VEVENT\nDESCRIPTION:This is a lo\n ng description\n\t that exists on a long line.\nEND:VEVENT\n";
+
+    let event = ics::parse(text).unwrap();
+
+    assert_eq!(
+        event.get_property("DESCRIPTION").unwrap().get_value(),
+        "This is a long description that exists on a long line."
+    );
+}
+
+#[test]
+fn test_parse_ics_unbalanced_components() {
+    assert!(ics::parse("BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nEND:VCALENDAR\r\n").is_err());
+    assert!(ics::parse("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n").is_err());
+}
+
+#[test]
+fn test_write_ics_escapes_and_folds() {
+    let mut event = ICalComponent::new("VEVENT");
+    event.add_property(ICalProperty::new("SUMMARY", "Lunch; with a, friend\nat noon"));
+    event.add_property(ICalProperty::new("DESCRIPTION", &"a".repeat(100)));
+    event.add_property(ICalProperty::new("RRULE", "FREQ=YEARLY;BYDAY=1SU,2SU"));
+    event.add_property(ICalProperty::new("ORGANIZER", "mailto:a@example.com").with_parameter("CN", "Doe, Jane"));
+
+    let text = ics::write(&event);
+
+    assert!(text.contains("SUMMARY:Lunch\\; with a\\, friend\\nat noon\r\n"));
+    assert!(text.contains("RRULE:FREQ=YEARLY;BYDAY=1SU,2SU\r\n"));
+    assert!(text.contains("ORGANIZER;CN=\"Doe, Jane\":mailto:a@example.com\r\n"));
+    assert!(text.lines().all(|line| line.len() <= 75));
+    assert_eq!(ics::parse(&text).unwrap(), event);
+}
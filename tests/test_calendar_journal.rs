@@ -0,0 +1,112 @@
+use cal_dav_fast::app::calendar::{
+    ical::{ics, ICalError},
+    models::{calendar_types::journal_status::JournalStatus, journal::Journal},
+};
+use chrono::{TimeZone, Utc};
+use pretty_assertions::assert_eq;
+
+fn calendar_with(components: &str) -> String {
+    format!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{}END:VCALENDAR\r\n", components)
+}
+
+const STAFF_MEETING_NOTES: &str = "BEGIN:VJOURNAL\r\n\
+                                   UID:19970901T130000Z-123405@example.com\r\n\
+                                   DTSTAMP:19970901T130000Z\r\n\
+                                   DTSTART;VALUE=DATE:19970317\r\n\
+                                   SUMMARY:Staff meeting minutes\r\n\
+                                   DESCRIPTION:1. Staff meeting: Participants include Joe\\,\r\n  Lisa\\, and Bob.\r\n\
+                                   DESCRIPTION:2. Telephone Conference: ABC Corp. sales representative\r\n\
+                                   STATUS:DRAFT\r\n\
+                                   ATTACH:http://example.com/notes.pdf\r\n\
+                                   ATTACH:http://example.com/slides.pdf\r\n\
+                                   RRULE:FREQ=YEARLY;COUNT=3\r\n\
+                                   EXDATE:19980317T000000Z\r\n\
+                                   END:VJOURNAL\r\n";
+
+#[test]
+fn test_journal_from_ical() {
+    let journals = Journal::from_calendar(&ics::parse(&calendar_with(STAFF_MEETING_NOTES)).unwrap()).unwrap();
+
+    assert_eq!(journals.len(), 1);
+    let journal = &journals[0];
+    assert_eq!(
+        journal.get_start(),
+        Some(Utc.with_ymd_and_hms(1997, 3, 17, 0, 0, 0).unwrap())
+    );
+    assert_eq!(journal.get_summary(), Some("Staff meeting minutes"));
+    assert_eq!(
+        journal.get_descriptions(),
+        vec![
+            "1. Staff meeting: Participants include Joe, Lisa, and Bob.",
+            "2. Telephone Conference: ABC Corp. sales representative"
+        ]
+    );
+    assert!(matches!(journal.get_status(), Some(JournalStatus::Draft)));
+    assert_eq!(journal.get_attachments().len(), 2);
+
+    let recurrence = journal.get_recurrence().unwrap();
+    assert_eq!(recurrence.count, Some(3));
+    assert_eq!(recurrence.excluded_dates.len(), 1);
+}
+
+#[test]
+fn test_journal_to_ical() {
+    let journals = Journal::from_calendar(&ics::parse(&calendar_with(STAFF_MEETING_NOTES)).unwrap()).unwrap();
+
+    let component = journals[0].to_ical();
+
+    assert!(component.is("VJOURNAL"));
+    assert_eq!(component.get_properties("DESCRIPTION").count(), 2);
+    assert_eq!(component.get_properties("ATTACH").count(), 2);
+    assert_eq!(component.get_property("STATUS").unwrap().get_value(), "DRAFT");
+    assert_eq!(
+        component.get_property("RRULE").unwrap().get_value(),
+        "FREQ=YEARLY;COUNT=3"
+    );
+    assert_eq!(
+        component.get_property("EXDATE").unwrap().get_value(),
+        "19980317T000000Z"
+    );
+
+    /* The written entry can be read back */
+    let text = ics::write(&component);
+    let journal = Journal::from_calendar(&ics::parse(&calendar_with(&text)).unwrap()).unwrap();
+    assert_eq!(journal[0].get_descriptions(), journals[0].get_descriptions());
+}
+
+#[test]
+fn test_journal_other_properties() {
+    let text = calendar_with(
+        "BEGIN:VJOURNAL\r\n\
+         UID:journal-1\r\n\
+         DTSTAMP:20210101T000000Z\r\n\
+         DTSTART;TZID=Europe/Madrid;X-SOURCE=import:20210104T100000\r\n\
+         RSTATUS:2.0;Success\r\n\
+         X-MOZ-GENERATION:2\r\n\
+         END:VJOURNAL\r\n",
+    );
+    let journals = Journal::from_calendar(&ics::parse(&text).unwrap()).unwrap();
+
+    let names: Vec<&str> = journals[0]
+        .get_other_properties()
+        .iter()
+        .map(|property| property.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["RSTATUS", "X-MOZ-GENERATION"]);
+
+    let component = journals[0].to_ical();
+    assert_eq!(component.get_property("RSTATUS").unwrap().get_value(), "2.0;Success");
+    let dtstart = component.get_property("DTSTART").unwrap();
+    assert_eq!(dtstart.get_parameter_value("TZID"), Some("Europe/Madrid"));
+    assert_eq!(dtstart.get_parameter_value("X-SOURCE"), Some("import"));
+}
+
+#[test]
+fn test_journal_invalid_status() {
+    let text = calendar_with("BEGIN:VJOURNAL\r\nUID:journal-1\r\nSTATUS:COMPLETED\r\nEND:VJOURNAL\r\n");
+
+    assert!(matches!(
+        Journal::from_calendar(&ics::parse(&text).unwrap()),
+        Err(ICalError::InvalidValue { .. })
+    ));
+}
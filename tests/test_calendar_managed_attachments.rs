@@ -0,0 +1,296 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use actix_web::{body::to_bytes, http::StatusCode, test::TestRequest};
+use async_trait::async_trait;
+use bson::oid::ObjectId;
+use cal_dav_fast::{
+    app::{
+        attachments::{
+            attachment_store::AttachmentError,
+            blob_store::{BlobInfo, BlobStore},
+            managed_attachments::{count_references, AttachmentUpload, ManagedAttachmentError, ManagedAttachments},
+        },
+        calendar::{ical::ics, models::event::Event},
+    },
+    controllers::attachments_controller::{AttachmentsController, ManagedAttachmentQuery, CAL_MANAGED_ID_HEADER},
+};
+use pretty_assertions::assert_eq;
+
+#[derive(Default)]
+struct MemoryBlobStore {
+    blobs: Mutex<HashMap<ObjectId, (BlobInfo, Vec<u8>)>>,
+}
+
+#[async_trait]
+impl BlobStore for MemoryBlobStore {
+    async fn upload(
+        &self,
+        filename: &str,
+        content_type: Option<&str>,
+        data: &[u8],
+    ) -> mongodb::error::Result<ObjectId> {
+        let id = ObjectId::new();
+        let info = BlobInfo {
+            filename: Some(filename.to_string()).filter(|filename| !filename.is_empty()),
+            content_type: content_type.map(str::to_string),
+            size: data.len() as u64,
+        };
+        self.blobs.lock().unwrap().insert(id, (info, data.to_vec()));
+        Ok(id)
+    }
+
+    async fn download(&self, id: ObjectId) -> mongodb::error::Result<Vec<u8>> {
+        Ok(self.blobs.lock().unwrap()[&id].1.clone())
+    }
+
+    async fn get_info(&self, id: ObjectId) -> mongodb::error::Result<Option<BlobInfo>> {
+        Ok(self.blobs.lock().unwrap().get(&id).map(|blob| blob.0.clone()))
+    }
+
+    async fn delete(&self, id: ObjectId) -> mongodb::error::Result<()> {
+        self.blobs.lock().unwrap().remove(&id);
+        Ok(())
+    }
+}
+
+fn parse_events() -> Vec<Event> {
+    /* A weekly event with an override of its second instance */
+    let text = "BEGIN:VCALENDAR\r\n\
+                VERSION:2.0\r\n\
+                BEGIN:VEVENT\r\n\
+                UID:event-1\r\n\
+                DTSTAMP:20210101T000000Z\r\n\
+                DTSTART:20210104T100000Z\r\n\
+                RRULE:FREQ=WEEKLY;COUNT=4\r\n\
+                END:VEVENT\r\n\
+                BEGIN:VEVENT\r\n\
+                UID:event-1\r\n\
+                DTSTAMP:20210101T000000Z\r\n\
+                RECURRENCE-ID:20210111T100000Z\r\n\
+                DTSTART:20210111T120000Z\r\n\
+                END:VEVENT\r\n\
+                END:VCALENDAR\r\n";
+    Event::from_calendar(&ics::parse(text).unwrap()).unwrap()
+}
+
+fn upload(data: &[u8]) -> AttachmentUpload {
+    AttachmentUpload {
+        data: data.to_vec(),
+        format_type: Some("text/plain".to_string()),
+        filename: Some("notes.txt".to_string()),
+    }
+}
+
+fn managed_attachments(blobs: &Arc<MemoryBlobStore>, max_size: u64) -> ManagedAttachments {
+    ManagedAttachments::new(blobs.clone(), max_size, "https://cal.example.com/")
+}
+
+#[test]
+fn test_recurrence_id() {
+    let events = parse_events();
+
+    assert_eq!(events[0].get_recurrence_id(), None);
+    assert_eq!(
+        events[1].get_recurrence_id().map(|date| date.to_rfc3339()),
+        Some("2021-01-11T10:00:00+00:00".to_string())
+    );
+    assert!(events[1].is_override());
+    assert_eq!(
+        events[1].to_ical().get_property("RECURRENCE-ID").unwrap().get_value(),
+        "20210111T100000Z"
+    );
+}
+
+#[actix_web::test]
+async fn test_add_attachment() {
+    let blobs = Arc::new(MemoryBlobStore::default());
+    let managed = managed_attachments(&blobs, 1024);
+    let mut events = parse_events();
+
+    let managed_id = managed.add(&mut events, None, &upload(b"Hello world")).await.unwrap();
+
+    /* Added to the master and its override, sharing the stored data */
+    assert_eq!(count_references(&events, &managed_id), 2);
+    assert_eq!(blobs.blobs.lock().unwrap().len(), 1);
+
+    let property = events[1].to_ical().get_property("ATTACH").cloned().unwrap();
+    assert_eq!(
+        property.get_value(),
+        format!("https://cal.example.com/attachments/{}", managed_id)
+    );
+    assert_eq!(property.get_parameter_value("MANAGED-ID"), Some(managed_id.as_str()));
+    assert_eq!(property.get_parameter_value("SIZE"), Some("11"));
+    assert_eq!(property.get_parameter_value("FMTTYPE"), Some("text/plain"));
+    assert_eq!(property.get_parameter_value("FILENAME"), Some("notes.txt"));
+
+    assert_eq!(managed.download(&managed_id).await.unwrap(), b"Hello world".to_vec());
+}
+
+#[actix_web::test]
+async fn test_add_attachment_to_instances() {
+    let blobs = Arc::new(MemoryBlobStore::default());
+    let managed = managed_attachments(&blobs, 1024);
+    let mut events = parse_events();
+
+    let managed_id = managed
+        .add(&mut events, Some("20210111T100000Z"), &upload(b"Hello"))
+        .await
+        .unwrap();
+    assert!(!events[0].has_managed_attachment(&managed_id));
+    assert!(events[1].has_managed_attachment(&managed_id));
+
+    let managed_id = managed.add(&mut events, Some("M"), &upload(b"Hello")).await.unwrap();
+    assert!(events[0].has_managed_attachment(&managed_id));
+    assert!(!events[1].has_managed_attachment(&managed_id));
+
+    /* Instances without an override are not valid */
+    assert!(matches!(
+        managed
+            .add(&mut events, Some("20210118T100000Z"), &upload(b"Hello"))
+            .await,
+        Err(ManagedAttachmentError::InvalidRecurrenceId(_))
+    ));
+    assert_eq!(blobs.blobs.lock().unwrap().len(), 2);
+}
+
+#[actix_web::test]
+async fn test_update_attachment() {
+    let blobs = Arc::new(MemoryBlobStore::default());
+    let managed = managed_attachments(&blobs, 1024);
+    let mut events = parse_events();
+    let managed_id = managed.add(&mut events, None, &upload(b"Hello")).await.unwrap();
+
+    let updated_id = managed
+        .update(&mut events, &managed_id, &upload(b"Hello world"))
+        .await
+        .unwrap();
+
+    assert_ne!(updated_id, managed_id);
+    assert_eq!(count_references(&events, &managed_id), 0);
+    assert_eq!(count_references(&events, &updated_id), 2);
+    assert_eq!(events[0].get_attachments()[0].get_size(), Some(11));
+    assert_eq!(blobs.blobs.lock().unwrap().len(), 1);
+
+    assert!(matches!(
+        managed.update(&mut events, &managed_id, &upload(b"Hello")).await,
+        Err(ManagedAttachmentError::InvalidManagedId(_))
+    ));
+}
+
+#[actix_web::test]
+async fn test_remove_attachment_reference_counting() {
+    let blobs = Arc::new(MemoryBlobStore::default());
+    let managed = managed_attachments(&blobs, 1024);
+    let mut events = parse_events();
+    let managed_id = managed.add(&mut events, None, &upload(b"Hello")).await.unwrap();
+
+    /* The override still references the data */
+    managed.remove(&mut events, &managed_id, Some("M")).await.unwrap();
+    assert!(events[0].get_attachments().is_empty());
+    assert_eq!(count_references(&events, &managed_id), 1);
+    assert_eq!(blobs.blobs.lock().unwrap().len(), 1);
+
+    assert!(matches!(
+        managed.remove(&mut events, &managed_id, Some("M")).await,
+        Err(ManagedAttachmentError::InvalidManagedId(_))
+    ));
+
+    managed
+        .remove(&mut events, &managed_id, Some("20210111T100000Z"))
+        .await
+        .unwrap();
+    assert!(blobs.blobs.lock().unwrap().is_empty());
+}
+
+#[actix_web::test]
+async fn test_release_attachments() {
+    let blobs = Arc::new(MemoryBlobStore::default());
+    let managed = managed_attachments(&blobs, 1024);
+    let mut events = parse_events();
+    managed.add(&mut events, None, &upload(b"Hello")).await.unwrap();
+    managed.add(&mut events, Some("M"), &upload(b"World")).await.unwrap();
+
+    managed.release(&events).await.unwrap();
+    assert!(blobs.blobs.lock().unwrap().is_empty());
+}
+
+#[actix_web::test]
+async fn test_attachment_size_limit() {
+    let blobs = Arc::new(MemoryBlobStore::default());
+    let managed = managed_attachments(&blobs, 8);
+    let mut events = parse_events();
+
+    assert!(matches!(
+        managed.add(&mut events, None, &upload(b"Hello world")).await,
+        Err(ManagedAttachmentError::Attachment(AttachmentError::TooLarge {
+            size: 11,
+            max_size: 8
+        }))
+    ));
+    assert!(events[0].get_attachments().is_empty());
+    assert!(blobs.blobs.lock().unwrap().is_empty());
+}
+
+#[actix_web::test]
+async fn test_attachment_actions() {
+    let blobs = Arc::new(MemoryBlobStore::default());
+    let controller = AttachmentsController::new(Arc::new(managed_attachments(&blobs, 1024)));
+    let mut events = parse_events();
+    let query = |action: &str, managed_id: Option<&str>| ManagedAttachmentQuery {
+        action: action.to_string(),
+        managed_id: managed_id.map(str::to_string),
+        rid: None,
+    };
+
+    let request = TestRequest::post()
+        .insert_header(("Content-Type", "text/plain"))
+        .insert_header(("Content-Disposition", "attachment;filename=\"notes.txt\""))
+        .to_http_request();
+    let response = controller
+        .action(&mut events, &query("attachment-add", None), &request, b"Hello world")
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let managed_id = response
+        .headers()
+        .get(CAL_MANAGED_ID_HEADER)
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+    assert_eq!(events[0].get_attachments()[0].filename.as_deref(), Some("notes.txt"));
+
+    let response = controller.get(&managed_id).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get("Content-Type").unwrap(), "text/plain");
+    assert_eq!(
+        response.headers().get("Content-Disposition").unwrap(),
+        "attachment; filename=\"notes.txt\""
+    );
+    assert_eq!(to_bytes(response.into_body()).await.unwrap().as_ref(), b"Hello world");
+
+    let response = controller
+        .action(&mut events, &query("attachment-remove", Some("unknown")), &request, b"")
+        .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let body = to_bytes(response.into_body()).await.unwrap();
+    assert!(String::from_utf8_lossy(&body).contains("<C:valid-managed-id/>"));
+
+    let response = controller
+        .action(
+            &mut events,
+            &query("attachment-remove", Some(&managed_id)),
+            &request,
+            b"",
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(controller.get(&managed_id).await.status(), StatusCode::NOT_FOUND);
+
+    let response = controller
+        .action(&mut events, &query("attachment-move", None), &request, b"")
+        .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,107 @@
+use cal_dav_fast::app::calendar::{
+    ical::{ics, property::ICalProperty, ICalError},
+    models::{
+        calendar_types::{
+            attendee::Attendee,
+            cal_address::{is_valid_email, parse_cal_address},
+            organizer::Organizer,
+        },
+        event::Event,
+    },
+};
+use pretty_assertions::assert_eq;
+
+fn parse_property(line: &str) -> ICalProperty {
+    let text = format!("BEGIN:VEVENT\r\n{}\r\nEND:VEVENT\r\n", line);
+    ics::parse(&text).unwrap().properties.remove(0)
+}
+
+#[test]
+fn test_cal_address() {
+    assert!(is_valid_email("jsmith@example.com"));
+    assert!(!is_valid_email("jsmith"));
+    assert!(!is_valid_email("@example.com"));
+    assert!(!is_valid_email("john smith@example.com"));
+
+    assert_eq!(
+        parse_cal_address("MAILTO:jsmith@example.com"),
+        Some("jsmith@example.com".to_string())
+    );
+    assert_eq!(
+        parse_cal_address("jsmith@example.com"),
+        Some("jsmith@example.com".to_string())
+    );
+    assert_eq!(parse_cal_address("mailto:"), None);
+    assert_eq!(parse_cal_address("urn:uuid:f81d4fae-7dec-11d0-a765-00a0c91e6bf6"), None);
+}
+
+#[test]
+fn test_organizer_from_ical() {
+    let property = parse_property(
+        "ORGANIZER;CN=John Smith;SENT-BY=\"mailto:jane_doe@example.com\";\
+         DIR=\"ldap://example.com:6666/o=ABC%20Industries,c=US???(cn=John%20Smith)\";LANGUAGE=en:\
+         mailto:jsmith@example.com",
+    );
+
+    let organizer = Organizer::from_ical(&property).unwrap();
+
+    assert_eq!(
+        organizer,
+        Organizer {
+            email: "jsmith@example.com".to_string(),
+            name: Some("John Smith".to_string()),
+            sent_by: Some("jane_doe@example.com".to_string()),
+            directory: Some("ldap://example.com:6666/o=ABC%20Industries,c=US???(cn=John%20Smith)".to_string()),
+            language: Some("en".to_string()),
+        }
+    );
+
+    let written = organizer.to_ical();
+    assert_eq!(written.get_value(), "mailto:jsmith@example.com");
+    assert_eq!(
+        written.get_parameter_value("SENT-BY"),
+        Some("mailto:jane_doe@example.com")
+    );
+    assert_eq!(Organizer::from_ical(&written).unwrap(), organizer);
+}
+
+#[test]
+fn test_invalid_cal_addresses() {
+    assert!(matches!(
+        Organizer::from_ical(&parse_property("ORGANIZER:mailto:jsmith")),
+        Err(ICalError::InvalidValue { .. })
+    ));
+    assert!(matches!(
+        Organizer::from_ical(&parse_property(
+            "ORGANIZER;SENT-BY=\"mailto:jane doe\":mailto:jsmith@example.com"
+        )),
+        Err(ICalError::InvalidValue { property, .. }) if property == "ORGANIZER;SENT-BY"
+    ));
+    assert!(matches!(
+        Attendee::from_ical(&parse_property("ATTENDEE:mailto:")),
+        Err(ICalError::InvalidValue { .. })
+    ));
+}
+
+#[test]
+fn test_event_organizer() {
+    let text = "BEGIN:VCALENDAR\r\n\
+                VERSION:2.0\r\n\
+                BEGIN:VEVENT\r\n\
+                UID:event-1\r\n\
+                DTSTAMP:20210101T000000Z\r\n\
+                DTSTART:20210104T100000Z\r\n\
+                DTEND:20210104T110000Z\r\n\
+                ORGANIZER;CN=John Smith:mailto:jsmith@example.com\r\n\
+                END:VEVENT\r\n\
+                END:VCALENDAR\r\n";
+    let event = Event::from_calendar(&ics::parse(text).unwrap()).unwrap().remove(0);
+
+    let organizer = event.get_organizer().unwrap();
+    assert_eq!(organizer.email, "jsmith@example.com");
+    assert_eq!(organizer.name.as_deref(), Some("John Smith"));
+
+    let property = event.to_ical().get_property("ORGANIZER").unwrap().clone();
+    assert_eq!(property.get_value(), "mailto:jsmith@example.com");
+    assert_eq!(property.get_parameter_value("CN"), Some("John Smith"));
+}
@@ -0,0 +1,125 @@
+use cal_dav_fast::app::calendar::{
+    ical::{ics, property::ICalProperty},
+    models::{calendar_types::other_property::OtherProperty, event::Event},
+};
+use chrono::{TimeZone, Utc};
+use pretty_assertions::assert_eq;
+
+fn parse_event(properties: &str) -> Event {
+    let text = format!(
+        "BEGIN:VCALENDAR\r\n\
+         VERSION:2.0\r\n\
+         BEGIN:VEVENT\r\n\
+         UID:event-1\r\n\
+         DTSTAMP:20210101T000000Z\r\n\
+         {}END:VEVENT\r\n\
+         END:VCALENDAR\r\n",
+        properties
+    );
+    Event::from_calendar(&ics::parse(&text).unwrap()).unwrap().remove(0)
+}
+
+fn get_properties(event: &Event, name: &str) -> Vec<ICalProperty> {
+    event.to_ical().get_properties(name).cloned().collect()
+}
+
+#[test]
+fn test_other_properties() {
+    let event = parse_event(
+        "DTSTART:20210101T100000Z\r\n\
+         RDATE;VALUE=DATE-TIME:20210105T100000Z,20210107T100000Z\r\n\
+         X-MOZ-GENERATION:3\r\n\
+         RELATED-TO;RELTYPE=PARENT:parent-1\r\n\
+         RESOURCES:PROJECTOR,EASEL\r\n\
+         RSTATUS:2.0;Success\r\n",
+    );
+
+    let names: Vec<&str> = event
+        .get_other_properties()
+        .iter()
+        .map(|property| property.name.as_str())
+        .collect();
+    assert_eq!(
+        names,
+        vec!["RDATE", "X-MOZ-GENERATION", "RELATED-TO", "RESOURCES", "RSTATUS"]
+    );
+
+    let component = event.to_ical();
+    assert_eq!(component.get_property("X-MOZ-GENERATION").unwrap().get_value(), "3");
+    assert_eq!(component.get_property("RSTATUS").unwrap().get_value(), "2.0;Success");
+    let rdate = component.get_property("RDATE").unwrap();
+    assert_eq!(rdate.get_parameter_value("VALUE"), Some("DATE-TIME"));
+    assert_eq!(rdate.values, vec!["20210105T100000Z", "20210107T100000Z"]);
+    assert_eq!(
+        component
+            .get_property("RELATED-TO")
+            .unwrap()
+            .get_parameter_value("RELTYPE"),
+        Some("PARENT")
+    );
+    assert_eq!(
+        component.get_property("RESOURCES").unwrap().values,
+        vec!["PROJECTOR", "EASEL"]
+    );
+
+    /* Parsed again, the written event keeps them */
+    let text = ics::write(&component);
+    let written = Event::from_ical(&ics::parse(&text).unwrap(), &Default::default()).unwrap();
+    assert_eq!(written.get_other_properties(), event.get_other_properties());
+}
+
+#[test]
+fn test_other_parameters() {
+    let event = parse_event(
+        "DTSTART;TZID=Europe/Madrid;X-SOURCE=import:20210101T100000\r\n\
+         SUMMARY;LANGUAGE=fr:Réunion\r\n\
+         COMMENT:First\r\n\
+         COMMENT;ALTREP=\"http://example.com/second\":Second\r\n\
+         ATTENDEE;X-ROLE-NAME=host:mailto:a@example.com\r\n",
+    );
+
+    let dtstart = &get_properties(&event, "DTSTART")[0];
+    assert_eq!(dtstart.get_parameter_value("TZID"), Some("Europe/Madrid"));
+    assert_eq!(dtstart.get_parameter_value("X-SOURCE"), Some("import"));
+    assert_eq!(dtstart.get_value(), "20210101T100000");
+
+    let summary = &get_properties(&event, "SUMMARY")[0];
+    assert_eq!(summary.get_parameter_value("LANGUAGE"), Some("fr"));
+
+    let comments = get_properties(&event, "COMMENT");
+    assert_eq!(comments[0].get_parameter_value("ALTREP"), None);
+    assert_eq!(
+        comments[1].get_parameter_value("ALTREP"),
+        Some("http://example.com/second")
+    );
+
+    /* Attendees keep their parameters once */
+    let attendee = &get_properties(&event, "ATTENDEE")[0];
+    let roles = attendee
+        .parameters
+        .iter()
+        .filter(|parameter| parameter.name == "X-ROLE-NAME")
+        .count();
+    assert_eq!(roles, 1);
+}
+
+#[test]
+fn test_instance_without_rdate() {
+    let event = parse_event(
+        "DTSTART:20210101T100000Z\r\n\
+         RRULE:FREQ=DAILY;COUNT=3\r\n\
+         RDATE:20210110T100000Z\r\n\
+         X-MOZ-GENERATION:3\r\n",
+    );
+
+    let instance = event.get_instance(Utc.with_ymd_and_hms(2021, 1, 2, 10, 0, 0).unwrap());
+
+    assert_eq!(
+        instance.get_other_properties(),
+        &[OtherProperty {
+            name: "X-MOZ-GENERATION".to_string(),
+            parameters: vec![],
+            values: vec!["3".to_string()],
+        }]
+    );
+}
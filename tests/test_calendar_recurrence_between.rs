@@ -0,0 +1,158 @@
+/* factori create! always appends a struct update, even when every field is overridden */
+#![allow(clippy::needless_update)]
+
+use cal_dav_fast::app::calendar::models::calendar_types::recurrence::{
+    date::Date, recurrence_builder::RecurrenceBuilder, recurrence_frequency::Frequency, weekday::Weekday, Recurrence,
+    MAX_INSTANCES,
+};
+use pretty_assertions::assert_eq;
+
+mod common;
+use common::*;
+
+fn assert_same_ocurrences(recurrence: &Recurrence, start_date: Date, from_date: Date, end_date: Date) {
+    /* Skipping the periods before from_date returns the ocurrences calculated from start_date */
+    let expected: Vec<Date> = recurrence
+        .calculate_ocurrences(start_date, end_date)
+        .into_iter()
+        .filter(|ocurrence| ocurrence >= &from_date)
+        .collect();
+    assert!(!expected.is_empty());
+    assert_eq!(
+        recurrence.calculate_ocurrences_between(start_date, from_date, end_date),
+        expected
+    );
+}
+
+#[test]
+fn test_between_skips_periods() {
+    let start_date = create!(Date, month: 1, day: 5, hour: 9, minute: 30, second: 15);
+
+    let secondly = RecurrenceBuilder::new(Frequency::Secondly).set_interval(7).build();
+    assert_same_ocurrences(
+        &secondly,
+        start_date,
+        create!(Date, month: 1, day: 5, hour: 10, minute: 0, second: 0),
+        create!(Date, month: 1, day: 5, hour: 10, minute: 30, second: 0),
+    );
+
+    let minutely = RecurrenceBuilder::new(Frequency::Minutely)
+        .set_interval(7)
+        .set_seconds(vec![0, 30])
+        .build();
+    assert_same_ocurrences(
+        &minutely,
+        start_date,
+        create!(Date, month: 1, day: 6, hour: 3, minute: 0, second: 0),
+        create!(Date, month: 1, day: 6, hour: 9, minute: 0, second: 0),
+    );
+
+    let hourly = RecurrenceBuilder::new(Frequency::Hourly)
+        .set_interval(5)
+        .set_hours(vec![0, 5, 10, 15, 20])
+        .build();
+    assert_same_ocurrences(
+        &hourly,
+        start_date,
+        create!(Date, month: 2, day: 1, hour: 12, minute: 0, second: 0),
+        create!(Date, month: 3, day: 1, hour: 0, minute: 0, second: 0),
+    );
+
+    let daily = RecurrenceBuilder::new(Frequency::Daily).set_interval(3).build();
+    assert_same_ocurrences(
+        &daily,
+        start_date,
+        create!(Date, month: 3, day: 1, hour: 12, minute: 0, second: 0),
+        create!(Date, month: 6, day: 1, hour: 0, minute: 0, second: 0),
+    );
+
+    let weekly = RecurrenceBuilder::new(Frequency::Weekly)
+        .set_interval(2)
+        .set_weekdays(vec![Weekday::Monday, Weekday::Friday])
+        .set_week_start(Weekday::Sunday)
+        .build();
+    assert_same_ocurrences(
+        &weekly,
+        start_date,
+        create!(Date, month: 6, day: 9, hour: 0, minute: 0, second: 0),
+        create!(Date, year: 2022, month: 1, day: 1, hour: 0, minute: 0, second: 0),
+    );
+
+    let monthly = RecurrenceBuilder::new(Frequency::Monthly)
+        .set_interval(5)
+        .set_month_days(vec![5, 31])
+        .build();
+    assert_same_ocurrences(
+        &monthly,
+        start_date,
+        create!(Date, year: 2024, month: 3, day: 6, hour: 0, minute: 0, second: 0),
+        create!(Date, year: 2030, month: 1, day: 1, hour: 0, minute: 0, second: 0),
+    );
+
+    let yearly = RecurrenceBuilder::new(Frequency::Yearly)
+        .set_interval(3)
+        .set_months(vec![2])
+        .set_month_days(vec![29])
+        .build();
+    assert_same_ocurrences(
+        &yearly,
+        start_date,
+        create!(Date, year: 2030, month: 1, day: 1, hour: 0, minute: 0, second: 0),
+        create!(Date, year: 2100, month: 1, day: 1, hour: 0, minute: 0, second: 0),
+    );
+}
+
+#[test]
+fn test_between_far_from_start() {
+    /* A century of minutes before the range */
+    let start_date = create!(Date, minute: 0, second: 15);
+    let recurrence = RecurrenceBuilder::new(Frequency::Minutely).set_interval(7).build();
+
+    let ocurrences = recurrence.calculate_ocurrences_between(
+        start_date,
+        create!(Date, year: 2121, month: 1, day: 1, hour: 0, minute: 0, second: 0),
+        create!(Date, year: 2121, month: 1, day: 1, hour: 0, minute: 30, second: 0),
+    );
+    assert_eq!(
+        ocurrences,
+        vec![
+            create!(Date, year: 2121, month: 1, day: 1, hour: 0, minute: 3, second: 15),
+            create!(Date, year: 2121, month: 1, day: 1, hour: 0, minute: 10, second: 15),
+            create!(Date, year: 2121, month: 1, day: 1, hour: 0, minute: 17, second: 15),
+            create!(Date, year: 2121, month: 1, day: 1, hour: 0, minute: 24, second: 15),
+        ]
+    );
+}
+
+#[test]
+fn test_between_max_instances() {
+    let start_date = create!(Date, minute: 0);
+    let recurrence = RecurrenceBuilder::new(Frequency::Secondly).build();
+
+    let ocurrences = recurrence.calculate_ocurrences_between(
+        start_date,
+        start_date,
+        create!(Date, month: 2, day: 1, hour: 0, minute: 0, second: 0),
+    );
+    assert_eq!(ocurrences.len(), MAX_INSTANCES as usize);
+    assert_eq!(ocurrences.last(), Some(&start_date.add_seconds(MAX_INSTANCES - 1)));
+
+    /* Unlimited recurrences are only bounded by the expansion */
+    assert!(!recurrence.exceeds_max_instances(start_date));
+    let count = |count| RecurrenceBuilder::new(Frequency::Daily).set_count(count).build();
+    assert!(!count(MAX_INSTANCES).exceeds_max_instances(start_date));
+    assert!(count(MAX_INSTANCES + 1).exceeds_max_instances(start_date));
+
+    let until = |frequency| {
+        RecurrenceBuilder::new(frequency)
+            .set_until_date(create!(Date, year: 2022, month: 1, day: 1, hour: 0, minute: 0, second: 0))
+            .build()
+    };
+    assert!(!until(Frequency::Daily).exceeds_max_instances(start_date));
+    assert!(until(Frequency::Minutely).exceeds_max_instances(start_date));
+    let mut limited = until(Frequency::Minutely);
+    limited.count = Some(MAX_INSTANCES + 1);
+    assert!(limited.exceeds_max_instances(start_date));
+    limited.count = Some(10);
+    assert!(!limited.exceeds_max_instances(start_date));
+}
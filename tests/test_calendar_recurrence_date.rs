@@ -1,3 +1,6 @@
+/* factori create! always appends a struct update, even when every field is overridden */
+#![allow(clippy::needless_update)]
+
 use cal_dav_fast::app::calendar::models::calendar_types::recurrence::{
     recurrence_vec::RecurrenceVec, weekday::Weekday,
 };
@@ -1445,3 +1448,31 @@ fn test_hours_to_date_same_year() {
 
     assert_eq!(hours, 802);
 }
+
+#[test]
+fn test_days_to_date() {
+    let date1 = create!(Date, month: 1, day: 1, hour: 23, minute: 0, second: 0);
+    let date2 = create!(Date, month: 2, day: 3, hour: 1, minute: 0, second: 0);
+
+    assert_eq!(date1.days_to_date(&date2), 33);
+    assert_eq!(date2.days_to_date(&date1), 0);
+}
+
+#[test]
+fn test_weeks_to_date() {
+    /* Friday 1st to Monday 11th: the weeks starting on monday 28th and 11th, or on tuesday 29th and 5th */
+    let date1 = create!(Date, month: 1, day: 1, hour: 0, minute: 0, second: 0);
+    let date2 = create!(Date, month: 1, day: 11, hour: 0, minute: 0, second: 0);
+
+    assert_eq!(date1.weeks_to_date(&date2, Weekday::Monday), 2);
+    assert_eq!(date1.weeks_to_date(&date2, Weekday::Tuesday), 1);
+}
+
+#[test]
+fn test_months_to_date() {
+    let date1 = create!(Date, year: 2021, month: 11, day: 30, hour: 0, minute: 0, second: 0);
+    let date2 = create!(Date, year: 2023, month: 2, day: 1, hour: 0, minute: 0, second: 0);
+
+    assert_eq!(date1.months_to_date(&date2), 15);
+    assert_eq!(date1.years_to_date(&date2), 2);
+}
@@ -0,0 +1,95 @@
+use cal_dav_fast::app::calendar::models::calendar_types::recurrence::{
+    recurrence_builder::RecurrenceBuilder, recurrence_frequency::Frequency, weekday::Weekday,
+};
+use pretty_assertions::assert_eq;
+
+mod common;
+use common::*;
+
+#[test]
+fn test_daily_interval1() {
+    /*
+    Starts at January 30th 2021 at 09:00:00, every day, 4 times.
+    */
+    let start_date = create!(Date, month: 1, day: 30, hour: 9);
+    let end_date = create!(Date, year: 2022);
+
+    let recurrence = RecurrenceBuilder::new(Frequency::Daily).set_count(4).build();
+
+    let ocurrences = recurrence.calculate_ocurrences(start_date, end_date);
+
+    assert_eq!(ocurrences.len(), 4);
+    assert_eq!(ocurrences[0], create!(Date, month: 1, day: 30, hour: 9));
+    assert_eq!(ocurrences[1], create!(Date, month: 1, day: 31, hour: 9));
+    assert_eq!(ocurrences[2], create!(Date, month: 2, day: 1, hour: 9));
+    assert_eq!(ocurrences[3], create!(Date, month: 2, day: 2, hour: 9));
+}
+
+#[test]
+fn test_daily_interval3_until() {
+    /*
+    Starts at January 1st 2021 at 10:30:00, every 3 days until January 10th.
+    */
+    let start_date = create!(Date, hour: 10, minute: 30);
+    let end_date = create!(Date, day: 10, hour: 10, minute: 30);
+
+    let recurrence = RecurrenceBuilder::new(Frequency::Daily)
+        .set_interval(3)
+        .set_until_date(end_date)
+        .build();
+
+    let ocurrences = recurrence.calculate_ocurrences(start_date, end_date);
+
+    assert_eq!(ocurrences.len(), 4);
+    for (i, ocurrence) in ocurrences.iter().enumerate() {
+        assert_eq!(*ocurrence, create!(Date, day: 1 + 3 * i as u32, hour: 10, minute: 30));
+    }
+}
+
+#[test]
+fn test_daily_weekdays() {
+    /*
+    Starts at Friday January 1st 2021, every weekday of the first week.
+    */
+    let start_date = create!(Date, hour: 8);
+    let end_date = create!(Date, day: 8);
+
+    let recurrence = RecurrenceBuilder::new(Frequency::Daily)
+        .set_weekdays(vec![
+            Weekday::Monday,
+            Weekday::Tuesday,
+            Weekday::Wednesday,
+            Weekday::Thursday,
+            Weekday::Friday,
+        ])
+        .build();
+
+    let ocurrences = recurrence.calculate_ocurrences(start_date, end_date);
+
+    assert_eq!(ocurrences.len(), 5);
+    assert_eq!(ocurrences[0], create!(Date, day: 1, hour: 8));
+    assert_eq!(ocurrences[1], create!(Date, day: 4, hour: 8));
+    assert_eq!(ocurrences[4], create!(Date, day: 7, hour: 8));
+}
+
+#[test]
+fn test_daily_hours_and_months() {
+    /*
+    Starts at January 31st 2021, every day of March at 08:00 and 20:00, limited to 3 ocurrences.
+    */
+    let start_date = create!(Date, day: 31);
+    let end_date = create!(Date, year: 2022);
+
+    let recurrence = RecurrenceBuilder::new(Frequency::Daily)
+        .set_months(vec![3])
+        .set_hours(vec![8, 20])
+        .set_count(3)
+        .build();
+
+    let ocurrences = recurrence.calculate_ocurrences(start_date, end_date);
+
+    assert_eq!(ocurrences.len(), 3);
+    assert_eq!(ocurrences[0], create!(Date, month: 3, day: 1, hour: 8));
+    assert_eq!(ocurrences[1], create!(Date, month: 3, day: 1, hour: 20));
+    assert_eq!(ocurrences[2], create!(Date, month: 3, day: 2, hour: 8));
+}
@@ -1,3 +1,6 @@
+/* factori create! always appends a struct update, and the tests clone their dates */
+#![allow(clippy::needless_update, clippy::clone_on_copy)]
+
 use cal_dav_fast::app::calendar::models::calendar_types::recurrence::{
     recurrence_builder::RecurrenceBuilder, recurrence_frequency::Frequency,
 };
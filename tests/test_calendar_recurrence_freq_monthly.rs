@@ -0,0 +1,146 @@
+use cal_dav_fast::app::calendar::models::calendar_types::recurrence::{
+    recurrence_builder::RecurrenceBuilder, recurrence_frequency::Frequency, weekday::Weekday, RecurrenceDay,
+};
+use pretty_assertions::assert_eq;
+
+mod common;
+use common::*;
+
+#[test]
+fn test_monthly_interval1() {
+    /*
+    Starts at January 15th 2021 at 18:00:00, every month, 3 times.
+    */
+    let start_date = create!(Date, day: 15, hour: 18);
+    let end_date = create!(Date, year: 2022);
+
+    let recurrence = RecurrenceBuilder::new(Frequency::Monthly).set_count(3).build();
+
+    let ocurrences = recurrence.calculate_ocurrences(start_date, end_date);
+
+    assert_eq!(ocurrences.len(), 3);
+    for (i, ocurrence) in ocurrences.iter().enumerate() {
+        assert_eq!(*ocurrence, create!(Date, month: 1 + i as u32, day: 15, hour: 18));
+    }
+}
+
+#[test]
+fn test_monthly_skips_invalid_days() {
+    /*
+    Starts at January 31st 2021, every month, until June. Months without a 31st day are skipped.
+    */
+    let start_date = create!(Date, day: 31);
+    let end_date = create!(Date, month: 6, day: 30);
+
+    let recurrence = RecurrenceBuilder::new(Frequency::Monthly).build();
+
+    let ocurrences = recurrence.calculate_ocurrences(start_date, end_date);
+
+    let months: Vec<u32> = ocurrences.iter().map(|ocurrence| ocurrence.get_month()).collect();
+    assert_eq!(months, vec![1, 3, 5]);
+}
+
+#[test]
+fn test_monthly_interval2_across_years() {
+    /*
+    Starts at November 10th 2021, every 2 months, 3 times.
+    */
+    let start_date = create!(Date, month: 11, day: 10);
+    let end_date = create!(Date, year: 2023);
+
+    let recurrence = RecurrenceBuilder::new(Frequency::Monthly)
+        .set_interval(2)
+        .set_count(3)
+        .build();
+
+    let ocurrences = recurrence.calculate_ocurrences(start_date, end_date);
+
+    assert_eq!(ocurrences[0], create!(Date, month: 11, day: 10));
+    assert_eq!(ocurrences[1], create!(Date, year: 2022, month: 1, day: 10));
+    assert_eq!(ocurrences[2], create!(Date, year: 2022, month: 3, day: 10));
+}
+
+#[test]
+fn test_monthly_ordinal_weekdays() {
+    /*
+    RFC 5545 example: monthly on the first and last sunday, 4 times.
+    DTSTART:19970907T090000 RRULE:FREQ=MONTHLY;COUNT=4;BYDAY=1SU,-1SU
+        ==> (1997 EDT) September 7,28 (1997 EST) October 5,26
+    */
+    let start_date = create!(Date, year: 1997, month: 9, day: 7, hour: 9);
+    let end_date = create!(Date, year: 1998);
+
+    let recurrence = RecurrenceBuilder::new(Frequency::Monthly)
+        .set_recurrences(vec![
+            RecurrenceDay {
+                recurence: 1,
+                weekday: Weekday::Sunday,
+            },
+            RecurrenceDay {
+                recurence: -1,
+                weekday: Weekday::Sunday,
+            },
+        ])
+        .set_count(4)
+        .build();
+
+    let ocurrences = recurrence.calculate_ocurrences(start_date, end_date);
+
+    assert_eq!(ocurrences.len(), 4);
+    assert_eq!(ocurrences[0], create!(Date, year: 1997, month: 9, day: 7, hour: 9));
+    assert_eq!(ocurrences[1], create!(Date, year: 1997, month: 9, day: 28, hour: 9));
+    assert_eq!(ocurrences[2], create!(Date, year: 1997, month: 10, day: 5, hour: 9));
+    assert_eq!(ocurrences[3], create!(Date, year: 1997, month: 10, day: 26, hour: 9));
+}
+
+#[test]
+fn test_monthly_friday_13th() {
+    /*
+    RFC 5545 example: every friday the 13th, 3 times.
+    DTSTART:19970902T090000 RRULE:FREQ=MONTHLY;BYDAY=FR;BYMONTHDAY=13
+        ==> (1998 EST) February 13;March 13;November 13
+    */
+    let start_date = create!(Date, year: 1997, month: 9, day: 2, hour: 9);
+    let end_date = create!(Date, year: 2000);
+
+    let recurrence = RecurrenceBuilder::new(Frequency::Monthly)
+        .set_weekdays(vec![Weekday::Friday])
+        .set_month_days(vec![13])
+        .set_count(3)
+        .build();
+
+    let ocurrences = recurrence.calculate_ocurrences(start_date, end_date);
+
+    assert_eq!(ocurrences.len(), 3);
+    assert_eq!(ocurrences[0], create!(Date, year: 1998, month: 2, day: 13, hour: 9));
+    assert_eq!(ocurrences[1], create!(Date, year: 1998, month: 3, day: 13, hour: 9));
+    assert_eq!(ocurrences[2], create!(Date, year: 1998, month: 11, day: 13, hour: 9));
+}
+
+#[test]
+fn test_monthly_last_workday() {
+    /*
+    Last work day of the month, 3 times.
+    DTSTART:20210101T000000 RRULE:FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1;COUNT=3
+    */
+    let start_date = create!(Date);
+    let end_date = create!(Date, year: 2022);
+
+    let recurrence = RecurrenceBuilder::new(Frequency::Monthly)
+        .set_weekdays(vec![
+            Weekday::Monday,
+            Weekday::Tuesday,
+            Weekday::Wednesday,
+            Weekday::Thursday,
+            Weekday::Friday,
+        ])
+        .set_positions(vec![-1])
+        .set_count(3)
+        .build();
+
+    let ocurrences = recurrence.calculate_ocurrences(start_date, end_date);
+
+    assert_eq!(ocurrences[0], create!(Date, month: 1, day: 29));
+    assert_eq!(ocurrences[1], create!(Date, month: 2, day: 26));
+    assert_eq!(ocurrences[2], create!(Date, month: 3, day: 31));
+}
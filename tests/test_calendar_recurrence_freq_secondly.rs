@@ -1,3 +1,6 @@
+/* factori create! always appends a struct update, and the tests clone their dates and index the ocurrences */
+#![allow(clippy::needless_update, clippy::clone_on_copy, clippy::needless_range_loop)]
+
 use cal_dav_fast::app::calendar::models::calendar_types::recurrence::{
     recurrence_builder::RecurrenceBuilder, recurrence_frequency::Frequency, weekday::Weekday,
 };
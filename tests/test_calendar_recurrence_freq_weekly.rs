@@ -0,0 +1,103 @@
+use cal_dav_fast::app::calendar::models::calendar_types::recurrence::{
+    recurrence_builder::RecurrenceBuilder, recurrence_frequency::Frequency, weekday::Weekday,
+};
+use pretty_assertions::assert_eq;
+
+mod common;
+use common::*;
+
+#[test]
+fn test_weekly_interval1() {
+    /*
+    Starts at Friday January 1st 2021 at 10:00:00, every week, 3 times.
+    */
+    let start_date = create!(Date, hour: 10);
+    let end_date = create!(Date, year: 2022);
+
+    let recurrence = RecurrenceBuilder::new(Frequency::Weekly).set_count(3).build();
+
+    let ocurrences = recurrence.calculate_ocurrences(start_date, end_date);
+
+    assert_eq!(ocurrences.len(), 3);
+    assert_eq!(ocurrences[0], create!(Date, day: 1, hour: 10));
+    assert_eq!(ocurrences[1], create!(Date, day: 8, hour: 10));
+    assert_eq!(ocurrences[2], create!(Date, day: 15, hour: 10));
+}
+
+#[test]
+fn test_weekly_interval2_weekdays() {
+    /*
+    Starts at Friday January 1st 2021, every 2 weeks on tuesday and friday, until January 31st.
+    The start date is included, as the week it belongs to is the first one.
+    */
+    let start_date = create!(Date, hour: 9);
+    let end_date = create!(Date, day: 31);
+
+    let recurrence = RecurrenceBuilder::new(Frequency::Weekly)
+        .set_interval(2)
+        .set_weekdays(vec![Weekday::Tuesday, Weekday::Friday])
+        .set_until_date(end_date)
+        .build();
+
+    let ocurrences = recurrence.calculate_ocurrences(start_date, end_date);
+
+    let days: Vec<u32> = ocurrences.iter().map(|ocurrence| ocurrence.get_month_day()).collect();
+    assert_eq!(days, vec![1, 12, 15, 26, 29]);
+    assert!(ocurrences.iter().all(|ocurrence| ocurrence.get_hour() == 9));
+}
+
+#[test]
+fn test_weekly_week_start() {
+    /*
+    RFC 5545 example: the week start changes which days belong to each 2 week interval.
+    DTSTART:19970805T090000 RRULE:FREQ=WEEKLY;INTERVAL=2;COUNT=4;BYDAY=TU,SU;WKST=MO
+        ==> (1997 EDT) August 5,10,19,24
+    DTSTART:19970805T090000 RRULE:FREQ=WEEKLY;INTERVAL=2;COUNT=4;BYDAY=TU,SU;WKST=SU
+        ==> (1997 EDT) August 5,17,19,31
+    */
+    let start_date = create!(Date, year: 1997, month: 8, day: 5, hour: 9);
+    let end_date = create!(Date, year: 1998);
+
+    let days = |week_start: Weekday| -> Vec<u32> {
+        RecurrenceBuilder::new(Frequency::Weekly)
+            .set_interval(2)
+            .set_count(4)
+            .set_weekdays(vec![Weekday::Tuesday, Weekday::Sunday])
+            .set_week_start(week_start)
+            .build()
+            .calculate_ocurrences(start_date, end_date)
+            .iter()
+            .map(|ocurrence| ocurrence.get_month_day())
+            .collect()
+    };
+
+    assert_eq!(days(Weekday::Monday), vec![5, 10, 19, 24]);
+    assert_eq!(days(Weekday::Sunday), vec![5, 17, 19, 31]);
+}
+
+#[test]
+fn test_weekly_positions() {
+    /*
+    Starts at Monday January 4th 2021, the last weekday of each week, 2 times.
+    */
+    let start_date = create!(Date, day: 4);
+    let end_date = create!(Date, year: 2022);
+
+    let recurrence = RecurrenceBuilder::new(Frequency::Weekly)
+        .set_weekdays(vec![
+            Weekday::Monday,
+            Weekday::Tuesday,
+            Weekday::Wednesday,
+            Weekday::Thursday,
+            Weekday::Friday,
+        ])
+        .set_positions(vec![-1])
+        .set_count(2)
+        .build();
+
+    let ocurrences = recurrence.calculate_ocurrences(start_date, end_date);
+
+    assert_eq!(ocurrences.len(), 2);
+    assert_eq!(ocurrences[0], create!(Date, day: 8));
+    assert_eq!(ocurrences[1], create!(Date, day: 15));
+}
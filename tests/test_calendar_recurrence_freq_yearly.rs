@@ -0,0 +1,193 @@
+use cal_dav_fast::app::calendar::models::calendar_types::recurrence::{
+    recurrence_builder::RecurrenceBuilder, recurrence_frequency::Frequency, weekday::Weekday, RecurrenceDay,
+};
+use pretty_assertions::assert_eq;
+
+mod common;
+use common::*;
+
+#[test]
+fn test_yearly_interval1() {
+    /*
+    Starts at March 5th 2021 at 10:30:00, every year until 2024.
+    */
+    let start_date = create!(Date, year: 2021, month: 3, day: 5, hour: 10, minute: 30);
+    let end_date = create!(Date, year: 2024, month: 12, day: 31);
+
+    let recurrence = RecurrenceBuilder::new(Frequency::Yearly).set_interval(1).build();
+
+    let ocurrences = recurrence.calculate_ocurrences(start_date, end_date);
+
+    assert_eq!(ocurrences.len(), 4);
+    for (i, ocurrence) in ocurrences.iter().enumerate() {
+        assert_eq!(
+            *ocurrence,
+            create!(Date, year: 2021 + i as i32, month: 3, day: 5, hour: 10, minute: 30)
+        );
+    }
+}
+
+#[test]
+fn test_yearly_interval2_count() {
+    /*
+    Starts at January 1st 2021, every 2 years, 3 times.
+    */
+    let start_date = create!(Date);
+    let end_date = create!(Date, year: 2040);
+
+    let recurrence = RecurrenceBuilder::new(Frequency::Yearly)
+        .set_interval(2)
+        .set_count(3)
+        .build();
+
+    let ocurrences = recurrence.calculate_ocurrences(start_date, end_date);
+
+    assert_eq!(ocurrences.len(), 3);
+    assert_eq!(ocurrences[0], create!(Date, year: 2021));
+    assert_eq!(ocurrences[1], create!(Date, year: 2023));
+    assert_eq!(ocurrences[2], create!(Date, year: 2025));
+}
+
+#[test]
+fn test_yearly_february_29th_only_in_leap_years() {
+    let start_date = create!(Date, year: 2020, month: 2, day: 29);
+    let end_date = create!(Date, year: 2028, month: 12, day: 31);
+
+    let recurrence = RecurrenceBuilder::new(Frequency::Yearly).build();
+
+    let ocurrences = recurrence.calculate_ocurrences(start_date, end_date);
+
+    assert_eq!(ocurrences.len(), 3);
+    assert_eq!(ocurrences[0], create!(Date, year: 2020, month: 2, day: 29));
+    assert_eq!(ocurrences[1], create!(Date, year: 2024, month: 2, day: 29));
+    assert_eq!(ocurrences[2], create!(Date, year: 2028, month: 2, day: 29));
+}
+
+#[test]
+fn test_yearly_by_month() {
+    /*
+    Every year on January and March, the start date day.
+    */
+    let start_date = create!(Date, year: 2021, month: 1, day: 10, hour: 9);
+    let end_date = create!(Date, year: 2022, month: 12, day: 31);
+
+    let recurrence = RecurrenceBuilder::new(Frequency::Yearly).set_months(vec![3, 1]).build();
+
+    let ocurrences = recurrence.calculate_ocurrences(start_date, end_date);
+
+    assert_eq!(ocurrences.len(), 4);
+    assert_eq!(ocurrences[0], create!(Date, year: 2021, month: 1, day: 10, hour: 9));
+    assert_eq!(ocurrences[1], create!(Date, year: 2021, month: 3, day: 10, hour: 9));
+    assert_eq!(ocurrences[2], create!(Date, year: 2022, month: 1, day: 10, hour: 9));
+    assert_eq!(ocurrences[3], create!(Date, year: 2022, month: 3, day: 10, hour: 9));
+}
+
+#[test]
+fn test_yearly_second_sunday_of_march() {
+    /*
+    FREQ=YEARLY;BYMONTH=3;BYDAY=2SU, the start of daylight saving time in the US.
+    */
+    let start_date = create!(Date, year: 2007, month: 3, day: 11, hour: 2);
+    let end_date = create!(Date, year: 2010, month: 12, day: 31);
+
+    let recurrence = RecurrenceBuilder::new(Frequency::Yearly)
+        .set_months(vec![3])
+        .set_recurrences(vec![RecurrenceDay {
+            recurence: 2,
+            weekday: Weekday::Sunday,
+        }])
+        .build();
+
+    let ocurrences = recurrence.calculate_ocurrences(start_date, end_date);
+
+    assert_eq!(ocurrences.len(), 4);
+    assert_eq!(ocurrences[0], create!(Date, year: 2007, month: 3, day: 11, hour: 2));
+    assert_eq!(ocurrences[1], create!(Date, year: 2008, month: 3, day: 9, hour: 2));
+    assert_eq!(ocurrences[2], create!(Date, year: 2009, month: 3, day: 8, hour: 2));
+    assert_eq!(ocurrences[3], create!(Date, year: 2010, month: 3, day: 14, hour: 2));
+}
+
+#[test]
+fn test_yearly_last_sunday_of_october() {
+    /*
+    FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU, the end of summer time in the EU.
+    */
+    let start_date = create!(Date, year: 2021, month: 1, day: 1, hour: 3);
+    let end_date = create!(Date, year: 2022, month: 12, day: 31);
+
+    let recurrence = RecurrenceBuilder::new(Frequency::Yearly)
+        .set_months(vec![10])
+        .set_recurrences(vec![RecurrenceDay {
+            recurence: -1,
+            weekday: Weekday::Sunday,
+        }])
+        .build();
+
+    let ocurrences = recurrence.calculate_ocurrences(start_date, end_date);
+
+    assert_eq!(ocurrences.len(), 2);
+    assert_eq!(ocurrences[0], create!(Date, year: 2021, month: 10, day: 31, hour: 3));
+    assert_eq!(ocurrences[1], create!(Date, year: 2022, month: 10, day: 30, hour: 3));
+}
+
+#[test]
+fn test_yearly_by_year_day() {
+    let start_date = create!(Date, year: 2021);
+    let end_date = create!(Date, year: 2021, month: 12, day: 31);
+
+    let recurrence = RecurrenceBuilder::new(Frequency::Yearly)
+        .set_year_days(vec![1, 100, 200])
+        .build();
+
+    let ocurrences = recurrence.calculate_ocurrences(start_date, end_date);
+
+    assert_eq!(ocurrences.len(), 3);
+    assert_eq!(ocurrences[0], create!(Date, year: 2021, month: 1, day: 1));
+    assert_eq!(ocurrences[1], create!(Date, year: 2021, month: 4, day: 10));
+    assert_eq!(ocurrences[2], create!(Date, year: 2021, month: 7, day: 19));
+}
+
+#[test]
+fn test_yearly_by_month_day_and_weekday() {
+    /*
+    Every Friday the 13th.
+    */
+    let start_date = create!(Date, year: 2021);
+    let end_date = create!(Date, year: 2021, month: 12, day: 31);
+
+    let recurrence = RecurrenceBuilder::new(Frequency::Yearly)
+        .set_month_days(vec![13])
+        .set_weekdays(vec![Weekday::Friday])
+        .build();
+
+    let ocurrences = recurrence.calculate_ocurrences(start_date, end_date);
+
+    assert_eq!(ocurrences.len(), 1);
+    assert_eq!(ocurrences[0], create!(Date, year: 2021, month: 8, day: 13));
+}
+
+#[test]
+fn test_yearly_positions() {
+    /*
+    The last weekday of each year.
+    */
+    let start_date = create!(Date, year: 2021);
+    let end_date = create!(Date, year: 2022, month: 12, day: 31);
+
+    let recurrence = RecurrenceBuilder::new(Frequency::Yearly)
+        .set_weekdays(vec![
+            Weekday::Monday,
+            Weekday::Tuesday,
+            Weekday::Wednesday,
+            Weekday::Thursday,
+            Weekday::Friday,
+        ])
+        .set_positions(vec![-1])
+        .build();
+
+    let ocurrences = recurrence.calculate_ocurrences(start_date, end_date);
+
+    assert_eq!(ocurrences.len(), 2);
+    assert_eq!(ocurrences[0], create!(Date, year: 2021, month: 12, day: 31));
+    assert_eq!(ocurrences[1], create!(Date, year: 2022, month: 12, day: 30));
+}
@@ -0,0 +1,265 @@
+use cal_dav_fast::app::calendar::{
+    ical::ics,
+    models::{
+        calendar_types::{
+            recurrence::{date::Date, recurrence_frequency::Frequency, Recurrence},
+            timezone::timezone_registry::TimezoneRegistry,
+        },
+        event::Event,
+    },
+};
+use chrono::{NaiveDate, TimeZone, Utc};
+use pretty_assertions::assert_eq;
+
+const NEW_YORK: &str = "BEGIN:VTIMEZONE\r\n\
+                        TZID:America/New_York\r\n\
+                        BEGIN:DAYLIGHT\r\n\
+                        DTSTART:20070311T020000\r\n\
+                        RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=2SU\r\n\
+                        TZOFFSETFROM:-0500\r\n\
+                        TZOFFSETTO:-0400\r\n\
+                        TZNAME:EDT\r\n\
+                        END:DAYLIGHT\r\n\
+                        BEGIN:STANDARD\r\n\
+                        DTSTART:20071104T020000\r\n\
+                        RRULE:FREQ=YEARLY;BYMONTH=11;BYDAY=1SU\r\n\
+                        TZOFFSETFROM:-0400\r\n\
+                        TZOFFSETTO:-0500\r\n\
+                        TZNAME:EST\r\n\
+                        END:STANDARD\r\n\
+                        END:VTIMEZONE\r\n";
+
+fn calendar_with(components: &str) -> String {
+    format!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{}END:VCALENDAR\r\n", components)
+}
+
+#[test]
+fn test_rrule_from_ical() {
+    let recurrence = Recurrence::from_ical("FREQ=YEARLY;INTERVAL=2;BYMONTH=3;BYDAY=2SU,-1MO,TU;BYSETPOS=-1").unwrap();
+
+    assert!(matches!(recurrence.frequency, Frequency::Yearly));
+    assert_eq!(recurrence.interval, 2);
+    assert_eq!(recurrence.recurrences.len(), 2);
+    assert_eq!(recurrence.recurrences[0].recurence, 2);
+    assert_eq!(recurrence.recurrences[1].recurence, -1);
+    assert_eq!(recurrence.weekdays.length(), 1);
+    assert_eq!(
+        recurrence.to_ical(),
+        "FREQ=YEARLY;INTERVAL=2;BYDAY=TU,2SU,-1MO;BYMONTH=3;BYSETPOS=-1"
+    );
+}
+
+#[test]
+fn test_rrule_from_ical_invalid() {
+    assert!(Recurrence::from_ical("INTERVAL=2").is_err());
+    assert!(Recurrence::from_ical("FREQ=SOMETIMES").is_err());
+    assert!(Recurrence::from_ical("FREQ=DAILY;COUNT=many").is_err());
+    assert!(Recurrence::from_ical("FREQ=WEEKLY;BYDAY=XX").is_err());
+    assert!(Recurrence::from_ical("FREQ=YEARLY;BYWEEKNO=20").is_err());
+    assert!(Recurrence::from_ical("FREQ=DAILY;X-NAME=value").is_err());
+}
+
+#[test]
+fn test_timezone_offsets() {
+    let calendar = ics::parse(&calendar_with(NEW_YORK)).unwrap();
+    let registry = TimezoneRegistry::from_calendar(&calendar).unwrap();
+    let timezone = registry.get("America/New_York").unwrap();
+
+    let winter = Utc.with_ymd_and_hms(2021, 1, 15, 12, 0, 0).unwrap();
+    let summer = Utc.with_ymd_and_hms(2021, 7, 15, 12, 0, 0).unwrap();
+    let before_definition = Utc.with_ymd_and_hms(2000, 7, 15, 12, 0, 0).unwrap();
+
+    assert_eq!(timezone.get_offset_at(winter), -5 * 3600);
+    assert_eq!(timezone.get_offset_at(summer), -4 * 3600);
+    assert_eq!(timezone.get_offset_at(before_definition), -5 * 3600);
+}
+
+#[test]
+fn test_timezone_transition_boundaries() {
+    let calendar = ics::parse(&calendar_with(NEW_YORK)).unwrap();
+    let registry = TimezoneRegistry::from_calendar(&calendar).unwrap();
+    let timezone = registry.get("America/New_York").unwrap();
+
+    /* 2021-03-14 02:00 EST is 07:00 UTC */
+    assert_eq!(
+        timezone.get_offset_at(Utc.with_ymd_and_hms(2021, 3, 14, 6, 59, 59).unwrap()),
+        -5 * 3600
+    );
+    assert_eq!(
+        timezone.get_offset_at(Utc.with_ymd_and_hms(2021, 3, 14, 7, 0, 0).unwrap()),
+        -4 * 3600
+    );
+
+    /* 2021-11-07 02:00 EDT is 06:00 UTC */
+    assert_eq!(
+        timezone.get_offset_at(Utc.with_ymd_and_hms(2021, 11, 7, 5, 59, 59).unwrap()),
+        -4 * 3600
+    );
+    assert_eq!(
+        timezone.get_offset_at(Utc.with_ymd_and_hms(2021, 11, 7, 6, 0, 0).unwrap()),
+        -5 * 3600
+    );
+}
+
+#[test]
+fn test_timezone_local_to_utc() {
+    let calendar = ics::parse(&calendar_with(NEW_YORK)).unwrap();
+    let registry = TimezoneRegistry::from_calendar(&calendar).unwrap();
+    let timezone = registry.get("America/New_York").unwrap();
+
+    let local = NaiveDate::from_ymd_opt(2021, 7, 4)
+        .unwrap()
+        .and_hms_opt(9, 0, 0)
+        .unwrap();
+    let utc = timezone.to_utc(&local);
+
+    assert_eq!(utc, Utc.with_ymd_and_hms(2021, 7, 4, 13, 0, 0).unwrap());
+    assert_eq!(timezone.to_local(&utc), local);
+}
+
+#[test]
+fn test_event_dates_with_tzid() {
+    let text = calendar_with(&format!(
+        "{}BEGIN:VEVENT\r\n\
+         UID:event-1\r\n\
+         DTSTAMP:20210101T000000Z\r\n\
+         DTSTART;TZID=America/New_York:20210115T090000\r\n\
+         DTEND;TZID=America/New_York:20210715T100000\r\n\
+         RRULE:FREQ=YEARLY;COUNT=3\r\n\
+         EXDATE;TZID=America/New_York:20220115T090000\r\n\
+         END:VEVENT\r\n",
+        NEW_YORK
+    ));
+
+    let events = Event::from_calendar(&ics::parse(&text).unwrap()).unwrap();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        events[0].get_start(),
+        Utc.with_ymd_and_hms(2021, 1, 15, 14, 0, 0).unwrap()
+    );
+    assert_eq!(
+        events[0].get_end(),
+        Some(Utc.with_ymd_and_hms(2021, 7, 15, 14, 0, 0).unwrap())
+    );
+
+    let recurrence = events[0].get_recurrence().unwrap();
+    assert_eq!(recurrence.count, Some(3));
+    assert_eq!(recurrence.excluded_dates.len(), 1);
+}
+
+#[test]
+fn test_recurrence_across_daylight_saving() {
+    /* 09:00 in Madrid is 08:00Z in winter, and 07:00Z in summer */
+    let madrid = "BEGIN:VTIMEZONE\r\n\
+                  TZID:Custom/Madrid\r\n\
+                  BEGIN:DAYLIGHT\r\n\
+                  DTSTART:19810329T020000\r\n\
+                  RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU\r\n\
+                  TZOFFSETFROM:+0100\r\n\
+                  TZOFFSETTO:+0200\r\n\
+                  END:DAYLIGHT\r\n\
+                  BEGIN:STANDARD\r\n\
+                  DTSTART:19961027T030000\r\n\
+                  RRULE:FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU\r\n\
+                  TZOFFSETFROM:+0200\r\n\
+                  TZOFFSETTO:+0100\r\n\
+                  END:STANDARD\r\n\
+                  END:VTIMEZONE\r\n";
+    for tzid in ["Europe/Madrid", "Custom/Madrid"] {
+        let text = calendar_with(&format!(
+            "{}BEGIN:VEVENT\r\n\
+             UID:event-1\r\n\
+             DTSTAMP:20210101T000000Z\r\n\
+             DTSTART;TZID={tzid}:20210104T090000\r\n\
+             RRULE:FREQ=WEEKLY;UNTIL=20211231T000000Z\r\n\
+             EXDATE;TZID={tzid}:20210712T090000\r\n\
+             END:VEVENT\r\n",
+            madrid
+        ));
+        let event = Event::from_calendar(&ics::parse(&text).unwrap()).unwrap().remove(0);
+        let recurrence = event.get_recurrence().unwrap();
+
+        let ocurrences: Vec<_> = recurrence
+            .calculate_ocurrences_between(
+                Date::new(event.get_start()),
+                Date::new(Utc.with_ymd_and_hms(2021, 3, 20, 0, 0, 0).unwrap()),
+                Date::new(Utc.with_ymd_and_hms(2021, 4, 1, 0, 0, 0).unwrap()),
+            )
+            .iter()
+            .map(Date::get_date_time)
+            .collect();
+        assert_eq!(
+            ocurrences,
+            vec![
+                Utc.with_ymd_and_hms(2021, 3, 22, 8, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2021, 3, 29, 7, 0, 0).unwrap(),
+            ]
+        );
+
+        let ocurrences: Vec<_> = recurrence
+            .calculate_ocurrences_between(
+                Date::new(event.get_start()),
+                Date::new(Utc.with_ymd_and_hms(2021, 7, 1, 0, 0, 0).unwrap()),
+                Date::new(Utc.with_ymd_and_hms(2021, 7, 20, 0, 0, 0).unwrap()),
+            )
+            .iter()
+            .map(Date::get_date_time)
+            .collect();
+        assert_eq!(
+            ocurrences,
+            vec![
+                Utc.with_ymd_and_hms(2021, 7, 5, 7, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2021, 7, 19, 7, 0, 0).unwrap(),
+            ]
+        );
+
+        /* Back to winter time, until the UNTIL instant */
+        let ocurrences = recurrence.calculate_ocurrences(
+            Date::new(event.get_start()),
+            Date::new(Utc.with_ymd_and_hms(2022, 1, 31, 0, 0, 0).unwrap()),
+        );
+        assert_eq!(ocurrences.len(), 51);
+        assert_eq!(
+            ocurrences.last().unwrap().get_date_time(),
+            Utc.with_ymd_and_hms(2021, 12, 27, 8, 0, 0).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_custom_timezone() {
+    let text = calendar_with(
+        "BEGIN:VTIMEZONE\r\n\
+         TZID:Custom/Fixed+0530\r\n\
+         BEGIN:STANDARD\r\n\
+         DTSTART:19700101T000000\r\n\
+         TZOFFSETFROM:+0530\r\n\
+         TZOFFSETTO:+0530\r\n\
+         END:STANDARD\r\n\
+         END:VTIMEZONE\r\n\
+         BEGIN:VEVENT\r\n\
+         UID:event-2\r\n\
+         DTSTART;TZID=Custom/Fixed+0530:20210601T120000\r\n\
+         END:VEVENT\r\n",
+    );
+
+    let events = Event::from_calendar(&ics::parse(&text).unwrap()).unwrap();
+
+    assert_eq!(
+        events[0].get_start(),
+        Utc.with_ymd_and_hms(2021, 6, 1, 6, 30, 0).unwrap()
+    );
+}
+
+#[test]
+fn test_invalid_timezone() {
+    let text = calendar_with("BEGIN:VTIMEZONE\r\nTZID:Broken\r\nEND:VTIMEZONE\r\n");
+    assert!(TimezoneRegistry::from_calendar(&ics::parse(&text).unwrap()).is_err());
+
+    let text = calendar_with(
+        "BEGIN:VTIMEZONE\r\nTZID:Broken\r\nBEGIN:STANDARD\r\nDTSTART:19700101T000000\r\n\
+         TZOFFSETFROM:0100\r\nTZOFFSETTO:+0100\r\nEND:STANDARD\r\nEND:VTIMEZONE\r\n",
+    );
+    assert!(TimezoneRegistry::from_calendar(&ics::parse(&text).unwrap()).is_err());
+}
@@ -0,0 +1,155 @@
+use cal_dav_fast::{
+    app::calendar::{
+        ical::{component::ICalComponent, ics},
+        models::{
+            calendar_types::timezone::{
+                iana_timezone::is_standard_tzid,
+                timezone_registry::TimezoneRegistry,
+                timezones_by_reference::{apply_timezones_preference, should_include_timezones},
+                Timezone,
+            },
+            event::Event,
+        },
+    },
+    controllers::options_controller::OptionsController,
+};
+use chrono::{TimeZone, Utc};
+use pretty_assertions::assert_eq;
+
+const EVENT_WITHOUT_TIMEZONE: &str = "BEGIN:VCALENDAR\r\n\
+                                      VERSION:2.0\r\n\
+                                      BEGIN:VEVENT\r\n\
+                                      UID:event-1\r\n\
+                                      DTSTAMP:20210101T000000Z\r\n\
+                                      DTSTART;TZID=Europe/Madrid:20210715T090000\r\n\
+                                      DTEND;TZID=Europe/Madrid:20210715T100000\r\n\
+                                      END:VEVENT\r\n\
+                                      END:VCALENDAR\r\n";
+
+fn get_tzids(calendar: &ICalComponent) -> Vec<String> {
+    calendar
+        .get_components("VTIMEZONE")
+        .map(|timezone| timezone.get_property("TZID").unwrap().get_value().to_string())
+        .collect()
+}
+
+#[test]
+fn test_standard_tzids() {
+    assert!(is_standard_tzid("Europe/Madrid"));
+    assert!(is_standard_tzid("America/Argentina/Buenos_Aires"));
+    assert!(!is_standard_tzid("Custom/Zone"));
+}
+
+#[test]
+fn test_caldav_timezones_header() {
+    assert!(should_include_timezones(None));
+    assert!(should_include_timezones(Some("T")));
+    assert!(!should_include_timezones(Some("F")));
+    assert!(!should_include_timezones(Some(" f ")));
+}
+
+#[test]
+fn test_event_with_bare_standard_tzid() {
+    let events = Event::from_calendar(&ics::parse(EVENT_WITHOUT_TIMEZONE).unwrap()).unwrap();
+
+    assert_eq!(
+        events[0].get_start(),
+        Utc.with_ymd_and_hms(2021, 7, 15, 7, 0, 0).unwrap()
+    );
+    assert_eq!(
+        events[0].get_end(),
+        Some(Utc.with_ymd_and_hms(2021, 7, 15, 8, 0, 0).unwrap())
+    );
+    assert_eq!(events[0].get_timezone(), Some("Europe/Madrid"));
+
+    let dtstart = events[0].to_ical().get_property("DTSTART").unwrap().clone();
+    assert_eq!(dtstart.get_value(), "20210715T090000");
+    assert_eq!(dtstart.get_parameter_value("TZID"), Some("Europe/Madrid"));
+}
+
+#[test]
+fn test_iana_timezone_definition() {
+    let timezone = Timezone::from_iana("Europe/Madrid", 2021, 2022).unwrap();
+
+    /* Two transitions each year, grouped in a daylight and a standard observance */
+    let transitions: usize = timezone
+        .observances
+        .iter()
+        .filter(|observance| observance.offset_from != observance.offset_to)
+        .map(|observance| 1 + observance.dates.len())
+        .sum();
+    assert_eq!(transitions, 4);
+
+    /* The generated VTIMEZONE resolves dates like the database */
+    let mut calendar = ICalComponent::calendar(vec![timezone.to_ical()]);
+    calendar = ics::parse(&ics::write(&calendar)).unwrap();
+    let registry = TimezoneRegistry::from_calendar(&calendar).unwrap();
+    let parsed = registry.get("Europe/Madrid").unwrap();
+
+    assert_eq!(
+        parsed.get_offset_at(Utc.with_ymd_and_hms(2022, 1, 10, 0, 0, 0).unwrap()),
+        3600
+    );
+    assert_eq!(
+        parsed.get_offset_at(Utc.with_ymd_and_hms(2022, 3, 27, 0, 59, 59).unwrap()),
+        3600
+    );
+    assert_eq!(
+        parsed.get_offset_at(Utc.with_ymd_and_hms(2022, 3, 27, 1, 0, 0).unwrap()),
+        7200
+    );
+    assert_eq!(
+        parsed.get_offset_at(Utc.with_ymd_and_hms(2022, 10, 30, 1, 0, 0).unwrap()),
+        3600
+    );
+}
+
+#[test]
+fn test_inject_missing_timezones() {
+    let events = Event::from_calendar(&ics::parse(EVENT_WITHOUT_TIMEZONE).unwrap()).unwrap();
+    let mut calendar = ICalComponent::calendar(vec![events[0].to_ical()]);
+
+    apply_timezones_preference(&mut calendar, true);
+
+    assert_eq!(get_tzids(&calendar), vec!["Europe/Madrid"]);
+    assert!(calendar.components[0].is("VTIMEZONE"));
+
+    /* Applying it again does not duplicate the definition */
+    apply_timezones_preference(&mut calendar, true);
+    assert_eq!(get_tzids(&calendar), vec!["Europe/Madrid"]);
+}
+
+#[test]
+fn test_strip_standard_timezones() {
+    let text = "BEGIN:VCALENDAR\r\n\
+                BEGIN:VTIMEZONE\r\n\
+                TZID:Europe/Madrid\r\n\
+                BEGIN:STANDARD\r\n\
+                DTSTART:19700101T000000\r\n\
+                TZOFFSETFROM:+0100\r\n\
+                TZOFFSETTO:+0100\r\n\
+                END:STANDARD\r\n\
+                END:VTIMEZONE\r\n\
+                BEGIN:VTIMEZONE\r\n\
+                TZID:Custom/Zone\r\n\
+                BEGIN:STANDARD\r\n\
+                DTSTART:19700101T000000\r\n\
+                TZOFFSETFROM:+0300\r\n\
+                TZOFFSETTO:+0300\r\n\
+                END:STANDARD\r\n\
+                END:VTIMEZONE\r\n\
+                END:VCALENDAR\r\n";
+    let mut calendar = ics::parse(text).unwrap();
+
+    apply_timezones_preference(&mut calendar, false);
+
+    assert_eq!(get_tzids(&calendar), vec!["Custom/Zone"]);
+}
+
+#[actix_web::test]
+async fn test_options_advertises_no_timezone() {
+    let response = OptionsController::new().options().await;
+
+    let dav = response.headers().get("DAV").unwrap().to_str().unwrap();
+    assert!(dav.split(", ").any(|class| class == "calendar-no-timezone"));
+}
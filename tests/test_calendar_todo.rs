@@ -0,0 +1,146 @@
+use cal_dav_fast::app::calendar::{
+    ical::{ics, ICalError},
+    models::{
+        calendar_types::{relation::RelationType, todo_status::TodoStatus},
+        todo::Todo,
+    },
+};
+use chrono::{TimeZone, Utc};
+use pretty_assertions::assert_eq;
+
+fn calendar_with(components: &str) -> String {
+    format!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{}END:VCALENDAR\r\n", components)
+}
+
+#[test]
+fn test_todo_from_ical() {
+    let text = calendar_with(
+        "BEGIN:VTODO\r\n\
+         UID:todo-1\r\n\
+         DTSTAMP:20210101T000000Z\r\n\
+         DTSTART;TZID=Europe/Madrid:20210105T090000\r\n\
+         DUE;TZID=Europe/Madrid:20210110T180000\r\n\
+         COMPLETED:20210109T120000Z\r\n\
+         PERCENT-COMPLETE:100\r\n\
+         STATUS:COMPLETED\r\n\
+         SUMMARY:Submit quarterly report\r\n\
+         CLASS:CONFIDENTIAL\r\n\
+         RELATED-TO:project-1\r\n\
+         RELATED-TO;RELTYPE=CHILD:todo-2\r\n\
+         END:VTODO\r\n\
+         BEGIN:VEVENT\r\n\
+         UID:event-1\r\n\
+         DTSTART:20210101T000000Z\r\n\
+         END:VEVENT\r\n",
+    );
+
+    let todos = Todo::from_calendar(&ics::parse(&text).unwrap()).unwrap();
+
+    assert_eq!(todos.len(), 1);
+    let todo = &todos[0];
+    assert_eq!(todo.get_uid(), "todo-1");
+    assert_eq!(
+        todo.get_start(),
+        Some(Utc.with_ymd_and_hms(2021, 1, 5, 8, 0, 0).unwrap())
+    );
+    assert_eq!(
+        todo.get_due(),
+        Some(Utc.with_ymd_and_hms(2021, 1, 10, 17, 0, 0).unwrap())
+    );
+    assert_eq!(
+        todo.get_completed(),
+        Some(Utc.with_ymd_and_hms(2021, 1, 9, 12, 0, 0).unwrap())
+    );
+    assert_eq!(todo.get_timezone(), Some("Europe/Madrid"));
+    assert_eq!(todo.get_percent_complete(), Some(100));
+    assert!(matches!(todo.get_status(), Some(TodoStatus::Completed)));
+    assert!(todo.is_completed());
+
+    assert_eq!(todo.get_relations().len(), 2);
+    assert_eq!(todo.get_relations()[1].relation_type, RelationType::Child);
+    assert_eq!(todo.get_parent_uid(), Some("project-1"));
+}
+
+#[test]
+fn test_todo_to_ical() {
+    let text = calendar_with(
+        "BEGIN:VTODO\r\n\
+         UID:todo-1\r\n\
+         DTSTAMP:20210101T000000Z\r\n\
+         DUE:20210110T180000Z\r\n\
+         PERCENT-COMPLETE:40\r\n\
+         STATUS:IN-PROCESS\r\n\
+         RELATED-TO;RELTYPE=SIBLING:todo-3\r\n\
+         RRULE:FREQ=YEARLY;COUNT=2\r\n\
+         END:VTODO\r\n",
+    );
+    let todos = Todo::from_calendar(&ics::parse(&text).unwrap()).unwrap();
+
+    let component = todos[0].to_ical();
+
+    assert!(component.is("VTODO"));
+    assert!(component.get_property("DTSTART").is_none());
+    assert_eq!(component.get_property("DUE").unwrap().get_value(), "20210110T180000Z");
+    assert_eq!(component.get_property("PERCENT-COMPLETE").unwrap().get_value(), "40");
+    assert_eq!(component.get_property("STATUS").unwrap().get_value(), "IN-PROCESS");
+    assert_eq!(
+        component.get_property("RRULE").unwrap().get_value(),
+        "FREQ=YEARLY;COUNT=2"
+    );
+
+    let related_to = component.get_property("RELATED-TO").unwrap();
+    assert_eq!(related_to.get_value(), "todo-3");
+    assert_eq!(related_to.get_parameter_value("RELTYPE"), Some("SIBLING"));
+    assert!(!todos[0].is_completed());
+}
+
+#[test]
+fn test_todo_invalid_values() {
+    let parse = |properties: &str| {
+        let text = calendar_with(&format!(
+            "BEGIN:VTODO\r\nUID:todo-1\r\nDTSTAMP:20210101T000000Z\r\n{}END:VTODO\r\n",
+            properties
+        ));
+        Todo::from_calendar(&ics::parse(&text).unwrap())
+    };
+
+    assert!(matches!(
+        parse("PERCENT-COMPLETE:101\r\n"),
+        Err(ICalError::InvalidValue { .. })
+    ));
+    assert!(matches!(
+        parse("STATUS:TENTATIVE\r\n"),
+        Err(ICalError::InvalidValue { .. })
+    ));
+    assert!(matches!(
+        parse("RELATED-TO;RELTYPE=COUSIN:todo-2\r\n"),
+        Err(ICalError::InvalidValue { .. })
+    ));
+    assert!(matches!(
+        parse("DTSTART:20210110T000000Z\r\nDUE:20210105T000000Z\r\n"),
+        Err(ICalError::InvalidValue { .. })
+    ));
+    assert!(parse("").is_ok());
+}
+
+#[test]
+fn test_todo_bson_round_trip() {
+    let text = calendar_with(
+        "BEGIN:VTODO\r\n\
+         UID:todo-1\r\n\
+         DTSTAMP:20210101T000000Z\r\n\
+         DUE:20210110T180000Z\r\n\
+         RELATED-TO:project-1\r\n\
+         END:VTODO\r\n",
+    );
+    let todos = Todo::from_calendar(&ics::parse(&text).unwrap()).unwrap();
+
+    let document = bson::to_document(&todos[0]).unwrap();
+    assert!(matches!(document.get("due"), Some(bson::Bson::DateTime(_))));
+    assert!(matches!(document.get("completed"), Some(bson::Bson::Null)));
+
+    let todo: Todo = bson::from_document(document).unwrap();
+    assert_eq!(todo.get_due(), todos[0].get_due());
+    assert_eq!(todo.get_completed(), None);
+    assert_eq!(todo.get_parent_uid(), Some("project-1"));
+}
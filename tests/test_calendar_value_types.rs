@@ -0,0 +1,207 @@
+use cal_dav_fast::app::calendar::{
+    ical::{ics, ICalError},
+    models::{
+        calendar_types::{
+            event_classification::EventClassification, event_status::EventStatus, geolocation::GeoLocation,
+            priority::Priority, todo_status::TodoStatus, uri::Uri,
+        },
+        event::Event,
+        todo::Todo,
+    },
+};
+use pretty_assertions::assert_eq;
+
+fn parse_event(properties: &str) -> Result<Event, ICalError> {
+    let text = format!(
+        "BEGIN:VCALENDAR\r\n\
+         VERSION:2.0\r\n\
+         BEGIN:VEVENT\r\n\
+         UID:event-1\r\n\
+         DTSTAMP:20210101T000000Z\r\n\
+         {}END:VEVENT\r\n\
+         END:VCALENDAR\r\n",
+        properties
+    );
+    Event::from_calendar(&ics::parse(&text).unwrap()).map(|mut events| events.remove(0))
+}
+
+fn parse_todo(properties: &str) -> Todo {
+    let text = format!(
+        "BEGIN:VCALENDAR\r\n\
+         VERSION:2.0\r\n\
+         BEGIN:VTODO\r\n\
+         UID:todo-1\r\n\
+         DTSTAMP:20210101T000000Z\r\n\
+         {}END:VTODO\r\n\
+         END:VCALENDAR\r\n",
+        properties
+    );
+    Todo::from_calendar(&ics::parse(&text).unwrap()).unwrap().remove(0)
+}
+
+#[test]
+fn test_priority() {
+    assert_eq!(Priority::new(1), Some(Priority::HIGHEST));
+    assert_eq!(Priority::new(10), None);
+    assert!(Priority::UNDEFINED.is_undefined());
+    assert!(Priority::new(4).unwrap().is_high());
+    assert!(!Priority::MEDIUM.is_high() && !Priority::MEDIUM.is_low());
+    assert!(Priority::LOWEST.is_low());
+    assert_eq!(Priority::from_ical(" 3 "), Priority::new(3));
+    assert_eq!(Priority::from_ical("-1"), None);
+
+    let event = parse_event("DTSTART:20210104T100000Z\r\nPRIORITY:2\r\n").unwrap();
+    assert_eq!(event.get_priority(), Priority::new(2));
+    assert_eq!(event.to_ical().get_property("PRIORITY").unwrap().get_value(), "2");
+
+    assert!(matches!(
+        parse_event("DTSTART:20210104T100000Z\r\nPRIORITY:10\r\n"),
+        Err(ICalError::InvalidValue { property, .. }) if property == "PRIORITY"
+    ));
+}
+
+#[test]
+fn test_geolocation() {
+    let location = GeoLocation::from_ical("37.386013;-122.082932").unwrap();
+    assert_eq!(location.get_latitude(), 37.386013);
+    assert_eq!(location.get_longitude(), -122.082932);
+
+    assert!(GeoLocation::new(90.0, 180.0).is_some());
+    assert!(GeoLocation::new(90.5, 0.0).is_none());
+    assert!(GeoLocation::new(0.0, -180.5).is_none());
+    assert!(GeoLocation::from_ical("NaN;0").is_none());
+
+    assert!(matches!(
+        parse_event("DTSTART:20210104T100000Z\r\nGEO:91;10\r\n"),
+        Err(ICalError::InvalidValue { property, .. }) if property == "GEO"
+    ));
+}
+
+#[test]
+fn test_uri() {
+    let uri = Uri::parse("http://example.com/my-report.txt?id=1#top").unwrap();
+    assert_eq!(uri.get_scheme(), "http");
+    assert_eq!(uri.as_str(), "http://example.com/my-report.txt?id=1#top");
+
+    assert!(Uri::parse("mailto:jane_doe@example.com").is_some());
+    assert!(Uri::parse("urn:ietf:params:xml:ns:caldav").is_some());
+    assert!(Uri::parse("http://example.com/a%20b").is_some());
+    assert!(Uri::parse("http://example.com/a b").is_none());
+    assert!(Uri::parse("http://example.com/a%2").is_none());
+    assert!(Uri::parse("example.com/report").is_none());
+    assert!(Uri::parse("1http://example.com").is_none());
+
+    let event = parse_event("DTSTART:20210104T100000Z\r\nURL:http://example.com/pub/calendars/event.ics\r\n").unwrap();
+    assert_eq!(
+        event.get_url().map(Uri::as_str),
+        Some("http://example.com/pub/calendars/event.ics")
+    );
+
+    assert!(matches!(
+        parse_event("DTSTART:20210104T100000Z\r\nURL:not a url\r\n"),
+        Err(ICalError::InvalidValue { property, .. }) if property == "URL"
+    ));
+}
+
+#[test]
+fn test_extension_values() {
+    let event = parse_event("DTSTART:20210104T100000Z\r\nCLASS:x-company-internal\r\nSTATUS:X-POSTPONED\r\n").unwrap();
+
+    let classification = event.get_classification().unwrap();
+    assert_eq!(
+        classification,
+        &EventClassification::Other("X-COMPANY-INTERNAL".to_string())
+    );
+    assert_eq!(classification.get_effective(), EventClassification::Private);
+    assert!(!classification.is_public());
+    assert_eq!(event.get_status(), Some(&EventStatus::Other("X-POSTPONED".to_string())));
+
+    let component = event.to_ical();
+    assert_eq!(
+        component.get_property("CLASS").unwrap().get_value(),
+        "X-COMPANY-INTERNAL"
+    );
+    assert_eq!(component.get_property("STATUS").unwrap().get_value(), "X-POSTPONED");
+
+    let document = bson::to_document(&event).unwrap();
+    let event: Event = bson::from_document(document).unwrap();
+    assert_eq!(
+        event.get_classification(),
+        Some(&EventClassification::Other("X-COMPANY-INTERNAL".to_string()))
+    );
+
+    /* Values of the other components are not valid */
+    assert_eq!(TodoStatus::from_ical("in-process"), Some(TodoStatus::InProcess));
+    assert_eq!(TodoStatus::from_ical("FINAL"), None);
+    assert!(matches!(
+        parse_event("DTSTART:20210104T100000Z\r\nSTATUS:COMPLETED\r\n"),
+        Err(ICalError::InvalidValue { property, .. }) if property == "STATUS"
+    ));
+    assert_eq!(EventClassification::from_ical("NOT VALID"), None);
+}
+
+#[test]
+fn test_invalid_documents() {
+    let event = parse_event(
+        "DTSTART:20210104T100000Z\r\n\
+         PRIORITY:1\r\n\
+         GEO:37.386013;-122.082932\r\n\
+         URL:http://example.com/event\r\n",
+    )
+    .unwrap();
+    let document = bson::to_document(&event).unwrap();
+    assert!(bson::from_document::<Event>(document.clone()).is_ok());
+
+    let mut invalid = document.clone();
+    invalid.insert("priority", 12);
+    assert!(bson::from_document::<Event>(invalid).is_err());
+
+    let mut invalid = document.clone();
+    invalid.insert("geo_location", bson::doc! { "latitude": 120.0, "longitude": 0.0 });
+    assert!(bson::from_document::<Event>(invalid).is_err());
+
+    let mut invalid = document;
+    invalid.insert("url", "not a url");
+    assert!(bson::from_document::<Event>(invalid).is_err());
+}
+
+#[test]
+fn test_event_sequence() {
+    let previous = parse_event("DTSTART:20210104T100000Z\r\nSEQUENCE:2\r\nSUMMARY:Meeting\r\n").unwrap();
+
+    /* Changing the summary is not significant */
+    let mut event = parse_event("DTSTART:20210104T100000Z\r\nSEQUENCE:2\r\nSUMMARY:Team meeting\r\n").unwrap();
+    event.update_sequence(&previous);
+    assert_eq!(event.get_version(), 2);
+
+    /* A lower sequence never replaces the stored one */
+    let mut event = parse_event("DTSTART:20210104T100000Z\r\nSUMMARY:Meeting\r\n").unwrap();
+    event.update_sequence(&previous);
+    assert_eq!(event.get_version(), 2);
+
+    let mut event = parse_event("DTSTART:20210104T110000Z\r\nSEQUENCE:2\r\n").unwrap();
+    event.update_sequence(&previous);
+    assert_eq!(event.get_version(), 3);
+
+    let mut event = parse_event("DTSTART:20210104T100000Z\r\nDURATION:PT1H\r\nSTATUS:CANCELLED\r\n").unwrap();
+    event.update_sequence(&previous);
+    assert_eq!(event.get_version(), 3);
+
+    /* Clients may have incremented it already */
+    let mut event = parse_event("DTSTART:20210104T100000Z\r\nRRULE:FREQ=DAILY\r\nSEQUENCE:5\r\n").unwrap();
+    event.update_sequence(&previous);
+    assert_eq!(event.get_version(), 5);
+}
+
+#[test]
+fn test_todo_sequence() {
+    let previous = parse_todo("DUE:20210104T100000Z\r\nPRIORITY:5\r\n");
+
+    let mut todo = parse_todo("DUE:20210104T100000Z\r\nPRIORITY:1\r\n");
+    todo.update_sequence(&previous);
+    assert_eq!(todo.get_version(), 0);
+
+    let mut todo = parse_todo("DUE:20210105T100000Z\r\nPRIORITY:5\r\n");
+    todo.update_sequence(&previous);
+    assert_eq!(todo.get_version(), 1);
+}
@@ -0,0 +1,162 @@
+use cal_dav_fast::app::calendar::{
+    ical::{component::ICalComponent, property::ICalProperty, xcal},
+    models::{
+        calendar_types::{event_status::EventStatus, timezone::timezone_registry::TimezoneRegistry},
+        event::Event,
+    },
+};
+use chrono::{TimeZone, Utc};
+use pretty_assertions::assert_eq;
+
+const XCAL_EVENT: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<icalendar xmlns="urn:ietf:params:xml:ns:icalendar-2.0">
+  <vcalendar>
+    <properties>
+      <prodid><text>-//Example Inc.//Example Calendar//EN</text></prodid>
+      <version><text>2.0</text></version>
+    </properties>
+    <components>
+      <vevent>
+        <properties>
+          <dtstamp><date-time>2008-02-05T19:12:24Z</date-time></dtstamp>
+          <dtstart><date-time>2008-10-06T10:00:00Z</date-time></dtstart>
+          <dtend><date-time>2008-10-06T11:30:00Z</date-time></dtend>
+          <summary><text>Planning meeting &amp; lunch</text></summary>
+          <uid><text>4088E990AD89CB3DBB484909</text></uid>
+          <status><text>CONFIRMED</text></status>
+          <geo><geo><latitude>37.386013</latitude><longitude>-122.082932</longitude></geo></geo>
+          <categories><text>WORK</text><text>MEETING</text></categories>
+          <attendee>
+            <parameters>
+              <cn><text>John Smith</text></cn>
+              <partstat><text>ACCEPTED</text></partstat>
+            </parameters>
+            <cal-address>mailto:jsmith@example.com</cal-address>
+          </attendee>
+        </properties>
+      </vevent>
+    </components>
+  </vcalendar>
+</icalendar>"#;
+
+#[test]
+fn test_parse_xcal_calendar() {
+    let calendar = xcal::parse(XCAL_EVENT).unwrap();
+
+    assert!(calendar.is("VCALENDAR"));
+    assert_eq!(calendar.get_property("VERSION").unwrap().get_value(), "2.0");
+    assert_eq!(calendar.components.len(), 1);
+
+    let event = &calendar.components[0];
+    assert!(event.is("VEVENT"));
+    assert_eq!(event.get_property("DTSTART").unwrap().get_value(), "20081006T100000Z");
+    assert_eq!(
+        event.get_property("SUMMARY").unwrap().get_value(),
+        "Planning meeting & lunch"
+    );
+    assert_eq!(event.get_property("GEO").unwrap().get_value(), "37.386013;-122.082932");
+    assert_eq!(
+        event.get_property("CATEGORIES").unwrap().values,
+        vec!["WORK", "MEETING"]
+    );
+    assert_eq!(
+        event.get_property("ATTENDEE").unwrap().get_parameter_value("CN"),
+        Some("John Smith")
+    );
+}
+
+#[test]
+fn test_parse_xcal_event() {
+    let calendar = xcal::parse(XCAL_EVENT).unwrap();
+    let event = Event::from_ical(&calendar.components[0], &TimezoneRegistry::new()).unwrap();
+
+    assert_eq!(event.get_uid(), "4088E990AD89CB3DBB484909");
+    assert_eq!(event.get_start(), Utc.with_ymd_and_hms(2008, 10, 6, 10, 0, 0).unwrap());
+    assert_eq!(
+        event.get_end(),
+        Some(Utc.with_ymd_and_hms(2008, 10, 6, 11, 30, 0).unwrap())
+    );
+    assert_eq!(
+        event.get_created_at(),
+        Utc.with_ymd_and_hms(2008, 2, 5, 19, 12, 24).unwrap()
+    );
+    assert_eq!(event.get_summary(), Some("Planning meeting & lunch"));
+    assert!(matches!(event.get_status(), Some(EventStatus::Confirmed)));
+    assert_eq!(event.get_geo_location().unwrap().get_latitude(), 37.386013);
+    assert_eq!(event.get_categories(), &["WORK".to_string(), "MEETING".to_string()]);
+    assert_eq!(event.get_attendees().len(), 1);
+    assert_eq!(event.get_attendees()[0].email, "jsmith@example.com");
+    assert_eq!(event.get_attendees()[0].name, "John Smith");
+}
+
+#[test]
+fn test_event_xcal_roundtrip() {
+    let calendar = xcal::parse(XCAL_EVENT).unwrap();
+    let event = Event::from_ical(&calendar.components[0], &TimezoneRegistry::new()).unwrap();
+
+    let written = xcal::write(&ICalComponent::calendar(vec![event.to_ical()]));
+    let parsed = xcal::parse(&written).unwrap();
+
+    assert_eq!(parsed.components[0], event.to_ical());
+    assert!(written.contains("<dtstart><date-time>2008-10-06T10:00:00Z</date-time></dtstart>"));
+    assert!(written.contains("<summary><text>Planning meeting &amp; lunch</text></summary>"));
+}
+
+#[test]
+fn test_event_without_uid_is_rejected() {
+    let mut component = ICalComponent::new("VEVENT");
+    component.add_property(ICalProperty::new("DTSTART", "20081006T100000Z"));
+
+    assert!(Event::from_ical(&component, &TimezoneRegistry::new()).is_err());
+}
+
+#[test]
+fn test_xcal_date_value_sets_value_parameter() {
+    let xml = r#"<icalendar xmlns="urn:ietf:params:xml:ns:icalendar-2.0"><vcalendar><components><vevent><properties>
+        <dtstart><date>2011-05-17</date></dtstart>
+    </properties></vevent></components></vcalendar></icalendar>"#;
+
+    let calendar = xcal::parse(xml).unwrap();
+    let dtstart = calendar.components[0].get_property("DTSTART").unwrap();
+
+    assert_eq!(dtstart.get_value(), "20110517");
+    assert_eq!(dtstart.get_parameter_value("VALUE"), Some("DATE"));
+
+    let written = xcal::write(&calendar);
+    assert!(written.contains("<dtstart><date>2011-05-17</date></dtstart>"));
+}
+
+#[test]
+fn test_xcal_recur_and_utc_offset() {
+    let mut standard = ICalComponent::new("STANDARD");
+    standard.add_property(ICalProperty::new("TZOFFSETFROM", "-0400"));
+    standard.add_property(ICalProperty::new(
+        "RRULE",
+        "FREQ=YEARLY;UNTIL=20061029T060000Z;BYDAY=-1SU;BYMONTH=10",
+    ));
+
+    let written = xcal::write(&ICalComponent::calendar(vec![standard.clone()]));
+
+    assert!(written.contains("<tzoffsetfrom><utc-offset>-04:00</utc-offset></tzoffsetfrom>"));
+    assert!(written.contains(
+        "<rrule><recur><freq>YEARLY</freq><until>2006-10-29T06:00:00Z</until><byday>-1SU</byday><bymonth>10</bymonth></recur></rrule>"
+    ));
+    assert_eq!(xcal::parse(&written).unwrap().components[0], standard);
+}
+
+#[test]
+fn test_xcal_malformed_values_are_kept() {
+    /* Multi-byte characters where the dates and offsets are split */
+    let mut standard = ICalComponent::new("STANDARD");
+    standard.add_property(ICalProperty::new("DTSTART", "200é0205T1é1224Z"));
+    standard.add_property(ICalProperty::new("TZOFFSETFROM", "+0é00"));
+    standard.add_property(ICalProperty::new("TZOFFSETTO", "-0"));
+    standard.add_property(ICalProperty::new("RRULE", "FREQ=YEARLY;UNTIL=2006102é"));
+
+    let written = xcal::write(&ICalComponent::calendar(vec![standard]));
+
+    assert!(written.contains("<date-time>200é0205T1é1224Z</date-time>"));
+    assert!(written.contains("<utc-offset>+0é00</utc-offset>"));
+    assert!(written.contains("<utc-offset>-0</utc-offset>"));
+    assert!(written.contains("<until>2006102é</until>"));
+}
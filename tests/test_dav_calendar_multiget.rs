@@ -0,0 +1,231 @@
+mod common;
+use std::sync::Arc;
+
+use actix_web::{http::StatusCode, test::TestRequest, HttpResponse};
+use cal_dav_fast::{
+    app::calendar::{ical::xcal, models::calendar::Calendar},
+    architecture::xml::{XmlElement, CALDAV_NAMESPACE, DAV_NAMESPACE},
+    controllers::dav_controller::DavController,
+};
+use common::{read_body, MemoryCalendarStore};
+use pretty_assertions::assert_eq;
+
+const MEETING: &str = "BEGIN:VCALENDAR\r\n\
+                       VERSION:2.0\r\n\
+                       BEGIN:VEVENT\r\n\
+                       UID:meeting\r\n\
+                       DTSTAMP:20210101T000000Z\r\n\
+                       DTSTART:20210104T100000Z\r\n\
+                       DTEND:20210104T110000Z\r\n\
+                       SUMMARY:Budget meeting\r\n\
+                       DESCRIPTION:Yearly budget\r\n\
+                       BEGIN:VALARM\r\n\
+                       ACTION:DISPLAY\r\n\
+                       DESCRIPTION:Reminder\r\n\
+                       TRIGGER:-PT15M\r\n\
+                       END:VALARM\r\n\
+                       END:VEVENT\r\n\
+                       END:VCALENDAR\r\n";
+
+const LUNCH: &str = "BEGIN:VCALENDAR\r\n\
+                     VERSION:2.0\r\n\
+                     BEGIN:VEVENT\r\n\
+                     UID:lunch\r\n\
+                     DTSTAMP:20210101T000000Z\r\n\
+                     DTSTART:20210105T120000Z\r\n\
+                     SUMMARY:Lunch\r\n\
+                     END:VEVENT\r\n\
+                     END:VCALENDAR\r\n";
+
+fn controller() -> DavController {
+    let store = MemoryCalendarStore::default();
+    let work = store.add_calendar(Calendar::new("work"));
+    store.add_object(work, "meeting.ics", MEETING);
+    store.add_object(work, "lunch 1.ics", LUNCH);
+    let home = store.add_calendar(Calendar::new("home"));
+    store.add_object(home, "lunch.ics", LUNCH);
+    DavController::new(Arc::new(store), 1024)
+}
+
+async fn report(path: &str, body: &str) -> HttpResponse {
+    let request = TestRequest::default().uri(path).to_http_request();
+    controller().report(&request, body).await
+}
+
+async fn get_responses(response: HttpResponse) -> Vec<XmlElement> {
+    assert_eq!(response.status(), StatusCode::MULTI_STATUS);
+    let root = XmlElement::parse(&read_body(response).await).unwrap();
+    root.children_named(DAV_NAMESPACE, "response").cloned().collect()
+}
+
+fn get_href(response: &XmlElement) -> &str {
+    &response.child(DAV_NAMESPACE, "href").unwrap().text
+}
+
+fn get_status(response: &XmlElement) -> Option<&str> {
+    response
+        .child(DAV_NAMESPACE, "status")
+        .map(|status| status.text.as_str())
+}
+
+fn get_property<'a>(response: &'a XmlElement, namespace: &str, name: &str) -> Option<&'a XmlElement> {
+    response
+        .child(DAV_NAMESPACE, "propstat")?
+        .child(DAV_NAMESPACE, "prop")?
+        .child(namespace, name)
+}
+
+fn multiget_body(calendar_data: &str, hrefs: &[&str]) -> String {
+    let hrefs: String = hrefs.iter().map(|href| format!("<D:href>{}</D:href>", href)).collect();
+    format!(
+        "<C:calendar-multiget xmlns:D=\"DAV:\" xmlns:C=\"urn:ietf:params:xml:ns:caldav\">\
+         <D:prop><D:getetag/>{}</D:prop>{}</C:calendar-multiget>",
+        calendar_data, hrefs
+    )
+}
+
+#[actix_web::test]
+async fn test_calendar_multiget() {
+    let body = multiget_body(
+        "<C:calendar-data/>",
+        &[
+            "/calendars/work/meeting.ics",
+            "/calendars/work/missing.ics",
+            "https://cal.example.com/calendars/work/lunch%201.ics",
+            "/calendars/home/lunch.ics",
+            "/calendars/work/",
+        ],
+    );
+    let responses = get_responses(report("/calendars/work/", &body).await).await;
+
+    let hrefs: Vec<&str> = responses.iter().map(get_href).collect();
+    assert_eq!(
+        hrefs,
+        vec![
+            "/calendars/work/meeting.ics",
+            "/calendars/work/missing.ics",
+            "/calendars/work/lunch%201.ics",
+            "/calendars/home/lunch.ics",
+            "/calendars/work/",
+        ]
+    );
+    assert_eq!(get_status(&responses[1]), Some("HTTP/1.1 404 Not Found"));
+    assert_eq!(get_status(&responses[4]), Some("HTTP/1.1 404 Not Found"));
+
+    for response in [&responses[0], &responses[2], &responses[3]] {
+        assert!(get_property(response, DAV_NAMESPACE, "getetag")
+            .unwrap()
+            .text
+            .starts_with('"'));
+    }
+    let data = &get_property(&responses[0], CALDAV_NAMESPACE, "calendar-data")
+        .unwrap()
+        .text;
+    assert!(data.contains("UID:meeting"));
+    assert!(data.contains("BEGIN:VALARM"));
+    let data = &get_property(&responses[2], CALDAV_NAMESPACE, "calendar-data")
+        .unwrap()
+        .text;
+    assert!(data.contains("UID:lunch"));
+}
+
+#[actix_web::test]
+async fn test_calendar_multiget_partial_data() {
+    let calendar_data = "<C:calendar-data><C:comp name=\"VCALENDAR\">\
+                         <C:prop name=\"VERSION\"/>\
+                         <C:comp name=\"VEVENT\">\
+                         <C:prop name=\"UID\"/><C:prop name=\"SUMMARY\"/><C:prop name=\"DESCRIPTION\" novalue=\"yes\"/>\
+                         </C:comp></C:comp></C:calendar-data>";
+    let body = multiget_body(calendar_data, &["/calendars/work/meeting.ics"]);
+    let responses = get_responses(report("/calendars/work/meeting.ics", &body).await).await;
+
+    let data = &get_property(&responses[0], CALDAV_NAMESPACE, "calendar-data")
+        .unwrap()
+        .text;
+    assert_eq!(
+        data.as_str(),
+        "BEGIN:VCALENDAR\r\n\
+         VERSION:2.0\r\n\
+         BEGIN:VEVENT\r\n\
+         UID:meeting\r\n\
+         SUMMARY:Budget meeting\r\n\
+         DESCRIPTION:\r\n\
+         END:VEVENT\r\n\
+         END:VCALENDAR\r\n"
+    );
+
+    /* Every property and component of the selected ones */
+    let calendar_data = "<C:calendar-data><C:comp name=\"VCALENDAR\"><C:allprop/>\
+                         <C:comp name=\"VEVENT\"><C:allprop/><C:allcomp/></C:comp></C:comp></C:calendar-data>";
+    let body = multiget_body(calendar_data, &["/calendars/work/meeting.ics"]);
+    let responses = get_responses(report("/calendars/work/", &body).await).await;
+    let data = &get_property(&responses[0], CALDAV_NAMESPACE, "calendar-data")
+        .unwrap()
+        .text;
+    assert!(data.contains("PRODID:"));
+    assert!(data.contains("DTSTART:20210104T100000Z"));
+    assert!(data.contains("TRIGGER:-PT15M"));
+}
+
+#[actix_web::test]
+async fn test_calendar_query_partial_data() {
+    let body = "<C:calendar-query xmlns:D=\"DAV:\" xmlns:C=\"urn:ietf:params:xml:ns:caldav\">\
+                <D:prop><C:calendar-data><C:comp name=\"VCALENDAR\"><C:comp name=\"VEVENT\">\
+                <C:prop name=\"UID\"/></C:comp></C:comp></C:calendar-data></D:prop>\
+                <C:filter><C:comp-filter name=\"VCALENDAR\"/></C:filter></C:calendar-query>";
+    let responses = get_responses(report("/calendars/home/", body).await).await;
+    assert_eq!(
+        get_property(&responses[0], CALDAV_NAMESPACE, "calendar-data")
+            .unwrap()
+            .text,
+        "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:lunch\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n"
+    );
+}
+
+#[actix_web::test]
+async fn test_calendar_multiget_xcal() {
+    let body = multiget_body(
+        "<C:calendar-data content-type=\"application/calendar+xml\" version=\"2.0\"/>",
+        &["/calendars/work/meeting.ics"],
+    );
+    let responses = get_responses(report("/calendars/work/", &body).await).await;
+
+    let data = get_property(&responses[0], CALDAV_NAMESPACE, "calendar-data").unwrap();
+    assert_eq!(data.get_attribute("content-type"), Some("application/calendar+xml"));
+    let calendar = xcal::parse(&data.text).unwrap();
+    let event = calendar.get_components("VEVENT").next().unwrap();
+    assert_eq!(event.get_property("UID").unwrap().get_value(), "meeting");
+    assert_eq!(event.get_property("DTSTART").unwrap().get_value(), "20210104T100000Z");
+}
+
+#[actix_web::test]
+async fn test_calendar_multiget_errors() {
+    let body = multiget_body(
+        "<C:calendar-data content-type=\"application/calendar+json\"/>",
+        &["/calendars/work/meeting.ics"],
+    );
+    let response = report("/calendars/work/", &body).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(read_body(response).await.contains("<C:supported-calendar-data/>"));
+
+    let body = multiget_body(
+        "<C:calendar-data><C:comp/></C:calendar-data>",
+        &["/calendars/work/meeting.ics"],
+    );
+    assert_eq!(
+        report("/calendars/work/", &body).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+
+    let body = multiget_body("", &[]);
+    assert_eq!(
+        report("/calendars/work/", &body).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+
+    let body = multiget_body("", &["/calendars/work/meeting.ics"]);
+    assert_eq!(
+        report("/calendars/school/", &body).await.status(),
+        StatusCode::NOT_FOUND
+    );
+}
@@ -0,0 +1,484 @@
+mod common;
+use std::sync::Arc;
+
+use actix_web::{http::StatusCode, test::TestRequest, HttpResponse};
+use bson::oid::ObjectId;
+use cal_dav_fast::{
+    app::calendar::{calendar_store::CalendarStore, models::calendar::Calendar},
+    controllers::{
+        attachments_controller::ManagedAttachmentQuery, calendar_object_controller::CalendarObjectController,
+    },
+};
+use chrono::{Duration, TimeZone, Utc};
+use common::{event, object_controller, read_body, MemoryBlobStore, MemoryCalendarStore};
+use pretty_assertions::assert_eq;
+
+struct Setup {
+    store: Arc<MemoryCalendarStore>,
+    blobs: Arc<MemoryBlobStore>,
+    calendar_id: ObjectId,
+    controller: CalendarObjectController,
+}
+
+fn setup() -> Setup {
+    let store = Arc::new(MemoryCalendarStore::default());
+    let blobs = Arc::new(MemoryBlobStore::default());
+    let mut calendar = Calendar::new("work");
+    calendar.touch(Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap());
+    let calendar_id = store.add_calendar(calendar);
+    let controller = object_controller(store.clone(), blobs.clone(), 16);
+    Setup {
+        store,
+        blobs,
+        calendar_id,
+        controller,
+    }
+}
+
+fn request(path: &str) -> TestRequest {
+    TestRequest::default()
+        .uri(path)
+        .insert_header(("Content-Type", "text/calendar; charset=utf-8"))
+}
+
+fn get_header(response: &HttpResponse, name: &str) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .map(|value| value.to_str().unwrap().to_string())
+}
+
+#[actix_web::test]
+async fn test_put_and_get() {
+    let setup = setup();
+    let path = "/calendars/work/event-1.ics";
+    let created_at = Utc::now();
+
+    let response = setup
+        .controller
+        .put(
+            &request(path).to_http_request(),
+            &event("event-1", "SUMMARY:Meeting\r\nLAST-MODIFIED:20210101T000000Z\r\n"),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    /* Stamped by the server, so not the data sent */
+    assert_eq!(get_header(&response, "ETag"), None);
+
+    let response = setup.controller.get(&request(path).to_http_request()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let etag = get_header(&response, "ETag").unwrap();
+    assert_eq!(
+        get_header(&response, "Content-Type").as_deref(),
+        Some("text/calendar; charset=utf-8; component=vevent")
+    );
+    /* Stamped by the server, whatever the client sent */
+    assert_ne!(
+        get_header(&response, "Last-Modified").as_deref(),
+        Some("Fri, 01 Jan 2021 00:00:00 GMT")
+    );
+    let stored = setup.store.events.lock().unwrap()[0].clone();
+    assert!(stored.get_created_at() >= created_at);
+    assert_eq!(stored.get_updated_at(), stored.get_created_at());
+    let body = read_body(response).await;
+    assert!(body.contains("UID:event-1\r\n"));
+    assert!(body.contains("SUMMARY:Meeting\r\n"));
+
+    /* The collection changed */
+    let calendar = setup.store.get_calendar("work").await.unwrap().unwrap();
+    assert!(calendar.get_updated_at() > Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap());
+
+    let response = setup
+        .controller
+        .put(
+            &request(path).to_http_request(),
+            &event("event-1", "SUMMARY:Team meeting\r\n"),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let response = setup.controller.get(&request(path).to_http_request()).await;
+    assert_ne!(get_header(&response, "ETag"), Some(etag));
+    assert_eq!(setup.store.events.lock().unwrap().len(), 1);
+
+    /* The creation date is the one of the first version */
+    let updated = setup.store.events.lock().unwrap()[0].clone();
+    assert_eq!(updated.get_created_at(), stored.get_created_at());
+    assert!(updated.get_updated_at() >= stored.get_updated_at());
+
+    let response = setup
+        .controller
+        .get(&request("/calendars/work/unknown.ics").to_http_request())
+        .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn test_put_todo() {
+    let setup = setup();
+    let path = "/calendars/work/todo-1.ics";
+    let todo = |properties: &str| {
+        format!(
+            "BEGIN:VCALENDAR\r\n\
+             VERSION:2.0\r\n\
+             BEGIN:VTODO\r\n\
+             UID:todo-1\r\n\
+             DTSTAMP:20210101T000000Z\r\n\
+             DTSTART:20210104T100000Z\r\n\
+             DUE:20210105T100000Z\r\n\
+             {}END:VTODO\r\n\
+             END:VCALENDAR\r\n",
+            properties
+        )
+    };
+
+    let response = setup
+        .controller
+        .put(&request(path).to_http_request(), &todo("SUMMARY:Report\r\n"))
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(setup.store.todos.lock().unwrap().len(), 1);
+    assert!(setup.store.events.lock().unwrap().is_empty());
+
+    let response = setup.controller.get(&request(path).to_http_request()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        get_header(&response, "Content-Type").as_deref(),
+        Some("text/calendar; charset=utf-8; component=vtodo")
+    );
+    let body = read_body(response).await;
+    assert!(body.contains("BEGIN:VTODO\r\n"));
+    assert!(body.contains("SUMMARY:Report\r\n"));
+
+    /* A significant change increments the SEQUENCE */
+    let response = setup
+        .controller
+        .put(
+            &request(path).to_http_request(),
+            &todo("SUMMARY:Report\r\nSTATUS:COMPLETED\r\n"),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let stored = setup.store.todos.lock().unwrap()[0].clone();
+    assert!(stored.is_completed());
+    assert_eq!(stored.get_version(), 1);
+
+    /* The UID is taken by the to-do */
+    let response = setup
+        .controller
+        .put(
+            &request("/calendars/work/other.ics").to_http_request(),
+            &event("todo-1", ""),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(read_body(response).await.contains("<C:no-uid-conflict>"));
+
+    let response = setup.controller.delete(&request(path).to_http_request()).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(setup.store.todos.lock().unwrap().is_empty());
+}
+
+#[actix_web::test]
+async fn test_put_journal() {
+    let setup = setup();
+    let path = "/calendars/work/notes.ics";
+    let journal = "BEGIN:VCALENDAR\r\n\
+                   VERSION:2.0\r\n\
+                   BEGIN:VJOURNAL\r\n\
+                   UID:notes\r\n\
+                   DTSTAMP:20210101T000000Z\r\n\
+                   DTSTART;VALUE=DATE:20210104\r\n\
+                   SUMMARY:Staff meeting minutes\r\n\
+                   DESCRIPTION:Budget approved\r\n\
+                   END:VJOURNAL\r\n\
+                   END:VCALENDAR\r\n";
+
+    let response = setup.controller.put(&request(path).to_http_request(), journal).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(setup.store.journals.lock().unwrap().len(), 1);
+
+    let response = setup.controller.get(&request(path).to_http_request()).await;
+    assert_eq!(
+        get_header(&response, "Content-Type").as_deref(),
+        Some("text/calendar; charset=utf-8; component=vjournal")
+    );
+    let body = read_body(response).await;
+    assert!(body.contains("DTSTART;VALUE=DATE:20210104\r\n"));
+    assert!(body.contains("DESCRIPTION:Budget approved\r\n"));
+
+    /* A single component type per resource */
+    let mixed = journal.replace(
+        "END:VCALENDAR",
+        "BEGIN:VTODO\r\nUID:notes\r\nDTSTAMP:20210101T000000Z\r\nEND:VTODO\r\nEND:VCALENDAR",
+    );
+    let response = setup.controller.put(&request(path).to_http_request(), &mixed).await;
+    assert!(read_body(response)
+        .await
+        .contains("<C:valid-calendar-object-resource/>"));
+
+    let response = setup.controller.delete(&request(path).to_http_request()).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(setup.store.journals.lock().unwrap().is_empty());
+}
+
+#[actix_web::test]
+async fn test_put_xcal() {
+    let setup = setup();
+    let path = "/calendars/work/event-1.ics";
+    let xcal = "<icalendar xmlns=\"urn:ietf:params:xml:ns:icalendar-2.0\"><vcalendar><components><vevent><properties>\
+                <uid><text>event-1</text></uid>\
+                <dtstamp><date-time>2021-01-01T00:00:00Z</date-time></dtstamp>\
+                <dtstart><date-time>2021-01-04T10:00:00Z</date-time></dtstart>\
+                <summary><text>Meeting</text></summary>\
+                </properties></vevent></components></vcalendar></icalendar>";
+
+    let request = TestRequest::default()
+        .uri(path)
+        .insert_header(("Content-Type", "application/calendar+xml; charset=utf-8"))
+        .to_http_request();
+    let response = setup.controller.put(&request, xcal).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let object = setup
+        .store
+        .get_object(setup.calendar_id, "event-1.ics")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(object.get_uid(), Some("event-1"));
+    assert_eq!(
+        object.get_events()[0].get_start(),
+        Utc.with_ymd_and_hms(2021, 1, 4, 10, 0, 0).unwrap()
+    );
+
+    let request = TestRequest::default()
+        .uri(path)
+        .insert_header(("Content-Type", "application/calendar+xml"))
+        .to_http_request();
+    let response = setup.controller.put(&request, &event("event-1", "")).await;
+    assert!(read_body(response).await.contains("<C:valid-calendar-data/>"));
+}
+
+#[actix_web::test]
+async fn test_put_sequence() {
+    let setup = setup();
+    let path = "/calendars/work/event-1.ics";
+    setup
+        .controller
+        .put(&request(path).to_http_request(), &event("event-1", "SEQUENCE:1\r\n"))
+        .await;
+
+    /* Rescheduling the event is a significant change */
+    let rescheduled =
+        event("event-1", "SEQUENCE:1\r\n").replace("DTSTART:20210104T100000Z", "DTSTART:20210105T100000Z");
+    setup
+        .controller
+        .put(&request(path).to_http_request(), &rescheduled)
+        .await;
+    let object = setup
+        .store
+        .get_object(setup.calendar_id, "event-1.ics")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(object.get_events()[0].get_version(), 2);
+}
+
+#[actix_web::test]
+async fn test_put_next_alarm() {
+    let setup = setup();
+    let body = event(
+        "event-1",
+        "RRULE:FREQ=DAILY\r\n\
+         BEGIN:VALARM\r\n\
+         ACTION:AUDIO\r\n\
+         TRIGGER:-PT10M\r\n\
+         END:VALARM\r\n",
+    );
+    setup
+        .controller
+        .put(&request("/calendars/work/event-1.ics").to_http_request(), &body)
+        .await;
+
+    /* The alarm scheduler loads the event from the time of its next alarm */
+    let object = setup
+        .store
+        .get_object(setup.calendar_id, "event-1.ics")
+        .await
+        .unwrap()
+        .unwrap();
+    let next_alarm_at = object.get_events()[0].get_next_alarm_at().unwrap();
+    assert!(next_alarm_at > Utc::now() && next_alarm_at <= Utc::now() + Duration::days(1));
+}
+
+#[actix_web::test]
+async fn test_put_preconditions() {
+    let setup = setup();
+    let put = |path: &str, body: String| {
+        let request = request(path).to_http_request();
+        let controller = &setup.controller;
+        async move { controller.put(&request, &body).await }
+    };
+    put("/calendars/work/event-1.ics", event("event-1", "")).await;
+
+    let response = put("/calendars/work/other.ics", event("event-1", "")).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(read_body(response)
+        .await
+        .contains("<C:no-uid-conflict><D:href>/calendars/work/event-1.ics</D:href></C:no-uid-conflict>"));
+
+    let response = put("/calendars/work/other.ics", "BEGIN:VCALENDAR".to_string()).await;
+    assert!(read_body(response).await.contains("<C:valid-calendar-data/>"));
+
+    /* More instances than the server expands, only unlimited recurrences being accepted */
+    for rrule in ["FREQ=DAILY;COUNT=10001", "FREQ=MINUTELY;UNTIL=20220101T000000Z"] {
+        let response = put(
+            "/calendars/work/other.ics",
+            event("event-2", &format!("RRULE:{}\r\n", rrule)),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert!(read_body(response).await.contains("<C:max-instances/>"));
+    }
+    let response = put("/calendars/work/other.ics", event("event-2", "RRULE:FREQ=MINUTELY\r\n")).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    setup
+        .controller
+        .delete(&request("/calendars/work/other.ics").to_http_request())
+        .await;
+
+    /* A rule the server cannot expand is refused rather than changed */
+    let response = put(
+        "/calendars/work/other.ics",
+        event("event-2", "RRULE:FREQ=YEARLY;BYWEEKNO=20;BYDAY=MO\r\n"),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(read_body(response).await.contains("<C:valid-calendar-data/>"));
+
+    let two_uids = event("event-2", "").replace(
+        "END:VCALENDAR",
+        "BEGIN:VEVENT\r\nUID:event-3\r\nDTSTAMP:20210101T000000Z\r\nDTSTART:20210104T100000Z\r\nEND:VEVENT\r\nEND:VCALENDAR",
+    );
+    let response = put("/calendars/work/other.ics", two_uids).await;
+    assert!(read_body(response)
+        .await
+        .contains("<C:valid-calendar-object-resource/>"));
+
+    let method = event("event-2", "").replace("VERSION:2.0\r\n", "VERSION:2.0\r\nMETHOD:REQUEST\r\n");
+    let response = put("/calendars/work/other.ics", method).await;
+    assert!(read_body(response)
+        .await
+        .contains("<C:valid-calendar-object-resource/>"));
+
+    let todo = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VTODO\r\nUID:todo-1\r\nDTSTAMP:20210101T000000Z\r\n\
+                END:VTODO\r\nEND:VCALENDAR\r\n";
+    let mixed = todo.replace(
+        "END:VCALENDAR",
+        "BEGIN:VEVENT\r\nUID:todo-1\r\nDTSTAMP:20210101T000000Z\r\nDTSTART:20210104T100000Z\r\nEND:VEVENT\r\nEND:VCALENDAR",
+    );
+    let response = put("/calendars/work/todo.ics", mixed).await;
+    assert!(read_body(response)
+        .await
+        .contains("<C:valid-calendar-object-resource/>"));
+    let events_only = {
+        let mut calendar = Calendar::new("events");
+        calendar.set_supported_components(vec!["VEVENT".to_string()]);
+        calendar
+    };
+    setup.store.add_calendar(events_only);
+    let response = put("/calendars/events/todo.ics", todo.to_string()).await;
+    assert!(read_body(response).await.contains("<C:supported-calendar-component/>"));
+
+    let response = setup
+        .controller
+        .put(
+            &TestRequest::default()
+                .uri("/calendars/work/other.ics")
+                .insert_header(("Content-Type", "application/json"))
+                .to_http_request(),
+            &event("event-2", ""),
+        )
+        .await;
+    assert!(read_body(response).await.contains("<C:supported-calendar-data/>"));
+
+    assert_eq!(
+        put("/calendars/unknown/event.ics", event("event-2", "")).await.status(),
+        StatusCode::CONFLICT
+    );
+    assert_eq!(
+        put("/calendars/work/", event("event-2", "")).await.status(),
+        StatusCode::METHOD_NOT_ALLOWED
+    );
+    assert_eq!(setup.store.events.lock().unwrap().len(), 1);
+}
+
+#[actix_web::test]
+async fn test_inline_attachments() {
+    let setup = setup();
+    let path = "/calendars/work/event-1.ics";
+
+    /* "Hello world" */
+    let attachment = "ATTACH;FMTTYPE=text/plain;ENCODING=BASE64;VALUE=BINARY:SGVsbG8gd29ybGQ=\r\n";
+    let response = setup
+        .controller
+        .put(&request(path).to_http_request(), &event("event-1", attachment))
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(setup.blobs.blobs.lock().unwrap().len(), 1);
+
+    let body = read_body(setup.controller.get(&request(path).to_http_request()).await).await;
+    assert!(body.contains(":SGVsbG8gd29ybGQ=\r\n"));
+
+    /* Larger than the maximum of 16 bytes */
+    let attachment = "ATTACH;ENCODING=BASE64;VALUE=BINARY:SGVsbG8gd29ybGQsIEhlbGxvIHdvcmxk\r\n";
+    let response = setup
+        .controller
+        .put(&request(path).to_http_request(), &event("event-1", attachment))
+        .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(read_body(response).await.contains("<C:max-attachment-size/>"));
+
+    let response = setup.controller.delete(&request(path).to_http_request()).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(setup.blobs.blobs.lock().unwrap().is_empty());
+    assert!(setup.store.events.lock().unwrap().is_empty());
+
+    let response = setup.controller.delete(&request(path).to_http_request()).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn test_managed_attachment_post() {
+    let setup = setup();
+    let path = "/calendars/work/event-1.ics";
+    let response = setup
+        .controller
+        .put(&request(path).to_http_request(), &event("event-1", ""))
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let etag = get_header(&setup.controller.get(&request(path).to_http_request()).await, "ETag");
+
+    let query = ManagedAttachmentQuery {
+        action: "attachment-add".to_string(),
+        managed_id: None,
+        rid: None,
+    };
+    let post = TestRequest::post()
+        .uri(path)
+        .insert_header(("Content-Type", "text/plain"))
+        .to_http_request();
+    let response = setup.controller.post(&post, &query, b"Hello").await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_ne!(get_header(&response, "ETag"), etag);
+
+    /* Unfolded content lines */
+    let body = read_body(setup.controller.get(&request(path).to_http_request()).await)
+        .await
+        .replace("\r\n ", "");
+    assert!(body.contains("ATTACH;"));
+    assert!(body.contains("https://cal.example.com/attachments/"));
+
+    setup.controller.delete(&request(path).to_http_request()).await;
+    assert!(setup.blobs.blobs.lock().unwrap().is_empty());
+}
@@ -0,0 +1,586 @@
+mod common;
+use actix_web::{http::StatusCode, test::TestRequest, HttpResponse};
+use cal_dav_fast::{
+    app::{
+        calendar::models::calendar::Calendar,
+        dav::calendar_filter::{Collation, FilterError, TextMatch, TimeRange},
+    },
+    architecture::xml::{XmlElement, CALDAV_NAMESPACE, DAV_NAMESPACE},
+    controllers::dav_controller::DavController,
+};
+use chrono::{TimeZone, Utc};
+use common::{read_body, setup_calendar};
+use pretty_assertions::assert_eq;
+
+const MEETING: &str = "BEGIN:VCALENDAR\r\n\
+                       VERSION:2.0\r\n\
+                       BEGIN:VEVENT\r\n\
+                       UID:meeting\r\n\
+                       DTSTAMP:20210101T000000Z\r\n\
+                       DTSTART:20210104T100000Z\r\n\
+                       DTEND:20210104T110000Z\r\n\
+                       SUMMARY:Budget meeting\r\n\
+                       ATTENDEE;PARTSTAT=ACCEPTED:mailto:lisa@example.com\r\n\
+                       BEGIN:VALARM\r\n\
+                       ACTION:DISPLAY\r\n\
+                       DESCRIPTION:Reminder\r\n\
+                       TRIGGER:-PT15M\r\n\
+                       END:VALARM\r\n\
+                       END:VEVENT\r\n\
+                       END:VCALENDAR\r\n";
+
+const WEEKLY: &str = "BEGIN:VCALENDAR\r\n\
+                      VERSION:2.0\r\n\
+                      BEGIN:VEVENT\r\n\
+                      UID:weekly\r\n\
+                      DTSTAMP:20210101T000000Z\r\n\
+                      DTSTART:20210101T090000Z\r\n\
+                      DURATION:PT30M\r\n\
+                      SUMMARY:Weekly review\r\n\
+                      LOCATION:Room 1\r\n\
+                      RRULE:FREQ=WEEKLY;COUNT=10\r\n\
+                      END:VEVENT\r\n\
+                      BEGIN:VEVENT\r\n\
+                      UID:weekly\r\n\
+                      DTSTAMP:20210101T000000Z\r\n\
+                      RECURRENCE-ID:20210115T090000Z\r\n\
+                      DTSTART:20210220T090000Z\r\n\
+                      DURATION:PT30M\r\n\
+                      SUMMARY:Weekly review (moved)\r\n\
+                      END:VEVENT\r\n\
+                      END:VCALENDAR\r\n";
+
+const HOLIDAY: &str = "BEGIN:VCALENDAR\r\n\
+                       VERSION:2.0\r\n\
+                       BEGIN:VEVENT\r\n\
+                       UID:holiday\r\n\
+                       DTSTAMP:20210101T000000Z\r\n\
+                       DTSTART;VALUE=DATE:20210714\r\n\
+                       SUMMARY:Fête nationale\r\n\
+                       CATEGORIES:HOLIDAY,FRANCE\r\n\
+                       END:VEVENT\r\n\
+                       END:VCALENDAR\r\n";
+
+const TODOS: [(&str, &str); 4] = [
+    (
+        "report.ics",
+        "BEGIN:VCALENDAR\r\n\
+         VERSION:2.0\r\n\
+         BEGIN:VTODO\r\n\
+         UID:report\r\n\
+         DTSTAMP:20210101T000000Z\r\n\
+         DTSTART:20210104T100000Z\r\n\
+         DUE:20210108T170000Z\r\n\
+         SUMMARY:Quarterly report\r\n\
+         BEGIN:VALARM\r\n\
+         ACTION:DISPLAY\r\n\
+         DESCRIPTION:Reminder\r\n\
+         TRIGGER:-PT1H\r\n\
+         END:VALARM\r\n\
+         END:VTODO\r\n\
+         END:VCALENDAR\r\n",
+    ),
+    (
+        "chores.ics",
+        "BEGIN:VCALENDAR\r\n\
+         VERSION:2.0\r\n\
+         BEGIN:VTODO\r\n\
+         UID:chores\r\n\
+         DTSTAMP:20210101T000000Z\r\n\
+         DTSTART:20210201T080000Z\r\n\
+         DURATION:PT1H\r\n\
+         RRULE:FREQ=WEEKLY;COUNT=4\r\n\
+         END:VTODO\r\n\
+         END:VCALENDAR\r\n",
+    ),
+    (
+        "deadline.ics",
+        "BEGIN:VCALENDAR\r\n\
+         VERSION:2.0\r\n\
+         BEGIN:VTODO\r\n\
+         UID:deadline\r\n\
+         DTSTAMP:20210101T000000Z\r\n\
+         DUE:20210301T120000Z\r\n\
+         END:VTODO\r\n\
+         END:VCALENDAR\r\n",
+    ),
+    (
+        "someday.ics",
+        "BEGIN:VCALENDAR\r\n\
+         VERSION:2.0\r\n\
+         BEGIN:VTODO\r\n\
+         UID:someday\r\n\
+         DTSTAMP:20210101T000000Z\r\n\
+         CREATED:20210110T000000Z\r\n\
+         END:VTODO\r\n\
+         END:VCALENDAR\r\n",
+    ),
+];
+
+const JOURNALS: [(&str, &str); 3] = [
+    (
+        "minutes.ics",
+        "BEGIN:VCALENDAR\r\n\
+         VERSION:2.0\r\n\
+         BEGIN:VJOURNAL\r\n\
+         UID:minutes\r\n\
+         DTSTAMP:20210101T000000Z\r\n\
+         DTSTART:20210104T100000Z\r\n\
+         SUMMARY:Staff meeting minutes\r\n\
+         END:VJOURNAL\r\n\
+         END:VCALENDAR\r\n",
+    ),
+    (
+        "diary.ics",
+        "BEGIN:VCALENDAR\r\n\
+         VERSION:2.0\r\n\
+         BEGIN:VJOURNAL\r\n\
+         UID:diary\r\n\
+         DTSTAMP:20210101T000000Z\r\n\
+         DTSTART;VALUE=DATE:20210201\r\n\
+         RRULE:FREQ=DAILY;COUNT=3\r\n\
+         END:VJOURNAL\r\n\
+         END:VCALENDAR\r\n",
+    ),
+    (
+        "ideas.ics",
+        "BEGIN:VCALENDAR\r\n\
+         VERSION:2.0\r\n\
+         BEGIN:VJOURNAL\r\n\
+         UID:ideas\r\n\
+         DTSTAMP:20210101T000000Z\r\n\
+         END:VJOURNAL\r\n\
+         END:VCALENDAR\r\n",
+    ),
+];
+
+fn controller() -> DavController {
+    let objects = [
+        ("meeting.ics", MEETING),
+        ("weekly.ics", WEEKLY),
+        ("holiday.ics", HOLIDAY),
+    ];
+    setup_calendar(Calendar::new("work"), &objects).1
+}
+
+async fn report(path: &str, depth: &str, body: &str) -> HttpResponse {
+    let request = TestRequest::default()
+        .uri(path)
+        .insert_header(("Depth", depth))
+        .to_http_request();
+    controller().report(&request, body).await
+}
+
+fn query_body(filter: &str) -> String {
+    format!(
+        "<C:calendar-query xmlns:D=\"DAV:\" xmlns:C=\"urn:ietf:params:xml:ns:caldav\">\
+         <D:prop><D:getetag/></D:prop>\
+         <C:filter><C:comp-filter name=\"VCALENDAR\">{}</C:comp-filter></C:filter>\
+         </C:calendar-query>",
+        filter
+    )
+}
+
+async fn query(filter: &str) -> Vec<String> {
+    query_in(controller(), filter).await
+}
+
+async fn query_in(controller: DavController, filter: &str) -> Vec<String> {
+    /* Hrefs of the matching resources */
+    let request = TestRequest::default()
+        .uri("/calendars/work/")
+        .insert_header(("Depth", "1"))
+        .to_http_request();
+    let response = controller.report(&request, &query_body(filter)).await;
+    assert_eq!(response.status(), StatusCode::MULTI_STATUS);
+    let root = XmlElement::parse(&read_body(response).await).unwrap();
+    let mut hrefs: Vec<String> = root
+        .children_named(DAV_NAMESPACE, "response")
+        .map(|response| response.child(DAV_NAMESPACE, "href").unwrap().text.clone())
+        .collect();
+    hrefs.sort();
+    hrefs
+}
+
+fn hrefs(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| format!("/calendars/work/{}", name)).collect()
+}
+
+fn event_range(start: &str, end: &str) -> String {
+    format!(
+        "<C:comp-filter name=\"VEVENT\"><C:time-range start=\"{}\" end=\"{}\"/></C:comp-filter>",
+        start, end
+    )
+}
+
+#[test]
+fn test_time_range() {
+    let parse = |xml: &str| TimeRange::parse(&XmlElement::parse(xml).unwrap());
+    let range = parse("<time-range start=\"20210104T000000Z\" end=\"20210105T000000Z\"/>").unwrap();
+    let date = |day, hour| Utc.with_ymd_and_hms(2021, 1, day, hour, 0, 0).unwrap();
+
+    assert!(range.overlaps(date(3, 23), date(4, 1)));
+    assert!(!range.overlaps(date(3, 22), date(4, 0)));
+    assert!(range.overlaps(date(4, 0), date(4, 0)));
+    assert!(!range.overlaps(date(5, 0), date(5, 0)));
+    assert!(range.contains(date(4, 23)));
+
+    let open = parse("<time-range start=\"20210104T000000Z\"/>").unwrap();
+    assert!(open.overlaps(date(30, 0), date(30, 1)));
+
+    assert!(parse("<time-range/>").is_err());
+    assert!(parse("<time-range start=\"20210104T000000\"/>").is_err());
+    assert!(parse("<time-range start=\"20210105T000000Z\" end=\"20210104T000000Z\"/>").is_err());
+}
+
+#[test]
+fn test_text_match() {
+    let parse = |xml: &str| TextMatch::parse(&XmlElement::parse(xml).unwrap());
+
+    let ascii = parse("<text-match>MEET</text-match>").unwrap();
+    assert_eq!(ascii.collation, Collation::AsciiCasemap);
+    assert!(ascii.matches("Budget meeting"));
+    assert!(!ascii.matches("Review"));
+
+    let octet = parse("<text-match collation=\"i;octet\">MEET</text-match>").unwrap();
+    assert!(!octet.matches("Budget meeting"));
+
+    let unicode = parse("<text-match collation=\"i;unicode-casemap\">FÊTE</text-match>").unwrap();
+    assert!(unicode.matches("Fête nationale"));
+    assert!(!parse("<text-match>FÊTE</text-match>")
+        .unwrap()
+        .matches("Fête nationale"));
+
+    let negated = parse("<text-match negate-condition=\"yes\">meeting</text-match>").unwrap();
+    assert!(!negated.matches("Budget meeting"));
+    assert!(negated.matches("Review"));
+
+    assert_eq!(
+        parse("<text-match collation=\"i;basic\">a</text-match>").unwrap_err(),
+        FilterError::UnsupportedCollation("i;basic".to_string())
+    );
+}
+
+#[actix_web::test]
+async fn test_calendar_query_time_range() {
+    assert_eq!(
+        query(&event_range("20210104T090000Z", "20210104T120000Z")).await,
+        hrefs(&["meeting.ics"])
+    );
+    /* Instances of the recurring event */
+    assert_eq!(
+        query(&event_range("20210122T000000Z", "20210123T000000Z")).await,
+        hrefs(&["weekly.ics"])
+    );
+    assert!(query(&event_range("20210601T000000Z", "20210602T000000Z"))
+        .await
+        .is_empty());
+
+    /* The overridden instance is matched by the component overriding it */
+    assert!(query(&event_range("20210115T000000Z", "20210116T000000Z"))
+        .await
+        .is_empty());
+    assert_eq!(
+        query(&event_range("20210220T000000Z", "20210221T000000Z")).await,
+        hrefs(&["weekly.ics"])
+    );
+
+    /* All day events last the whole day */
+    assert_eq!(
+        query(&event_range("20210714T120000Z", "20210714T130000Z")).await,
+        hrefs(&["holiday.ics"])
+    );
+    assert_eq!(
+        query("<C:comp-filter name=\"VEVENT\"><C:time-range start=\"20210401T000000Z\"/></C:comp-filter>").await,
+        hrefs(&["holiday.ics"])
+    );
+}
+
+#[actix_web::test]
+async fn test_calendar_query_todo_time_range() {
+    let query = |start: &str, end: &str| {
+        let filter = format!(
+            "<C:comp-filter name=\"VTODO\"><C:time-range start=\"{}\" end=\"{}\"/></C:comp-filter>",
+            start, end
+        );
+        async move { query_in(setup_calendar(Calendar::new("work"), &TODOS).1, &filter).await }
+    };
+
+    /* Between DTSTART and DUE */
+    assert_eq!(
+        query("20210105T000000Z", "20210106T000000Z").await,
+        hrefs(&["report.ics"])
+    );
+    /* An instance of the recurring to-do, and the to-do without dates created before the end */
+    assert_eq!(
+        query("20210208T000000Z", "20210209T000000Z").await,
+        hrefs(&["chores.ics", "someday.ics"])
+    );
+    /* A to-do with a DUE only matches ranges holding it, the range start excluded */
+    assert_eq!(
+        query("20210301T110000Z", "20210301T130000Z").await,
+        hrefs(&["deadline.ics", "someday.ics"])
+    );
+    assert_eq!(
+        query("20210301T120000Z", "20210302T000000Z").await,
+        hrefs(&["someday.ics"])
+    );
+    assert!(query("20201201T000000Z", "20201202T000000Z").await.is_empty());
+
+    /* Alarms of the to-dos */
+    let alarm_filter = "<C:comp-filter name=\"VTODO\"><C:comp-filter name=\"VALARM\">\
+                        <C:time-range start=\"20210104T085000Z\" end=\"20210104T091000Z\"/>\
+                        </C:comp-filter></C:comp-filter>";
+    assert_eq!(
+        query_in(setup_calendar(Calendar::new("work"), &TODOS).1, alarm_filter).await,
+        hrefs(&["report.ics"])
+    );
+}
+
+#[actix_web::test]
+async fn test_calendar_query_journal_time_range() {
+    let query = |start: &str, end: &str| {
+        let filter = format!(
+            "<C:comp-filter name=\"VJOURNAL\"><C:time-range start=\"{}\" end=\"{}\"/></C:comp-filter>",
+            start, end
+        );
+        async move { query_in(setup_calendar(Calendar::new("work"), &JOURNALS).1, &filter).await }
+    };
+
+    /* A date-time start within the range, the range end excluded */
+    assert_eq!(
+        query("20210104T100000Z", "20210104T110000Z").await,
+        hrefs(&["minutes.ics"])
+    );
+    assert!(query("20210104T090000Z", "20210104T100000Z").await.is_empty());
+    /* A date start lasts its whole day, for each instance */
+    assert_eq!(
+        query("20210203T120000Z", "20210203T130000Z").await,
+        hrefs(&["diary.ics"])
+    );
+    assert!(query("20210204T000000Z", "20210205T000000Z").await.is_empty());
+
+    /* Without DTSTART, only matched without time range */
+    let all = query_in(
+        setup_calendar(Calendar::new("work"), &JOURNALS).1,
+        "<C:comp-filter name=\"VJOURNAL\"/>",
+    )
+    .await;
+    assert_eq!(all, hrefs(&["diary.ics", "ideas.ics", "minutes.ics"]));
+}
+
+#[actix_web::test]
+async fn test_calendar_query_far_from_start() {
+    /* Over a thousand instances before the range */
+    let standup = "BEGIN:VCALENDAR\r\n\
+         VERSION:2.0\r\n\
+         BEGIN:VEVENT\r\n\
+         UID:standup\r\n\
+         DTSTAMP:20200101T000000Z\r\n\
+         DTSTART:20200101T090000Z\r\n\
+         DURATION:PT15M\r\n\
+         SUMMARY:Standup\r\n\
+         RRULE:FREQ=DAILY\r\n\
+         END:VEVENT\r\n\
+         END:VCALENDAR\r\n";
+    let (_, controller) = setup_calendar(Calendar::new("work"), &[("standup.ics", standup)]);
+    let query = |filter: String| {
+        let request = TestRequest::default()
+            .uri("/calendars/work/")
+            .insert_header(("Depth", "1"))
+            .to_http_request();
+        let controller = &controller;
+        async move { read_body(controller.report(&request, &query_body(&filter)).await).await }
+    };
+
+    let body = query(event_range("20261019T090500Z", "20261019T091000Z")).await;
+    assert!(body.contains("/calendars/work/standup.ics"));
+    let body = query(event_range("20261019T100000Z", "20261019T110000Z")).await;
+    assert!(!body.contains("/calendars/work/standup.ics"));
+}
+
+#[actix_web::test]
+async fn test_calendar_query_property_time_range_with_tzid() {
+    /* 22:00 in New York is 03:00Z the next day */
+    let call = "BEGIN:VCALENDAR\r\n\
+         VERSION:2.0\r\n\
+         BEGIN:VEVENT\r\n\
+         UID:call\r\n\
+         DTSTAMP:20210101T000000Z\r\n\
+         DTSTART;TZID=America/New_York:20210104T220000\r\n\
+         SUMMARY:Call\r\n\
+         END:VEVENT\r\n\
+         END:VCALENDAR\r\n";
+    let (_, controller) = setup_calendar(Calendar::new("work"), &[("call.ics", call)]);
+    let query = |start: &str, end: &str| {
+        let request = TestRequest::default()
+            .uri("/calendars/work/")
+            .insert_header(("Depth", "1"))
+            .to_http_request();
+        let body = query_body(&format!(
+            "<C:comp-filter name=\"VEVENT\"><C:prop-filter name=\"DTSTART\">\
+             <C:time-range start=\"{}\" end=\"{}\"/></C:prop-filter></C:comp-filter>",
+            start, end
+        ));
+        let controller = &controller;
+        async move { read_body(controller.report(&request, &body).await).await }
+    };
+
+    assert!(query("20210105T000000Z", "20210105T060000Z")
+        .await
+        .contains("/calendars/work/call.ics"));
+    assert!(!query("20210104T200000Z", "20210105T000000Z")
+        .await
+        .contains("/calendars/work/call.ics"));
+}
+
+#[actix_web::test]
+async fn test_calendar_query_properties() {
+    assert_eq!(query("").await, hrefs(&["holiday.ics", "meeting.ics", "weekly.ics"]));
+    assert_eq!(
+        query(
+            "<C:comp-filter name=\"VEVENT\"><C:prop-filter name=\"SUMMARY\">\
+             <C:text-match>REVIEW</C:text-match></C:prop-filter></C:comp-filter>"
+        )
+        .await,
+        hrefs(&["weekly.ics"])
+    );
+    assert_eq!(
+        query(
+            "<C:comp-filter name=\"VEVENT\"><C:prop-filter name=\"LOCATION\">\
+             <C:is-not-defined/></C:prop-filter></C:comp-filter>"
+        )
+        .await,
+        hrefs(&["holiday.ics", "meeting.ics", "weekly.ics"])
+    );
+    assert_eq!(
+        query(
+            "<C:comp-filter name=\"VEVENT\"><C:prop-filter name=\"CATEGORIES\">\
+             <C:text-match>france</C:text-match></C:prop-filter></C:comp-filter>"
+        )
+        .await,
+        hrefs(&["holiday.ics"])
+    );
+    assert_eq!(
+        query(
+            "<C:comp-filter name=\"VEVENT\"><C:prop-filter name=\"ATTENDEE\">\
+             <C:param-filter name=\"PARTSTAT\"><C:text-match>ACCEPTED</C:text-match></C:param-filter>\
+             </C:prop-filter></C:comp-filter>"
+        )
+        .await,
+        hrefs(&["meeting.ics"])
+    );
+    assert_eq!(
+        query(
+            "<C:comp-filter name=\"VEVENT\"><C:prop-filter name=\"DTSTART\">\
+             <C:time-range start=\"20210701T000000Z\" end=\"20210801T000000Z\"/></C:prop-filter></C:comp-filter>"
+        )
+        .await,
+        hrefs(&["holiday.ics"])
+    );
+    assert!(query("<C:comp-filter name=\"VTODO\"/>").await.is_empty());
+    assert_eq!(
+        query("<C:comp-filter name=\"VTODO\"><C:is-not-defined/></C:comp-filter>")
+            .await
+            .len(),
+        3
+    );
+}
+
+#[actix_web::test]
+async fn test_calendar_query_alarms() {
+    let alarm_range = |start: &str, end: &str| {
+        format!(
+            "<C:comp-filter name=\"VEVENT\"><C:comp-filter name=\"VALARM\">\
+             <C:time-range start=\"{}\" end=\"{}\"/></C:comp-filter></C:comp-filter>",
+            start, end
+        )
+    };
+    assert_eq!(
+        query(&alarm_range("20210104T093000Z", "20210104T095000Z")).await,
+        hrefs(&["meeting.ics"])
+    );
+    assert!(query(&alarm_range("20210104T095000Z", "20210104T120000Z"))
+        .await
+        .is_empty());
+}
+
+#[actix_web::test]
+async fn test_calendar_query_response() {
+    let body = "<C:calendar-query xmlns:D=\"DAV:\" xmlns:C=\"urn:ietf:params:xml:ns:caldav\">\
+                <D:prop><D:getetag/><C:calendar-data/></D:prop>\
+                <C:filter><C:comp-filter name=\"VCALENDAR\"/></C:filter></C:calendar-query>";
+
+    let response = report("/calendars/work/meeting.ics", "0", body).await;
+    assert_eq!(response.status(), StatusCode::MULTI_STATUS);
+    let root = XmlElement::parse(&read_body(response).await).unwrap();
+    let responses: Vec<_> = root.children_named(DAV_NAMESPACE, "response").collect();
+    assert_eq!(responses.len(), 1);
+    let prop = responses[0]
+        .child(DAV_NAMESPACE, "propstat")
+        .unwrap()
+        .child(DAV_NAMESPACE, "prop")
+        .unwrap();
+    assert!(prop.child(DAV_NAMESPACE, "getetag").unwrap().text.starts_with('"'));
+    assert!(prop
+        .child(CALDAV_NAMESPACE, "calendar-data")
+        .unwrap()
+        .text
+        .contains("UID:meeting"));
+
+    /* The collection itself is not a calendar object resource */
+    let response = report("/calendars/work/", "0", body).await;
+    assert!(get_responses(response).await.is_empty());
+    let response = report("/calendars/", "infinity", body).await;
+    assert_eq!(get_responses(response).await.len(), 3);
+
+    assert_eq!(
+        report("/calendars/home/", "1", body).await.status(),
+        StatusCode::NOT_FOUND
+    );
+}
+
+async fn get_responses(response: HttpResponse) -> Vec<XmlElement> {
+    let root = XmlElement::parse(&read_body(response).await).unwrap();
+    root.children_named(DAV_NAMESPACE, "response").cloned().collect()
+}
+
+#[actix_web::test]
+async fn test_calendar_query_errors() {
+    let response = report(
+        "/calendars/work/",
+        "1",
+        &query_body(
+            "<C:comp-filter name=\"VEVENT\"><C:prop-filter name=\"SUMMARY\">\
+                     <C:text-match collation=\"i;basic\">a</C:text-match></C:prop-filter></C:comp-filter>",
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(read_body(response).await.contains("<C:supported-collation/>"));
+
+    let response = report(
+        "/calendars/work/",
+        "1",
+        &query_body("<C:comp-filter name=\"VEVENT\"><C:time-range/></C:comp-filter>"),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(read_body(response).await.contains("<C:valid-filter/>"));
+
+    let body = "<C:calendar-query xmlns:C=\"urn:ietf:params:xml:ns:caldav\"><C:filter>\
+                <C:comp-filter name=\"VEVENT\"/></C:filter></C:calendar-query>";
+    assert_eq!(
+        report("/calendars/work/", "1", body).await.status(),
+        StatusCode::FORBIDDEN
+    );
+
+    let response = report("/calendars/work/", "1", "<D:expand-property xmlns:D=\"DAV:\"/>").await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(read_body(response).await.contains("<D:supported-report/>"));
+
+    assert_eq!(
+        report("/calendars/work/", "1", "<C:calendar-query").await.status(),
+        StatusCode::BAD_REQUEST
+    );
+}
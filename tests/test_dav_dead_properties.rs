@@ -0,0 +1,176 @@
+mod common;
+use std::sync::Arc;
+
+use actix_web::{http::StatusCode, test::TestRequest, HttpResponse};
+use cal_dav_fast::{
+    app::calendar::{
+        calendar_store::CalendarStore,
+        models::{calendar::Calendar, dead_property::DeadProperty},
+    },
+    architecture::xml::{XmlElement, DAV_NAMESPACE},
+    controllers::dav_controller::DavController,
+};
+use common::{event, object_controller, read_body, setup_calendar, MemoryBlobStore, MemoryCalendarStore};
+use pretty_assertions::assert_eq;
+
+const APPLE_NAMESPACE: &str = "http://apple.com/ns/ical/";
+const MOZILLA_NAMESPACE: &str = "http://mozilla.org/ns/";
+
+fn setup() -> (Arc<MemoryCalendarStore>, DavController) {
+    setup_calendar(
+        Calendar::new("work"),
+        &[("event-1.ics", &event("event-1", "SUMMARY:Meeting\r\n"))],
+    )
+}
+
+async fn proppatch(controller: &DavController, path: &str, instructions: &str) -> HttpResponse {
+    let request = TestRequest::default().uri(path).to_http_request();
+    let body = format!(
+        "<D:propertyupdate xmlns:D=\"DAV:\" xmlns:M=\"http://mozilla.org/ns/\" \
+         xmlns:A=\"http://apple.com/ns/ical/\">{}</D:propertyupdate>",
+        instructions
+    );
+    controller.proppatch(&request, &body).await
+}
+
+async fn propfind(controller: &DavController, path: &str, body: &str) -> Vec<XmlElement> {
+    /* Properties found */
+    let request = TestRequest::default()
+        .uri(path)
+        .insert_header(("Depth", "0"))
+        .to_http_request();
+    let root = XmlElement::parse(&read_body(controller.propfind(&request, body).await).await).unwrap();
+    let properties = root
+        .child(DAV_NAMESPACE, "response")
+        .unwrap()
+        .children_named(DAV_NAMESPACE, "propstat")
+        .find(|propstat| propstat.child(DAV_NAMESPACE, "status").unwrap().text == "HTTP/1.1 200 OK")
+        .map(|propstat| propstat.child(DAV_NAMESPACE, "prop").unwrap().children.clone())
+        .unwrap_or_default();
+    properties
+}
+
+fn find<'a>(properties: &'a [XmlElement], namespace: &str, name: &str) -> Option<&'a XmlElement> {
+    properties.iter().find(|property| property.is(namespace, name))
+}
+
+#[test]
+fn test_dead_property_value() {
+    let element = XmlElement::parse(
+        "<M:alarms xmlns:M=\"http://mozilla.org/ns/\" xmlns:X=\"http://example.com/\" enabled=\"true\">\
+         <M:alarm>PT15M</M:alarm><X:other>value</X:other></M:alarms>",
+    )
+    .unwrap();
+
+    let property = DeadProperty::from_xml(&element);
+    assert!(property.is(MOZILLA_NAMESPACE, "alarms"));
+    assert_eq!(property.to_xml(), Some(element));
+}
+
+#[actix_web::test]
+async fn test_calendar_dead_properties() {
+    let (store, controller) = setup();
+    let response = proppatch(
+        &controller,
+        "/calendars/work/",
+        "<D:set><D:prop><A:calendar-order>3</A:calendar-order>\
+         <M:settings><M:cache enabled=\"true\"/></M:settings></D:prop></D:set>",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::MULTI_STATUS);
+    let calendar = store.get_calendar("work").await.unwrap().unwrap();
+    assert!(calendar
+        .get_dead_properties()
+        .get(APPLE_NAMESPACE, "calendar-order")
+        .is_some());
+
+    /* Returned by allprop and propname requests */
+    let properties = propfind(&controller, "/calendars/work/", "").await;
+    assert_eq!(find(&properties, APPLE_NAMESPACE, "calendar-order").unwrap().text, "3");
+    let settings = find(&properties, MOZILLA_NAMESPACE, "settings").unwrap();
+    assert_eq!(settings.children[0].get_attribute("enabled"), Some("true"));
+
+    let names = propfind(
+        &controller,
+        "/calendars/work/",
+        "<D:propfind xmlns:D=\"DAV:\"><D:propname/></D:propfind>",
+    )
+    .await;
+    assert!(find(&names, APPLE_NAMESPACE, "calendar-order").is_some_and(|name| name.text.is_empty()));
+    assert!(find(&names, DAV_NAMESPACE, "displayname").is_some());
+
+    /* Replaced, then removed */
+    proppatch(
+        &controller,
+        "/calendars/work/",
+        "<D:set><D:prop><A:calendar-order>1</A:calendar-order></D:prop></D:set>",
+    )
+    .await;
+    let properties = propfind(&controller, "/calendars/work/", "").await;
+    assert_eq!(find(&properties, APPLE_NAMESPACE, "calendar-order").unwrap().text, "1");
+
+    proppatch(
+        &controller,
+        "/calendars/work/",
+        "<D:remove><D:prop><A:calendar-order/><M:settings/></D:prop></D:remove>",
+    )
+    .await;
+    let calendar = store.get_calendar("work").await.unwrap().unwrap();
+    assert!(calendar.get_dead_properties().is_empty());
+}
+
+#[actix_web::test]
+async fn test_resource_dead_properties() {
+    let (store, controller) = setup();
+    let path = "/calendars/work/event-1.ics";
+    let etag_body = "<D:propfind xmlns:D=\"DAV:\"><D:prop><D:getetag/></D:prop></D:propfind>";
+    let etag = propfind(&controller, path, etag_body).await[0].text.clone();
+
+    let response = proppatch(
+        &controller,
+        path,
+        "<D:set><D:prop><M:snooze-time>20210104T094500Z</M:snooze-time></D:prop></D:set>",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::MULTI_STATUS);
+    assert!(read_body(response).await.contains("HTTP/1.1 200 OK"));
+
+    let properties = propfind(&controller, path, "").await;
+    assert_eq!(
+        find(&properties, MOZILLA_NAMESPACE, "snooze-time").unwrap().text,
+        "20210104T094500Z"
+    );
+    /* The content, and so the entity tag, did not change */
+    assert_eq!(find(&properties, DAV_NAMESPACE, "getetag").unwrap().text, etag);
+
+    /* Kept when the content changes */
+    let objects = object_controller(store.clone(), Arc::new(MemoryBlobStore::default()), 1024);
+    let request = TestRequest::default().uri(path).to_http_request();
+    assert_eq!(
+        objects
+            .put(&request, &event("event-1", "SUMMARY:Team meeting\r\n"))
+            .await
+            .status(),
+        StatusCode::NO_CONTENT
+    );
+    let properties = propfind(&controller, path, "").await;
+    assert!(find(&properties, MOZILLA_NAMESPACE, "snooze-time").is_some());
+
+    proppatch(
+        &controller,
+        path,
+        "<D:remove><D:prop><M:snooze-time/></D:prop></D:remove>",
+    )
+    .await;
+    let properties = propfind(&controller, path, "").await;
+    assert!(find(&properties, MOZILLA_NAMESPACE, "snooze-time").is_none());
+
+    /* Nothing is stored on the root collection */
+    let response = proppatch(
+        &controller,
+        "/calendars/",
+        "<D:set><D:prop><M:snooze-time>20210104T094500Z</M:snooze-time></D:prop></D:set>",
+    )
+    .await;
+    assert!(read_body(response).await.contains("HTTP/1.1 403 Forbidden"));
+}
@@ -0,0 +1,236 @@
+mod common;
+use actix_web::{http::StatusCode, test::TestRequest, HttpResponse};
+use cal_dav_fast::{
+    app::{
+        calendar::{ical::ics, models::calendar::Calendar},
+        dav::free_busy::{FreeBusy, FreeBusyPeriod, FreeBusyType},
+    },
+    controllers::dav_controller::DavController,
+};
+use chrono::{DateTime, TimeZone, Utc};
+use common::{read_body, setup_calendar};
+use pretty_assertions::assert_eq;
+
+fn event(uid: &str, start: &str, end: &str, extra: &str) -> String {
+    format!(
+        "BEGIN:VCALENDAR\r\n\
+         VERSION:2.0\r\n\
+         BEGIN:VEVENT\r\n\
+         UID:{}\r\n\
+         DTSTAMP:20210101T000000Z\r\n\
+         DTSTART:{}\r\n\
+         DTEND:{}\r\n\
+         {}\
+         END:VEVENT\r\n\
+         END:VCALENDAR\r\n",
+        uid, start, end, extra
+    )
+}
+
+const DAILY: &str = "BEGIN:VCALENDAR\r\n\
+                     VERSION:2.0\r\n\
+                     BEGIN:VEVENT\r\n\
+                     UID:daily\r\n\
+                     DTSTAMP:20210101T000000Z\r\n\
+                     DTSTART:20210104T080000Z\r\n\
+                     DURATION:PT30M\r\n\
+                     RRULE:FREQ=DAILY;COUNT=3\r\n\
+                     END:VEVENT\r\n\
+                     BEGIN:VEVENT\r\n\
+                     UID:daily\r\n\
+                     DTSTAMP:20210101T000000Z\r\n\
+                     RECURRENCE-ID:20210105T080000Z\r\n\
+                     DTSTART:20210105T180000Z\r\n\
+                     DURATION:PT30M\r\n\
+                     STATUS:TENTATIVE\r\n\
+                     END:VEVENT\r\n\
+                     END:VCALENDAR\r\n";
+
+fn controller() -> DavController {
+    let objects = [
+        (
+            "a.ics",
+            event("a", "20210104T090000Z", "20210104T100000Z", "STATUS:CONFIRMED\r\n"),
+        ),
+        ("b.ics", event("b", "20210104T100000Z", "20210104T103000Z", "")),
+        (
+            "c.ics",
+            event("c", "20210104T101500Z", "20210104T120000Z", "STATUS:TENTATIVE\r\n"),
+        ),
+        (
+            "d.ics",
+            event("d", "20210104T130000Z", "20210104T140000Z", "TRANSP:TRANSPARENT\r\n"),
+        ),
+        (
+            "e.ics",
+            event("e", "20210104T150000Z", "20210104T160000Z", "STATUS:CANCELLED\r\n"),
+        ),
+        ("f.ics", event("f", "20210104T170000Z", "20210104T170000Z", "")),
+        ("g.ics", event("g", "20210103T220000Z", "20210104T010000Z", "")),
+        ("daily.ics", DAILY.to_string()),
+    ];
+    let objects: Vec<(&str, &str)> = objects.iter().map(|(name, text)| (*name, text.as_str())).collect();
+    setup_calendar(Calendar::new("work"), &objects).1
+}
+
+async fn free_busy_query(path: &str, time_range: &str) -> HttpResponse {
+    report(&controller(), path, "1", time_range).await
+}
+
+async fn report(controller: &DavController, path: &str, depth: &str, time_range: &str) -> HttpResponse {
+    let body = format!(
+        "<C:free-busy-query xmlns:C=\"urn:ietf:params:xml:ns:caldav\">{}</C:free-busy-query>",
+        time_range
+    );
+    let request = TestRequest::default()
+        .uri(path)
+        .insert_header(("Depth", depth))
+        .to_http_request();
+    controller.report(&request, &body).await
+}
+
+async fn get_periods(response: HttpResponse) -> Vec<(String, String)> {
+    /* FBTYPE and value of the FREEBUSY properties */
+    assert_eq!(response.status(), StatusCode::OK);
+    let calendar = ics::parse(&read_body(response).await).unwrap();
+    let free_busy = calendar.get_components("VFREEBUSY").next().unwrap();
+    free_busy
+        .get_properties("FREEBUSY")
+        .map(|property| {
+            (
+                property.get_parameter_value("FBTYPE").unwrap().to_string(),
+                property.values.join(","),
+            )
+        })
+        .collect()
+}
+
+fn date(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2021, 1, day, hour, minute, 0).unwrap()
+}
+
+#[actix_web::test]
+async fn test_free_busy_query() {
+    let response = free_busy_query(
+        "/calendars/work/",
+        "<C:time-range start=\"20210104T000000Z\" end=\"20210106T000000Z\"/>",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("Content-Type").unwrap(),
+        "text/calendar; charset=utf-8"
+    );
+    let calendar = ics::parse(&read_body(response).await).unwrap();
+    let free_busy = calendar.get_components("VFREEBUSY").next().unwrap();
+
+    assert_eq!(free_busy.get_text("DTSTART").as_deref(), Some("20210104T000000Z"));
+    assert_eq!(free_busy.get_text("DTEND").as_deref(), Some("20210106T000000Z"));
+    let periods: Vec<(&str, String)> = free_busy
+        .get_properties("FREEBUSY")
+        .map(|property| {
+            (
+                property.get_parameter_value("FBTYPE").unwrap(),
+                property.values.join(","),
+            )
+        })
+        .collect();
+    assert_eq!(
+        periods,
+        vec![
+            ("BUSY", "20210104T000000Z/20210104T010000Z".to_string()),
+            ("BUSY", "20210104T080000Z/20210104T083000Z".to_string()),
+            ("BUSY", "20210104T090000Z/20210104T103000Z".to_string()),
+            ("BUSY-TENTATIVE", "20210104T103000Z/20210104T120000Z".to_string()),
+            ("BUSY-TENTATIVE", "20210105T180000Z/20210105T183000Z".to_string()),
+        ]
+    );
+}
+
+#[actix_web::test]
+async fn test_free_busy_query_depth() {
+    /* The collection reports the busy time of its resources, whatever the depth */
+    let controller = controller();
+    let time_range = "<C:time-range start=\"20210104T080000Z\" end=\"20210104T100000Z\"/>";
+    let expected = vec![
+        ("BUSY".to_string(), "20210104T080000Z/20210104T083000Z".to_string()),
+        ("BUSY".to_string(), "20210104T090000Z/20210104T100000Z".to_string()),
+    ];
+    for depth in ["0", "1", "infinity"] {
+        let periods = get_periods(report(&controller, "/calendars/work/", depth, time_range).await).await;
+        assert_eq!(periods, expected);
+    }
+}
+
+#[actix_web::test]
+async fn test_free_busy_query_far_from_start() {
+    /* Over a thousand instances before the range */
+    let standup = "BEGIN:VCALENDAR\r\n\
+         VERSION:2.0\r\n\
+         BEGIN:VEVENT\r\n\
+         UID:standup\r\n\
+         DTSTAMP:20200101T000000Z\r\n\
+         DTSTART:20200101T090000Z\r\n\
+         DURATION:PT15M\r\n\
+         RRULE:FREQ=DAILY\r\n\
+         END:VEVENT\r\n\
+         END:VCALENDAR\r\n";
+    let (_, controller) = setup_calendar(Calendar::new("work"), &[("standup.ics", standup)]);
+
+    let time_range = "<C:time-range start=\"20261019T000000Z\" end=\"20261021T000000Z\"/>";
+    let periods = get_periods(report(&controller, "/calendars/work/", "1", time_range).await).await;
+    assert_eq!(
+        periods,
+        vec![
+            ("BUSY".to_string(), "20261019T090000Z/20261019T091500Z".to_string()),
+            ("BUSY".to_string(), "20261020T090000Z/20261020T091500Z".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_free_busy_coalescing() {
+    let mut free_busy = FreeBusy::new(date(4, 0, 0), date(5, 0, 0));
+    free_busy.add_period(FreeBusyType::BusyTentative, date(4, 8, 0), date(4, 18, 0));
+    free_busy.add_period(FreeBusyType::Busy, date(4, 9, 0), date(4, 10, 0));
+    free_busy.add_period(FreeBusyType::Busy, date(4, 9, 30), date(4, 11, 0));
+    free_busy.add_period(FreeBusyType::BusyUnavailable, date(4, 10, 30), date(4, 12, 0));
+    free_busy.add_period(FreeBusyType::Busy, date(4, 23, 0), date(5, 2, 0));
+
+    let period = |free_busy_type, start, end| FreeBusyPeriod {
+        free_busy_type,
+        start,
+        end,
+    };
+    assert_eq!(
+        free_busy.get_periods(),
+        vec![
+            period(FreeBusyType::BusyTentative, date(4, 8, 0), date(4, 9, 0)),
+            period(FreeBusyType::Busy, date(4, 9, 0), date(4, 10, 30)),
+            period(FreeBusyType::BusyUnavailable, date(4, 10, 30), date(4, 12, 0)),
+            period(FreeBusyType::BusyTentative, date(4, 12, 0), date(4, 18, 0)),
+            period(FreeBusyType::Busy, date(4, 23, 0), date(5, 0, 0)),
+        ]
+    );
+}
+
+#[actix_web::test]
+async fn test_free_busy_query_errors() {
+    let time_range = "<C:time-range start=\"20210104T000000Z\" end=\"20210106T000000Z\"/>";
+    let response = free_busy_query("/calendars/work/a.ics", time_range).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert_eq!(
+        free_busy_query("/calendars/home/", time_range).await.status(),
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        free_busy_query("/calendars/work/", "<C:time-range start=\"20210104T000000Z\"/>")
+            .await
+            .status(),
+        StatusCode::BAD_REQUEST
+    );
+    assert_eq!(
+        free_busy_query("/calendars/work/", "").await.status(),
+        StatusCode::BAD_REQUEST
+    );
+}
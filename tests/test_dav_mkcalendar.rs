@@ -0,0 +1,194 @@
+mod common;
+use actix_web::{http::StatusCode, test::TestRequest};
+use cal_dav_fast::{
+    app::calendar::calendar_store::CalendarStore,
+    architecture::xml::{XmlElement, CALDAV_NAMESPACE, DAV_NAMESPACE},
+};
+use common::{read_body, setup};
+use pretty_assertions::assert_eq;
+
+const TIMEZONE: &str = "BEGIN:VCALENDAR\r\n\
+                        VERSION:2.0\r\n\
+                        BEGIN:VTIMEZONE\r\n\
+                        TZID:Europe/Paris\r\n\
+                        BEGIN:STANDARD\r\n\
+                        DTSTART:19701025T030000\r\n\
+                        TZOFFSETFROM:+0200\r\n\
+                        TZOFFSETTO:+0100\r\n\
+                        RRULE:FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU\r\n\
+                        END:STANDARD\r\n\
+                        BEGIN:DAYLIGHT\r\n\
+                        DTSTART:19700329T020000\r\n\
+                        TZOFFSETFROM:+0100\r\n\
+                        TZOFFSETTO:+0200\r\n\
+                        RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU\r\n\
+                        END:DAYLIGHT\r\n\
+                        END:VTIMEZONE\r\n\
+                        END:VCALENDAR\r\n";
+
+fn mkcalendar_body(properties: &str) -> String {
+    format!(
+        "<C:mkcalendar xmlns:D=\"DAV:\" xmlns:C=\"urn:ietf:params:xml:ns:caldav\" \
+         xmlns:I=\"http://apple.com/ns/ical/\"><D:set><D:prop>{}</D:prop></D:set></C:mkcalendar>",
+        properties
+    )
+}
+
+#[actix_web::test]
+async fn test_mkcalendar() {
+    let (store, controller) = setup();
+    let body = mkcalendar_body(&format!(
+        "<D:displayname>Lisa's Events</D:displayname>\
+         <C:calendar-description xml:lang=\"en\">Calendar restricted to events.</C:calendar-description>\
+         <C:supported-calendar-component-set><C:comp name=\"VEVENT\"/></C:supported-calendar-component-set>\
+         <C:calendar-timezone><![CDATA[{}]]></C:calendar-timezone>\
+         <I:calendar-color>#FF5800FF</I:calendar-color>",
+        TIMEZONE
+    ));
+    let request = TestRequest::default().uri("/calendars/events/").to_http_request();
+
+    let response = controller.mkcalendar(&request, &body).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let calendar = store.get_calendar("events").await.unwrap().unwrap();
+    assert_eq!(calendar.get_display_name(), Some("Lisa's Events"));
+    assert_eq!(calendar.get_description(), Some("Calendar restricted to events."));
+    assert_eq!(calendar.get_supported_components(), &["VEVENT".to_string()]);
+    assert_eq!(calendar.get_timezone(), Some(TIMEZONE));
+    assert_eq!(calendar.get_color(), Some("#FF5800FF"));
+
+    /* Without body */
+    let request = TestRequest::default().uri("/calendars/home").to_http_request();
+    assert_eq!(controller.mkcalendar(&request, "").await.status(), StatusCode::CREATED);
+    assert!(store.get_calendar("home").await.unwrap().is_some());
+}
+
+#[actix_web::test]
+async fn test_mkcalendar_location() {
+    let (_, controller) = setup();
+    let mkcalendar = |path: &str| {
+        let request = TestRequest::default().uri(path).to_http_request();
+        let controller = &controller;
+        async move { controller.mkcalendar(&request, "").await }
+    };
+
+    let response = mkcalendar("/calendars/work/").await;
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    assert!(read_body(response).await.contains("<D:resource-must-be-null/>"));
+
+    assert_eq!(mkcalendar("/calendars/").await.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+    let response = mkcalendar("/calendars/work/nested/").await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(read_body(response)
+        .await
+        .contains("<C:calendar-collection-location-ok/>"));
+
+    assert_eq!(
+        mkcalendar("/calendars/unknown/nested/").await.status(),
+        StatusCode::CONFLICT
+    );
+    assert_eq!(mkcalendar("/calendars/a/b/c/").await.status(), StatusCode::CONFLICT);
+}
+
+#[actix_web::test]
+async fn test_mkcalendar_invalid_properties() {
+    let (store, controller) = setup();
+    let body = mkcalendar_body(
+        "<D:displayname>Tasks</D:displayname>\
+         <C:supported-calendar-component-set><C:comp name=\"VFREEBUSY\"/></C:supported-calendar-component-set>\
+         <C:calendar-timezone>BEGIN:VCALENDAR</C:calendar-timezone>\
+         <I:calendar-color>red</I:calendar-color>\
+         <D:getetag>\"1\"</D:getetag>",
+    );
+    let request = TestRequest::default().uri("/calendars/tasks/").to_http_request();
+
+    let response = controller.mkcalendar(&request, &body).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(store.get_calendar("tasks").await.unwrap().is_none());
+
+    let root = XmlElement::parse(&read_body(response).await).unwrap();
+    assert!(root.is(CALDAV_NAMESPACE, "mkcalendar-response"));
+    let statuses: Vec<(String, Vec<String>, Option<String>)> = root
+        .children_named(DAV_NAMESPACE, "propstat")
+        .map(|propstat| {
+            let properties = propstat.child(DAV_NAMESPACE, "prop").unwrap();
+            (
+                propstat.child(DAV_NAMESPACE, "status").unwrap().text.clone(),
+                properties
+                    .children
+                    .iter()
+                    .map(|property| property.name.clone())
+                    .collect(),
+                propstat
+                    .child(DAV_NAMESPACE, "error")
+                    .map(|error| error.children[0].name.clone()),
+            )
+        })
+        .collect();
+    assert_eq!(
+        statuses,
+        vec![
+            (
+                "HTTP/1.1 424 Failed Dependency".to_string(),
+                vec!["displayname".to_string()],
+                None
+            ),
+            (
+                "HTTP/1.1 403 Forbidden".to_string(),
+                vec!["supported-calendar-component-set".to_string()],
+                Some("supported-calendar-component".to_string())
+            ),
+            (
+                "HTTP/1.1 403 Forbidden".to_string(),
+                vec!["calendar-timezone".to_string()],
+                Some("valid-calendar-data".to_string())
+            ),
+            (
+                "HTTP/1.1 409 Conflict".to_string(),
+                vec!["calendar-color".to_string()],
+                None
+            ),
+            (
+                "HTTP/1.1 403 Forbidden".to_string(),
+                vec!["getetag".to_string()],
+                Some("cannot-modify-protected-property".to_string())
+            ),
+        ]
+    );
+}
+
+#[actix_web::test]
+async fn test_extended_mkcol() {
+    let (store, controller) = setup();
+    let request = TestRequest::default().uri("/calendars/events/").to_http_request();
+    let body = "<D:mkcol xmlns:D=\"DAV:\" xmlns:C=\"urn:ietf:params:xml:ns:caldav\"><D:set><D:prop>\
+                <D:resourcetype><D:collection/><C:calendar/></D:resourcetype>\
+                <D:displayname>Events</D:displayname></D:prop></D:set></D:mkcol>";
+
+    assert_eq!(controller.mkcol(&request, body).await.status(), StatusCode::CREATED);
+    let calendar = store.get_calendar("events").await.unwrap().unwrap();
+    assert_eq!(calendar.get_display_name(), Some("Events"));
+
+    /* Plain collections are not supported */
+    let request = TestRequest::default().uri("/calendars/files/").to_http_request();
+    let response = controller.mkcol(&request, "").await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(read_body(response).await.contains("<D:valid-resourcetype/>"));
+
+    let body = "<D:mkcol xmlns:D=\"DAV:\"><D:set><D:prop>\
+                <D:resourcetype><D:collection/></D:resourcetype></D:prop></D:set></D:mkcol>";
+    assert_eq!(controller.mkcol(&request, body).await.status(), StatusCode::FORBIDDEN);
+
+    /* The resource type of MKCALENDAR cannot be set */
+    let body = mkcalendar_body("<D:resourcetype><D:collection/><C:calendar/></D:resourcetype>");
+    assert_eq!(
+        controller.mkcalendar(&request, &body).await.status(),
+        StatusCode::FORBIDDEN
+    );
+
+    assert_eq!(
+        controller.mkcol(&request, "<D:mkcol").await.status(),
+        StatusCode::BAD_REQUEST
+    );
+}
@@ -0,0 +1,229 @@
+mod common;
+use std::sync::Arc;
+
+use actix_web::{
+    http::{Method, StatusCode},
+    test::TestRequest,
+    HttpResponse,
+};
+use cal_dav_fast::{
+    app::{calendar::calendar_store::CalendarStore, dav::preconditions::Preconditions},
+    controllers::calendar_object_controller::CalendarObjectController,
+};
+use common::{event, object_controller, MemoryBlobStore, MemoryCalendarStore};
+use pretty_assertions::assert_eq;
+
+const HREF: &str = "/calendars/work/event-1.ics";
+
+fn setup() -> (Arc<MemoryCalendarStore>, CalendarObjectController) {
+    let (store, _) = common::setup();
+    let controller = object_controller(store.clone(), Arc::new(MemoryBlobStore::default()), 1024);
+    (store, controller)
+}
+
+fn request(headers: &[(&str, &str)]) -> TestRequest {
+    headers
+        .iter()
+        .fold(TestRequest::default().uri(HREF), |request, header| {
+            request.insert_header(*header)
+        })
+}
+
+async fn put(controller: &CalendarObjectController, headers: &[(&str, &str)], summary: &str) -> HttpResponse {
+    controller
+        .put(
+            &request(headers).method(Method::PUT).to_http_request(),
+            &event("event-1", &format!("SUMMARY:{}\r\n", summary)),
+        )
+        .await
+}
+
+fn get_etag(response: &HttpResponse) -> String {
+    response.headers().get("ETag").unwrap().to_str().unwrap().to_string()
+}
+
+async fn get_current_etag(controller: &CalendarObjectController) -> String {
+    /* A PUT changing the data does not return the entity tag */
+    get_etag(&controller.get(&request(&[]).to_http_request()).await)
+}
+
+fn preconditions(if_match: Option<&str>, if_none_match: Option<&str>, if_header: Option<&str>) -> Preconditions {
+    Preconditions::parse(if_match, if_none_match, if_header).unwrap()
+}
+
+#[test]
+fn test_if_match() {
+    let etag = Some("\"xyzzy\"");
+    assert!(preconditions(Some("\"xyzzy\""), None, None).evaluate(HREF, etag, true));
+    assert!(preconditions(Some("\"a\", \"xyzzy\""), None, None).evaluate(HREF, etag, true));
+    assert!(!preconditions(Some("\"a\""), None, None).evaluate(HREF, etag, true));
+    assert!(preconditions(Some("*"), None, None).evaluate(HREF, etag, true));
+    assert!(!preconditions(Some("*"), None, None).evaluate(HREF, None, false));
+
+    /* Strong comparison */
+    assert!(!preconditions(Some("W/\"xyzzy\""), None, None).evaluate(HREF, etag, true));
+}
+
+#[test]
+fn test_if_none_match() {
+    let etag = Some("\"xyzzy\"");
+    assert!(preconditions(None, Some("*"), None).evaluate(HREF, None, false));
+    assert!(!preconditions(None, Some("*"), None).evaluate(HREF, etag, true));
+    assert!(!preconditions(None, Some("W/\"xyzzy\""), None).evaluate(HREF, etag, true));
+    assert!(preconditions(None, Some("\"a\""), None).evaluate(HREF, etag, true));
+}
+
+#[test]
+fn test_if_header() {
+    let etag = Some("\"xyzzy\"");
+    let evaluate = |header: &str| preconditions(None, None, Some(header)).evaluate(HREF, etag, true);
+
+    assert!(evaluate("([\"xyzzy\"])"));
+    assert!(!evaluate("([\"other\"])"));
+    assert!(evaluate("(Not [\"other\"])"));
+
+    /* No lock matches the resource */
+    assert!(!evaluate("(<urn:uuid:181d4fae-7d8c-11d0-a765-00a0c91e6bf2>)"));
+    assert!(evaluate("(Not <DAV:no-lock> [\"xyzzy\"])"));
+    assert!(evaluate(
+        "(<urn:uuid:181d4fae> [\"xyzzy\"]) (Not <DAV:no-lock> [\"xyzzy\"])"
+    ));
+    assert!(!evaluate(
+        "(<urn:uuid:181d4fae> [\"xyzzy\"]) (Not <DAV:no-lock> [\"other\"])"
+    ));
+
+    /* Tagged lists */
+    assert!(evaluate(
+        "<http://cal.example.com/calendars/work/event-1.ics> ([\"xyzzy\"])"
+    ));
+    assert!(!evaluate("</calendars/work/event-1.ics> ([\"other\"])"));
+    assert!(evaluate("</calendars/work/other.ics> ([\"other\"])"));
+
+    assert!(Preconditions::parse(None, None, Some("[\"xyzzy\"]")).is_none());
+    assert!(Preconditions::parse(None, None, Some("(\"xyzzy\")")).is_none());
+    assert!(Preconditions::parse(None, None, Some("([\"xyzzy\"]) </a> ([\"xyzzy\"])")).is_none());
+    assert!(Preconditions::parse(None, None, None).unwrap().is_empty());
+}
+
+#[actix_web::test]
+async fn test_conditional_put() {
+    let (_, controller) = setup();
+
+    /* Create only */
+    let response = put(&controller, &[("If-None-Match", "*")], "Meeting").await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let etag = get_current_etag(&controller).await;
+    let response = put(&controller, &[("If-None-Match", "*")], "Other").await;
+    assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+
+    let response = put(&controller, &[("If-Match", "\"other\"")], "Other").await;
+    assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+    let response = put(&controller, &[("If-Match", &etag)], "Team meeting").await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    /* The entity tag changed */
+    let response = put(&controller, &[("If", &format!("([{}])", etag))], "Other").await;
+    assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+    let response = put(&controller, &[("If", "([")], "Other").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn test_conditional_get() {
+    let (_, controller) = setup();
+    put(&controller, &[], "Meeting").await;
+    let etag = get_current_etag(&controller).await;
+
+    /* Not modified */
+    for method in [Method::GET, Method::HEAD] {
+        let response = controller
+            .get(&request(&[("If-None-Match", &etag)]).method(method).to_http_request())
+            .await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(get_etag(&response), etag);
+    }
+    let response = controller
+        .get(&request(&[("If-None-Match", "*")]).to_http_request())
+        .await;
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+    let response = controller
+        .get(&request(&[("If-None-Match", "\"other\"")]).to_http_request())
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = controller
+        .get(&request(&[("If-Match", "\"other\""), ("If-None-Match", &etag)]).to_http_request())
+        .await;
+    assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+}
+
+#[actix_web::test]
+async fn test_conditional_delete() {
+    let (store, controller) = setup();
+    put(&controller, &[], "Meeting").await;
+    let etag = get_current_etag(&controller).await;
+
+    let response = controller
+        .delete(&request(&[("If-Match", "\"other\"")]).to_http_request())
+        .await;
+    assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+    assert_eq!(store.events.lock().unwrap().len(), 1);
+
+    let response = controller
+        .delete(&request(&[("If-Match", &etag)]).to_http_request())
+        .await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(store.events.lock().unwrap().is_empty());
+}
+
+#[actix_web::test]
+async fn test_state_tokens() {
+    let (store, controller) = setup();
+    put(&controller, &[], "Meeting").await;
+    let etag = get_current_etag(&controller).await;
+
+    /* No lock is held, so a state token never matches */
+    let lock = "(<urn:uuid:181d4fae-7d8c-11d0-a765-00a0c91e6bf2>)";
+    let response = put(&controller, &[("If", lock)], "Other").await;
+    assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+    let response = put(
+        &controller,
+        &[("If", "(Not <urn:uuid:181d4fae-7d8c-11d0-a765-00a0c91e6bf2>)")],
+        "Other",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let etag_after_put = get_current_etag(&controller).await;
+    assert!(etag != etag_after_put);
+
+    let response = controller.delete(&request(&[("If", lock)]).to_http_request()).await;
+    assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+    let response = controller
+        .delete(&request(&[("If", &format!("<{}> (Not <DAV:no-lock> [{}])", HREF, etag))]).to_http_request())
+        .await;
+    assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+    assert_eq!(store.events.lock().unwrap().len(), 1);
+
+    let response = controller
+        .delete(&request(&[("If", &format!("<{}> (Not <DAV:no-lock> [{}])", HREF, etag_after_put))]).to_http_request())
+        .await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(store.events.lock().unwrap().is_empty());
+}
+
+#[actix_web::test]
+async fn test_concurrent_writes() {
+    let (store, controller) = setup();
+    put(&controller, &[], "Meeting").await;
+    let calendar_id = store.get_calendar("work").await.unwrap().unwrap().get_id().unwrap();
+
+    /* Two writers reading the same state: only the first one succeeds */
+    let first = store.get_object(calendar_id, "event-1.ics").await.unwrap().unwrap();
+    let second = first.clone();
+    let mut updated = first.clone();
+    updated.update_etag();
+    assert!(store.save_object(calendar_id, &updated, Some(&first)).await.unwrap());
+    assert!(!store.save_object(calendar_id, &updated, Some(&second)).await.unwrap());
+    assert!(!store.delete_object(calendar_id, &second).await.unwrap());
+    assert!(!store.save_object(calendar_id, &updated, None).await.unwrap());
+}
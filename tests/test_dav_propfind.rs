@@ -0,0 +1,230 @@
+mod common;
+use actix_web::{http::StatusCode, test::TestRequest, HttpResponse};
+use cal_dav_fast::{
+    app::{
+        calendar::models::calendar::Calendar,
+        dav::{
+            dav_path::DavPath,
+            property_name::PropertyName,
+            propfind::{Depth, PropfindRequest},
+        },
+    },
+    architecture::xml::{XmlElement, CALDAV_NAMESPACE, CALENDARSERVER_NAMESPACE, DAV_NAMESPACE},
+    controllers::dav_controller::DavController,
+};
+use common::{read_body, setup_calendar};
+use pretty_assertions::assert_eq;
+
+const EVENT: &str = "BEGIN:VCALENDAR\r\n\
+                     VERSION:2.0\r\n\
+                     BEGIN:VEVENT\r\n\
+                     UID:event-1\r\n\
+                     DTSTAMP:20210101T000000Z\r\n\
+                     DTSTART:20210104T100000Z\r\n\
+                     SUMMARY:Meeting\r\n\
+                     END:VEVENT\r\n\
+                     END:VCALENDAR\r\n";
+
+fn controller() -> DavController {
+    let mut calendar = Calendar::new("work");
+    calendar.set_display_name(Some("Work".to_string()));
+    let (store, controller) = setup_calendar(calendar, &[("event 1.ics", EVENT)]);
+    store.add_calendar(Calendar::new("home"));
+    controller
+}
+
+async fn propfind(path: &str, depth: Option<&str>, body: &str) -> (StatusCode, String) {
+    let mut request = TestRequest::default().uri(path);
+    if let Some(depth) = depth {
+        request = request.insert_header(("Depth", depth));
+    }
+    let response: HttpResponse = controller().propfind(&request.to_http_request(), body).await;
+    (response.status(), read_body(response).await)
+}
+
+fn get_responses(body: &str) -> Vec<XmlElement> {
+    let root = XmlElement::parse(body).unwrap();
+    assert!(root.is(DAV_NAMESPACE, "multistatus"));
+    root.children_named(DAV_NAMESPACE, "response").cloned().collect()
+}
+
+fn get_href(response: &XmlElement) -> &str {
+    &response.child(DAV_NAMESPACE, "href").unwrap().text
+}
+
+fn get_propstat<'a>(response: &'a XmlElement, status: &str) -> Option<&'a XmlElement> {
+    response
+        .children_named(DAV_NAMESPACE, "propstat")
+        .find(|propstat| propstat.child(DAV_NAMESPACE, "status").unwrap().text == status)
+        .map(|propstat| propstat.child(DAV_NAMESPACE, "prop").unwrap())
+}
+
+#[test]
+fn test_dav_path() {
+    assert_eq!(DavPath::parse("/calendars"), Some(DavPath::Root));
+    assert_eq!(DavPath::parse("/calendars/"), Some(DavPath::Root));
+    assert_eq!(
+        DavPath::parse("/calendars/work/"),
+        Some(DavPath::Calendar("work".to_string()))
+    );
+    assert_eq!(
+        DavPath::parse("/calendars/work/event%201.ics"),
+        Some(DavPath::Object {
+            calendar: "work".to_string(),
+            name: "event 1.ics".to_string()
+        })
+    );
+    assert_eq!(DavPath::parse("/calendarsx/work"), None);
+    assert_eq!(DavPath::parse("/calendars/work/a/b"), None);
+    assert_eq!(DavPath::parse("/calendars/work/.."), None);
+
+    let path = DavPath::Object {
+        calendar: "my work".to_string(),
+        name: "a/b?.ics".to_string(),
+    };
+    assert_eq!(path.get_href(), "/calendars/my%20work/a%2Fb%3F.ics");
+}
+
+#[test]
+fn test_parse_propfind() {
+    assert_eq!(
+        PropfindRequest::parse("").unwrap(),
+        PropfindRequest::AllProp { include: Vec::new() }
+    );
+    assert_eq!(
+        PropfindRequest::parse("<propfind xmlns=\"DAV:\"><propname/></propfind>").unwrap(),
+        PropfindRequest::PropName
+    );
+    assert_eq!(
+        PropfindRequest::parse(
+            "<D:propfind xmlns:D=\"DAV:\" xmlns:C=\"urn:ietf:params:xml:ns:caldav\">\
+             <D:allprop/><D:include><C:calendar-description/></D:include></D:propfind>"
+        )
+        .unwrap(),
+        PropfindRequest::AllProp {
+            include: vec![PropertyName::new(CALDAV_NAMESPACE, "calendar-description")]
+        }
+    );
+    assert_eq!(
+        PropfindRequest::parse(
+            "<propfind xmlns=\"DAV:\"><prop><getetag/><x:color xmlns:x=\"urn:x\"/></prop></propfind>"
+        )
+        .unwrap(),
+        PropfindRequest::Prop(vec![
+            PropertyName::new(DAV_NAMESPACE, "getetag"),
+            PropertyName::new("urn:x", "color")
+        ])
+    );
+    assert!(PropfindRequest::parse("<propfind xmlns=\"urn:x\"><prop/></propfind>").is_err());
+    assert!(PropfindRequest::parse("<propfind xmlns=\"DAV:\"><prop>").is_err());
+
+    assert_eq!(Depth::parse(None), Some(Depth::Infinity));
+    assert_eq!(Depth::parse(Some("1")), Some(Depth::One));
+    assert_eq!(Depth::parse(Some("2")), None);
+}
+
+#[actix_web::test]
+async fn test_propfind_depth() {
+    let (status, body) = propfind("/calendars/", Some("1"), "").await;
+    assert_eq!(status, StatusCode::MULTI_STATUS);
+    let hrefs: Vec<String> = get_responses(&body).iter().map(|r| get_href(r).to_string()).collect();
+    assert_eq!(hrefs, vec!["/calendars/", "/calendars/home/", "/calendars/work/"]);
+
+    let (_, body) = propfind("/calendars/work/", Some("1"), "").await;
+    let hrefs: Vec<String> = get_responses(&body).iter().map(|r| get_href(r).to_string()).collect();
+    assert_eq!(hrefs, vec!["/calendars/work/", "/calendars/work/event%201.ics"]);
+
+    let (_, body) = propfind("/calendars/work/", Some("0"), "").await;
+    assert_eq!(get_responses(&body).len(), 1);
+
+    let (status, body) = propfind("/calendars/work/", None, "").await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert!(body.contains("<D:propfind-finite-depth/>"));
+
+    let (status, _) = propfind("/calendars/work/", Some("2"), "").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = propfind("/calendars/unknown/", Some("0"), "").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = propfind("/calendars/work/unknown.ics", Some("0"), "").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn test_propfind_prop() {
+    let body = "<D:propfind xmlns:D=\"DAV:\" xmlns:C=\"urn:ietf:params:xml:ns:caldav\" \
+                xmlns:CS=\"http://calendarserver.org/ns/\">\
+                <D:prop><D:resourcetype/><D:displayname/><CS:getctag/><C:supported-calendar-component-set/>\
+                <C:supported-calendar-data/><C:calendar-description/><X:color xmlns:X=\"urn:x\"/></D:prop></D:propfind>";
+    let (status, body) = propfind("/calendars/work/", Some("0"), body).await;
+    assert_eq!(status, StatusCode::MULTI_STATUS);
+    let responses = get_responses(&body);
+
+    let found = get_propstat(&responses[0], "HTTP/1.1 200 OK").unwrap();
+    let resource_type = found.child(DAV_NAMESPACE, "resourcetype").unwrap();
+    assert!(resource_type.child(DAV_NAMESPACE, "collection").is_some());
+    assert!(resource_type.child(CALDAV_NAMESPACE, "calendar").is_some());
+    assert_eq!(found.child(DAV_NAMESPACE, "displayname").unwrap().text, "Work");
+    assert!(found.child(CALENDARSERVER_NAMESPACE, "getctag").is_some());
+    let components = found
+        .child(CALDAV_NAMESPACE, "supported-calendar-component-set")
+        .unwrap();
+    assert_eq!(components.children[0].get_attribute("name"), Some("VEVENT"));
+    let content_types: Vec<Option<&str>> = found
+        .child(CALDAV_NAMESPACE, "supported-calendar-data")
+        .unwrap()
+        .children
+        .iter()
+        .map(|calendar_data| calendar_data.get_attribute("content-type"))
+        .collect();
+    assert_eq!(
+        content_types,
+        vec![Some("text/calendar"), Some("application/calendar+xml")]
+    );
+
+    /* Properties without value are not found */
+    let not_found = get_propstat(&responses[0], "HTTP/1.1 404 Not Found").unwrap();
+    let names: Vec<PropertyName> = not_found.children.iter().map(PropertyName::from_xml).collect();
+    assert_eq!(
+        names,
+        vec![
+            PropertyName::new(CALDAV_NAMESPACE, "calendar-description"),
+            PropertyName::new("urn:x", "color")
+        ]
+    );
+}
+
+#[actix_web::test]
+async fn test_propfind_object() {
+    let body = "<propfind xmlns=\"DAV:\"><prop><getetag/><getcontenttype/><calendar-data \
+                xmlns=\"urn:ietf:params:xml:ns:caldav\"/></prop></propfind>";
+    let (_, body) = propfind("/calendars/work/event%201.ics", Some("0"), body).await;
+    let responses = get_responses(&body);
+    let found = get_propstat(&responses[0], "HTTP/1.1 200 OK").unwrap();
+
+    assert!(found.child(DAV_NAMESPACE, "getetag").unwrap().text.starts_with('"'));
+    assert_eq!(
+        found.child(DAV_NAMESPACE, "getcontenttype").unwrap().text,
+        "text/calendar; charset=utf-8; component=vevent"
+    );
+    let calendar_data = &found.child(CALDAV_NAMESPACE, "calendar-data").unwrap().text;
+    assert!(calendar_data.contains("UID:event-1\r\n"));
+    assert!(calendar_data.contains("SUMMARY:Meeting\r\n"));
+}
+
+#[actix_web::test]
+async fn test_propfind_allprop_and_propname() {
+    let (_, body) = propfind("/calendars/work/event%201.ics", Some("0"), "").await;
+    let responses = get_responses(&body);
+    let found = get_propstat(&responses[0], "HTTP/1.1 200 OK").unwrap();
+    assert!(found.child(DAV_NAMESPACE, "getetag").is_some());
+    assert!(found.child(DAV_NAMESPACE, "getlastmodified").is_some());
+    assert!(found.child(CALDAV_NAMESPACE, "calendar-data").is_none());
+    assert!(get_propstat(&responses[0], "HTTP/1.1 404 Not Found").is_none());
+
+    let body = "<propfind xmlns=\"DAV:\"><propname/></propfind>";
+    let (_, body) = propfind("/calendars/work/event%201.ics", Some("0"), body).await;
+    let responses = get_responses(&body);
+    let found = get_propstat(&responses[0], "HTTP/1.1 200 OK").unwrap();
+    let calendar_data = found.child(CALDAV_NAMESPACE, "calendar-data").unwrap();
+    assert!(calendar_data.text.is_empty());
+}
@@ -0,0 +1,201 @@
+mod common;
+use std::sync::Arc;
+
+use actix_web::{http::StatusCode, test::TestRequest, HttpResponse};
+use cal_dav_fast::{
+    app::calendar::{calendar_store::CalendarStore, models::calendar::Calendar},
+    architecture::xml::{XmlElement, DAV_NAMESPACE},
+    controllers::dav_controller::DavController,
+};
+use common::{read_body, setup_calendar, MemoryCalendarStore};
+use pretty_assertions::assert_eq;
+
+const EVENT: &str = "BEGIN:VCALENDAR\r\n\
+                     VERSION:2.0\r\n\
+                     BEGIN:VEVENT\r\n\
+                     UID:event-1\r\n\
+                     DTSTAMP:20210101T000000Z\r\n\
+                     DTSTART:20210104T100000Z\r\n\
+                     SUMMARY:Meeting\r\n\
+                     END:VEVENT\r\n\
+                     END:VCALENDAR\r\n";
+
+fn setup() -> (Arc<MemoryCalendarStore>, DavController) {
+    let mut calendar = Calendar::new("work");
+    calendar.set_display_name(Some("Work".to_string()));
+    calendar.set_color(Some("#FF0000".to_string()));
+    setup_calendar(calendar, &[("event-1.ics", EVENT)])
+}
+
+fn proppatch_body(instructions: &str) -> String {
+    format!(
+        "<D:propertyupdate xmlns:D=\"DAV:\" xmlns:C=\"urn:ietf:params:xml:ns:caldav\" \
+         xmlns:I=\"http://apple.com/ns/ical/\" xmlns:Z=\"http://example.com/ns/\">{}</D:propertyupdate>",
+        instructions
+    )
+}
+
+async fn proppatch(controller: &DavController, path: &str, body: &str) -> HttpResponse {
+    let request = TestRequest::default().uri(path).to_http_request();
+    controller.proppatch(&request, body).await
+}
+
+async fn get_statuses(response: HttpResponse) -> Vec<(String, Vec<String>, Option<String>)> {
+    /* Status, properties and error of each propstat */
+    let root = XmlElement::parse(&read_body(response).await).unwrap();
+    let response = root.child(DAV_NAMESPACE, "response").unwrap();
+    response
+        .children_named(DAV_NAMESPACE, "propstat")
+        .map(|propstat| {
+            (
+                propstat.child(DAV_NAMESPACE, "status").unwrap().text.clone(),
+                propstat
+                    .child(DAV_NAMESPACE, "prop")
+                    .unwrap()
+                    .children
+                    .iter()
+                    .map(|property| property.name.clone())
+                    .collect(),
+                propstat
+                    .child(DAV_NAMESPACE, "error")
+                    .map(|error| error.children[0].name.clone()),
+            )
+        })
+        .collect()
+}
+
+#[actix_web::test]
+async fn test_proppatch_calendar() {
+    let (store, controller) = setup();
+    let before = store.get_calendar("work").await.unwrap().unwrap();
+    let body = proppatch_body(
+        "<D:set><D:prop>\
+         <D:displayname>Office</D:displayname>\
+         <C:calendar-description>Meetings and reviews</C:calendar-description>\
+         <Z:calendar-order>2</Z:calendar-order>\
+         </D:prop></D:set>\
+         <D:remove><D:prop><I:calendar-color/><Z:unknown/></D:prop></D:remove>",
+    );
+
+    let response = proppatch(&controller, "/calendars/work/", &body).await;
+    assert_eq!(response.status(), StatusCode::MULTI_STATUS);
+    assert_eq!(
+        get_statuses(response).await,
+        vec![(
+            "HTTP/1.1 200 OK".to_string(),
+            vec![
+                "displayname".to_string(),
+                "calendar-description".to_string(),
+                "calendar-order".to_string(),
+                "calendar-color".to_string(),
+                "unknown".to_string(),
+            ],
+            None
+        )]
+    );
+
+    let calendar = store.get_calendar("work").await.unwrap().unwrap();
+    assert_eq!(calendar.get_display_name(), Some("Office"));
+    assert_eq!(calendar.get_description(), Some("Meetings and reviews"));
+    assert_eq!(calendar.get_color(), None);
+    assert_ne!(calendar.get_ctag(), before.get_ctag());
+
+    /* The dead property is returned as set */
+    let request = TestRequest::default()
+        .uri("/calendars/work/")
+        .insert_header(("Depth", "0"))
+        .to_http_request();
+    let body = "<D:propfind xmlns:D=\"DAV:\"><D:prop><Z:calendar-order xmlns:Z=\"http://example.com/ns/\"/>\
+                </D:prop></D:propfind>";
+    let root = XmlElement::parse(&read_body(controller.propfind(&request, body).await).await).unwrap();
+    let order = &root.children[0].children[1].children[0].children[0];
+    assert!(order.is("http://example.com/ns/", "calendar-order"));
+    assert_eq!(order.text, "2");
+
+    /* Removed */
+    let body = proppatch_body("<D:remove><D:prop><Z:calendar-order/></D:prop></D:remove>");
+    assert_eq!(
+        proppatch(&controller, "/calendars/work/", &body).await.status(),
+        StatusCode::MULTI_STATUS
+    );
+    let calendar = store.get_calendar("work").await.unwrap().unwrap();
+    assert!(calendar.get_dead_properties().is_empty());
+}
+
+#[actix_web::test]
+async fn test_proppatch_atomicity() {
+    let (store, controller) = setup();
+    let body = proppatch_body(
+        "<D:set><D:prop>\
+         <D:displayname>Office</D:displayname>\
+         <CS:getctag xmlns:CS=\"http://calendarserver.org/ns/\">\"1\"</CS:getctag>\
+         <I:calendar-color>red</I:calendar-color>\
+         <C:supported-calendar-component-set><C:comp name=\"VEVENT\"/></C:supported-calendar-component-set>\
+         </D:prop></D:set>",
+    );
+
+    let response = proppatch(&controller, "/calendars/work/", &body).await;
+    assert_eq!(response.status(), StatusCode::MULTI_STATUS);
+    assert_eq!(
+        get_statuses(response).await,
+        vec![
+            (
+                "HTTP/1.1 424 Failed Dependency".to_string(),
+                vec!["displayname".to_string()],
+                None
+            ),
+            (
+                "HTTP/1.1 403 Forbidden".to_string(),
+                vec!["getctag".to_string(), "supported-calendar-component-set".to_string()],
+                Some("cannot-modify-protected-property".to_string())
+            ),
+            (
+                "HTTP/1.1 409 Conflict".to_string(),
+                vec!["calendar-color".to_string()],
+                None
+            ),
+        ]
+    );
+
+    /* Nothing changed */
+    let calendar = store.get_calendar("work").await.unwrap().unwrap();
+    assert_eq!(calendar.get_display_name(), Some("Work"));
+    assert_eq!(calendar.get_color(), Some("#FF0000"));
+}
+
+#[actix_web::test]
+async fn test_proppatch_resources() {
+    let (_, controller) = setup();
+    let body = proppatch_body("<D:set><D:prop><D:getetag>\"1\"</D:getetag></D:prop></D:set>");
+
+    let response = proppatch(&controller, "/calendars/work/event-1.ics", &body).await;
+    assert_eq!(response.status(), StatusCode::MULTI_STATUS);
+    assert_eq!(
+        get_statuses(response).await,
+        vec![(
+            "HTTP/1.1 403 Forbidden".to_string(),
+            vec!["getetag".to_string()],
+            Some("cannot-modify-protected-property".to_string())
+        )]
+    );
+
+    assert_eq!(
+        proppatch(&controller, "/calendars/home/", &body).await.status(),
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        proppatch(&controller, "/calendars/work/", "<D:propfind xmlns:D=\"DAV:\"/>")
+            .await
+            .status(),
+        StatusCode::BAD_REQUEST
+    );
+
+    let request = TestRequest::default()
+        .uri("/calendars/work/event-1.ics")
+        .insert_header(("If-Match", "\"other\""))
+        .to_http_request();
+    assert_eq!(
+        controller.proppatch(&request, &body).await.status(),
+        StatusCode::PRECONDITION_FAILED
+    );
+}
@@ -0,0 +1,189 @@
+mod common;
+use actix_web::{http::StatusCode, test::TestRequest, HttpResponse};
+use cal_dav_fast::{
+    app::calendar::{
+        ical::{component::ICalComponent, ics},
+        models::calendar::Calendar,
+    },
+    architecture::xml::{XmlElement, CALDAV_NAMESPACE, DAV_NAMESPACE},
+};
+use common::{read_body, setup_calendar};
+use pretty_assertions::assert_eq;
+
+const WEEKLY: &str = "BEGIN:VCALENDAR\r\n\
+                      VERSION:2.0\r\n\
+                      BEGIN:VEVENT\r\n\
+                      UID:weekly\r\n\
+                      DTSTAMP:20210101T000000Z\r\n\
+                      DTSTART;TZID=Europe/Paris:20210101T090000\r\n\
+                      DURATION:PT1H\r\n\
+                      SUMMARY:Weekly review\r\n\
+                      RRULE:FREQ=WEEKLY;UNTIL=20210130T000000Z\r\n\
+                      EXDATE;TZID=Europe/Paris:20210122T090000\r\n\
+                      END:VEVENT\r\n\
+                      BEGIN:VEVENT\r\n\
+                      UID:weekly\r\n\
+                      DTSTAMP:20210101T000000Z\r\n\
+                      RECURRENCE-ID;TZID=Europe/Paris:20210115T090000\r\n\
+                      DTSTART;TZID=Europe/Paris:20210115T140000\r\n\
+                      DURATION:PT1H\r\n\
+                      SUMMARY:Weekly review (moved)\r\n\
+                      END:VEVENT\r\n\
+                      END:VCALENDAR\r\n";
+
+/* Without end, started years before the ranges queried */
+const DAILY: &str = "BEGIN:VCALENDAR\r\n\
+                     VERSION:2.0\r\n\
+                     BEGIN:VEVENT\r\n\
+                     UID:daily\r\n\
+                     DTSTAMP:20200101T000000Z\r\n\
+                     DTSTART:20200101T090000Z\r\n\
+                     DURATION:PT15M\r\n\
+                     SUMMARY:Standup\r\n\
+                     RRULE:FREQ=DAILY\r\n\
+                     END:VEVENT\r\n\
+                     END:VCALENDAR\r\n";
+
+async fn get_calendar_data(calendar_data: &str) -> HttpResponse {
+    get_object_calendar_data("weekly.ics", calendar_data).await
+}
+
+async fn get_object_calendar_data(name: &str, calendar_data: &str) -> HttpResponse {
+    let objects = [("weekly.ics", WEEKLY), ("daily.ics", DAILY)];
+    let (_, controller) = setup_calendar(Calendar::new("work"), &objects);
+
+    let body = format!(
+        "<C:calendar-multiget xmlns:D=\"DAV:\" xmlns:C=\"urn:ietf:params:xml:ns:caldav\">\
+         <D:prop>{}</D:prop><D:href>/calendars/work/{}</D:href></C:calendar-multiget>",
+        calendar_data, name
+    );
+    let request = TestRequest::default().uri("/calendars/work/").to_http_request();
+    controller.report(&request, &body).await
+}
+
+async fn get_calendar(calendar_data: &str) -> ICalComponent {
+    get_object_calendar("weekly.ics", calendar_data).await
+}
+
+async fn get_object_calendar(name: &str, calendar_data: &str) -> ICalComponent {
+    let response = get_object_calendar_data(name, calendar_data).await;
+    assert_eq!(response.status(), StatusCode::MULTI_STATUS);
+    let root = XmlElement::parse(&read_body(response).await).unwrap();
+    let data = root
+        .child(DAV_NAMESPACE, "response")
+        .and_then(|response| response.child(DAV_NAMESPACE, "propstat"))
+        .and_then(|propstat| propstat.child(DAV_NAMESPACE, "prop"))
+        .and_then(|prop| prop.child(CALDAV_NAMESPACE, "calendar-data"))
+        .unwrap();
+    ics::parse(&data.text).unwrap()
+}
+
+async fn get_events(calendar_data: &str) -> Vec<ICalComponent> {
+    let calendar = get_calendar(calendar_data).await;
+    calendar.get_components("VEVENT").cloned().collect()
+}
+
+fn get_value<'a>(event: &'a ICalComponent, name: &str) -> Option<&'a str> {
+    event.get_property(name).map(|property| property.get_value())
+}
+
+#[actix_web::test]
+async fn test_expand() {
+    let events = get_events(
+        "<C:calendar-data><C:expand start=\"20210107T000000Z\" end=\"20210120T000000Z\"/></C:calendar-data>",
+    )
+    .await;
+    assert_eq!(events.len(), 2);
+
+    assert_eq!(get_value(&events[0], "RECURRENCE-ID"), Some("20210108T080000Z"));
+    assert_eq!(get_value(&events[0], "DTSTART"), Some("20210108T080000Z"));
+    assert_eq!(get_value(&events[0], "DURATION"), Some("PT1H"));
+    assert_eq!(get_value(&events[0], "SUMMARY"), Some("Weekly review"));
+    assert_eq!(get_value(&events[0], "RRULE"), None);
+    assert_eq!(get_value(&events[0], "EXDATE"), None);
+
+    /* The override replaces the instance */
+    assert_eq!(get_value(&events[1], "RECURRENCE-ID"), Some("20210115T080000Z"));
+    assert_eq!(get_value(&events[1], "DTSTART"), Some("20210115T130000Z"));
+    assert_eq!(get_value(&events[1], "SUMMARY"), Some("Weekly review (moved)"));
+
+    /* In UTC, without timezones */
+    let calendar = get_calendar(
+        "<C:calendar-data><C:expand start=\"20210107T000000Z\" end=\"20210120T000000Z\"/></C:calendar-data>",
+    )
+    .await;
+    assert!(calendar.get_components("VTIMEZONE").next().is_none());
+    for event in events.iter() {
+        for property in event.properties.iter() {
+            assert_eq!(property.get_parameter("TZID"), None);
+        }
+    }
+
+    /* Excluded dates have no instance */
+    let events = get_events(
+        "<C:calendar-data><C:expand start=\"20210120T000000Z\" end=\"20210201T000000Z\"/></C:calendar-data>",
+    )
+    .await;
+    let starts: Vec<_> = events.iter().map(|event| get_value(event, "DTSTART")).collect();
+    assert_eq!(starts, vec![Some("20210129T080000Z")]);
+
+    let events = get_events(
+        "<C:calendar-data><C:expand start=\"20210201T000000Z\" end=\"20210301T000000Z\"/></C:calendar-data>",
+    )
+    .await;
+    assert!(events.is_empty());
+}
+
+#[actix_web::test]
+async fn test_expand_far_from_start() {
+    /* Over a thousand instances before the range */
+    let calendar = get_object_calendar(
+        "daily.ics",
+        "<C:calendar-data><C:expand start=\"20261019T000000Z\" end=\"20261021T000000Z\"/></C:calendar-data>",
+    )
+    .await;
+    let starts: Vec<_> = calendar
+        .get_components("VEVENT")
+        .map(|event| get_value(event, "RECURRENCE-ID"))
+        .collect();
+    assert_eq!(starts, vec![Some("20261019T090000Z"), Some("20261020T090000Z")]);
+}
+
+#[actix_web::test]
+async fn test_limit_recurrence_set() {
+    let limit = |start: &str, end: &str| {
+        format!(
+            "<C:calendar-data><C:limit-recurrence-set start=\"{}\" end=\"{}\"/></C:calendar-data>",
+            start, end
+        )
+    };
+
+    let events = get_events(&limit("20210101T000000Z", "20210110T000000Z")).await;
+    assert_eq!(events.len(), 1);
+    assert!(get_value(&events[0], "RRULE").is_some());
+
+    /* Overrides of an instance in the range, or moved into it */
+    for (start, end) in [
+        ("20210115T073000Z", "20210115T083000Z"),
+        ("20210115T120000Z", "20210115T150000Z"),
+    ] {
+        let events = get_events(&limit(start, end)).await;
+        assert_eq!(events.len(), 2);
+        assert_eq!(get_value(&events[1], "SUMMARY"), Some("Weekly review (moved)"));
+    }
+}
+
+#[actix_web::test]
+async fn test_invalid_recurrence_set() {
+    for calendar_data in [
+        "<C:calendar-data><C:expand start=\"20210101T000000Z\"/></C:calendar-data>",
+        "<C:calendar-data><C:expand start=\"20210101T000000Z\" end=\"20210102T000000Z\"/>\
+         <C:limit-recurrence-set start=\"20210101T000000Z\" end=\"20210102T000000Z\"/></C:calendar-data>",
+        "<C:calendar-data><C:limit-recurrence-set start=\"20210102T000000Z\" end=\"20210101T000000Z\"/></C:calendar-data>",
+    ] {
+        assert_eq!(
+            get_calendar_data(calendar_data).await.status(),
+            StatusCode::BAD_REQUEST
+        );
+    }
+}
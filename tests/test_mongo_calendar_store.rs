@@ -0,0 +1,195 @@
+/*
+Integration tests of the MongoDB calendar store, against the server of .env.test. They are skipped unless
+MONGO_TESTS=true, the server being a replica set for the transactions:
+
+       docker compose up -d
+       MONGO_TESTS=true cargo test --test test_mongo_calendar_store
+
+Each test uses its own database, dropped before and after it runs.
+*/
+
+mod common;
+use bson::oid::ObjectId;
+use cal_dav_fast::app::calendar::{
+    calendar_store::{CalendarStore, MongoCalendarStore},
+    ical::ics,
+    models::{calendar::Calendar, calendar_object::CalendarObject},
+};
+use chrono::{TimeZone, Utc};
+use common::event;
+use futures::future::join_all;
+use mongodb::Database;
+use pretty_assertions::assert_eq;
+
+async fn database(name: &str) -> Option<Database> {
+    if std::env::var("MONGO_TESTS").as_deref() != Ok("true") {
+        return None;
+    }
+    dotenv::from_filename(".env.test").ok();
+    let uri = std::env::var("MONGO_DB").expect("MONGO_DB is needed by the MongoDB tests");
+    let client = mongodb::Client::with_uri_str(&uri).await.unwrap();
+    let db = client.database(&format!("caldav_test_{}", name));
+    db.drop(None).await.unwrap();
+    Some(db)
+}
+
+async fn create_calendar(store: &MongoCalendarStore) -> ObjectId {
+    store.create_calendar(&Calendar::new("work")).await.unwrap().unwrap()
+}
+
+fn object(calendar_id: ObjectId, name: &str, text: &str) -> CalendarObject {
+    let supported = ["VEVENT".to_string()];
+    let mut object = CalendarObject::from_ical(name, &ics::parse(text).unwrap(), &supported).unwrap();
+    for event in object.get_events_mut() {
+        event.set_resource(calendar_id, name);
+    }
+    object.update_etag();
+    object
+}
+
+fn event_at(uid: &str, start: &str, properties: &str) -> String {
+    event(uid, properties).replace("DTSTART:20210104T100000Z", &format!("DTSTART:{}", start))
+}
+
+#[actix_web::test]
+async fn test_get_objects_in_range() {
+    let Some(db) = database("objects_in_range").await else {
+        return;
+    };
+    let store = MongoCalendarStore::new(db.clone());
+    let calendar_id = create_calendar(&store).await;
+    let objects = [
+        (
+            "in.ics",
+            event_at("in", "20210104T100000Z", "DTEND:20210104T110000Z\r\n"),
+        ),
+        (
+            "before.ics",
+            event_at("before", "20201201T100000Z", "DTEND:20201201T110000Z\r\n"),
+        ),
+        (
+            "after.ics",
+            event_at("after", "20210201T100000Z", "DTEND:20210201T110000Z\r\n"),
+        ),
+        (
+            "across.ics",
+            event_at("across", "20210103T100000Z", "DTEND:20210104T120000Z\r\n"),
+        ),
+        /* Kept by the store, their end is unknown */
+        (
+            "no-end.ics",
+            event_at("no-end", "20201231T100000Z", "DURATION:PT1H\r\n"),
+        ),
+        /* Kept by the store, their instances are not stored */
+        (
+            "recurring.ics",
+            event_at("recurring", "20201201T100000Z", "RRULE:FREQ=DAILY;COUNT=2\r\n"),
+        ),
+    ];
+    for (name, text) in objects.iter() {
+        let object = object(calendar_id, name, text);
+        assert!(store.save_object(calendar_id, &object, None).await.unwrap());
+    }
+
+    let start = Utc.with_ymd_and_hms(2021, 1, 4, 0, 0, 0).unwrap();
+    let end = Utc.with_ymd_and_hms(2021, 1, 5, 0, 0, 0).unwrap();
+    let names = |objects: Vec<CalendarObject>| -> Vec<String> {
+        objects.iter().map(|object| object.get_name().to_string()).collect()
+    };
+    assert_eq!(
+        names(
+            store
+                .get_objects_in_range(calendar_id, Some(start), Some(end))
+                .await
+                .unwrap()
+        ),
+        vec!["across.ics", "in.ics", "no-end.ics", "recurring.ics"]
+    );
+    assert_eq!(
+        names(
+            store
+                .get_objects_in_range(calendar_id, None, Some(start))
+                .await
+                .unwrap()
+        ),
+        vec!["across.ics", "before.ics", "no-end.ics", "recurring.ics"]
+    );
+    assert_eq!(
+        names(store.get_objects_in_range(calendar_id, Some(end), None).await.unwrap()),
+        vec!["after.ics", "no-end.ics", "recurring.ics"]
+    );
+
+    db.drop(None).await.unwrap();
+}
+
+#[actix_web::test]
+async fn test_concurrent_creates() {
+    let Some(db) = database("concurrent_creates").await else {
+        return;
+    };
+    let store = MongoCalendarStore::new(db.clone());
+    let calendar_id = create_calendar(&store).await;
+
+    /* Writers creating the same resource: only one of them succeeds */
+    let objects: Vec<CalendarObject> = (0..8)
+        .map(|index| {
+            let summary = format!("SUMMARY:Meeting {}\r\n", index);
+            object(calendar_id, "event-1.ics", &event("event-1", &summary))
+        })
+        .collect();
+    let results = join_all(
+        objects
+            .iter()
+            .map(|object| store.save_object(calendar_id, object, None)),
+    )
+    .await;
+    let created: Vec<bool> = results.into_iter().map(Result::unwrap).collect();
+    assert_eq!(created.iter().filter(|created| **created).count(), 1);
+
+    let stored = store.get_object(calendar_id, "event-1.ics").await.unwrap().unwrap();
+    assert_eq!(stored.get_events().len(), 1);
+    let winner = created.iter().position(|created| *created).unwrap();
+    assert_eq!(stored.get_etag(), objects[winner].get_etag());
+
+    db.drop(None).await.unwrap();
+}
+
+#[actix_web::test]
+async fn test_concurrent_writes() {
+    let Some(db) = database("concurrent_writes").await else {
+        return;
+    };
+    let store = MongoCalendarStore::new(db.clone());
+    let calendar_id = create_calendar(&store).await;
+    let created = object(calendar_id, "event-1.ics", &event("event-1", "SUMMARY:Meeting\r\n"));
+    assert!(store.save_object(calendar_id, &created, None).await.unwrap());
+    let previous = store.get_object(calendar_id, "event-1.ics").await.unwrap().unwrap();
+
+    /* Writers having read the same version: only one of them replaces it */
+    let objects: Vec<CalendarObject> = (0..8)
+        .map(|index| {
+            let summary = format!("SUMMARY:Meeting {}\r\n", index);
+            object(calendar_id, "event-1.ics", &event("event-1", &summary))
+        })
+        .collect();
+    let results = join_all(
+        objects
+            .iter()
+            .map(|object| store.save_object(calendar_id, object, Some(&previous))),
+    )
+    .await;
+    let saved: Vec<bool> = results.into_iter().map(Result::unwrap).collect();
+    assert_eq!(saved.iter().filter(|saved| **saved).count(), 1);
+
+    let stored = store.get_object(calendar_id, "event-1.ics").await.unwrap().unwrap();
+    assert_eq!(stored.get_events().len(), 1);
+    let winner = saved.iter().position(|saved| *saved).unwrap();
+    assert_eq!(stored.get_etag(), objects[winner].get_etag());
+
+    /* The resource changed since read */
+    assert!(!store.delete_object(calendar_id, &previous).await.unwrap());
+    assert!(store.delete_object(calendar_id, &stored).await.unwrap());
+    assert!(store.get_object(calendar_id, "event-1.ics").await.unwrap().is_none());
+
+    db.drop(None).await.unwrap();
+}